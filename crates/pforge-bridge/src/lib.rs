@@ -6,13 +6,25 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int};
 use std::slice;
+use std::sync::Arc;
 
-/// Opaque handle to a handler context
+use pforge_runtime::{Error as RuntimeError, HandlerRegistry};
+
+/// Opaque handle to a handler context.
+///
+/// Backed by [`HandlerContextInner`], which owns the same [`HandlerRegistry`]
+/// the in-process runtime dispatches through, plus a dedicated Tokio runtime
+/// to `block_on` the registry's async dispatch from synchronous FFI callers.
 #[repr(C)]
 pub struct HandlerContext {
     _private: [u8; 0],
 }
 
+struct HandlerContextInner {
+    registry: Arc<HandlerRegistry>,
+    runtime: tokio::runtime::Runtime,
+}
+
 /// Result structure for FFI calls
 #[repr(C)]
 pub struct FfiResult {
@@ -26,76 +38,97 @@ pub struct FfiResult {
     pub error: *const c_char,
 }
 
-/// Execute a handler by name with JSON input
+/// Create an opaque handler context wrapping `registry`, for
+/// [`pforge_execute_handler`] to dispatch through.
+///
+/// This is a Rust-to-Rust entry point, not part of the C ABI: the embedding
+/// binary that builds `registry` (typically a `pforge_runtime::Server`)
+/// calls this once at startup and hands the resulting pointer across the
+/// FFI boundary, where the foreign caller only ever treats it as opaque.
+/// The context owns a dedicated single-threaded Tokio runtime used to drive
+/// the registry's async dispatch from [`pforge_execute_handler`]'s
+/// synchronous C ABI.
+pub fn pforge_context_new(registry: Arc<HandlerRegistry>) -> *mut HandlerContext {
+    let inner = Box::new(HandlerContextInner {
+        registry,
+        runtime: tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build embedded Tokio runtime for FFI dispatch"),
+    });
+    Box::into_raw(inner) as *mut HandlerContext
+}
+
+/// Free a context created by [`pforge_context_new`].
 ///
 /// # Safety
+/// - `ctx` must have been returned by `pforge_context_new` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn pforge_context_free(ctx: *mut HandlerContext) {
+    if !ctx.is_null() {
+        drop(Box::from_raw(ctx as *mut HandlerContextInner));
+    }
+}
+
+/// Execute a handler by name with JSON input.
+///
+/// Looks up `handler_name` in `ctx`'s registry, deserializes `input_json`
+/// into the handler's declared `Input`, and awaits its `handle` on the
+/// context's embedded Tokio runtime - the same dispatch path (coercion,
+/// schema validation, and dispatch middleware included) the in-process
+/// runtime uses for tool calls.
+///
+/// # Safety
+/// - `ctx` must have been returned by `pforge_context_new` and not yet freed
 /// - `handler_name` must be a valid null-terminated string
 /// - `input_json` must be a valid pointer to JSON bytes
 /// - `input_len` must be the correct length of input data
 /// - Caller must free result data with `pforge_free_result`
 #[no_mangle]
 pub unsafe extern "C" fn pforge_execute_handler(
+    ctx: *const HandlerContext,
     handler_name: *const c_char,
     input_json: *const u8,
     input_len: usize,
 ) -> FfiResult {
     // Validate inputs
-    if handler_name.is_null() || input_json.is_null() {
-        return FfiResult {
-            code: -1,
-            data: std::ptr::null_mut(),
-            data_len: 0,
-            error: create_error_string("Null pointer provided"),
-        };
+    if ctx.is_null() || handler_name.is_null() || input_json.is_null() {
+        return error_result(-1, "Null pointer provided");
     }
 
     // Convert handler name
     let name = match CStr::from_ptr(handler_name).to_str() {
         Ok(s) => s,
-        Err(_) => {
-            return FfiResult {
-                code: -2,
-                data: std::ptr::null_mut(),
-                data_len: 0,
-                error: create_error_string("Invalid UTF-8 in handler name"),
-            }
-        }
+        Err(_) => return error_result(-2, "Invalid UTF-8 in handler name"),
     };
 
     // Get input bytes
-    let _input = slice::from_raw_parts(input_json, input_len);
-
-    // TODO: Actually dispatch to handler registry
-    // For now, return a simple echo response
-    let response = serde_json::json!({
-        "handler": name,
-        "input_size": input_len,
-        "status": "ok"
-    });
+    let input = slice::from_raw_parts(input_json, input_len);
 
-    match serde_json::to_vec(&response) {
-        Ok(data) => {
-            let mut boxed = data.into_boxed_slice();
-            let data_ptr = boxed.as_mut_ptr();
-            let data_len = boxed.len();
-            // SAFETY: Transfer ownership to C caller. Memory will be freed via pforge_free_result.
-            // This is the correct pattern for FFI memory management.
-            #[allow(clippy::mem_forget)]
-            std::mem::forget(boxed);
-
-            FfiResult {
-                code: 0,
-                data: data_ptr,
-                data_len,
-                error: std::ptr::null(),
-            }
-        }
-        Err(e) => FfiResult {
-            code: -3,
-            data: std::ptr::null_mut(),
-            data_len: 0,
-            error: create_error_string(&format!("Serialization error: {}", e)),
-        },
+    let inner = &*(ctx as *const HandlerContextInner);
+    match inner.runtime.block_on(inner.registry.dispatch(name, input)) {
+        Ok(output) => success_result(output),
+        Err(e) => error_result(ffi_error_code(&e), &e.to_string()),
+    }
+}
+
+/// Map a dispatch [`RuntimeError`] to a distinct negative `code`, so a
+/// caller across the FFI boundary can branch on failure kind without
+/// parsing `error`.
+fn ffi_error_code(error: &RuntimeError) -> c_int {
+    match error {
+        RuntimeError::ToolNotFound(_) => -4,
+        RuntimeError::Handler(_) => -5,
+        RuntimeError::Serialization(_) => -6,
+        RuntimeError::Io(_) => -7,
+        RuntimeError::Http(_) => -8,
+        RuntimeError::Timeout => -9,
+        RuntimeError::Decryption(_) => -10,
+        RuntimeError::Validation(_) => -11,
+        RuntimeError::OutputValidation(_) => -12,
+        RuntimeError::Unauthorized(_) => -13,
+        RuntimeError::Codec(_) => -14,
+        RuntimeError::Classified(_, _) => -15,
     }
 }
 
@@ -124,6 +157,72 @@ pub unsafe extern "C" fn pforge_version() -> *const c_char {
     VERSION.as_ptr() as *const c_char
 }
 
+/// Current C ABI version. Bumped only on breaking `FfiResult`/calling
+/// convention changes - independent of [`pforge_version`]'s crate semver,
+/// which can change release to release without the ABI itself moving.
+pub const PFORGE_ABI_VERSION: u16 = 1;
+
+/// Stable identifier for this bridge implementation, returned by
+/// [`pforge_negotiate`] so host bindings can tell which bridge they're
+/// talking to.
+const BRIDGE_NAME: &str = "pforge-bridge";
+
+/// Optional capability a host binding may rely on, reported as a bit in the
+/// `capabilities`/`granted_capabilities` fields [`pforge_negotiate`] returns.
+#[repr(u32)]
+pub enum Capability {
+    /// `pforge_execute_handler` drives the handler's async `handle` to
+    /// completion on the context's embedded runtime; the call still blocks
+    /// the caller's thread until the handler finishes.
+    AsyncDispatch = 1 << 0,
+    /// Input JSON is read directly from the caller's buffer without an
+    /// intermediate copy.
+    ZeroCopyInput = 1 << 1,
+    /// Handlers can stream partial results back instead of returning a
+    /// single buffered `FfiResult`. Not yet implemented by this bridge.
+    StreamingResults = 1 << 2,
+}
+
+/// Capabilities this build of the bridge actually supports. Kept honest
+/// with the implementation above it - a flag only appears here once the
+/// matching behavior exists.
+const SUPPORTED_CAPABILITIES: u32 = Capability::AsyncDispatch as u32 | Capability::ZeroCopyInput as u32;
+
+/// The C ABI version this build of the bridge speaks. Host bindings should
+/// call this (or [`pforge_negotiate`]) at load time and refuse to proceed
+/// on a mismatch, rather than trusting `FfiResult`'s layout to still match
+/// what they were compiled against.
+#[no_mangle]
+pub extern "C" fn pforge_abi_version() -> u16 {
+    PFORGE_ABI_VERSION
+}
+
+/// Negotiate ABI compatibility and capabilities at load time.
+///
+/// Returns a JSON descriptor - `{"abi_version", "bridge_name", "compatible",
+/// "capabilities", "granted_capabilities"}` - where `compatible` is whether
+/// `requested_abi` matches [`PFORGE_ABI_VERSION`], `capabilities` is the
+/// full bitset this build supports, and `granted_capabilities` is that
+/// bitset intersected with the caller's `capability_flags`. A host binding
+/// should refuse to proceed when `compatible` is `false`, the same way
+/// `pforge_execute_handler`'s caller refuses to proceed on a non-zero
+/// `FfiResult.code`.
+#[no_mangle]
+pub extern "C" fn pforge_negotiate(requested_abi: u16, capability_flags: u32) -> FfiResult {
+    let descriptor = serde_json::json!({
+        "abi_version": PFORGE_ABI_VERSION,
+        "bridge_name": BRIDGE_NAME,
+        "compatible": requested_abi == PFORGE_ABI_VERSION,
+        "capabilities": SUPPORTED_CAPABILITIES,
+        "granted_capabilities": capability_flags & SUPPORTED_CAPABILITIES,
+    });
+
+    match serde_json::to_vec(&descriptor) {
+        Ok(bytes) => success_result(bytes),
+        Err(e) => error_result(-6, &format!("Serialization error: {}", e)),
+    }
+}
+
 // Helper functions
 
 fn create_error_string(msg: &str) -> *const c_char {
@@ -133,11 +232,72 @@ fn create_error_string(msg: &str) -> *const c_char {
     }
 }
 
+fn error_result(code: c_int, message: &str) -> FfiResult {
+    FfiResult {
+        code,
+        data: std::ptr::null_mut(),
+        data_len: 0,
+        error: create_error_string(message),
+    }
+}
+
+/// Transfer ownership of `data` to the C caller, to be released via
+/// `pforge_free_result`.
+fn success_result(data: Vec<u8>) -> FfiResult {
+    let mut boxed = data.into_boxed_slice();
+    let data_ptr = boxed.as_mut_ptr();
+    let data_len = boxed.len();
+    // SAFETY: Transfer ownership to C caller. Memory will be freed via pforge_free_result.
+    // This is the correct pattern for FFI memory management.
+    #[allow(clippy::mem_forget)]
+    std::mem::forget(boxed);
+
+    FfiResult {
+        code: 0,
+        data: data_ptr,
+        data_len,
+        error: std::ptr::null(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
     use std::ffi::CString;
 
+    #[derive(Debug, Deserialize, JsonSchema)]
+    struct EchoInput {
+        value: i32,
+    }
+
+    #[derive(Debug, Serialize, JsonSchema)]
+    struct EchoOutput {
+        doubled: i32,
+    }
+
+    struct EchoHandler;
+
+    #[async_trait::async_trait]
+    impl pforge_runtime::Handler for EchoHandler {
+        type Input = EchoInput;
+        type Output = EchoOutput;
+        type Error = pforge_runtime::Error;
+
+        async fn handle(&self, input: Self::Input) -> pforge_runtime::Result<Self::Output> {
+            Ok(EchoOutput {
+                doubled: input.value * 2,
+            })
+        }
+    }
+
+    fn test_context() -> *mut HandlerContext {
+        let mut registry = HandlerRegistry::new();
+        registry.register("double", EchoHandler);
+        pforge_context_new(Arc::new(registry))
+    }
+
     #[test]
     fn test_version() {
         unsafe {
@@ -151,32 +311,92 @@ mod tests {
     #[test]
     fn test_execute_handler_null_safety() {
         unsafe {
-            // Null handler name
-            let result = pforge_execute_handler(std::ptr::null(), std::ptr::null(), 0);
+            let result = pforge_execute_handler(std::ptr::null(), std::ptr::null(), std::ptr::null(), 0);
             assert_eq!(result.code, -1);
             pforge_free_result(result);
         }
     }
 
     #[test]
-    fn test_execute_handler_success() {
+    fn test_execute_handler_dispatches_to_registered_handler() {
         unsafe {
-            let handler_name = CString::new("test_handler").unwrap();
-            let input = b"{}";
+            let ctx = test_context();
+            let handler_name = CString::new("double").unwrap();
+            let input = br#"{"value": 21}"#;
 
-            let result = pforge_execute_handler(handler_name.as_ptr(), input.as_ptr(), input.len());
+            let result =
+                pforge_execute_handler(ctx, handler_name.as_ptr(), input.as_ptr(), input.len());
 
             assert_eq!(result.code, 0);
             assert!(!result.data.is_null());
-            assert!(result.data_len > 0);
 
-            // Parse result
             let data_slice = slice::from_raw_parts(result.data, result.data_len);
             let response: serde_json::Value = serde_json::from_slice(data_slice).unwrap();
-            assert_eq!(response["handler"], "test_handler");
-            assert_eq!(response["status"], "ok");
+            assert_eq!(response["doubled"], 42);
 
             pforge_free_result(result);
+            pforge_context_free(ctx);
         }
     }
+
+    #[test]
+    fn test_execute_handler_unknown_name_maps_to_tool_not_found_code() {
+        unsafe {
+            let ctx = test_context();
+            let handler_name = CString::new("missing").unwrap();
+            let input = b"{}";
+
+            let result =
+                pforge_execute_handler(ctx, handler_name.as_ptr(), input.as_ptr(), input.len());
+
+            assert_eq!(result.code, -4);
+            assert!(result.data.is_null());
+            assert!(!result.error.is_null());
+
+            pforge_free_result(result);
+            pforge_context_free(ctx);
+        }
+    }
+
+    #[test]
+    fn test_abi_version_is_nonzero() {
+        assert_ne!(pforge_abi_version(), 0);
+        assert_eq!(pforge_abi_version(), PFORGE_ABI_VERSION);
+    }
+
+    fn parse_negotiate_result(result: &FfiResult) -> serde_json::Value {
+        unsafe {
+            let data_slice = slice::from_raw_parts(result.data, result.data_len);
+            serde_json::from_slice(data_slice).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_negotiate_matching_abi_is_compatible() {
+        let result = pforge_negotiate(PFORGE_ABI_VERSION, 0);
+        assert_eq!(result.code, 0);
+        let descriptor = parse_negotiate_result(&result);
+        assert_eq!(descriptor["compatible"], true);
+        assert_eq!(descriptor["bridge_name"], "pforge-bridge");
+        unsafe { pforge_free_result(result) };
+    }
+
+    #[test]
+    fn test_negotiate_mismatched_abi_is_incompatible() {
+        let result = pforge_negotiate(PFORGE_ABI_VERSION + 1, 0);
+        let descriptor = parse_negotiate_result(&result);
+        assert_eq!(descriptor["compatible"], false);
+        unsafe { pforge_free_result(result) };
+    }
+
+    #[test]
+    fn test_negotiate_grants_only_requested_and_supported_capabilities() {
+        let requested = Capability::AsyncDispatch as u32 | Capability::StreamingResults as u32;
+        let result = pforge_negotiate(PFORGE_ABI_VERSION, requested);
+        let descriptor = parse_negotiate_result(&result);
+
+        let granted = descriptor["granted_capabilities"].as_u64().unwrap() as u32;
+        assert_eq!(granted, Capability::AsyncDispatch as u32);
+        unsafe { pforge_free_result(result) };
+    }
 }
@@ -0,0 +1,431 @@
+use anyhow::{Context, Result};
+use pforge_config::parse_config;
+use pforge_runtime::McpServer;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::Instant as TokioInstant;
+
+/// A declarative workload file describing a benchmark run.
+#[derive(Debug, Deserialize)]
+struct Workload {
+    name: String,
+    #[serde(default)]
+    warmup_iterations: usize,
+    steps: Vec<WorkloadStep>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkloadStep {
+    tool: String,
+    #[serde(default = "default_input")]
+    input: serde_json::Value,
+    #[serde(default)]
+    repeat: usize,
+    /// Relative selection weight for `pforge bench --load`'s weighted
+    /// sampling; ignored by the fixed-repeat regression mode below.
+    #[serde(default = "default_weight")]
+    weight: f64,
+}
+
+fn default_input() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// Latency percentiles and throughput for a single workload step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StepStats {
+    tool: String,
+    iterations: usize,
+    p50_micros: u64,
+    p90_micros: u64,
+    p99_micros: u64,
+    max_micros: u64,
+    ops_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BenchReport {
+    name: String,
+    steps: Vec<StepStats>,
+}
+
+pub async fn execute(
+    config_path: &str,
+    workload_path: &str,
+    baseline_path: Option<&str>,
+    threshold: f64,
+    report_url: Option<&str>,
+) -> Result<()> {
+    println!("Running pforge bench...");
+    println!("  Config: {}", config_path);
+    println!("  Workload: {}", workload_path);
+
+    let config = parse_config(Path::new(config_path)).context("Failed to parse configuration")?;
+    let workload_json =
+        std::fs::read_to_string(workload_path).context("Failed to read workload file")?;
+    let workload: Workload =
+        serde_json::from_str(&workload_json).context("Failed to parse workload file")?;
+
+    let server = McpServer::new(config);
+    server
+        .register_handlers()
+        .await
+        .context("Failed to register handlers")?;
+    let registry = server.registry();
+    let registry = registry.read().await;
+
+    let mut report = BenchReport {
+        name: workload.name.clone(),
+        steps: Vec::with_capacity(workload.steps.len()),
+    };
+
+    for step in &workload.steps {
+        let input_bytes = serde_json::to_vec(&step.input)?;
+
+        for _ in 0..workload.warmup_iterations {
+            let _ = registry.dispatch(&step.tool, &input_bytes).await;
+        }
+
+        let mut latencies = Vec::with_capacity(step.repeat);
+        let start = Instant::now();
+        for _ in 0..step.repeat {
+            let call_start = Instant::now();
+            registry
+                .dispatch(&step.tool, &input_bytes)
+                .await
+                .with_context(|| format!("Dispatch failed for tool '{}'", step.tool))?;
+            latencies.push(call_start.elapsed().as_micros() as u64);
+        }
+        let elapsed = start.elapsed();
+
+        let stats = summarize(&step.tool, latencies, elapsed);
+        println!(
+            "  {} :: p50={}us p90={}us p99={}us max={}us ops/sec={:.1}",
+            stats.tool, stats.p50_micros, stats.p90_micros, stats.p99_micros, stats.max_micros,
+            stats.ops_per_sec
+        );
+        report.steps.push(stats);
+    }
+
+    if let Some(baseline_path) = baseline_path {
+        check_regressions(&report, baseline_path, threshold)?;
+    }
+
+    if let Some(url) = report_url {
+        post_report(&report, url).await?;
+    }
+
+    Ok(())
+}
+
+fn summarize(tool: &str, mut latencies: Vec<u64>, elapsed: Duration) -> StepStats {
+    latencies.sort_unstable();
+    let iterations = latencies.len();
+    let ops_per_sec = if elapsed.as_secs_f64() > 0.0 {
+        iterations as f64 / elapsed.as_secs_f64()
+    } else {
+        0.0
+    };
+
+    StepStats {
+        tool: tool.to_string(),
+        iterations,
+        p50_micros: percentile(&latencies, 0.50),
+        p90_micros: percentile(&latencies, 0.90),
+        p99_micros: percentile(&latencies, 0.99),
+        max_micros: latencies.last().copied().unwrap_or(0),
+        ops_per_sec,
+    }
+}
+
+/// Nearest-rank percentile over a sorted sample.
+fn percentile(sorted_latencies: &[u64], p: f64) -> u64 {
+    if sorted_latencies.is_empty() {
+        return 0;
+    }
+    let rank = ((sorted_latencies.len() as f64) * p).ceil() as usize;
+    let index = rank.saturating_sub(1).min(sorted_latencies.len() - 1);
+    sorted_latencies[index]
+}
+
+fn check_regressions(report: &BenchReport, baseline_path: &str, threshold: f64) -> Result<()> {
+    let baseline_json =
+        std::fs::read_to_string(baseline_path).context("Failed to read baseline file")?;
+    let baseline: BenchReport =
+        serde_json::from_str(&baseline_json).context("Failed to parse baseline file")?;
+
+    let mut regressed = false;
+    for step in &report.steps {
+        let Some(base_step) = baseline.steps.iter().find(|b| b.tool == step.tool) else {
+            continue;
+        };
+
+        for (label, current, base) in [
+            ("p50", step.p50_micros, base_step.p50_micros),
+            ("p90", step.p90_micros, base_step.p90_micros),
+            ("p99", step.p99_micros, base_step.p99_micros),
+        ] {
+            if base == 0 {
+                continue;
+            }
+            let regression = (current as f64 - base as f64) / base as f64;
+            if regression > threshold {
+                regressed = true;
+                eprintln!(
+                    "✗ regression in {} {}: {}us vs baseline {}us ({:.1}% > {:.1}% threshold)",
+                    step.tool,
+                    label,
+                    current,
+                    base,
+                    regression * 100.0,
+                    threshold * 100.0
+                );
+            }
+        }
+    }
+
+    if regressed {
+        anyhow::bail!("Benchmark regressed beyond threshold against {}", baseline_path);
+    }
+
+    println!("✓ No regressions against baseline");
+    Ok(())
+}
+
+async fn post_report(report: &BenchReport, url: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    client
+        .post(url)
+        .json(report)
+        .send()
+        .await
+        .context("Failed to POST bench report")?;
+    println!("  Reported results to {}", url);
+    Ok(())
+}
+
+/// Aggregate statistics from a sustained load-generation run
+/// (`pforge bench --load`), as opposed to [`BenchReport`]'s per-step,
+/// fixed-repeat regression numbers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Stats {
+    total_requests: u64,
+    requests_per_second: f64,
+    error_count: u64,
+    p50_micros: u64,
+    p95_micros: u64,
+    p99_micros: u64,
+}
+
+/// Token-bucket rate limiter gating dispatch to a target rate, shared by
+/// every load-generation worker so the *aggregate* throughput (not each
+/// worker individually) tracks `ops_per_sec`. `acquire` hands out evenly
+/// spaced slots from a single running schedule, so workers queue up behind
+/// one another rather than racing to dispatch as fast as possible.
+struct RateLimiter {
+    period: Duration,
+    next_slot: tokio::sync::Mutex<TokioInstant>,
+}
+
+impl RateLimiter {
+    fn new(ops_per_sec: f64) -> Self {
+        let period = if ops_per_sec > 0.0 {
+            Duration::from_secs_f64(1.0 / ops_per_sec)
+        } else {
+            Duration::ZERO
+        };
+        Self {
+            period,
+            next_slot: tokio::sync::Mutex::new(TokioInstant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        if self.period.is_zero() {
+            return;
+        }
+        let wait_until = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(TokioInstant::now());
+            *next_slot = slot + self.period;
+            slot
+        };
+        tokio::time::sleep_until(wait_until).await;
+    }
+}
+
+/// Drive a running server's `HandlerRegistry` under sustained load:
+/// `concurrency` tokio tasks repeatedly pick a weighted workload step (via a
+/// seeded `StdRng`, reproducible across runs), wait their turn on a shared
+/// `RateLimiter`, and dispatch it, until `bench_length_seconds` elapses. If
+/// `stop_on_fatal` is set, the first handler error flips a shared
+/// `AtomicBool` that halts every worker early. Writes the resulting
+/// [`Stats`] as JSON to `output_path`, and optionally scrapes
+/// `scrape_metrics_url` (the server's own Prometheus exporter) alongside it
+/// so a run's counters and this harness's independently-measured latency
+/// can be compared.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_load(
+    config_path: &str,
+    workload_path: &str,
+    ops_per_sec: f64,
+    bench_length_seconds: u64,
+    concurrency: usize,
+    seed: u64,
+    stop_on_fatal: bool,
+    output_path: &str,
+    scrape_metrics_url: Option<&str>,
+) -> Result<()> {
+    println!("Running pforge bench --load...");
+    println!("  Config: {}", config_path);
+    println!("  Workload: {}", workload_path);
+    println!(
+        "  Target: {:.1} ops/sec for {}s across {} workers",
+        ops_per_sec, bench_length_seconds, concurrency
+    );
+
+    let config = parse_config(Path::new(config_path)).context("Failed to parse configuration")?;
+    let workload_json =
+        std::fs::read_to_string(workload_path).context("Failed to read workload file")?;
+    let workload: Workload =
+        serde_json::from_str(&workload_json).context("Failed to parse workload file")?;
+    anyhow::ensure!(!workload.steps.is_empty(), "workload must declare at least one step");
+
+    let server = McpServer::new(config);
+    server
+        .register_handlers()
+        .await
+        .context("Failed to register handlers")?;
+    let registry = server.registry();
+
+    let steps: Vec<(String, Vec<u8>)> = workload
+        .steps
+        .iter()
+        .map(|s| Ok::<_, anyhow::Error>((s.tool.clone(), serde_json::to_vec(&s.input)?)))
+        .collect::<Result<_>>()?;
+
+    let mut cumulative_weights = Vec::with_capacity(workload.steps.len());
+    let mut running_weight = 0.0;
+    for step in &workload.steps {
+        running_weight += step.weight.max(0.0);
+        cumulative_weights.push(running_weight);
+    }
+    anyhow::ensure!(running_weight > 0.0, "workload steps must have a positive total weight");
+    let total_weight = running_weight;
+
+    let rate_limiter = Arc::new(RateLimiter::new(ops_per_sec));
+    let halt = Arc::new(AtomicBool::new(false));
+    let samples = Arc::new(tokio::sync::Mutex::new(Vec::<u64>::new()));
+    let errors = Arc::new(AtomicU64::new(0));
+    let total_requests = Arc::new(AtomicU64::new(0));
+    let deadline = TokioInstant::now() + Duration::from_secs(bench_length_seconds);
+
+    let mut workers = Vec::with_capacity(concurrency);
+    for worker_id in 0..concurrency {
+        let registry = registry.clone();
+        let rate_limiter = rate_limiter.clone();
+        let halt = halt.clone();
+        let samples = samples.clone();
+        let errors = errors.clone();
+        let total_requests = total_requests.clone();
+        let steps = steps.clone();
+        let cumulative_weights = cumulative_weights.clone();
+        // Each worker gets its own deterministic stream, derived from the
+        // shared seed plus its own id - reproducible across runs without
+        // workers contending over one shared RNG.
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(worker_id as u64));
+
+        workers.push(tokio::spawn(async move {
+            while TokioInstant::now() < deadline && !halt.load(Ordering::Relaxed) {
+                rate_limiter.acquire().await;
+
+                let r: f64 = rng.gen_range(0.0..total_weight);
+                let idx = cumulative_weights
+                    .iter()
+                    .position(|&w| r < w)
+                    .unwrap_or(steps.len() - 1);
+                let (tool, input) = &steps[idx];
+
+                let call_start = Instant::now();
+                let result = registry.read().await.dispatch(tool, input).await;
+                let elapsed_micros = call_start.elapsed().as_micros() as u64;
+
+                total_requests.fetch_add(1, Ordering::Relaxed);
+                samples.lock().await.push(elapsed_micros);
+
+                if result.is_err() {
+                    errors.fetch_add(1, Ordering::Relaxed);
+                    if stop_on_fatal {
+                        halt.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        }));
+    }
+
+    let run_start = Instant::now();
+    for worker in workers {
+        worker.await.context("load-generation worker panicked")?;
+    }
+    let run_elapsed = run_start.elapsed();
+
+    let mut sorted_samples = Arc::try_unwrap(samples)
+        .expect("all worker tasks have joined, so no other Arc clones remain")
+        .into_inner();
+    sorted_samples.sort_unstable();
+
+    let total = total_requests.load(Ordering::Relaxed);
+    let stats = Stats {
+        total_requests: total,
+        requests_per_second: if run_elapsed.as_secs_f64() > 0.0 {
+            total as f64 / run_elapsed.as_secs_f64()
+        } else {
+            0.0
+        },
+        error_count: errors.load(Ordering::Relaxed),
+        p50_micros: percentile(&sorted_samples, 0.50),
+        p95_micros: percentile(&sorted_samples, 0.95),
+        p99_micros: percentile(&sorted_samples, 0.99),
+    };
+
+    println!(
+        "  total_requests={} requests_per_second={:.1} errors={} p50={}us p95={}us p99={}us",
+        stats.total_requests,
+        stats.requests_per_second,
+        stats.error_count,
+        stats.p50_micros,
+        stats.p95_micros,
+        stats.p99_micros
+    );
+
+    std::fs::write(output_path, serde_json::to_string_pretty(&stats)?)
+        .with_context(|| format!("Failed to write stats to {}", output_path))?;
+    println!("  Wrote stats to {}", output_path);
+
+    if let Some(url) = scrape_metrics_url {
+        let body = reqwest::get(url)
+            .await
+            .context("Failed to scrape metrics endpoint")?
+            .text()
+            .await
+            .context("Failed to read metrics response body")?;
+        let scrape_path = format!("{}.metrics.txt", output_path);
+        std::fs::write(&scrape_path, body)
+            .with_context(|| format!("Failed to write scraped metrics to {}", scrape_path))?;
+        println!("  Wrote scraped metrics to {}", scrape_path);
+    }
+
+    if stop_on_fatal && halt.load(Ordering::Relaxed) {
+        anyhow::bail!("Load run halted early: a handler error was hit with --stop-on-fatal set");
+    }
+
+    Ok(())
+}
@@ -1,13 +1,185 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use notify::Watcher;
+use pforge_config::{parse_config, ForgeConfig};
+use pforge_runtime::McpServer;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::task::JoinHandle;
+
+/// The first event for a path is forwarded and further events for it within
+/// [`DEBOUNCE`] of that one are dropped, the same per-path scheme
+/// [`pforge_runtime::resource::FileWatcher`] applies with its caller-chosen
+/// window.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// What kind of relevant path changed, decided the same way Deno's
+/// file-watcher decides whether a changed module actually affects the
+/// running program: only `pforge.yaml` itself or a `.rs` file under `src/`
+/// can change what the server would do, so anything else (editor swap
+/// files, `target/`, `.git/`) is ignored rather than forcing a reload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RelevantChange {
+    Config,
+    Source,
+}
+
+fn classify_change(path: &Path, config_path: &Path, src_dir: &Path) -> Option<RelevantChange> {
+    if path == config_path {
+        return Some(RelevantChange::Config);
+    }
+    if path.starts_with(src_dir) && path.extension().is_some_and(|ext| ext == "rs") {
+        return Some(RelevantChange::Source);
+    }
+    None
+}
 
 pub async fn execute(config_path: &str, watch: bool) -> Result<()> {
     println!("Starting pforge in development mode...");
     println!("  Config: {}", config_path);
     println!("  Watch: {}", watch);
 
-    // TODO: Hot reload implementation
-    println!("\n⚠ Development mode with hot reload pending");
-    println!("  Falling back to serve mode...");
+    if !watch {
+        println!("\nWatch disabled, falling back to serve mode...");
+        return super::serve::execute(config_path).await;
+    }
+
+    let config_path = Path::new(config_path)
+        .canonicalize()
+        .context("Failed to resolve config path")?;
+    let project_dir = config_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let src_dir = project_dir.join("src");
+
+    let config = parse_config(&config_path).context("Failed to parse configuration")?;
+    let mut config_json = serde_json::to_value(&config)?;
+    let mut server_task = spawn_server(config);
+
+    let (tx, mut rx) = unbounded_channel();
+    let _watcher = start_watcher(&project_dir, &src_dir, &config_path, tx)?;
+
+    println!(
+        "\n✓ Watching {} for changes (Ctrl+C to stop)...",
+        project_dir.display()
+    );
+
+    while let Some(change) = rx.recv().await {
+        if change == RelevantChange::Source {
+            println!("\nSource change detected, rebuilding...");
+            if let Err(e) = rebuild(&project_dir) {
+                println!("✗ build failed, keeping previous server: {:#}", e);
+                continue;
+            }
+        }
+
+        let new_config = match parse_config(&config_path) {
+            Ok(new_config) => new_config,
+            Err(e) => {
+                println!("✗ reload failed, keeping previous server: {}", e);
+                continue;
+            }
+        };
+
+        let new_config_json = serde_json::to_value(&new_config)?;
+        if new_config_json == config_json {
+            println!("(no effective change, skipping restart)");
+            continue;
+        }
+
+        // The running server exposes no shutdown handle of its own (`run`
+        // only returns on stdin EOF or Ctrl+C), so a reload aborts its task
+        // outright rather than draining in-flight requests first - abrupt,
+        // but it replaces only the spawned task, never the `pforge dev`
+        // process itself.
+        server_task.abort();
+        config_json = new_config_json;
+        server_task = spawn_server(new_config);
+        println!("✓ reloaded");
+    }
+
+    server_task.abort();
+    Ok(())
+}
+
+/// Run `cargo build` in `project_dir`, mirroring [`super::build::execute`]
+/// but always in debug - dev mode never builds `--release`.
+fn rebuild(project_dir: &Path) -> Result<()> {
+    let status = Command::new("cargo")
+        .arg("build")
+        .current_dir(project_dir)
+        .status()
+        .context("Failed to spawn cargo build")?;
+
+    if !status.success() {
+        anyhow::bail!("cargo build exited with {}", status);
+    }
+
+    Ok(())
+}
+
+fn spawn_server(config: ForgeConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let server = McpServer::new(config);
+        if let Err(e) = server.run().await {
+            eprintln!("Server error: {}", e);
+        }
+    })
+}
+
+/// Watch `project_dir` (non-recursively, for `pforge.yaml`) and `src_dir`
+/// (recursively, for handler sources), debouncing bursts of OS events per
+/// path the same way [`pforge_runtime::resource::FileWatcher`] does, and
+/// forwarding only changes [`classify_change`] considers relevant.
+fn start_watcher(
+    project_dir: &Path,
+    src_dir: &Path,
+    config_path: &Path,
+    tx: UnboundedSender<RelevantChange>,
+) -> Result<notify::RecommendedWatcher> {
+    let src_dir_owned = src_dir.to_path_buf();
+    let config_path_owned = config_path.to_path_buf();
+    let mut last_seen: std::collections::HashMap<PathBuf, Instant> =
+        std::collections::HashMap::new();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        if !matches!(
+            event.kind,
+            notify::EventKind::Create(_) | notify::EventKind::Modify(_)
+        ) {
+            return;
+        }
+
+        for path in event.paths {
+            let Some(change) = classify_change(&path, &config_path_owned, &src_dir_owned) else {
+                continue;
+            };
+
+            let now = Instant::now();
+            if let Some(last) = last_seen.get(&path) {
+                if now.duration_since(*last) < DEBOUNCE {
+                    continue;
+                }
+            }
+            last_seen.insert(path, now);
+
+            let _ = tx.send(change);
+        }
+    })
+    .context("failed to create file watcher")?;
+
+    watcher
+        .watch(project_dir, notify::RecursiveMode::NonRecursive)
+        .with_context(|| format!("failed to watch {}", project_dir.display()))?;
+
+    if src_dir.is_dir() {
+        watcher
+            .watch(src_dir, notify::RecursiveMode::Recursive)
+            .with_context(|| format!("failed to watch {}", src_dir.display()))?;
+    }
 
-    super::serve::execute(config_path).await
+    Ok(watcher)
 }
@@ -0,0 +1,7 @@
+pub mod bench;
+pub mod build;
+pub mod dev;
+pub mod new;
+pub mod run;
+pub mod serve;
+pub mod test;
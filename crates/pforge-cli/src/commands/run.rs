@@ -0,0 +1,35 @@
+use anyhow::{Context, Result};
+use pforge_config::parse_config;
+use pforge_runtime::McpServer;
+use std::path::Path;
+
+/// Dispatch a single tool or (expanded) alias by name, the same way an MCP
+/// client would - a quick CLI shortcut for trying a tool/alias out without
+/// standing up a transport.
+pub async fn execute(config_path: &str, tool: &str, input: Option<&str>) -> Result<()> {
+    let config = parse_config(Path::new(config_path)).context("Failed to parse configuration")?;
+
+    let server = McpServer::new(config);
+    server
+        .register_handlers()
+        .await
+        .context("Failed to register handlers")?;
+
+    let input_bytes = match input {
+        Some(json) => json.as_bytes().to_vec(),
+        None => b"{}".to_vec(),
+    };
+
+    let registry = server.registry();
+    let result = registry
+        .read()
+        .await
+        .dispatch(tool, &input_bytes)
+        .await
+        .with_context(|| format!("Failed to dispatch '{}'", tool))?;
+
+    let output: serde_json::Value = serde_json::from_slice(&result)?;
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
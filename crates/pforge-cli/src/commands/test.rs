@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use pforge_config::parse_config;
+use pforge_runtime::{
+    format_summary, run_suite, run_suite_against_container, ContainerFixture, McpServer,
+    RunOptions, TestSuite,
+};
+use std::path::Path;
+
+pub async fn execute(
+    config_path: &str,
+    suite_path: &str,
+    filter: Option<&str>,
+    shuffle_seed: Option<u64>,
+    concurrency: usize,
+    format: &str,
+) -> Result<()> {
+    let config = parse_config(Path::new(config_path)).context("Failed to parse configuration")?;
+    let suite_yaml = std::fs::read_to_string(suite_path).context("Failed to read test suite file")?;
+    let suite: TestSuite =
+        serde_yaml::from_str(&suite_yaml).context("Failed to parse test suite file")?;
+
+    let server = McpServer::new(config);
+    server
+        .register_handlers()
+        .await
+        .context("Failed to register handlers")?;
+
+    let options = RunOptions {
+        filter: filter.map(str::to_string),
+        shuffle_seed,
+        concurrency,
+    };
+
+    let report = run_suite(&suite, server.registry(), &options).await?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        _ => {
+            println!("Running suite '{}' ({} cases)...\n", report.name, report.total);
+            print!("{}", format_summary(&report));
+        }
+    }
+
+    if report.failed > 0 {
+        anyhow::bail!("{} of {} test cases failed", report.failed, report.total);
+    }
+
+    Ok(())
+}
+
+/// Like [`execute`], but runs the suite against a real Docker container
+/// (built from `build`, if given) rather than dispatching in-process -
+/// validating that a generated server actually serves its declared tools in
+/// a clean environment, not just that the crate compiles.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_integration(
+    config_path: &str,
+    suite_path: &str,
+    filter: Option<&str>,
+    shuffle_seed: Option<u64>,
+    format: &str,
+    image: Option<&str>,
+    build: Option<&str>,
+    readiness_tool: Option<&str>,
+) -> Result<()> {
+    let image = image.context("--integration requires --image")?;
+    let suite_yaml = std::fs::read_to_string(suite_path).context("Failed to read test suite file")?;
+    let suite: TestSuite =
+        serde_yaml::from_str(&suite_yaml).context("Failed to parse test suite file")?;
+
+    if let Some(context_dir) = build {
+        ContainerFixture::build_image(Path::new(context_dir), image)
+            .context("Failed to build container image")?;
+    }
+
+    let mut fixture = ContainerFixture::new(image).mount_config(config_path);
+    if let Some(tool) = readiness_tool {
+        fixture = fixture.readiness_probe(tool, serde_json::json!({}));
+    }
+
+    let mut container = fixture.start().context("Failed to start container")?;
+
+    let options = RunOptions {
+        filter: filter.map(str::to_string),
+        shuffle_seed,
+        concurrency: 1,
+    };
+
+    let report = run_suite_against_container(&suite, &mut container, &options);
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        _ => {
+            println!(
+                "Running suite '{}' against container '{}' ({} cases)...\n",
+                report.name,
+                container.name(),
+                report.total
+            );
+            print!("{}", format_summary(&report));
+        }
+    }
+
+    if report.failed > 0 {
+        anyhow::bail!("{} of {} test cases failed", report.failed, report.total);
+    }
+
+    Ok(())
+}
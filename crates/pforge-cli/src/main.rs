@@ -3,6 +3,13 @@ mod commands;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+/// Swap in mimalloc as the process-wide allocator when built with the
+/// `mimalloc` feature; the generated servers this CLI builds are typically
+/// allocation-heavy handler dispatch loops where it measurably helps.
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
 #[derive(Parser)]
 #[command(name = "pforge")]
 #[command(about = "Declarative MCP server framework", long_about = None)]
@@ -47,6 +54,122 @@ enum Commands {
         #[arg(short, long, default_value_t = true)]
         watch: bool,
     },
+
+    /// Dispatch a single tool or alias, printing its JSON result
+    Run {
+        /// Path to pforge.yaml config
+        #[arg(short, long, default_value = "pforge.yaml")]
+        config: String,
+
+        /// Name of the tool or alias to invoke
+        tool: String,
+
+        /// JSON input for the tool (defaults to `{}`)
+        #[arg(long)]
+        input: Option<String>,
+    },
+
+    /// Run a declarative golden-test suite against the handler registry
+    Test {
+        /// Path to pforge.yaml config
+        #[arg(short, long, default_value = "pforge.yaml")]
+        config: String,
+
+        /// Path to the test suite YAML file
+        suite: String,
+
+        /// Only run cases whose name contains this substring
+        #[arg(long)]
+        filter: Option<String>,
+
+        /// Deterministically reshuffle case order using this seed
+        #[arg(long)]
+        shuffle: Option<u64>,
+
+        /// Maximum number of cases dispatched concurrently
+        #[arg(long, default_value_t = 1)]
+        concurrency: usize,
+
+        /// Output format
+        #[arg(long, default_value = "pretty")]
+        format: String,
+
+        /// Run the suite against a real Docker container instead of
+        /// dispatching in-process - see `ContainerFixture`
+        #[arg(long)]
+        integration: bool,
+
+        /// Docker image tag to run (or build, with `--build`) for
+        /// `--integration`
+        #[arg(long, requires = "integration")]
+        image: Option<String>,
+
+        /// Directory containing the Dockerfile to build `--image` from
+        /// before starting it
+        #[arg(long, requires = "integration")]
+        build: Option<String>,
+
+        /// Name of a tool to call as a readiness probe before running the
+        /// suite, given the probe input is `{}`
+        #[arg(long, requires = "integration")]
+        readiness_tool: Option<String>,
+    },
+
+    /// Run a declarative benchmark workload against the handler registry
+    Bench {
+        /// Path to pforge.yaml config
+        #[arg(short, long, default_value = "pforge.yaml")]
+        config: String,
+
+        /// Path to the workload JSON file
+        workload: String,
+
+        /// Path to a saved baseline report to diff against
+        #[arg(long)]
+        baseline: Option<String>,
+
+        /// Fractional regression threshold (e.g. 0.1 = 10%) before failing
+        #[arg(long, default_value_t = 0.1)]
+        threshold: f64,
+
+        /// Drive the workload as sustained rate-limited load instead of a
+        /// fixed-repeat regression check (see `--rate`, `--duration-secs`)
+        #[arg(long)]
+        load: bool,
+
+        /// Target aggregate dispatch rate in operations/second, for `--load`
+        #[arg(long, requires = "load", default_value_t = 100.0)]
+        rate: f64,
+
+        /// How long to sustain `--rate` for, in seconds, for `--load`
+        #[arg(long, requires = "load", default_value_t = 30)]
+        duration_secs: u64,
+
+        /// Number of concurrent workers dispatching load, for `--load`
+        #[arg(long, requires = "load", default_value_t = 8)]
+        concurrency: usize,
+
+        /// Seed for the weighted-workload RNG, for reproducible `--load` runs
+        #[arg(long, requires = "load", default_value_t = 42)]
+        seed: u64,
+
+        /// Halt every worker on the first handler error, for `--load`
+        #[arg(long, requires = "load")]
+        stop_on_fatal: bool,
+
+        /// Path to write the `--load` run's JSON stats to
+        #[arg(long, requires = "load", default_value = "bench-results.json")]
+        output: String,
+
+        /// URL to scrape the server's Prometheus exporter from alongside a
+        /// `--load` run, for comparing runs in CI
+        #[arg(long, requires = "load")]
+        scrape_metrics_url: Option<String>,
+
+        /// URL to POST the JSON results to
+        #[arg(long)]
+        report_url: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -66,6 +189,88 @@ async fn main() -> Result<()> {
         Commands::Dev { config, watch } => {
             commands::dev::execute(&config, watch).await?;
         }
+        Commands::Run {
+            config,
+            tool,
+            input,
+        } => {
+            commands::run::execute(&config, &tool, input.as_deref()).await?;
+        }
+        Commands::Test {
+            config,
+            suite,
+            filter,
+            shuffle,
+            concurrency,
+            format,
+            integration,
+            image,
+            build,
+            readiness_tool,
+        } => {
+            if integration {
+                commands::test::execute_integration(
+                    &config,
+                    &suite,
+                    filter.as_deref(),
+                    shuffle,
+                    &format,
+                    image.as_deref(),
+                    build.as_deref(),
+                    readiness_tool.as_deref(),
+                )
+                .await?;
+            } else {
+                commands::test::execute(
+                    &config,
+                    &suite,
+                    filter.as_deref(),
+                    shuffle,
+                    concurrency,
+                    &format,
+                )
+                .await?;
+            }
+        }
+        Commands::Bench {
+            config,
+            workload,
+            baseline,
+            threshold,
+            load,
+            rate,
+            duration_secs,
+            concurrency,
+            seed,
+            stop_on_fatal,
+            output,
+            scrape_metrics_url,
+            report_url,
+        } => {
+            if load {
+                commands::bench::execute_load(
+                    &config,
+                    &workload,
+                    rate,
+                    duration_secs,
+                    concurrency,
+                    seed,
+                    stop_on_fatal,
+                    &output,
+                    scrape_metrics_url.as_deref(),
+                )
+                .await?;
+            } else {
+                commands::bench::execute(
+                    &config,
+                    &workload,
+                    baseline.as_deref(),
+                    threshold,
+                    report_url.as_deref(),
+                )
+                .await?;
+            }
+        }
     }
 
     Ok(())
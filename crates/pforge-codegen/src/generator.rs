@@ -52,28 +52,63 @@ pub fn generate_param_struct(tool_name: &str, params: &ParamSchema) -> Result<St
     Ok(output)
 }
 
-/// Generate handler registration code
+/// Generate handler registration code.
+///
+/// A `ToolDef::Pipeline` resolves its steps against the very registry it is
+/// registered into (see [`pforge_runtime::handlers::pipeline::PipelineHandler`]),
+/// the same way [`pforge_runtime::McpServer::register_handlers`] wires it up
+/// dynamically - so, unlike the other tool kinds, it needs a live, shared
+/// handle rather than the bare `&mut HandlerRegistry` a one-shot register
+/// call is enough for. The generated function therefore takes the registry
+/// wrapped the same way the server holds it, and locks it for the duration
+/// of registration.
 pub fn generate_handler_registration(config: &ForgeConfig) -> Result<String> {
     let mut output = String::new();
 
-    output.push_str("pub fn register_handlers(registry: &mut HandlerRegistry) {\n");
+    output.push_str(
+        "pub async fn register_handlers(registry_handle: std::sync::Arc<tokio::sync::RwLock<HandlerRegistry>>) {\n",
+    );
+    output.push_str("    let mut registry = registry_handle.write().await;\n");
 
     for tool in &config.tools {
         match tool {
-            pforge_config::ToolDef::Native { name, handler, .. } => {
+            pforge_config::ToolDef::Native {
+                name,
+                handler,
+                params,
+                ..
+            } => {
                 // Extract handler path
                 let handler_path = &handler.path;
-                output.push_str(&format!(
-                    "    registry.register(\"{}\", {});\n",
-                    name, handler_path
-                ));
+                if params.fields.is_empty() {
+                    output.push_str(&format!(
+                        "    registry.register(\"{}\", {});\n",
+                        name, handler_path
+                    ));
+                } else {
+                    // Route `params:` field conversions (see
+                    // `pforge_runtime::conversion::Conversion`) through dispatch
+                    // so e.g. a `coerce: timestamp` field is parsed before the
+                    // handler ever sees it, not just when rendered as a prompt
+                    // argument.
+                    let params_json = serde_json::to_string(params).map_err(|e| {
+                        CodegenError::GenerationError(format!(
+                            "failed to serialize params for '{}': {}",
+                            name, e
+                        ))
+                    })?;
+                    output.push_str(&format!(
+                        "    registry.register_with_params(\"{}\", {}, serde_json::from_str(r#\"{}\"#).unwrap());\n",
+                        name, handler_path, params_json
+                    ));
+                }
             }
             pforge_config::ToolDef::Cli {
                 name,
                 command,
                 args,
                 cwd,
-                env: _,
+                env,
                 stream,
                 description: _,
             } => {
@@ -90,7 +125,7 @@ pub fn generate_handler_registration(config: &ForgeConfig) -> Result<String> {
                     output.push_str("        None,\n");
                 }
 
-                output.push_str("        HashMap::new(), // env\n");
+                output.push_str(&format!("        {}, // env\n", format_string_map(env)));
                 output.push_str("        None, // timeout\n");
                 output.push_str(&format!("        {},\n", stream));
                 output.push_str("    ));\n");
@@ -99,8 +134,8 @@ pub fn generate_handler_registration(config: &ForgeConfig) -> Result<String> {
                 name,
                 endpoint,
                 method,
-                headers: _,
-                auth: _,
+                headers,
+                auth,
                 description: _,
             } => {
                 output.push_str(&format!(
@@ -109,16 +144,29 @@ pub fn generate_handler_registration(config: &ForgeConfig) -> Result<String> {
                 ));
                 output.push_str(&format!("        \"{}\".to_string(),\n", endpoint));
                 output.push_str(&format!("        HttpMethod::{:?},\n", method));
-                output.push_str("        HashMap::new(), // headers\n");
-                output.push_str("        None, // auth\n");
+                output.push_str(&format!(
+                    "        {}, // headers\n",
+                    format_string_map(headers)
+                ));
+                output.push_str(&format!("        {}, // auth\n", format_auth_config(auth)));
                 output.push_str("    ));\n");
             }
             pforge_config::ToolDef::Pipeline {
-                name: _,
-                steps: _,
+                name,
+                steps,
                 description: _,
             } => {
-                output.push_str("    // Pipeline handler TBD\n");
+                output.push_str("    registry.register(\n");
+                output.push_str(&format!("        \"{}\",\n", name));
+                output.push_str("        PipelineHandler::new(\n");
+                output.push_str("            registry_handle.clone(),\n");
+                output.push_str("            vec![\n");
+                for step in steps {
+                    output.push_str(&format_pipeline_step(step)?);
+                }
+                output.push_str("            ],\n");
+                output.push_str("        ),\n");
+                output.push_str("    );\n");
             }
         }
     }
@@ -128,6 +176,58 @@ pub fn generate_handler_registration(config: &ForgeConfig) -> Result<String> {
     Ok(output)
 }
 
+fn format_pipeline_step(step: &pforge_config::PipelineStep) -> Result<String> {
+    // Same "serialize then parse back at runtime" trick as the `Native`
+    // params above, so arbitrarily nested step input survives as source
+    // text without hand-escaping quotes inside quotes.
+    let input = match &step.input {
+        Some(value) => {
+            let json = serde_json::to_string(value).map_err(|e| {
+                CodegenError::GenerationError(format!(
+                    "failed to serialize input for pipeline step '{}': {}",
+                    step.tool, e
+                ))
+            })?;
+            format!("Some(serde_json::from_str(r#\"{}\"#).unwrap())", json)
+        }
+        None => "None".to_string(),
+    };
+    let output_var = match &step.output_var {
+        Some(var) => format!("Some(\"{}\".to_string())", var),
+        None => "None".to_string(),
+    };
+    let condition = match &step.condition {
+        Some(cond) => format!("Some(\"{}\".to_string())", cond),
+        None => "None".to_string(),
+    };
+    let error_policy = match step.error_policy {
+        pforge_config::ErrorPolicy::FailFast => "ErrorPolicy::FailFast",
+        pforge_config::ErrorPolicy::Continue => "ErrorPolicy::Continue",
+    };
+
+    Ok(format!(
+        "                PipelineStep {{ tool: \"{}\".to_string(), input: {}, output_var: {}, condition: {}, error_policy: {} }},\n",
+        step.tool, input, output_var, condition, error_policy
+    ))
+}
+
+fn format_auth_config(auth: &Option<pforge_config::AuthConfig>) -> String {
+    match auth {
+        None => "None".to_string(),
+        Some(pforge_config::AuthConfig::Bearer { token }) => {
+            format!("Some(AuthConfig::Bearer {{ token: \"{}\".to_string() }})", token)
+        }
+        Some(pforge_config::AuthConfig::Basic { username, password }) => format!(
+            "Some(AuthConfig::Basic {{ username: \"{}\".to_string(), password: \"{}\".to_string() }})",
+            username, password
+        ),
+        Some(pforge_config::AuthConfig::ApiKey { key, header }) => format!(
+            "Some(AuthConfig::ApiKey {{ key: \"{}\".to_string(), header: \"{}\".to_string() }})",
+            key, header
+        ),
+    }
+}
+
 fn to_pascal_case(s: &str) -> String {
     s.split('_')
         .map(|word| {
@@ -158,6 +258,27 @@ fn format_string_vec(vec: &[String]) -> String {
         .join(", ")
 }
 
+/// Render a `HashMap<String, String>` (CLI `env`, HTTP `headers`) as a
+/// `HashMap::from([...])` literal. Entries are sorted by key so the same
+/// config always generates byte-identical source, regardless of the
+/// input map's iteration order.
+fn format_string_map(map: &std::collections::HashMap<String, String>) -> String {
+    if map.is_empty() {
+        return "HashMap::new()".to_string();
+    }
+
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let rendered = entries
+        .iter()
+        .map(|(k, v)| format!("(\"{}\".to_string(), \"{}\".to_string())", k, v))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("HashMap::from([{}])", rendered)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,6 +344,7 @@ mod tests {
                 description: Some("An optional field".to_string()),
                 default: None,
                 validation: None,
+                coerce: None,
             },
         );
 
@@ -242,7 +364,11 @@ mod tests {
                 name: "test".to_string(),
                 version: "1.0.0".to_string(),
                 transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
                 optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
             },
             tools: vec![ToolDef::Native {
                 name: "test_tool".to_string(),
@@ -258,13 +384,15 @@ mod tests {
             }],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         };
 
         let result = generate_handler_registration(&config);
         assert!(result.is_ok());
         let code = result.unwrap();
-        assert!(code.contains("pub fn register_handlers"));
+        assert!(code.contains("pub async fn register_handlers"));
         assert!(code.contains("registry.register(\"test_tool\", handlers::test_handler)"));
     }
 
@@ -275,7 +403,11 @@ mod tests {
                 name: "test".to_string(),
                 version: "1.0.0".to_string(),
                 transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
                 optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
             },
             tools: vec![ToolDef::Cli {
                 name: "cli_tool".to_string(),
@@ -288,7 +420,9 @@ mod tests {
             }],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         };
 
         let result = generate_handler_registration(&config);
@@ -299,6 +433,52 @@ mod tests {
         assert!(code.contains("\"hello\""));
     }
 
+    #[test]
+    fn test_generate_handler_registration_cli_renders_env() {
+        let mut env = HashMap::new();
+        env.insert("RUST_LOG".to_string(), "debug".to_string());
+        env.insert("API_KEY".to_string(), "secret".to_string());
+
+        let config = ForgeConfig {
+            forge: ForgeMetadata {
+                name: "test".to_string(),
+                version: "1.0.0".to_string(),
+                transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
+                optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
+            },
+            tools: vec![ToolDef::Cli {
+                name: "cli_tool".to_string(),
+                description: "CLI Test".to_string(),
+                command: "echo".to_string(),
+                args: vec![],
+                cwd: None,
+                env,
+                stream: false,
+            }],
+            resources: vec![],
+            prompts: vec![],
+            aliases: HashMap::new(),
+            state: None,
+            auth: None,
+        };
+
+        let result = generate_handler_registration(&config);
+        assert!(result.is_ok());
+        let code = result.unwrap();
+        assert!(code.contains("HashMap::from(["));
+        assert!(code.contains("(\"API_KEY\".to_string(), \"secret\".to_string())"));
+        assert!(code.contains("(\"RUST_LOG\".to_string(), \"debug\".to_string())"));
+        // Sorted by key regardless of insertion order, for stable output.
+        assert!(
+            code.find("API_KEY").unwrap() < code.find("RUST_LOG").unwrap(),
+            "env entries should be sorted by key"
+        );
+    }
+
     #[test]
     fn test_generate_handler_registration_http() {
         let config = ForgeConfig {
@@ -306,7 +486,11 @@ mod tests {
                 name: "test".to_string(),
                 version: "1.0.0".to_string(),
                 transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
                 optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
             },
             tools: vec![ToolDef::Http {
                 name: "http_tool".to_string(),
@@ -318,7 +502,9 @@ mod tests {
             }],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         };
 
         let result = generate_handler_registration(&config);
@@ -328,4 +514,99 @@ mod tests {
         assert!(code.contains("https://api.example.com"));
         assert!(code.contains("HttpMethod::Get"));
     }
+
+    #[test]
+    fn test_generate_handler_registration_http_renders_headers_and_auth() {
+        let mut headers = HashMap::new();
+        headers.insert("Accept".to_string(), "application/json".to_string());
+
+        let config = ForgeConfig {
+            forge: ForgeMetadata {
+                name: "test".to_string(),
+                version: "1.0.0".to_string(),
+                transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
+                optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
+            },
+            tools: vec![ToolDef::Http {
+                name: "http_tool".to_string(),
+                description: "HTTP Test".to_string(),
+                endpoint: "https://api.example.com".to_string(),
+                method: HttpMethod::Post,
+                headers,
+                auth: Some(AuthConfig::Bearer {
+                    token: "s3cr3t".to_string(),
+                }),
+            }],
+            resources: vec![],
+            prompts: vec![],
+            aliases: HashMap::new(),
+            state: None,
+            auth: None,
+        };
+
+        let result = generate_handler_registration(&config);
+        assert!(result.is_ok());
+        let code = result.unwrap();
+        assert!(code.contains(
+            "(\"Accept\".to_string(), \"application/json\".to_string())"
+        ));
+        assert!(code.contains("Some(AuthConfig::Bearer { token: \"s3cr3t\".to_string() })"));
+    }
+
+    #[test]
+    fn test_generate_handler_registration_pipeline() {
+        let config = ForgeConfig {
+            forge: ForgeMetadata {
+                name: "test".to_string(),
+                version: "1.0.0".to_string(),
+                transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
+                optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
+            },
+            tools: vec![ToolDef::Pipeline {
+                name: "pipeline_tool".to_string(),
+                description: "Pipeline Test".to_string(),
+                steps: vec![
+                    PipelineStep {
+                        tool: "fetch".to_string(),
+                        input: Some(serde_json::json!({"id": 1})),
+                        output_var: Some("fetched".to_string()),
+                        condition: None,
+                        error_policy: ErrorPolicy::FailFast,
+                    },
+                    PipelineStep {
+                        tool: "greet".to_string(),
+                        input: Some(serde_json::json!("{{fetched.name}}")),
+                        output_var: None,
+                        condition: Some("fetched.ok".to_string()),
+                        error_policy: ErrorPolicy::Continue,
+                    },
+                ],
+            }],
+            resources: vec![],
+            prompts: vec![],
+            aliases: HashMap::new(),
+            state: None,
+            auth: None,
+        };
+
+        let result = generate_handler_registration(&config);
+        assert!(result.is_ok());
+        let code = result.unwrap();
+        assert!(code.contains("pub async fn register_handlers(registry_handle:"));
+        assert!(code.contains("PipelineHandler::new(\n            registry_handle.clone(),"));
+        assert!(code.contains("tool: \"fetch\".to_string()"));
+        assert!(code.contains("tool: \"greet\".to_string()"));
+        assert!(code.contains("output_var: Some(\"fetched\".to_string())"));
+        assert!(code.contains("condition: Some(\"fetched.ok\".to_string())"));
+        assert!(code.contains("error_policy: ErrorPolicy::FailFast"));
+        assert!(code.contains("error_policy: ErrorPolicy::Continue"));
+    }
 }
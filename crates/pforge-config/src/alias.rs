@@ -0,0 +1,212 @@
+use crate::{ConfigError, ForgeConfig, PipelineStep, Result, ToolDef};
+use std::collections::HashMap;
+
+/// Expanding an alias that (transitively) refers to itself more than this
+/// many levels deep is almost certainly a mistake rather than a deliberately
+/// deep alias chain, so it's rejected rather than recursed into forever.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+/// Resolve `config.aliases` into ordinary [`ToolDef::Pipeline`] tools
+/// appended to `config.tools`, so that from every other point in the
+/// codebase (validation, registration, dispatch) an alias is
+/// indistinguishable from a hand-written pipeline. A step whose `tool` names
+/// another alias is inlined with that alias's own (recursively expanded)
+/// steps; `a -> b -> a` cycles and chains deeper than [`MAX_ALIAS_DEPTH`] are
+/// rejected instead of recursing forever.
+pub fn expand_aliases(config: &mut ForgeConfig) -> Result<()> {
+    if config.aliases.is_empty() {
+        return Ok(());
+    }
+
+    let mut names: Vec<String> = config.aliases.keys().cloned().collect();
+    names.sort_unstable();
+
+    let mut expanded: HashMap<String, Vec<PipelineStep>> = HashMap::new();
+    for name in &names {
+        if expanded.contains_key(name) {
+            continue;
+        }
+        let mut stack = Vec::new();
+        let steps = expand_alias(name, config, &mut stack, &mut expanded)?;
+        expanded.insert(name.clone(), steps);
+    }
+
+    for name in names {
+        let steps = expanded.remove(&name).expect("expanded above");
+        config.tools.push(ToolDef::Pipeline {
+            name: name.clone(),
+            description: format!("Alias for: {}", name),
+            steps,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the fully expanded step list for alias `name`, memoizing
+/// completed expansions into `expanded` and tracking the current expansion
+/// path in `stack` so a reference back to an alias already on the path is
+/// reported as a cycle instead of overflowing the stack.
+fn expand_alias(
+    name: &str,
+    config: &ForgeConfig,
+    stack: &mut Vec<String>,
+    expanded: &mut HashMap<String, Vec<PipelineStep>>,
+) -> Result<Vec<PipelineStep>> {
+    if let Some(steps) = expanded.get(name) {
+        return Ok(steps.clone());
+    }
+
+    if let Some(pos) = stack.iter().position(|n| n == name) {
+        let cycle: Vec<String> = stack[pos..]
+            .iter()
+            .cloned()
+            .chain(std::iter::once(name.to_string()))
+            .collect();
+        return Err(ConfigError::ValidationError(format!(
+            "cycle detected in alias expansion: {}",
+            cycle.join(" -> ")
+        )));
+    }
+
+    if stack.len() >= MAX_ALIAS_DEPTH {
+        return Err(ConfigError::ValidationError(format!(
+            "alias expansion exceeded max depth of {} while expanding: {}",
+            MAX_ALIAS_DEPTH, name
+        )));
+    }
+
+    let steps = config
+        .aliases
+        .get(name)
+        .ok_or_else(|| ConfigError::ValidationError(format!("unknown alias: {}", name)))?;
+
+    stack.push(name.to_string());
+    let mut result = Vec::with_capacity(steps.len());
+    for step in steps {
+        if config.aliases.contains_key(step.tool.as_str()) {
+            result.extend(expand_alias(&step.tool, config, stack, expanded)?);
+        } else {
+            result.push(step.clone());
+        }
+    }
+    stack.pop();
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorPolicy, ForgeMetadata, OptimizationLevel, TransportTuning, TransportType};
+
+    fn base_config() -> ForgeConfig {
+        ForgeConfig {
+            forge: ForgeMetadata {
+                name: "test".to_string(),
+                version: "1.0.0".to_string(),
+                transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
+                optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
+            },
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+            aliases: HashMap::new(),
+            state: None,
+            auth: None,
+        }
+    }
+
+    fn step(tool: &str) -> PipelineStep {
+        PipelineStep {
+            tool: tool.to_string(),
+            input: None,
+            output_var: None,
+            condition: None,
+            error_policy: ErrorPolicy::default(),
+        }
+    }
+
+    #[test]
+    fn test_expand_aliases_noop_when_no_aliases_declared() {
+        let mut config = base_config();
+        expand_aliases(&mut config).unwrap();
+        assert!(config.tools.is_empty());
+    }
+
+    #[test]
+    fn test_expand_simple_alias_into_pipeline_tool() {
+        let mut config = base_config();
+        config
+            .aliases
+            .insert("build_and_test".to_string(), vec![step("build"), step("test")]);
+
+        expand_aliases(&mut config).unwrap();
+
+        assert_eq!(config.tools.len(), 1);
+        match &config.tools[0] {
+            ToolDef::Pipeline { name, steps, .. } => {
+                assert_eq!(name, "build_and_test");
+                assert_eq!(steps.len(), 2);
+                assert_eq!(steps[0].tool, "build");
+                assert_eq!(steps[1].tool, "test");
+            }
+            other => panic!("expected ToolDef::Pipeline, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expand_nested_alias_of_alias() {
+        let mut config = base_config();
+        config.aliases.insert("ci".to_string(), vec![step("build_and_test")]);
+        config.aliases.insert(
+            "build_and_test".to_string(),
+            vec![step("build"), step("test")],
+        );
+
+        expand_aliases(&mut config).unwrap();
+
+        let ci = config
+            .tools
+            .iter()
+            .find(|t| t.name() == "ci")
+            .expect("ci alias should be present");
+        match ci {
+            ToolDef::Pipeline { steps, .. } => {
+                let tool_names: Vec<&str> = steps.iter().map(|s| s.tool.as_str()).collect();
+                assert_eq!(tool_names, vec!["build", "test"]);
+            }
+            other => panic!("expected ToolDef::Pipeline, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expand_aliases_rejects_direct_cycle() {
+        let mut config = base_config();
+        config.aliases.insert("a".to_string(), vec![step("b")]);
+        config.aliases.insert("b".to_string(), vec![step("a")]);
+
+        let err = expand_aliases(&mut config).unwrap_err();
+        match err {
+            ConfigError::ValidationError(msg) => {
+                assert!(msg.contains("cycle"));
+                assert!(msg.contains("a"));
+                assert!(msg.contains("b"));
+            }
+            other => panic!("expected ValidationError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expand_aliases_rejects_self_reference() {
+        let mut config = base_config();
+        config.aliases.insert("a".to_string(), vec![step("a")]);
+
+        let err = expand_aliases(&mut config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationError(_)));
+    }
+}
@@ -0,0 +1,243 @@
+//! Corpus-driven conformance harness for [`validate_config`].
+//!
+//! The proptests in `pforge-integration-tests` generate random configs, which
+//! is great for broad coverage but useless for pinning a specific tricky
+//! case as a regression. This module lets a curated suite of known-good and
+//! known-bad configs live as plain files on disk: each *vector* bundles a
+//! `config` (the raw YAML/JSON config text), an `expected` outcome, and a
+//! human `desc`, and [`run_corpus`] asserts `validate_config` agrees with
+//! every one of them.
+//!
+//! Vector documents may themselves be written as YAML or JSON - [`parse_vector`]
+//! accepts either, so the same corpus can be checked in next to fixtures
+//! authored by tools that only emit one or the other.
+
+use crate::{validate_config_with_source, ConfigError};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A single conformance test vector.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Vector {
+    /// Human-readable description of what this vector pins down.
+    pub desc: String,
+    /// The raw config text to parse and validate (YAML or JSON).
+    pub config: String,
+    /// The outcome `validate_config` must produce for `config`.
+    pub expected: Expected,
+}
+
+/// The outcome a [`Vector`] expects from validating its `config`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expected {
+    Ok,
+    Error(ErrorKind),
+}
+
+/// The coarse category a [`ConfigError`] falls into, for comparison against
+/// a vector's [`Expected::Error`] without pinning exact messages or spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    ParseError,
+    DuplicateToolName,
+    InvalidHandlerPath,
+    UnknownToolReference,
+    ValidationError,
+    InterpolationError,
+    /// More than one violation was found in a single pass; see
+    /// [`ConfigError::MultipleErrors`].
+    MultipleErrors,
+}
+
+fn classify(err: &ConfigError) -> ErrorKind {
+    match err {
+        ConfigError::IoError(..) => ErrorKind::ParseError,
+        ConfigError::ParseError { .. } => ErrorKind::ParseError,
+        ConfigError::DuplicateToolName { .. } => ErrorKind::DuplicateToolName,
+        ConfigError::InvalidHandlerPath { .. } => ErrorKind::InvalidHandlerPath,
+        ConfigError::UnknownToolReference { .. } => ErrorKind::UnknownToolReference,
+        ConfigError::ValidationError(_) => ErrorKind::ValidationError,
+        ConfigError::InterpolationError(_) => ErrorKind::InterpolationError,
+        ConfigError::MultipleErrors(_) => ErrorKind::MultipleErrors,
+    }
+}
+
+/// Parse a vector document, trying JSON first and falling back to YAML.
+pub fn parse_vector(source: &str) -> Result<Vector, ConfigError> {
+    if let Ok(vector) = serde_json::from_str::<Vector>(source) {
+        return Ok(vector);
+    }
+    Ok(serde_yaml::from_str(source)?)
+}
+
+/// A vector whose actual outcome didn't match its [`Expected`] one.
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub path: PathBuf,
+    pub desc: String,
+    pub message: String,
+}
+
+/// Result of running every vector in a corpus directory.
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub total: usize,
+    pub failures: Vec<Failure>,
+}
+
+impl RunReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Check a single vector's `config` against its `expected` outcome.
+fn check_vector(vector: &Vector) -> Result<(), String> {
+    let actual = match crate::parse_config_from_str(&vector.config) {
+        Ok(config) => validate_config_with_source(&config, &vector.config)
+            .err()
+            .map(|e| classify(&e)),
+        Err(_) => Some(ErrorKind::ParseError),
+    };
+
+    match (&vector.expected, actual) {
+        (Expected::Ok, None) => Ok(()),
+        (Expected::Ok, Some(kind)) => Err(format!("expected ok, got error {:?}", kind)),
+        (Expected::Error(expected), Some(actual)) if *expected == actual => Ok(()),
+        (Expected::Error(expected), Some(actual)) => {
+            Err(format!("expected error {:?}, got error {:?}", expected, actual))
+        }
+        (Expected::Error(expected), None) => Err(format!("expected error {:?}, got ok", expected)),
+    }
+}
+
+/// Load and check every vector file in `dir` (non-recursively), reporting
+/// which vector/desc failed rather than stopping at the first mismatch.
+pub fn run_corpus(dir: &Path) -> Result<RunReport, ConfigError> {
+    let mut total = 0;
+    let mut failures = Vec::new();
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| ConfigError::IoError(dir.to_path_buf(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let text =
+            std::fs::read_to_string(&path).map_err(|e| ConfigError::IoError(path.clone(), e))?;
+        let vector = parse_vector(&text)?;
+        total += 1;
+
+        if let Err(message) = check_vector(&vector) {
+            failures.push(Failure {
+                path: path.clone(),
+                desc: vector.desc.clone(),
+                message,
+            });
+        }
+    }
+
+    Ok(RunReport { total, failures })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vector_yaml() {
+        let source = "desc: minimal valid config\nconfig: \"forge:\\n  name: t\\n  version: 1.0.0\\ntools: []\\n\"\nexpected: ok\n";
+        let vector = parse_vector(source).unwrap();
+        assert_eq!(vector.desc, "minimal valid config");
+        assert_eq!(vector.expected, Expected::Ok);
+    }
+
+    #[test]
+    fn test_parse_vector_json() {
+        let source = r#"{"desc": "minimal valid config", "config": "forge:\n  name: t\n  version: 1.0.0\ntools: []\n", "expected": "ok"}"#;
+        let vector = parse_vector(source).unwrap();
+        assert_eq!(vector.desc, "minimal valid config");
+        assert_eq!(vector.expected, Expected::Ok);
+    }
+
+    #[test]
+    fn test_parse_vector_error_kind() {
+        let source = "desc: dup\nconfig: \"x\"\nexpected:\n  error: duplicate_tool_name\n";
+        let vector = parse_vector(source).unwrap();
+        assert_eq!(vector.expected, Expected::Error(ErrorKind::DuplicateToolName));
+    }
+
+    #[test]
+    fn test_check_vector_matches_ok() {
+        let vector = Vector {
+            desc: "empty tools".to_string(),
+            config: "forge:\n  name: t\n  version: 1.0.0\ntools: []\n".to_string(),
+            expected: Expected::Ok,
+        };
+        assert!(check_vector(&vector).is_ok());
+    }
+
+    #[test]
+    fn test_check_vector_reports_mismatch() {
+        let vector = Vector {
+            desc: "wrong expectation".to_string(),
+            config: "forge:\n  name: t\n  version: 1.0.0\ntools: []\n".to_string(),
+            expected: Expected::Error(ErrorKind::DuplicateToolName),
+        };
+        let err = check_vector(&vector).unwrap_err();
+        assert!(err.contains("expected error"));
+    }
+
+    #[test]
+    fn test_check_vector_matches_duplicate_tool_name() {
+        let source = r#"
+forge:
+  name: t
+  version: 1.0.0
+tools:
+  - type: cli
+    name: dup
+    description: First
+    command: echo
+    args: []
+  - type: cli
+    name: dup
+    description: Second
+    command: echo
+    args: []
+"#;
+        let vector = Vector {
+            desc: "duplicate tool names rejected".to_string(),
+            config: source.to_string(),
+            expected: Expected::Error(ErrorKind::DuplicateToolName),
+        };
+        assert!(check_vector(&vector).is_ok());
+    }
+
+    #[test]
+    fn test_run_corpus_reports_failure_desc() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+
+        std::fs::write(
+            dir.join("ok.yaml"),
+            "desc: passes\nconfig: \"forge:\\n  name: t\\n  version: 1.0.0\\ntools: []\\n\"\nexpected: ok\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("broken.yaml"),
+            "desc: wrongly expects an error\nconfig: \"forge:\\n  name: t\\n  version: 1.0.0\\ntools: []\\n\"\nexpected:\n  error: duplicate_tool_name\n",
+        )
+        .unwrap();
+
+        let report = run_corpus(dir).unwrap();
+
+        assert_eq!(report.total, 2);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].desc, "wrongly expects an error");
+    }
+}
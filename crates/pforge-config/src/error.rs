@@ -1,3 +1,4 @@
+use crate::span::{render_diagnostic, Span};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -6,17 +7,100 @@ pub enum ConfigError {
     #[error("IO error reading {0}: {1}")]
     IoError(PathBuf, #[source] std::io::Error),
 
-    #[error("Parse error: {0}")]
-    ParseError(String),
+    /// `span` is populated when the underlying `serde_yaml::Error` carries a
+    /// `Location` (true for essentially every parse failure), letting
+    /// [`ConfigError::to_diagnostic`] point a caret at the exact offending
+    /// line/column instead of just printing serde's message.
+    #[error("Parse error: {message}")]
+    ParseError {
+        message: String,
+        span: Option<Span>,
+    },
 
-    #[error("Duplicate tool name: {0}")]
-    DuplicateToolName(String),
+    /// `span` is populated by the [`crate::validator::validate_config_with_source`]
+    /// path, which has the original source text to re-scan; the plain
+    /// [`crate::validator::validate_config`] entry point leaves it `None`.
+    #[error("Duplicate tool name: {name}")]
+    DuplicateToolName { name: String, span: Option<Span> },
 
-    #[error("Invalid handler path: {0}")]
-    InvalidHandlerPath(String),
+    #[error("Invalid handler path: {message}")]
+    InvalidHandlerPath {
+        message: String,
+        span: Option<Span>,
+    },
+
+    #[error("Unknown tool reference: {name}")]
+    UnknownToolReference { name: String, span: Option<Span> },
 
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    #[error("Interpolation error: {0}")]
+    InterpolationError(String),
+
+    /// Raised by [`crate::validator::validate_config`] instead of a single
+    /// variant above when a config has more than one problem, so every
+    /// violation is visible in one pass rather than just the first.
+    #[error("{} configuration problems found", .0.len())]
+    MultipleErrors(Vec<ConfigError>),
+}
+
+impl ConfigError {
+    /// Render as a compiler-diagnostic (message, `<config>:line:col`, and the
+    /// trimmed source line with a caret) when a span was recorded, falling
+    /// back to the plain [`std::fmt::Display`] message otherwise.
+    pub fn to_diagnostic(&self, source: &str) -> String {
+        let span = match self {
+            ConfigError::DuplicateToolName { span, .. }
+            | ConfigError::InvalidHandlerPath { span, .. }
+            | ConfigError::UnknownToolReference { span, .. }
+            | ConfigError::ParseError { span, .. } => span.as_ref(),
+            _ => None,
+        };
+
+        match span {
+            Some(span) => render_diagnostic(&self.to_string(), span, source),
+            None => self.to_string(),
+        }
+    }
+}
+
+/// `serde_yaml`'s errors carry a [`serde_yaml::Location`] for essentially
+/// every parse failure, so converting through here (rather than the
+/// `.map_err(|e| ...)` every other variant needs) gets a [`Span`] - and so a
+/// rendered caret via [`ConfigError::to_diagnostic`] - for free at every
+/// `serde_yaml::from_str`/`from_value` call site.
+impl From<serde_yaml::Error> for ConfigError {
+    fn from(e: serde_yaml::Error) -> Self {
+        let span = e.location().map(|location| Span {
+            line: location.line(),
+            column: location.column(),
+            field: "yaml".to_string(),
+        });
+
+        ConfigError::ParseError {
+            message: e.to_string(),
+            span,
+        }
+    }
+}
+
+/// `serde_json::Error` reports a 1-indexed line/column directly (no
+/// `Location` wrapper), so this mirrors the `serde_yaml::Error` conversion
+/// above for JSON config sources.
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        let span = Some(Span {
+            line: e.line(),
+            column: e.column(),
+            field: "json".to_string(),
+        });
+
+        ConfigError::ParseError {
+            message: e.to_string(),
+            span,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, ConfigError>;
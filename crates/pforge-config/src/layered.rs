@@ -0,0 +1,671 @@
+//! Layered config loading: an ordered list of YAML documents deep-merged
+//! together (later sources winning), an optional `environments.<name>`
+//! overlay on top of that, programmatic dotted-path overrides, and finally
+//! `${VAR}` / `${VAR:-default}` interpolation against the process
+//! environment.
+//!
+//! This lets one `pforge.yaml` (plus optional overlay files) serve
+//! dev/staging/prod - varying, e.g., an HTTP tool's `endpoint` or a
+//! `StateDef`'s `path` - while keeping secrets like an
+//! `AuthConfig::Bearer { token }` out of the checked-in file entirely.
+
+use crate::{ConfigError, ForgeConfig, Result};
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+
+const ENVIRONMENTS_KEY: &str = "environments";
+
+/// An on-disk config source format, detected from a path's extension so
+/// [`ForgeConfig::load_layered_from_sources`] (and [`ConfigBuilder`]) can
+/// mix YAML, JSON, and TOML files in the same layered load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl SourceFormat {
+    /// Detect from `path`'s extension: `.yaml`/`.yml`, `.json`, or `.toml`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => Ok(SourceFormat::Yaml),
+            Some("json") => Ok(SourceFormat::Json),
+            Some("toml") => Ok(SourceFormat::Toml),
+            other => Err(ConfigError::ValidationError(format!(
+                "unrecognized config file extension {:?} (expected .yaml, .yml, .json, or .toml)",
+                other.unwrap_or("")
+            ))),
+        }
+    }
+}
+
+/// Parse `content` as `format` into the `serde_yaml::Value` document every
+/// other function in this module merges/overrides/interpolates - JSON and
+/// TOML documents are deserialized into their own value type first, then
+/// re-serialized into `serde_yaml::Value` so format never leaks past this
+/// one function.
+fn parse_source(format: SourceFormat, content: &str) -> Result<Value> {
+    match format {
+        SourceFormat::Yaml => Ok(serde_yaml::from_str(content)?),
+        SourceFormat::Json => {
+            let json: serde_json::Value = serde_json::from_str(content)?;
+            to_yaml_value(json)
+        }
+        SourceFormat::Toml => {
+            let toml: toml::Value = toml::from_str(content).map_err(|e| ConfigError::ParseError {
+                message: e.to_string(),
+                span: None,
+            })?;
+            to_yaml_value(toml)
+        }
+    }
+}
+
+fn to_yaml_value<T: serde::Serialize>(value: T) -> Result<Value> {
+    serde_yaml::to_value(value).map_err(|e| ConfigError::ParseError {
+        message: e.to_string(),
+        span: None,
+    })
+}
+
+/// Fluent builder over [`ForgeConfig::load_layered_from_sources`] for
+/// assembling dev/staging/prod configuration without editing the checked-in
+/// config file: add sources in precedence order, optionally read
+/// environment-variable overrides under a prefix, and layer on explicit
+/// overrides - all without touching the files on disk.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    sources: Vec<PathBuf>,
+    environment: Option<String>,
+    env_prefix: Option<String>,
+    overrides: Vec<String>,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a config file source, in precedence order - later sources are
+    /// deep-merged over earlier ones. Format (YAML/JSON/TOML) is detected
+    /// from each path's extension.
+    pub fn add_source(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sources.push(path.into());
+        self
+    }
+
+    /// Apply the `environments.<name>` overlay found in the merged sources.
+    pub fn environment(mut self, name: impl Into<String>) -> Self {
+        self.environment = Some(name.into());
+        self
+    }
+
+    /// Read overrides from environment variables named `<PREFIX>_...`,
+    /// translating `__` into the dotted-path separator and lowercasing -
+    /// e.g. with `.env_prefix("PFORGE")`, `PFORGE_FORGE__TRANSPORT=sse`
+    /// overrides `forge.transport` exactly as
+    /// `.set_override("forge.transport", "sse")` would. Applied before any
+    /// `set_override` calls, so an explicit override always wins over an
+    /// environment variable naming the same path.
+    pub fn env_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Set a `path.to.field` override directly, taking precedence over
+    /// every file source, the environment overlay, and `env_prefix`
+    /// variables.
+    pub fn set_override(mut self, path: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push(format!("{}={}", path.into(), value.into()));
+        self
+    }
+
+    /// Load and merge every configured source, applying the environment
+    /// overlay, then `env_prefix` variables, then explicit `set_override`s,
+    /// in that precedence order.
+    pub fn load(self) -> Result<ForgeConfig> {
+        if self.sources.is_empty() {
+            return Err(ConfigError::ValidationError(
+                "ConfigBuilder requires at least one add_source".to_string(),
+            ));
+        }
+
+        let mut overrides = Vec::new();
+        if let Some(prefix) = &self.env_prefix {
+            overrides.extend(env_var_overrides(prefix));
+        }
+        overrides.extend(self.overrides);
+
+        let override_refs: Vec<&str> = overrides.iter().map(String::as_str).collect();
+        ForgeConfig::load_layered_from_sources(
+            &self.sources,
+            self.environment.as_deref(),
+            &override_refs,
+        )
+    }
+}
+
+/// Collect `<PREFIX>_...` environment variables into `path=value` override
+/// strings, translating `__` into `.` and lowercasing each path segment.
+/// Sorted by path so the result (and thus which override wins when two env
+/// vars somehow name overlapping paths) is deterministic regardless of the
+/// process environment's iteration order.
+fn env_var_overrides(prefix: &str) -> Vec<String> {
+    let needle = format!("{}_", prefix);
+
+    let mut pairs: Vec<(String, String)> = std::env::vars()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(&needle).map(|rest| {
+                let path = rest
+                    .split("__")
+                    .map(str::to_lowercase)
+                    .collect::<Vec<_>>()
+                    .join(".");
+                (path, value)
+            })
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    pairs
+        .into_iter()
+        .map(|(path, value)| format!("{}={}", path, value))
+        .collect()
+}
+
+impl ForgeConfig {
+    /// Load `path`, merging the `environments.<env>` overlay (if `env` is
+    /// given) over the base document and interpolating `${VAR}` references
+    /// against `std::env`, before the usual `deny_unknown_fields`
+    /// deserialization.
+    pub fn load_layered(path: &Path, env: Option<&str>) -> Result<ForgeConfig> {
+        Self::load_layered_from_sources(std::slice::from_ref(&path.to_path_buf()), env, &[])
+    }
+
+    /// Same as [`ForgeConfig::load_layered`], but from an in-memory YAML string.
+    pub fn load_layered_from_str(yaml: &str, env: Option<&str>) -> Result<ForgeConfig> {
+        Self::load_layered_from_strs(&[yaml], env, &[])
+    }
+
+    /// Load and deep-merge an ordered list of config files - YAML, JSON, or
+    /// TOML, detected per-file from its extension (each mapping merged
+    /// recursively over the previous one, later files winning; sequences
+    /// are replaced wholesale rather than concatenated) - then apply the
+    /// `environments.<env>` overlay, `path.to.field=value` overrides, and
+    /// `${VAR}` interpolation exactly as [`ForgeConfig::load_layered`] does
+    /// for a single file.
+    pub fn load_layered_from_sources(
+        paths: &[PathBuf],
+        env: Option<&str>,
+        overrides: &[&str],
+    ) -> Result<ForgeConfig> {
+        let mut values = Vec::with_capacity(paths.len());
+        for path in paths {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| ConfigError::IoError(path.clone(), e))?;
+            let format = SourceFormat::from_path(path)?;
+            values.push(parse_source(format, &content)?);
+        }
+        Self::load_layered_values(values, env, overrides)
+    }
+
+    /// Same as [`ForgeConfig::load_layered_from_sources`], but from
+    /// in-memory YAML strings (base first, overlays after).
+    pub fn load_layered_from_strs(
+        sources: &[&str],
+        env: Option<&str>,
+        overrides: &[&str],
+    ) -> Result<ForgeConfig> {
+        let values: Vec<Value> = sources
+            .iter()
+            .map(|source| Ok(serde_yaml::from_str(source)?))
+            .collect::<Result<_>>()?;
+        Self::load_layered_values(values, env, overrides)
+    }
+
+    /// Shared core of [`ForgeConfig::load_layered_from_sources`] and
+    /// [`ForgeConfig::load_layered_from_strs`]: deep-merge already-parsed
+    /// documents, then apply the environment overlay, overrides, and
+    /// interpolation.
+    fn load_layered_values(
+        values: Vec<Value>,
+        env: Option<&str>,
+        overrides: &[&str],
+    ) -> Result<ForgeConfig> {
+        let Some((base, overlays)) = values.split_first() else {
+            return Err(ConfigError::ValidationError(
+                "at least one config source is required".to_string(),
+            ));
+        };
+
+        let mut root = base.clone();
+        for overlay in overlays {
+            merge_overlay(&mut root, overlay.clone());
+        }
+
+        let environments = match &mut root {
+            Value::Mapping(map) => map.remove(Value::String(ENVIRONMENTS_KEY.to_string())),
+            _ => None,
+        };
+
+        if let Some(env_name) = env {
+            let overlay = match environments {
+                Some(Value::Mapping(environments)) => environments
+                    .get(Value::String(env_name.to_string()))
+                    .cloned()
+                    .ok_or_else(|| {
+                        ConfigError::ValidationError(format!("unknown environment '{}'", env_name))
+                    })?,
+                _ => {
+                    return Err(ConfigError::ValidationError(format!(
+                        "no '{}' section defined in config",
+                        ENVIRONMENTS_KEY
+                    )))
+                }
+            };
+            merge_overlay(&mut root, overlay);
+        }
+
+        for assignment in overrides {
+            apply_override(&mut root, assignment)?;
+        }
+
+        interpolate_value(&mut root)?;
+
+        Ok(serde_yaml::from_value(root)?)
+    }
+}
+
+/// Deep-merge `overlay` over `base` in place: mappings merge key by key,
+/// anything else (scalars, sequences) is replaced wholesale by the overlay.
+fn merge_overlay(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base_map), Value::Mapping(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_overlay(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base_slot, overlay_value) => *base_slot = overlay_value,
+    }
+}
+
+/// Apply one `dotted.path=value` override (e.g. `forge.transport=sse`) to
+/// the raw YAML document before it's deserialized into [`ForgeConfig`].
+/// `value` is parsed as a YAML scalar, so `true`/`30000`/`sse` become
+/// bool/int/string respectively, matching how the same text would parse if
+/// written directly in the document.
+fn apply_override(root: &mut Value, assignment: &str) -> Result<()> {
+    let (path, value_str) = assignment.split_once('=').ok_or_else(|| {
+        ConfigError::ValidationError(format!(
+            "invalid override '{}': expected 'dotted.path=value'",
+            assignment
+        ))
+    })?;
+    if path.is_empty() {
+        return Err(ConfigError::ValidationError(format!(
+            "invalid override '{}': empty path",
+            assignment
+        )));
+    }
+
+    let value: Value =
+        serde_yaml::from_str(value_str).unwrap_or_else(|_| Value::String(value_str.to_string()));
+
+    let segments: Vec<&str> = path.split('.').collect();
+    set_path(root, &segments, value);
+    Ok(())
+}
+
+/// Set `value` at `segments` within `root`, creating intermediate mappings
+/// as needed and replacing any non-mapping value found along the way.
+fn set_path(root: &mut Value, segments: &[&str], value: Value) {
+    let Some((head, rest)) = segments.split_first() else {
+        return;
+    };
+
+    if !matches!(root, Value::Mapping(_)) {
+        *root = Value::Mapping(Default::default());
+    }
+    let Value::Mapping(map) = root else {
+        unreachable!("just normalized to a mapping");
+    };
+    let key = Value::String((*head).to_string());
+
+    if rest.is_empty() {
+        map.insert(key, value);
+        return;
+    }
+
+    if map.get(&key).is_none() {
+        map.insert(key.clone(), Value::Mapping(Default::default()));
+    }
+    let child = map.get_mut(&key).expect("just inserted");
+    set_path(child, rest, value);
+}
+
+/// Replace every `${VAR}` / `${VAR:-default}` reference found in any string
+/// in `value` (recursing into mappings and sequences) with the matching
+/// environment variable. `pub(crate)` so [`crate::parser::parse_config_from_str`]
+/// can interpolate plain (non-layered) configs with the same machinery
+/// instead of duplicating it.
+pub(crate) fn interpolate_value(value: &mut Value) -> Result<()> {
+    match value {
+        Value::String(s) => *s = interpolate_str(s)?,
+        Value::Mapping(map) => {
+            for (_, v) in map.iter_mut() {
+                interpolate_value(v)?;
+            }
+        }
+        Value::Sequence(seq) => {
+            for v in seq.iter_mut() {
+                interpolate_value(v)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replace every `${VAR}` / `${VAR:-default}` reference in `input` with the
+/// matching environment variable (or the default if it's unset).
+fn interpolate_str(input: &str) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| ConfigError::InterpolationError(format!("unterminated '${{' in '{}'", input)))?;
+        output.push_str(&resolve_var(&after[..end])?);
+        rest = &after[end + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+fn resolve_var(expr: &str) -> Result<String> {
+    if let Some((name, default)) = expr.split_once(":-") {
+        Ok(std::env::var(name).unwrap_or_else(|_| default.to_string()))
+    } else {
+        std::env::var(expr).map_err(|_| {
+            ConfigError::InterpolationError(format!("environment variable '{}' is not set", expr))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_yaml() -> &'static str {
+        r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+
+tools:
+  - type: http
+    name: api_call
+    description: "Call the API"
+    endpoint: "${API_URL:-https://default.example.com}"
+    method: "GET"
+
+environments:
+  production:
+    tools:
+      - type: http
+        name: api_call
+        description: "Call the API"
+        endpoint: "https://prod.example.com"
+        method: "GET"
+"#
+    }
+
+    #[test]
+    fn test_load_layered_without_env_uses_base_and_default() {
+        std::env::remove_var("API_URL_UNSET_TEST");
+        let config = ForgeConfig::load_layered_from_str(base_yaml(), None).unwrap();
+        let crate::ToolDef::Http { endpoint, .. } = &config.tools[0] else {
+            panic!("expected http tool");
+        };
+        assert_eq!(endpoint, "https://default.example.com");
+    }
+
+    #[test]
+    fn test_load_layered_applies_environment_overlay() {
+        let config = ForgeConfig::load_layered_from_str(base_yaml(), Some("production")).unwrap();
+        let crate::ToolDef::Http { endpoint, .. } = &config.tools[0] else {
+            panic!("expected http tool");
+        };
+        assert_eq!(endpoint, "https://prod.example.com");
+    }
+
+    #[test]
+    fn test_load_layered_unknown_environment_errors() {
+        let result = ForgeConfig::load_layered_from_str(base_yaml(), Some("nonexistent"));
+        assert!(matches!(result, Err(ConfigError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_interpolation_resolves_env_var() {
+        std::env::set_var("PFORGE_TEST_API_URL", "https://env.example.com");
+        let yaml = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+
+tools:
+  - type: http
+    name: api_call
+    description: "Call the API"
+    endpoint: "${PFORGE_TEST_API_URL}"
+    method: "GET"
+"#;
+        let config = ForgeConfig::load_layered_from_str(yaml, None).unwrap();
+        let crate::ToolDef::Http { endpoint, .. } = &config.tools[0] else {
+            panic!("expected http tool");
+        };
+        assert_eq!(endpoint, "https://env.example.com");
+        std::env::remove_var("PFORGE_TEST_API_URL");
+    }
+
+    #[test]
+    fn test_interpolation_missing_var_without_default_errors() {
+        std::env::remove_var("PFORGE_TEST_MISSING_VAR");
+        let yaml = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+
+tools:
+  - type: http
+    name: api_call
+    description: "Call the API"
+    endpoint: "${PFORGE_TEST_MISSING_VAR}"
+    method: "GET"
+"#;
+        let result = ForgeConfig::load_layered_from_str(yaml, None);
+        assert!(matches!(result, Err(ConfigError::InterpolationError(_))));
+    }
+
+    #[test]
+    fn test_load_layered_from_strs_deep_merges_multiple_sources() {
+        let base = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+  shutdown_timeout_ms: 30000
+
+tools: []
+"#;
+        let overlay = r#"
+forge:
+  version: 2.0.0
+"#;
+        let config = ForgeConfig::load_layered_from_strs(&[base, overlay], None, &[]).unwrap();
+        assert_eq!(config.forge.version, "2.0.0");
+        // Untouched by the overlay - merge should be recursive, not a wholesale replace.
+        assert_eq!(config.forge.name, "test-server");
+        assert_eq!(config.forge.shutdown_timeout_ms, 30_000);
+    }
+
+    #[test]
+    fn test_override_patches_dotted_path() {
+        let config =
+            ForgeConfig::load_layered_from_str(base_yaml(), None).unwrap();
+        assert_eq!(config.forge.transport, TransportType::Stdio);
+
+        let overridden =
+            ForgeConfig::load_layered_from_strs(&[base_yaml()], None, &["forge.transport=sse"])
+                .unwrap();
+        assert_eq!(overridden.forge.transport, TransportType::Sse);
+    }
+
+    #[test]
+    fn test_override_invalid_assignment_errors() {
+        let result =
+            ForgeConfig::load_layered_from_strs(&[base_yaml()], None, &["forge.transport"]);
+        assert!(matches!(result, Err(ConfigError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_override_creates_missing_path() {
+        let yaml = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+
+tools: []
+"#;
+        let config =
+            ForgeConfig::load_layered_from_strs(&[yaml], None, &["forge.slow_request_timeout_ms=5000"])
+                .unwrap();
+        assert_eq!(config.forge.slow_request_timeout_ms, Some(5000));
+    }
+
+    #[test]
+    fn test_source_format_detects_by_extension() {
+        assert_eq!(
+            SourceFormat::from_path(Path::new("pforge.yaml")).unwrap(),
+            SourceFormat::Yaml
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("pforge.yml")).unwrap(),
+            SourceFormat::Yaml
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("pforge.json")).unwrap(),
+            SourceFormat::Json
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("pforge.toml")).unwrap(),
+            SourceFormat::Toml
+        );
+        assert!(SourceFormat::from_path(Path::new("pforge.ini")).is_err());
+    }
+
+    #[test]
+    fn test_load_layered_from_sources_reads_json_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("pforge.json");
+        std::fs::write(
+            &path,
+            r#"{"forge": {"name": "json-server", "version": "0.1.0", "transport": "stdio"}, "tools": []}"#,
+        )
+        .unwrap();
+
+        let config =
+            ForgeConfig::load_layered_from_sources(std::slice::from_ref(&path), None, &[]).unwrap();
+        assert_eq!(config.forge.name, "json-server");
+    }
+
+    #[test]
+    fn test_load_layered_from_sources_reads_toml_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("pforge.toml");
+        std::fs::write(
+            &path,
+            "[forge]\nname = \"toml-server\"\nversion = \"0.1.0\"\ntransport = \"stdio\"\ntools = []\n",
+        )
+        .unwrap();
+
+        let config =
+            ForgeConfig::load_layered_from_sources(std::slice::from_ref(&path), None, &[]).unwrap();
+        assert_eq!(config.forge.name, "toml-server");
+    }
+
+    #[test]
+    fn test_load_layered_from_sources_mixes_yaml_and_json_overlay() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base_path = temp_dir.path().join("pforge.yaml");
+        std::fs::write(&base_path, base_yaml()).unwrap();
+        let overlay_path = temp_dir.path().join("pforge.prod.json");
+        std::fs::write(&overlay_path, r#"{"forge": {"version": "9.9.9"}}"#).unwrap();
+
+        let config = ForgeConfig::load_layered_from_sources(
+            &[base_path, overlay_path],
+            None,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(config.forge.version, "9.9.9");
+        assert_eq!(config.forge.name, "test-server");
+    }
+
+    #[test]
+    fn test_env_prefix_override_translates_double_underscore_path() {
+        std::env::set_var("PFORGETEST_FORGE__TRANSPORT", "sse");
+        let overrides = env_var_overrides("PFORGETEST");
+        std::env::remove_var("PFORGETEST_FORGE__TRANSPORT");
+
+        assert_eq!(overrides, vec!["forge.transport=sse".to_string()]);
+    }
+
+    #[test]
+    fn test_config_builder_loads_from_single_source() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("pforge.yaml");
+        std::fs::write(&path, base_yaml()).unwrap();
+
+        let config = ConfigBuilder::new().add_source(path.clone()).load().unwrap();
+        assert_eq!(config.forge.name, "test-server");
+    }
+
+    #[test]
+    fn test_config_builder_set_override_wins_over_env_prefix() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("pforge.yaml");
+        std::fs::write(&path, base_yaml()).unwrap();
+
+        std::env::set_var("PFORGETEST2_FORGE__TRANSPORT", "sse");
+        let config = ConfigBuilder::new()
+            .add_source(path.clone())
+            .env_prefix("PFORGETEST2")
+            .set_override("forge.transport", "websocket")
+            .load()
+            .unwrap();
+        std::env::remove_var("PFORGETEST2_FORGE__TRANSPORT");
+
+        assert_eq!(config.forge.transport, TransportType::WebSocket);
+    }
+
+    #[test]
+    fn test_config_builder_requires_at_least_one_source() {
+        let result = ConfigBuilder::new().load();
+        assert!(matches!(result, Err(ConfigError::ValidationError(_))));
+    }
+}
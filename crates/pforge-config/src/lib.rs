@@ -39,12 +39,20 @@
 //! - All required fields must be present
 //! - Transport type must be valid (stdio, sse, websocket)
 
+pub mod alias;
+pub mod conformance;
 pub mod error;
+pub mod layered;
 pub mod parser;
+pub mod span;
 pub mod types;
 pub mod validator;
 
+pub use alias::expand_aliases;
+pub use conformance::{run_corpus, ErrorKind, Expected, Failure, RunReport, Vector};
 pub use error::{ConfigError, Result};
+pub use layered::{ConfigBuilder, SourceFormat};
 pub use parser::{parse_config, parse_config_from_str};
+pub use span::Span;
 pub use types::*;
-pub use validator::validate_config;
+pub use validator::{validate_config, validate_config_with_source};
@@ -1,4 +1,4 @@
-use crate::{ConfigError, ForgeConfig, Result};
+use crate::{expand_aliases, layered::interpolate_value, ConfigError, ForgeConfig, Result};
 use std::path::Path;
 
 pub fn parse_config(path: &Path) -> Result<ForgeConfig> {
@@ -8,8 +8,18 @@ pub fn parse_config(path: &Path) -> Result<ForgeConfig> {
     parse_config_from_str(&content)
 }
 
+/// Parse a single YAML document into a [`ForgeConfig`], expanding
+/// `${VAR}` / `${VAR:-default}` references against the process environment
+/// first (the same interpolation [`ForgeConfig::load_layered`] applies, so
+/// an `endpoint: https://${API_HOST}/data` works whether or not the caller
+/// goes through the layered loader) and then [`expand_aliases`].
 pub fn parse_config_from_str(yaml: &str) -> Result<ForgeConfig> {
-    serde_yaml::from_str(yaml).map_err(|e| ConfigError::ParseError(e.to_string()))
+    let mut value: serde_yaml::Value = serde_yaml::from_str(yaml)?;
+    interpolate_value(&mut value)?;
+
+    let mut config: ForgeConfig = serde_yaml::from_value(value)?;
+    expand_aliases(&mut config)?;
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -46,7 +56,101 @@ tools:
         let yaml = "invalid: yaml: structure: [[[";
         let result = parse_config_from_str(yaml);
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), ConfigError::ParseError(_)));
+        assert!(matches!(
+            result.unwrap_err(),
+            ConfigError::ParseError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_config_malformed_yaml_carries_span() {
+        let yaml = "invalid: yaml: structure: [[[";
+        let err = parse_config_from_str(yaml).unwrap_err();
+        let ConfigError::ParseError { span, .. } = &err else {
+            panic!("expected ParseError, got {:?}", err);
+        };
+        assert!(span.is_some(), "expected a span for malformed YAML");
+    }
+
+    #[test]
+    fn test_parse_config_invalid_transport_type_carries_span() {
+        let yaml = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: carrier-pigeon
+
+tools: []
+"#;
+        let err = parse_config_from_str(yaml).unwrap_err();
+        let ConfigError::ParseError { message, span } = &err else {
+            panic!("expected ParseError, got {:?}", err);
+        };
+        assert!(message.contains("carrier-pigeon") || message.contains("unknown variant"));
+        assert!(span.is_some(), "expected a span for the bad transport value");
+        let diagnostic = err.to_diagnostic(yaml);
+        assert!(diagnostic.contains("-->"));
+        assert!(diagnostic.contains('^'));
+    }
+
+    #[test]
+    fn test_parse_config_invalid_optimization_level_carries_span() {
+        let yaml = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+  optimization: turbo
+
+tools: []
+"#;
+        let err = parse_config_from_str(yaml).unwrap_err();
+        assert!(matches!(err, ConfigError::ParseError { span: Some(_), .. }));
+    }
+
+    #[test]
+    fn test_parse_config_from_str_interpolates_env_vars() {
+        std::env::set_var("PFORGE_PARSER_TEST_API_HOST", "api.example.com");
+        let yaml = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+
+tools:
+  - type: http
+    name: api_call
+    description: "Call the API"
+    endpoint: "https://${PFORGE_PARSER_TEST_API_HOST}/data"
+    method: "GET"
+"#;
+        let config = parse_config_from_str(yaml).unwrap();
+        std::env::remove_var("PFORGE_PARSER_TEST_API_HOST");
+
+        let crate::ToolDef::Http { endpoint, .. } = &config.tools[0] else {
+            panic!("expected http tool");
+        };
+        assert_eq!(endpoint, "https://api.example.com/data");
+    }
+
+    #[test]
+    fn test_parse_config_from_str_missing_env_var_errors() {
+        std::env::remove_var("PFORGE_PARSER_TEST_MISSING_VAR");
+        let yaml = r#"
+forge:
+  name: test-server
+  version: 0.1.0
+  transport: stdio
+
+tools:
+  - type: http
+    name: api_call
+    description: "Call the API"
+    endpoint: "${PFORGE_PARSER_TEST_MISSING_VAR}"
+    method: "GET"
+"#;
+        let result = parse_config_from_str(yaml);
+        assert!(matches!(result, Err(ConfigError::InterpolationError(_))));
     }
 
     #[test]
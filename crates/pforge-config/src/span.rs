@@ -0,0 +1,131 @@
+//! Source-location tracking for validation diagnostics.
+//!
+//! `ForgeConfig` is deserialized straight into plain structs, so by the time
+//! [`crate::validator::validate_config`] runs there's no positional
+//! information left to report. Rather than switching to a spanned YAML
+//! deserializer, [`locate`] re-scans the original source text for the
+//! `field: value` pair a failed check names, giving `ConfigError` just enough
+//! to render a compiler-diagnostic-style message.
+
+use std::fmt;
+
+/// A 1-indexed line/column into the original config source, plus the field
+/// name it was resolved for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub field: String,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Find the first `field: value` occurrence in `source` (YAML's `key: value`
+/// shape, with `value` optionally quoted). Returns `None` if no line
+/// carries both the field name and the exact value.
+pub fn locate(source: &str, field: &str, value: &str) -> Option<Span> {
+    locate_nth(source, field, value, 0)
+}
+
+/// Like [`locate`], but skips the first `occurrence` matches - used to point
+/// at the *second* (offending) definition of a duplicate name rather than
+/// the first, legitimate one.
+pub fn locate_nth(source: &str, field: &str, value: &str, occurrence: usize) -> Option<Span> {
+    let needle_key = format!("{}:", field);
+    let mut seen = 0;
+
+    for (idx, line) in source.lines().enumerate() {
+        let Some(key_pos) = line.find(&needle_key) else {
+            continue;
+        };
+        let rest = &line[key_pos + needle_key.len()..];
+        let trimmed = rest.trim();
+        let unquoted = trimmed.trim_matches('"').trim_matches('\'');
+        if unquoted != value {
+            continue;
+        }
+
+        if seen == occurrence {
+            let leading_ws = rest.len() - rest.trim_start().len();
+            let column = key_pos + needle_key.len() + leading_ws + 1;
+            return Some(Span {
+                line: idx + 1,
+                column,
+                field: field.to_string(),
+            });
+        }
+        seen += 1;
+    }
+
+    None
+}
+
+/// Render `message` as a compiler-diagnostic: the message, a `line:column`
+/// locator, and the trimmed source line with a caret under the token.
+pub fn render_diagnostic(message: &str, span: &Span, source: &str) -> String {
+    let raw_line = source.lines().nth(span.line - 1).unwrap_or("");
+    let leading_ws = raw_line.len() - raw_line.trim_start().len();
+    let display_line = raw_line.trim_start();
+    let caret_col = span.column.saturating_sub(leading_ws).max(1);
+
+    format!(
+        "{message}\n  --> <config>:{span}\n   |\n{line_no:>3} | {display_line}\n   | {caret:>width$}",
+        message = message,
+        span = span,
+        line_no = span.line,
+        display_line = display_line,
+        caret = "^",
+        width = caret_col,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_finds_field_value_pair() {
+        let source = "forge:\n  name: test\n\ntools:\n  - type: cli\n    name: duplicate\n";
+        let span = locate(source, "name", "duplicate").unwrap();
+        assert_eq!(span.line, 6);
+        assert_eq!(span.field, "name");
+    }
+
+    #[test]
+    fn test_locate_nth_skips_earlier_matches() {
+        let source = "  - name: duplicate\n  - name: duplicate\n";
+        let first = locate_nth(source, "name", "duplicate", 0).unwrap();
+        let second = locate_nth(source, "name", "duplicate", 1).unwrap();
+        assert_eq!(first.line, 1);
+        assert_eq!(second.line, 2);
+    }
+
+    #[test]
+    fn test_locate_handles_quoted_values() {
+        let source = "    path: \"module::handler\"\n";
+        let span = locate(source, "path", "module::handler").unwrap();
+        assert_eq!(span.line, 1);
+    }
+
+    #[test]
+    fn test_locate_missing_value_returns_none() {
+        let source = "name: other\n";
+        assert!(locate(source, "name", "missing").is_none());
+    }
+
+    #[test]
+    fn test_render_diagnostic_includes_caret_under_token() {
+        let source = "  - type: cli\n    name: duplicate\n";
+        let span = locate(source, "name", "duplicate").unwrap();
+        let rendered = render_diagnostic("Duplicate tool name: duplicate", &span, source);
+
+        assert!(rendered.contains("Duplicate tool name: duplicate"));
+        assert!(rendered.contains("<config>:2:"));
+        assert!(rendered.contains("name: duplicate"));
+        assert!(rendered.lines().last().unwrap().trim_end().ends_with('^'));
+    }
+}
@@ -12,8 +12,31 @@ pub struct ForgeConfig {
     pub resources: Vec<ResourceDef>,
     #[serde(default)]
     pub prompts: Vec<PromptDef>,
+    /// Short names that expand into a sequence of tool invocations, the
+    /// same way a Cargo `[alias]` expands into a longer subcommand line.
+    /// Resolved by [`crate::alias::expand_aliases`] into an ordinary
+    /// `ToolDef::Pipeline` appended to `tools`, so from every other point
+    /// in the codebase (validation, registration, dispatch) an alias looks
+    /// exactly like a hand-written pipeline tool.
+    #[serde(default)]
+    pub aliases: HashMap<String, Vec<PipelineStep>>,
     #[serde(default)]
     pub state: Option<StateDef>,
+    /// Inbound connection authentication for non-stdio transports. `None`
+    /// (or an explicit `type: none`) leaves connections unauthenticated.
+    #[serde(default)]
+    pub auth: Option<ServerAuthConfig>,
+}
+
+/// How a `sse`/`websocket` transport authenticates an incoming connection
+/// during its handshake, before any JSON-RPC request is dispatched.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ServerAuthConfig {
+    /// No authentication; every connection is accepted.
+    None,
+    /// The handshake hello must carry this exact bearer token.
+    StaticToken { token: String },
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -22,8 +45,30 @@ pub struct ForgeMetadata {
     pub version: String,
     #[serde(default = "default_transport")]
     pub transport: TransportType,
+    /// Endpoint and connection tuning for whichever transport `transport`
+    /// selects, consumed by `pforge_runtime::transport::create_transport_with_config`.
+    /// Lives alongside `transport` rather than folded into it so existing
+    /// `transport: stdio`-style configs keep parsing unchanged - only the
+    /// variant actually selected ever reads its tuning.
+    #[serde(default)]
+    pub transport_tuning: TransportTuning,
     #[serde(default)]
     pub optimization: OptimizationLevel,
+    /// How long to wait for in-flight requests to drain after a shutdown
+    /// signal before forcing the process to exit.
+    #[serde(default = "default_shutdown_timeout_ms")]
+    pub shutdown_timeout_ms: u64,
+    /// Per-request timeout after which an in-progress dispatch is cancelled
+    /// and a timeout error is returned instead. `None` disables the cap.
+    #[serde(default)]
+    pub slow_request_timeout_ms: Option<u64>,
+    /// When `true`, every handler's serialized output is checked against its
+    /// declared `Handler::output_schema()` before being returned, raising
+    /// `Error::OutputValidation` on drift instead of letting a malformed
+    /// value propagate silently - most useful for `pipeline` tools, where
+    /// one step's output otherwise feeds the next step's input unchecked.
+    #[serde(default)]
+    pub validate_output: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -35,6 +80,154 @@ pub enum TransportType {
     WebSocket,
 }
 
+/// Per-transport endpoint and connection tuning, selected by `ForgeMetadata::transport`.
+/// Each sub-config defaults to the same localhost dev endpoint and timings
+/// pforge has always used, so a config that doesn't mention `transport_tuning`
+/// at all behaves exactly as before.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct TransportTuning {
+    #[serde(default)]
+    pub sse: SseTransportConfig,
+    #[serde(default)]
+    pub websocket: WebSocketTransportConfig,
+}
+
+/// Connection tuning for the `sse` transport.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SseTransportConfig {
+    #[serde(default = "default_sse_url")]
+    pub url: String,
+    #[serde(default = "default_sse_connection_timeout_ms")]
+    pub connection_timeout_ms: u64,
+    #[serde(default = "default_sse_keepalive_interval_ms")]
+    pub keepalive_interval_ms: u64,
+    #[serde(default = "default_sse_max_reconnects")]
+    pub max_reconnects: u32,
+    #[serde(default = "default_sse_reconnect_delay_ms")]
+    pub reconnect_delay_ms: u64,
+    /// Capacity of the bounded channel buffering outbound SSE messages.
+    #[serde(default = "default_sse_buffer_size")]
+    pub buffer_size: usize,
+    #[serde(default = "default_sse_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    #[serde(default = "default_true")]
+    pub enable_pooling: bool,
+    #[serde(default = "default_sse_max_connections")]
+    pub max_connections: usize,
+    #[serde(default)]
+    pub enable_compression: bool,
+}
+
+impl Default for SseTransportConfig {
+    fn default() -> Self {
+        Self {
+            url: default_sse_url(),
+            connection_timeout_ms: default_sse_connection_timeout_ms(),
+            keepalive_interval_ms: default_sse_keepalive_interval_ms(),
+            max_reconnects: default_sse_max_reconnects(),
+            reconnect_delay_ms: default_sse_reconnect_delay_ms(),
+            buffer_size: default_sse_buffer_size(),
+            flush_interval_ms: default_sse_flush_interval_ms(),
+            enable_pooling: true,
+            max_connections: default_sse_max_connections(),
+            enable_compression: false,
+        }
+    }
+}
+
+/// Connection tuning for the `websocket` transport.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebSocketTransportConfig {
+    #[serde(default = "default_websocket_url")]
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub auto_reconnect: bool,
+    #[serde(default = "default_websocket_reconnect_delay_ms")]
+    pub reconnect_delay_ms: u64,
+    #[serde(default = "default_websocket_max_reconnect_delay_ms")]
+    pub max_reconnect_delay_ms: u64,
+    #[serde(default = "default_websocket_max_reconnect_attempts")]
+    pub max_reconnect_attempts: Option<u32>,
+    #[serde(default = "default_websocket_ping_interval_ms")]
+    pub ping_interval_ms: Option<u64>,
+    #[serde(default = "default_websocket_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+impl Default for WebSocketTransportConfig {
+    fn default() -> Self {
+        Self {
+            url: default_websocket_url(),
+            auto_reconnect: true,
+            reconnect_delay_ms: default_websocket_reconnect_delay_ms(),
+            max_reconnect_delay_ms: default_websocket_max_reconnect_delay_ms(),
+            max_reconnect_attempts: default_websocket_max_reconnect_attempts(),
+            ping_interval_ms: default_websocket_ping_interval_ms(),
+            request_timeout_ms: default_websocket_request_timeout_ms(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_sse_url() -> String {
+    "http://localhost:8080/sse".to_string()
+}
+
+fn default_sse_connection_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_sse_keepalive_interval_ms() -> u64 {
+    15_000
+}
+
+fn default_sse_max_reconnects() -> u32 {
+    5
+}
+
+fn default_sse_reconnect_delay_ms() -> u64 {
+    1_000
+}
+
+fn default_sse_buffer_size() -> usize {
+    100
+}
+
+fn default_sse_flush_interval_ms() -> u64 {
+    100
+}
+
+fn default_sse_max_connections() -> usize {
+    10
+}
+
+fn default_websocket_url() -> String {
+    "ws://localhost:8080/ws".to_string()
+}
+
+fn default_websocket_reconnect_delay_ms() -> u64 {
+    1_000
+}
+
+fn default_websocket_max_reconnect_delay_ms() -> u64 {
+    30_000
+}
+
+fn default_websocket_max_reconnect_attempts() -> Option<u32> {
+    Some(5)
+}
+
+fn default_websocket_ping_interval_ms() -> Option<u64> {
+    Some(30_000)
+}
+
+fn default_websocket_request_timeout_ms() -> u64 {
+    10_000
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum OptimizationLevel {
@@ -122,6 +315,10 @@ pub enum ParamType {
         description: Option<String>,
         #[serde(default)]
         validation: Option<Validation>,
+        /// Name of a specific coercion to apply (see `pforge_runtime::conversion::Conversion`),
+        /// overriding the conversion that would otherwise be inferred from `ty`.
+        #[serde(default)]
+        coerce: Option<String>,
     },
 }
 
@@ -232,3 +429,7 @@ pub enum StateBackend {
 fn default_transport() -> TransportType {
     TransportType::Stdio
 }
+
+fn default_shutdown_timeout_ms() -> u64 {
+    30_000
+}
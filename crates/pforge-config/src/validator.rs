@@ -1,147 +1,590 @@
-use crate::{ConfigError, ForgeConfig, Result, ToolDef};
-use std::collections::HashSet;
+use crate::span::locate_nth;
+use crate::{ConfigError, ForgeConfig, PipelineStep, Result, ToolDef};
+use std::collections::{HashMap, HashSet};
 
+/// Tool names `McpServer` registers itself - a user-defined tool can never
+/// take one of these, even across different tool types.
+const RESERVED_TOOL_NAMES: &[&str] = &["health_check", "get_metrics"];
+
+/// Validate `config`, with no access to the original source text - every
+/// reported [`ConfigError`]'s `span` is `None`. Prefer
+/// [`validate_config_with_source`] when the YAML source is available, so
+/// errors can be rendered as compiler diagnostics.
 pub fn validate_config(config: &ForgeConfig) -> Result<()> {
-    // Check for duplicate tool names
-    let mut tool_names = HashSet::new();
+    validate(config, None)
+}
+
+/// Validate `config`, re-scanning `source` (the YAML text it was parsed
+/// from) to attach a line/column [`crate::Span`] to each reported error.
+pub fn validate_config_with_source(config: &ForgeConfig, source: &str) -> Result<()> {
+    validate(config, Some(source))
+}
+
+/// Runs every semantic check and only then reports what it found, so a
+/// config with several unrelated problems surfaces all of them in one pass
+/// instead of just the first. When exactly one check fails, that single
+/// [`ConfigError`] is returned directly (unwrapped) so existing callers
+/// matching on a specific variant keep working; two or more are collected
+/// into [`ConfigError::MultipleErrors`].
+fn validate(config: &ForgeConfig, source: Option<&str>) -> Result<()> {
+    let mut violations = collect_violations(config, source);
+
+    match violations.len() {
+        0 => Ok(()),
+        1 => Err(violations.remove(0)),
+        _ => Err(ConfigError::MultipleErrors(violations)),
+    }
+}
+
+fn collect_violations(config: &ForgeConfig, source: Option<&str>) -> Vec<ConfigError> {
+    let mut violations = Vec::new();
+
+    check_duplicate_and_reserved_names(config, source, &mut violations);
+
+    for tool in &config.tools {
+        if let ToolDef::Native { handler, .. } = tool {
+            if let Err(e) = validate_handler_path(&handler.path, source) {
+                violations.push(e);
+            }
+        }
+        if let ToolDef::Http { name, endpoint, .. } = tool {
+            if let Err(message) = validate_http_endpoint(endpoint) {
+                violations.push(ConfigError::ValidationError(format!(
+                    "tool '{}': {}",
+                    name, message
+                )));
+            }
+        }
+    }
+
+    check_pipeline_references(config, source, &mut violations);
+
+    for resource in &config.resources {
+        if let Err(message) = validate_uri_template(&resource.uri_template) {
+            violations.push(ConfigError::ValidationError(format!(
+                "resource '{}': {}",
+                resource.uri_template, message
+            )));
+        }
+    }
+
+    for prompt in &config.prompts {
+        for reference in extract_template_vars(&prompt.template) {
+            if !prompt.arguments.contains_key(&reference) {
+                violations.push(ConfigError::ValidationError(format!(
+                    "prompt '{}' references undeclared argument '{}'",
+                    prompt.name, reference
+                )));
+            }
+        }
+    }
+
+    violations
+}
+
+fn check_duplicate_and_reserved_names(
+    config: &ForgeConfig,
+    source: Option<&str>,
+    violations: &mut Vec<ConfigError>,
+) {
+    let mut seen = HashSet::new();
     for tool in &config.tools {
         let name = tool.name();
-        if !tool_names.insert(name) {
-            return Err(ConfigError::DuplicateToolName(name.to_string()));
+
+        if !seen.insert(name) {
+            let span = source.and_then(|s| locate_nth(s, "name", name, 1));
+            violations.push(ConfigError::DuplicateToolName {
+                name: name.to_string(),
+                span,
+            });
+        }
+
+        if RESERVED_TOOL_NAMES.contains(&name) {
+            violations.push(ConfigError::ValidationError(format!(
+                "tool name '{}' collides with a built-in tool",
+                name
+            )));
         }
     }
+}
 
-    // Validate handler paths for native tools
+fn check_pipeline_references(
+    config: &ForgeConfig,
+    source: Option<&str>,
+    violations: &mut Vec<ConfigError>,
+) {
+    let known_tools: HashSet<&str> = config.tools.iter().map(ToolDef::name).collect();
     for tool in &config.tools {
-        if let ToolDef::Native { handler, .. } = tool {
-            validate_handler_path(&handler.path)?;
+        if let ToolDef::Pipeline { steps, .. } = tool {
+            for step in steps {
+                if !known_tools.contains(step.tool.as_str()) {
+                    let span = source.and_then(|s| locate_nth(s, "tool", &step.tool, 0));
+                    violations.push(ConfigError::UnknownToolReference {
+                        name: step.tool.clone(),
+                        span,
+                    });
+                }
+            }
         }
     }
 
-    Ok(())
+    violations.extend(detect_pipeline_cycles(config));
 }
 
-fn validate_handler_path(path: &str) -> Result<()> {
+fn validate_handler_path(path: &str, source: Option<&str>) -> Result<()> {
     if path.is_empty() {
-        return Err(ConfigError::InvalidHandlerPath("empty path".to_string()));
+        return Err(ConfigError::InvalidHandlerPath {
+            message: "empty path".to_string(),
+            span: None,
+        });
     }
 
     // Basic validation: should contain ::
     if !path.contains("::") {
-        return Err(ConfigError::InvalidHandlerPath(format!(
-            "invalid format: {} (expected format: module::function)",
-            path
-        )));
+        let span = source.and_then(|s| locate_nth(s, "path", path, 0));
+        return Err(ConfigError::InvalidHandlerPath {
+            message: format!(
+                "invalid format: {} (expected format: module::function)",
+                path
+            ),
+            span,
+        });
     }
 
     Ok(())
 }
 
+fn validate_http_endpoint(endpoint: &str) -> std::result::Result<(), String> {
+    let host = endpoint
+        .strip_prefix("https://")
+        .or_else(|| endpoint.strip_prefix("http://"))
+        .ok_or_else(|| format!("endpoint '{}' must start with http:// or https://", endpoint))?;
+
+    if host.is_empty() || host.starts_with('/') {
+        return Err(format!("endpoint '{}' is missing a host", endpoint));
+    }
+
+    Ok(())
+}
+
+fn validate_uri_template(template: &str) -> std::result::Result<(), String> {
+    let mut depth = 0i32;
+    for ch in template.chars() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth < 0 {
+                    return Err(format!("uri_template '{}' has an unmatched '}}'", template));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(format!("uri_template '{}' has an unmatched '{{'", template));
+    }
+
+    Ok(())
+}
+
+/// Extract plain `{{variable}}` references from a Handlebars prompt
+/// template, skipping block helpers (`{{#if}}`, `{{/if}}`, `{{else}}`),
+/// comments (`{{! ... }}`) and partials (`{{> partial}}`).
+fn extract_template_vars(template: &str) -> Vec<String> {
+    let mut vars = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let inner = after[..end].trim().trim_matches('{').trim_matches('}');
+        rest = &after[end + 2..];
+
+        if inner.is_empty() || inner == "else" {
+            continue;
+        }
+        if matches!(inner.chars().next(), Some('#' | '/' | '!' | '>')) {
+            continue;
+        }
+
+        let name = inner.split_whitespace().next().unwrap_or(inner);
+        vars.push(name.to_string());
+    }
+
+    vars
+}
+
+/// DFS over pipeline-to-pipeline references, reporting each distinct cycle
+/// once. Nodes that come back clean, and nodes already folded into a
+/// reported cycle, are marked visited so later starting points don't
+/// re-walk (and re-report) the same ground.
+fn detect_pipeline_cycles(config: &ForgeConfig) -> Vec<ConfigError> {
+    let pipelines: HashMap<&str, &Vec<PipelineStep>> = config
+        .tools
+        .iter()
+        .filter_map(|tool| match tool {
+            ToolDef::Pipeline { name, steps, .. } => Some((name.as_str(), steps)),
+            _ => None,
+        })
+        .collect();
+
+    let mut visited = HashSet::new();
+    let mut violations = Vec::new();
+
+    let mut names: Vec<&str> = pipelines.keys().copied().collect();
+    names.sort_unstable();
+
+    for name in names {
+        if visited.contains(name) {
+            continue;
+        }
+        let mut stack = Vec::new();
+        if let Some(cycle) = find_cycle(name, &pipelines, &mut stack, &mut visited) {
+            violations.push(ConfigError::ValidationError(format!(
+                "cycle detected in pipeline references: {}",
+                cycle.join(" -> ")
+            )));
+        }
+    }
+
+    violations
+}
+
+fn find_cycle<'a>(
+    node: &'a str,
+    pipelines: &HashMap<&'a str, &Vec<PipelineStep>>,
+    stack: &mut Vec<&'a str>,
+    visited: &mut HashSet<&'a str>,
+) -> Option<Vec<String>> {
+    if let Some(pos) = stack.iter().position(|&n| n == node) {
+        let cycle = stack[pos..]
+            .iter()
+            .map(|s| s.to_string())
+            .chain(std::iter::once(node.to_string()))
+            .collect();
+        visited.extend(stack[pos..].iter().copied());
+        return Some(cycle);
+    }
+    if visited.contains(node) {
+        return None;
+    }
+
+    stack.push(node);
+    let mut found = None;
+    if let Some(steps) = pipelines.get(node) {
+        for step in *steps {
+            if pipelines.contains_key(step.tool.as_str()) {
+                found = find_cycle(step.tool.as_str(), pipelines, stack, visited);
+                if found.is_some() {
+                    break;
+                }
+            }
+        }
+    }
+    stack.pop();
+    visited.insert(node);
+    found
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::*;
 
-    #[test]
-    fn test_validate_config_success() {
-        let config = ForgeConfig {
+    fn minimal_tool(name: &str) -> ToolDef {
+        ToolDef::Native {
+            name: name.to_string(),
+            description: "Tool".to_string(),
+            handler: HandlerRef {
+                path: "module::handler".to_string(),
+                inline: None,
+            },
+            params: ParamSchema {
+                fields: std::collections::HashMap::new(),
+            },
+            timeout_ms: None,
+        }
+    }
+
+    fn base_config(tools: Vec<ToolDef>) -> ForgeConfig {
+        ForgeConfig {
             forge: ForgeMetadata {
                 name: "test".to_string(),
                 version: "1.0.0".to_string(),
                 transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
                 optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
             },
-            tools: vec![ToolDef::Native {
-                name: "tool1".to_string(),
-                description: "Tool 1".to_string(),
-                handler: HandlerRef {
-                    path: "module::handler".to_string(),
-                    inline: None,
-                },
-                params: ParamSchema {
-                    fields: std::collections::HashMap::new(),
-                },
-                timeout_ms: None,
-            }],
+            tools,
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
-        };
+            auth: None,
+        }
+    }
 
+    #[test]
+    fn test_validate_config_success() {
+        let config = base_config(vec![minimal_tool("tool1")]);
         assert!(validate_config(&config).is_ok());
     }
 
     #[test]
     fn test_validate_config_duplicate_tools() {
-        let config = ForgeConfig {
-            forge: ForgeMetadata {
-                name: "test".to_string(),
-                version: "1.0.0".to_string(),
-                transport: TransportType::Stdio,
-                optimization: OptimizationLevel::Debug,
-            },
-            tools: vec![
-                ToolDef::Native {
-                    name: "duplicate".to_string(),
-                    description: "Tool 1".to_string(),
-                    handler: HandlerRef {
-                        path: "module::handler1".to_string(),
-                        inline: None,
-                    },
-                    params: ParamSchema {
-                        fields: std::collections::HashMap::new(),
-                    },
-                    timeout_ms: None,
-                },
-                ToolDef::Native {
-                    name: "duplicate".to_string(),
-                    description: "Tool 2".to_string(),
-                    handler: HandlerRef {
-                        path: "module::handler2".to_string(),
-                        inline: None,
-                    },
-                    params: ParamSchema {
-                        fields: std::collections::HashMap::new(),
-                    },
-                    timeout_ms: None,
-                },
-            ],
-            resources: vec![],
-            prompts: vec![],
-            state: None,
-        };
+        let config = base_config(vec![minimal_tool("duplicate"), minimal_tool("duplicate")]);
 
         let result = validate_config(&config);
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            ConfigError::DuplicateToolName(_)
+            ConfigError::DuplicateToolName { .. }
         ));
     }
 
     #[test]
     fn test_validate_handler_path_empty() {
-        let result = validate_handler_path("");
+        let result = validate_handler_path("", None);
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            ConfigError::InvalidHandlerPath(_)
+            ConfigError::InvalidHandlerPath { .. }
         ));
     }
 
     #[test]
     fn test_validate_handler_path_invalid_format() {
-        let result = validate_handler_path("invalid_path");
+        let result = validate_handler_path("invalid_path", None);
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            ConfigError::InvalidHandlerPath(_)
+            ConfigError::InvalidHandlerPath { .. }
         ));
     }
 
     #[test]
     fn test_validate_handler_path_valid() {
-        let result = validate_handler_path("module::handler");
+        let result = validate_handler_path("module::handler", None);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_config_with_source_attaches_span_to_duplicate() {
+        let source = r#"
+forge:
+  name: test
+  version: 1.0.0
+
+tools:
+  - type: cli
+    name: duplicate
+    description: First
+    command: echo
+    args: []
+
+  - type: cli
+    name: duplicate
+    description: Second
+    command: echo
+    args: []
+"#;
+        let config: ForgeConfig = serde_yaml::from_str(source).unwrap();
+        let err = validate_config_with_source(&config, source).unwrap_err();
+
+        match &err {
+            ConfigError::DuplicateToolName { name, span } => {
+                assert_eq!(name, "duplicate");
+                let span = span.as_ref().expect("span should be resolved");
+                assert_eq!(span.line, 14);
+            }
+            other => panic!("expected DuplicateToolName, got {:?}", other),
+        }
+
+        let diagnostic = err.to_diagnostic(source);
+        assert!(diagnostic.contains("<config>:14:"));
+        assert!(diagnostic.contains('^'));
+    }
+
+    #[test]
+    fn test_validate_config_rejects_unknown_pipeline_reference() {
+        let source = r#"
+forge:
+  name: test
+  version: 1.0.0
+
+tools:
+  - type: pipeline
+    name: run_all
+    description: Runs a step
+    steps:
+      - tool: missing_tool
+"#;
+        let config: ForgeConfig = serde_yaml::from_str(source).unwrap();
+        let err = validate_config_with_source(&config, source).unwrap_err();
+
+        match err {
+            ConfigError::UnknownToolReference { name, span } => {
+                assert_eq!(name, "missing_tool");
+                assert!(span.is_some());
+            }
+            other => panic!("expected UnknownToolReference, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_config_without_source_omits_span() {
+        let config = base_config(vec![minimal_tool("duplicate"), minimal_tool("duplicate")]);
+
+        match validate_config(&config).unwrap_err() {
+            ConfigError::DuplicateToolName { span, .. } => assert!(span.is_none()),
+            other => panic!("expected DuplicateToolName, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_config_rejects_reserved_tool_name() {
+        let config = base_config(vec![minimal_tool("health_check")]);
+
+        let err = validate_config(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationError(_)));
+    }
+
+    #[test]
+    fn test_validate_config_rejects_malformed_http_endpoint() {
+        let mut config = base_config(vec![]);
+        config.tools.push(ToolDef::Http {
+            name: "api_call".to_string(),
+            description: "Call the API".to_string(),
+            endpoint: "example.com/api".to_string(),
+            method: HttpMethod::Get,
+            auth: None,
+            headers: std::collections::HashMap::new(),
+        });
+
+        let err = validate_config(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationError(_)));
+    }
+
+    #[test]
+    fn test_validate_config_accepts_well_formed_http_endpoint() {
+        let mut config = base_config(vec![]);
+        config.tools.push(ToolDef::Http {
+            name: "api_call".to_string(),
+            description: "Call the API".to_string(),
+            endpoint: "https://api.example.com/v1".to_string(),
+            method: HttpMethod::Get,
+            auth: None,
+            headers: std::collections::HashMap::new(),
+        });
+
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_config_detects_pipeline_cycle() {
+        let mut config = base_config(vec![]);
+        config.tools.push(ToolDef::Pipeline {
+            name: "a".to_string(),
+            description: "A".to_string(),
+            steps: vec![PipelineStep {
+                tool: "b".to_string(),
+                input: None,
+                output_var: None,
+                condition: None,
+                error_policy: ErrorPolicy::default(),
+            }],
+        });
+        config.tools.push(ToolDef::Pipeline {
+            name: "b".to_string(),
+            description: "B".to_string(),
+            steps: vec![PipelineStep {
+                tool: "a".to_string(),
+                input: None,
+                output_var: None,
+                condition: None,
+                error_policy: ErrorPolicy::default(),
+            }],
+        });
+
+        let err = validate_config(&config).unwrap_err();
+        match err {
+            ConfigError::ValidationError(message) => {
+                assert!(message.contains("cycle"));
+            }
+            other => panic!("expected ValidationError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_config_rejects_undeclared_prompt_argument() {
+        let mut config = base_config(vec![]);
+        config.prompts.push(PromptDef {
+            name: "greet".to_string(),
+            description: "Greet someone".to_string(),
+            template: "Hello, {{name}}!".to_string(),
+            arguments: std::collections::HashMap::new(),
+        });
+
+        let err = validate_config(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationError(_)));
+    }
+
+    #[test]
+    fn test_validate_config_accepts_declared_prompt_argument() {
+        let mut config = base_config(vec![]);
+        let mut arguments = std::collections::HashMap::new();
+        arguments.insert("name".to_string(), ParamType::Simple(SimpleType::String));
+        config.prompts.push(PromptDef {
+            name: "greet".to_string(),
+            description: "Greet someone".to_string(),
+            template: "Hello, {{name}}! {{#if loud}}HI{{/if}}".to_string(),
+            arguments,
+        });
+
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_config_rejects_malformed_resource_uri_template() {
+        let mut config = base_config(vec![]);
+        config.resources.push(ResourceDef {
+            uri_template: "file:///{path".to_string(),
+            handler: HandlerRef {
+                path: "module::handler".to_string(),
+                inline: None,
+            },
+            supports: vec![],
+        });
+
+        let err = validate_config(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationError(_)));
+    }
+
+    #[test]
+    fn test_validate_config_collects_multiple_violations() {
+        let mut config = base_config(vec![minimal_tool("duplicate"), minimal_tool("duplicate")]);
+        config.tools.push(ToolDef::Http {
+            name: "bad_http".to_string(),
+            description: "Bad".to_string(),
+            endpoint: "not-a-url".to_string(),
+            method: HttpMethod::Get,
+            auth: None,
+            headers: std::collections::HashMap::new(),
+        });
+
+        let err = validate_config(&config).unwrap_err();
+        match err {
+            ConfigError::MultipleErrors(errors) => {
+                assert_eq!(errors.len(), 2);
+            }
+            other => panic!("expected MultipleErrors, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,22 @@
+/// Conformance tests for `pforge_config::run_corpus`
+/// Pins known-good and known-bad configs as regression vectors, alongside
+/// the property tests in `property_test.rs` which cover the random case.
+use pforge_config::run_corpus;
+
+#[test]
+fn test_conformance_corpus_passes() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("conformance");
+    let report = run_corpus(&dir).expect("corpus directory should be readable");
+
+    assert!(report.total > 0, "corpus directory should contain vectors");
+
+    for failure in &report.failures {
+        eprintln!(
+            "conformance vector failed: {} ({}): {}",
+            failure.path.display(),
+            failure.desc,
+            failure.message
+        );
+    }
+    assert!(report.is_ok(), "{} conformance vector(s) failed", report.failures.len());
+}
@@ -2,9 +2,9 @@
 /// Tests end-to-end functionality across multiple crates
 use pforge_config::{ForgeConfig, ForgeMetadata, ToolDef, TransportType};
 use pforge_runtime::{
-    CircuitBreaker, CircuitBreakerConfig, ErrorTracker, MiddlewareChain, PromptManager,
-    RecoveryMiddleware, ResourceManager, RetryPolicy, StateManager, MemoryStateManager,
-    retry_with_policy, with_timeout,
+    CircuitBreaker, CircuitBreakerConfig, ErrorTracker, FailureDetectionMode, MiddlewareChain,
+    PromptManager, RecoveryMiddleware, ResourceManager, RetryPolicy, StateManager,
+    MemoryStateManager, retry_with_policy, with_timeout,
 };
 use serde_json::json;
 use std::time::Duration;
@@ -112,6 +112,8 @@ async fn test_middleware_chain_with_recovery() {
             failure_threshold: 3,
             timeout: Duration::from_secs(60),
             success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
         });
 
     let tracker = recovery.error_tracker();
@@ -166,6 +168,8 @@ async fn test_circuit_breaker_integration() {
         failure_threshold: 2,
         timeout: Duration::from_millis(100),
         success_threshold: 2,
+        failure_detection: FailureDetectionMode::Consecutive,
+        half_open_max_concurrent: 1,
     };
 
     let cb = CircuitBreaker::new(config);
@@ -245,18 +249,22 @@ async fn test_error_tracker_classification() {
 
     // Track various error types
     tracker
-        .track_error(&pforge_runtime::Error::Handler("timeout error".to_string()))
+        .track_error("demo_tool", &pforge_runtime::Error::Handler("timeout error".to_string()))
         .await;
     tracker
-        .track_error(&pforge_runtime::Error::Handler("connection failed".to_string()))
+        .track_error(
+            "demo_tool",
+            &pforge_runtime::Error::Handler("connection failed".to_string()),
+        )
         .await;
     tracker
-        .track_error(&pforge_runtime::Error::Handler("unknown issue".to_string()))
+        .track_error("demo_tool", &pforge_runtime::Error::Handler("unknown issue".to_string()))
         .await;
 
     assert_eq!(tracker.total_errors(), 3);
 
-    let by_type = tracker.errors_by_type().await;
+    let by_tool = tracker.errors_by_tool_and_type().await;
+    let by_type = &by_tool["demo_tool"];
     assert!(by_type.contains_key("timeout"));
     assert!(by_type.contains_key("connection"));
     assert!(by_type.contains_key("handler_error"));
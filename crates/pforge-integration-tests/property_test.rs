@@ -65,7 +65,11 @@ fn arb_forge_metadata() -> impl Strategy<Value = ForgeMetadata> {
             name,
             version,
             transport,
+            transport_tuning: TransportTuning::default(),
             optimization,
+            shutdown_timeout_ms: 30_000,
+            slow_request_timeout_ms: None,
+            validate_output: false,
         })
 }
 
@@ -166,7 +170,9 @@ fn arb_forge_config() -> impl Strategy<Value = ForgeConfig> {
                 tools: unique_tools,
                 resources: vec![],
                 prompts: vec![],
+                aliases: HashMap::new(),
                 state: None,
+                auth: None,
             }
         })
 }
@@ -273,7 +279,11 @@ proptest! {
                 name: "test".to_string(),
                 version: "1.0.0".to_string(),
                 transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
                 optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
             },
             tools: vec![
                 ToolDef::Native {
@@ -293,12 +303,17 @@ proptest! {
             ],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         };
 
         let result = validate_config(&config);
         prop_assert!(result.is_err(), "Duplicate tool names should fail validation");
-        prop_assert!(matches!(result.unwrap_err(), ConfigError::DuplicateToolName(_)));
+        prop_assert!(matches!(
+            result.unwrap_err(),
+            ConfigError::DuplicateToolName { .. }
+        ));
     }
 
     /// Property: Invalid handler paths are rejected
@@ -310,7 +325,11 @@ proptest! {
                 name: "test".to_string(),
                 version: "1.0.0".to_string(),
                 transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
                 optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
             },
             tools: vec![
                 ToolDef::Native {
@@ -323,7 +342,9 @@ proptest! {
             ],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         };
 
         let result = validate_config(&config);
@@ -344,7 +365,9 @@ proptest! {
             tools: vec![],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         };
 
         let result = validate_config(&config);
@@ -359,7 +382,9 @@ proptest! {
             tools: vec![tool],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         };
 
         let result = validate_config(&config);
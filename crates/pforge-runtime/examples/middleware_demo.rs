@@ -2,7 +2,8 @@
 //
 // Run with: cargo run --example middleware_demo
 
-use pforge_runtime::{Handler, LoggingMiddleware, Middleware, Result};
+use pforge_runtime::middleware::Extensions;
+use pforge_runtime::{BeforeOutcome, Handler, LoggingMiddleware, Middleware, Result};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -49,12 +50,12 @@ impl TimingMiddleware {
 
 #[async_trait::async_trait]
 impl Middleware for TimingMiddleware {
-    async fn before(&self, request: Value) -> Result<Value> {
+    async fn before(&self, request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
         println!("  ⏱️  Request started");
-        Ok(request)
+        Ok(BeforeOutcome::Continue(request))
     }
 
-    async fn after(&self, _request: Value, response: Value) -> Result<Value> {
+    async fn after(&self, _request: Value, response: Value, _extensions: &Extensions) -> Result<Value> {
         let elapsed = self.start.elapsed();
         println!("  ⏱️  Request completed in {:?}", elapsed);
         Ok(response)
@@ -81,13 +82,20 @@ async fn main() -> Result<()> {
     };
 
     let mut request = serde_json::to_value(&input)?;
+    let mut extensions = Extensions::new();
 
     // Before phase
     println!("1. LoggingMiddleware.before()");
-    request = logging_mw.before(request).await?;
+    request = match logging_mw.before(request, &mut extensions).await? {
+        BeforeOutcome::Continue(request) => request,
+        BeforeOutcome::ShortCircuit(response) => return Ok(println!("{}", response)),
+    };
 
     println!("2. TimingMiddleware.before()");
-    request = timing_mw.before(request).await?;
+    request = match timing_mw.before(request, &mut extensions).await? {
+        BeforeOutcome::Continue(request) => request,
+        BeforeOutcome::ShortCircuit(response) => return Ok(println!("{}", response)),
+    };
 
     // Handler execution
     println!("3. Execute handler");
@@ -98,10 +106,10 @@ async fn main() -> Result<()> {
 
     // After phase (reverse order)
     println!("4. TimingMiddleware.after()");
-    response = timing_mw.after(request.clone(), response).await?;
+    response = timing_mw.after(request.clone(), response, &extensions).await?;
 
     println!("5. LoggingMiddleware.after()");
-    response = logging_mw.after(request, response).await?;
+    response = logging_mw.after(request, response, &extensions).await?;
 
     println!("\n📥 Final response:");
     println!("{}", serde_json::to_string_pretty(&response)?);
@@ -0,0 +1,222 @@
+//! Inbound connection authentication for the `sse` and `websocket`
+//! transports.
+//!
+//! Unlike [`crate::handlers::http::AuthConfig`] (which authenticates
+//! *outbound* requests an HTTP tool handler makes), this module authenticates
+//! the *inbound* connection a client opens to the server, during its
+//! handshake and before any JSON-RPC request on that connection is
+//! dispatched.
+
+use crate::{Error, Result};
+use std::sync::Arc;
+
+/// Credentials a client presented during the connection handshake.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// No credentials were presented (no `Authorization` header, etc).
+    None,
+    /// A bearer token, extracted from an `Authorization: Bearer <token>`
+    /// header.
+    Token(String),
+}
+
+/// The caller a connection was authenticated as, once
+/// [`Authenticator::authenticate`] succeeds. Opaque beyond `subject` for now;
+/// handlers and middleware that want to know who's calling can read it off
+/// the request via [`identity_of`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub subject: String,
+}
+
+/// Resolves [`Credentials`] presented during a handshake into an
+/// [`Identity`], or rejects the connection.
+#[async_trait::async_trait]
+pub trait Authenticator: Send + Sync {
+    async fn authenticate(&self, credentials: &Credentials) -> Result<Identity>;
+}
+
+/// Accepts every connection, regardless of what (if anything) it presented.
+/// Used when `forge.auth` is absent or explicitly `type: none`.
+pub struct NoneAuthenticator;
+
+#[async_trait::async_trait]
+impl Authenticator for NoneAuthenticator {
+    async fn authenticate(&self, _credentials: &Credentials) -> Result<Identity> {
+        Ok(Identity {
+            subject: "anonymous".to_string(),
+        })
+    }
+}
+
+/// Accepts only connections presenting the exact configured bearer token.
+pub struct StaticTokenAuthenticator {
+    token: String,
+}
+
+impl StaticTokenAuthenticator {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Authenticator for StaticTokenAuthenticator {
+    async fn authenticate(&self, credentials: &Credentials) -> Result<Identity> {
+        match credentials {
+            Credentials::Token(token) if constant_time_eq(token.as_bytes(), self.token.as_bytes()) => {
+                Ok(Identity {
+                    subject: "bearer".to_string(),
+                })
+            }
+            _ => Err(Error::Unauthorized(
+                "missing or incorrect bearer token".to_string(),
+            )),
+        }
+    }
+}
+
+/// Compare two byte strings without branching on the first mismatching
+/// byte, so a bearer-token check can't leak how many leading bytes of a
+/// guess were correct through response timing. Always walks the full
+/// length of `b` regardless of where (or whether) a mismatch occurs; a
+/// length difference alone still short-circuits, but that leaks nothing
+/// beyond what the caller already tells an attacker (the token length).
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff: u8 = 0;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+/// Build the [`Authenticator`] a running server should use for its
+/// `sse`/`websocket` transport, from the `forge.auth` config (`None` and an
+/// explicit `type: none` behave identically).
+pub fn build_authenticator(config: Option<&pforge_config::ServerAuthConfig>) -> Arc<dyn Authenticator> {
+    match config {
+        None | Some(pforge_config::ServerAuthConfig::None) => Arc::new(NoneAuthenticator),
+        Some(pforge_config::ServerAuthConfig::StaticToken { token }) => {
+            Arc::new(StaticTokenAuthenticator::new(token.clone()))
+        }
+    }
+}
+
+/// Extract a bearer token from a raw HTTP header block (as collected by
+/// [`crate::server`]'s hand-rolled request parsing), i.e. a line of the form
+/// `Authorization: Bearer <token>`.
+pub fn bearer_token_from_headers(headers: &[String]) -> Credentials {
+    headers
+        .iter()
+        .find_map(|h| {
+            let (name, value) = h.split_once(':')?;
+            if !name.trim().eq_ignore_ascii_case("authorization") {
+                return None;
+            }
+            value.trim().strip_prefix("Bearer ").map(str::to_string)
+        })
+        .map(Credentials::Token)
+        .unwrap_or(Credentials::None)
+}
+
+const FIELD: &str = "_identity";
+
+/// Stamp the authenticated caller onto a request, mirroring how
+/// [`crate::correlation`] stamps `_correlation_id`, so handlers/middleware
+/// operating on the request `Value` can recover who's calling without
+/// threading a separate parameter through the dispatch path.
+pub fn stamp_identity(identity: &Identity, mut request: serde_json::Value) -> serde_json::Value {
+    if let serde_json::Value::Object(ref mut obj) = request {
+        obj.insert(
+            FIELD.to_string(),
+            serde_json::Value::String(identity.subject.clone()),
+        );
+    }
+    request
+}
+
+/// Recover the subject stamped by [`stamp_identity`], if any.
+pub fn identity_of(request: &serde_json::Value) -> Option<String> {
+    request
+        .get(FIELD)
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_none_authenticator_accepts_anything() {
+        let auth = NoneAuthenticator;
+        assert!(auth.authenticate(&Credentials::None).await.is_ok());
+        assert!(auth
+            .authenticate(&Credentials::Token("whatever".to_string()))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_static_token_authenticator_accepts_matching_token() {
+        let auth = StaticTokenAuthenticator::new("secret");
+        let identity = auth
+            .authenticate(&Credentials::Token("secret".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(identity.subject, "bearer");
+    }
+
+    #[tokio::test]
+    async fn test_static_token_authenticator_rejects_wrong_token() {
+        let auth = StaticTokenAuthenticator::new("secret");
+        let result = auth
+            .authenticate(&Credentials::Token("wrong".to_string()))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_static_token_authenticator_rejects_missing_token() {
+        let auth = StaticTokenAuthenticator::new("secret");
+        assert!(auth.authenticate(&Credentials::None).await.is_err());
+    }
+
+    #[test]
+    fn test_bearer_token_from_headers() {
+        let headers = vec!["Authorization: Bearer abc123\r\n".to_string()];
+        assert_eq!(
+            bearer_token_from_headers(&headers),
+            Credentials::Token("abc123".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bearer_token_from_headers_missing() {
+        let headers = vec!["Content-Type: application/json\r\n".to_string()];
+        assert_eq!(bearer_token_from_headers(&headers), Credentials::None);
+    }
+
+    #[test]
+    fn test_constant_time_eq_matches_and_mismatches() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"wrong!"));
+        assert!(!constant_time_eq(b"secret", b"secret-but-longer"));
+        assert!(!constant_time_eq(b"", b"secret"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn test_stamp_and_read_identity() {
+        let identity = Identity {
+            subject: "bearer".to_string(),
+        };
+        let request = stamp_identity(&identity, serde_json::json!({"input": 1}));
+        assert_eq!(identity_of(&request).as_deref(), Some("bearer"));
+    }
+}
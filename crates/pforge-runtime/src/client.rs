@@ -0,0 +1,257 @@
+//! Typed client abstraction over [`HandlerRegistry::dispatch`].
+//!
+//! Calling a handler directly means hand-serializing the input, dispatching
+//! raw bytes, and hand-deserializing the output. [`SyncClient`] and
+//! [`AsyncClient`] wrap that round trip behind a single typed `call`, and
+//! [`LoopbackClient`] implements both on top of an in-process
+//! [`HandlerRegistry`].
+
+use crate::{Error, HandlerRegistry, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Retry policy for client calls. `ToolNotFound` is never retried — it's
+/// a routing error, not a transient failure — but any other handler error
+/// is retried until `max_attempts` attempts in all have been made, with a
+/// linearly increasing delay.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientRetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl ClientRetryPolicy {
+    /// No retries: a single attempt.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::ZERO,
+        }
+    }
+
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+        }
+    }
+}
+
+impl Default for ClientRetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Async typed client over a handler registry.
+#[async_trait::async_trait]
+pub trait AsyncClient: Send + Sync {
+    /// Serialize `input`, dispatch to `tool`, and deserialize the result.
+    async fn call<I, O>(&self, tool: &str, input: &I) -> Result<O>
+    where
+        I: Serialize + Send + Sync,
+        O: DeserializeOwned;
+}
+
+/// Blocking typed client over a handler registry, for callers outside an
+/// async context.
+pub trait SyncClient: Send + Sync {
+    /// Serialize `input`, dispatch to `tool`, and deserialize the result,
+    /// driving the async dispatch to completion on a small runtime.
+    fn call<I, O>(&self, tool: &str, input: &I) -> Result<O>
+    where
+        I: Serialize + Send + Sync,
+        O: DeserializeOwned;
+}
+
+/// In-process client that dispatches directly against a shared
+/// [`HandlerRegistry`], with no network hop.
+pub struct LoopbackClient {
+    registry: Arc<HandlerRegistry>,
+    retry_policy: ClientRetryPolicy,
+    handle: Option<tokio::runtime::Handle>,
+}
+
+impl LoopbackClient {
+    /// Create a client with no retries.
+    pub fn new(registry: Arc<HandlerRegistry>) -> Self {
+        Self {
+            registry,
+            retry_policy: ClientRetryPolicy::default(),
+            handle: None,
+        }
+    }
+
+    /// Create a client with a custom retry policy.
+    pub fn with_retry_policy(registry: Arc<HandlerRegistry>, retry_policy: ClientRetryPolicy) -> Self {
+        Self {
+            registry,
+            retry_policy,
+            handle: None,
+        }
+    }
+
+    /// Drive [`SyncClient::call`] on the given runtime handle instead of
+    /// spinning up a throwaway current-thread runtime per call.
+    pub fn with_handle(mut self, handle: tokio::runtime::Handle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    async fn dispatch_typed<I, O>(&self, tool: &str, input: &I) -> Result<O>
+    where
+        I: Serialize + Send + Sync,
+        O: DeserializeOwned,
+    {
+        let params = serde_json::to_vec(input)?;
+        let mut attempt = 1;
+
+        loop {
+            match self.registry.dispatch(tool, &params).await {
+                Ok(bytes) => return serde_json::from_slice(&bytes).map_err(Into::into),
+                Err(Error::ToolNotFound(tool)) => return Err(Error::ToolNotFound(tool)),
+                Err(e) if attempt < self.retry_policy.max_attempts => {
+                    tokio::time::sleep(self.retry_policy.base_delay * attempt).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncClient for LoopbackClient {
+    async fn call<I, O>(&self, tool: &str, input: &I) -> Result<O>
+    where
+        I: Serialize + Send + Sync,
+        O: DeserializeOwned,
+    {
+        self.dispatch_typed(tool, input).await
+    }
+}
+
+impl SyncClient for LoopbackClient {
+    fn call<I, O>(&self, tool: &str, input: &I) -> Result<O>
+    where
+        I: Serialize + Send + Sync,
+        O: DeserializeOwned,
+    {
+        let fut = self.dispatch_typed(tool, input);
+        match &self.handle {
+            Some(handle) => tokio::task::block_in_place(|| handle.block_on(fut)),
+            None => {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .build()
+                    .map_err(Error::Io)?;
+                rt.block_on(fut)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Handler;
+    use async_trait::async_trait;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct EchoInput {
+        value: i32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct EchoOutput {
+        value: i32,
+    }
+
+    struct EchoHandler;
+
+    #[async_trait]
+    impl Handler for EchoHandler {
+        type Input = EchoInput;
+        type Output = EchoOutput;
+        type Error = Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            Ok(EchoOutput { value: input.value })
+        }
+    }
+
+    struct FlakyHandler {
+        failures_remaining: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Handler for FlakyHandler {
+        type Input = EchoInput;
+        type Output = EchoOutput;
+        type Error = Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            if self.failures_remaining.fetch_sub(1, Ordering::SeqCst) > 0 {
+                Err(Error::Handler("transient failure".to_string()))
+            } else {
+                Ok(EchoOutput { value: input.value })
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_client_round_trip() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+
+        let client = LoopbackClient::new(Arc::new(registry));
+        let output: EchoOutput = client.call("echo", &EchoInput { value: 7 }).await.unwrap();
+        assert_eq!(output.value, 7);
+    }
+
+    #[tokio::test]
+    async fn test_async_client_never_retries_tool_not_found() {
+        let registry = HandlerRegistry::new();
+        let client = LoopbackClient::with_retry_policy(
+            Arc::new(registry),
+            ClientRetryPolicy::new(5, Duration::from_millis(1)),
+        );
+
+        let result: Result<EchoOutput> = client.call("missing", &EchoInput { value: 1 }).await;
+        assert!(matches!(result, Err(Error::ToolNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_async_client_retries_transient_errors() {
+        let mut registry = HandlerRegistry::new();
+        registry.register(
+            "flaky",
+            FlakyHandler {
+                failures_remaining: AtomicU32::new(2),
+            },
+        );
+
+        let client = LoopbackClient::with_retry_policy(
+            Arc::new(registry),
+            ClientRetryPolicy::new(3, Duration::from_millis(1)),
+        );
+
+        let output: EchoOutput = client.call("flaky", &EchoInput { value: 9 }).await.unwrap();
+        assert_eq!(output.value, 9);
+    }
+
+    #[test]
+    fn test_sync_client_round_trip() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+
+        let client = LoopbackClient::new(Arc::new(registry));
+        let output: EchoOutput = client.call("echo", &EchoInput { value: 3 }).unwrap();
+        assert_eq!(output.value, 3);
+    }
+}
@@ -0,0 +1,233 @@
+//! # Wire Codecs
+//!
+//! Handlers exchange typed `Input`/`Output` values; [`Codec`] is the
+//! abstraction over how those values are serialized on the wire between a
+//! transport and [`crate::registry::HandlerRegistry`]. JSON remains the
+//! default and is always available. The other formats trade JSON's
+//! readability for smaller payloads and cheaper parsing - useful for
+//! high-throughput or embedded deployments - and are feature-gated since
+//! each pulls in an extra crate. Picking a format is a per-transport or
+//! per-tool concern handled by [`WireFormat`]; handler code never changes.
+
+use crate::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A wire serialization format: encodes a typed value to bytes and back.
+pub trait Codec: Send + Sync {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>>;
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T>;
+}
+
+/// The default, always-available format.
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// MessagePack, via `rmp-serde`: compact and self-describing like JSON, but
+/// binary.
+#[cfg(feature = "msgpack")]
+pub struct MessagePackCodec;
+
+#[cfg(feature = "msgpack")]
+impl Codec for MessagePackCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        rmp_serde::to_vec(value).map_err(|e| Error::Codec(format!("MessagePack encode: {}", e)))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        rmp_serde::from_slice(bytes).map_err(|e| Error::Codec(format!("MessagePack decode: {}", e)))
+    }
+}
+
+/// CBOR, via `serde_cbor`: self-describing binary format, also used for
+/// [`crate::state::SledStateManager`]'s on-disk entries.
+#[cfg(feature = "cbor")]
+pub struct CborCodec;
+
+#[cfg(feature = "cbor")]
+impl Codec for CborCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        serde_cbor::to_vec(value).map_err(|e| Error::Codec(format!("CBOR encode: {}", e)))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        serde_cbor::from_slice(bytes).map_err(|e| Error::Codec(format!("CBOR decode: {}", e)))
+    }
+}
+
+/// `bincode`: not self-describing, smallest and fastest of the four, but
+/// both ends must agree on the exact type layout.
+#[cfg(feature = "bincode")]
+pub struct BincodeCodec;
+
+#[cfg(feature = "bincode")]
+impl Codec for BincodeCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        bincode::serialize(value).map_err(|e| Error::Codec(format!("bincode encode: {}", e)))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        bincode::deserialize(bytes).map_err(|e| Error::Codec(format!("bincode decode: {}", e)))
+    }
+}
+
+/// `postcard`: like bincode, not self-describing, but uses varint framing
+/// and no allocator at the format level - the pick for embedded targets.
+#[cfg(feature = "postcard")]
+pub struct PostcardCodec;
+
+#[cfg(feature = "postcard")]
+impl Codec for PostcardCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        postcard::to_allocvec(value).map_err(|e| Error::Codec(format!("postcard encode: {}", e)))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        postcard::from_bytes(bytes).map_err(|e| Error::Codec(format!("postcard decode: {}", e)))
+    }
+}
+
+/// Selects which [`Codec`] a transport or tool dispatch negotiates for
+/// handler I/O. `Default` is `Json`, matching the runtime's historical
+/// JSON-only behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+    #[cfg(feature = "cbor")]
+    Cbor,
+    #[cfg(feature = "bincode")]
+    Bincode,
+    #[cfg(feature = "postcard")]
+    Postcard,
+}
+
+impl WireFormat {
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            WireFormat::Json => JsonCodec.encode(value),
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => MessagePackCodec.encode(value),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => CborCodec.encode(value),
+            #[cfg(feature = "bincode")]
+            WireFormat::Bincode => BincodeCodec.encode(value),
+            #[cfg(feature = "postcard")]
+            WireFormat::Postcard => PostcardCodec.encode(value),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        match self {
+            WireFormat::Json => JsonCodec.decode(bytes),
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => MessagePackCodec.decode(bytes),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => CborCodec.decode(bytes),
+            #[cfg(feature = "bincode")]
+            WireFormat::Bincode => BincodeCodec.decode(bytes),
+            #[cfg(feature = "postcard")]
+            WireFormat::Postcard => PostcardCodec.decode(bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Sample {
+        name: String,
+        value: i64,
+        tags: Vec<String>,
+    }
+
+    fn sample() -> Sample {
+        Sample {
+            name: "widget".to_string(),
+            value: 42,
+            tags: vec!["a".to_string(), "b".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_json_codec_roundtrip() {
+        let bytes = JsonCodec.encode(&sample()).unwrap();
+        let decoded: Sample = JsonCodec.decode(&bytes).unwrap();
+        assert_eq!(decoded, sample());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_codec_roundtrip() {
+        let bytes = MessagePackCodec.encode(&sample()).unwrap();
+        let decoded: Sample = MessagePackCodec.decode(&bytes).unwrap();
+        assert_eq!(decoded, sample());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_codec_roundtrip() {
+        let bytes = CborCodec.encode(&sample()).unwrap();
+        let decoded: Sample = CborCodec.decode(&bytes).unwrap();
+        assert_eq!(decoded, sample());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_codec_roundtrip() {
+        let bytes = BincodeCodec.encode(&sample()).unwrap();
+        let decoded: Sample = BincodeCodec.decode(&bytes).unwrap();
+        assert_eq!(decoded, sample());
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn test_postcard_codec_roundtrip() {
+        let bytes = PostcardCodec.encode(&sample()).unwrap();
+        let decoded: Sample = PostcardCodec.decode(&bytes).unwrap();
+        assert_eq!(decoded, sample());
+    }
+
+    #[test]
+    fn test_wire_format_default_is_json() {
+        assert_eq!(WireFormat::default(), WireFormat::Json);
+    }
+
+    /// Every compiled-in format round-trips the same value through
+    /// `WireFormat`, matching the per-format test split above but exercised
+    /// via the format-selection entry point handlers actually use.
+    #[test]
+    fn test_wire_format_conformance_matrix() {
+        let formats = [
+            WireFormat::Json,
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack,
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor,
+            #[cfg(feature = "bincode")]
+            WireFormat::Bincode,
+            #[cfg(feature = "postcard")]
+            WireFormat::Postcard,
+        ];
+
+        for format in formats {
+            let bytes = format.encode(&sample()).unwrap();
+            let decoded: Sample = format.decode(&bytes).unwrap();
+            assert_eq!(decoded, sample(), "format {:?} failed to round-trip", format);
+        }
+    }
+}
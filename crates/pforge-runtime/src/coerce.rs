@@ -0,0 +1,147 @@
+//! Declarative input coercion.
+//!
+//! JSON input arriving as raw bytes doesn't always match a handler's
+//! declared schema exactly -- a numeric field might arrive as the string
+//! `"42"` (common when params are forwarded from a CLI arg or a loosely
+//! typed client). Rather than make every handler tolerate that itself,
+//! [`crate::registry::HandlerRegistry::dispatch`] coerces primitive
+//! mismatches against the handler's `input_schema()` before deserializing,
+//! driven entirely by the schema rather than per-handler code.
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+
+/// Coerce the top-level object fields of `value` toward the primitive types
+/// declared in `schema`, in place. Only lossless, unambiguous conversions are
+/// applied (numeric/boolean strings, numbers/bools to strings); anything
+/// that doesn't parse cleanly is left untouched so serde still reports its
+/// usual deserialization error.
+pub fn coerce_to_schema(value: &mut Value, schema: &RootSchema) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+    let Some(object_validation) = &schema.schema.object else {
+        return;
+    };
+
+    for (key, prop_schema) in &object_validation.properties {
+        if let Some(field) = obj.get_mut(key) {
+            coerce_field(field, prop_schema);
+        }
+    }
+}
+
+/// Extract the declared primitive type of a schema node, if it has exactly
+/// one (ignoring `null` in a nullable union). Shared with [`crate::diagnostics`].
+pub(crate) fn instance_type(schema: &Schema) -> Option<InstanceType> {
+    let Schema::Object(SchemaObject { instance_type, .. }) = schema else {
+        return None;
+    };
+
+    match instance_type {
+        Some(SingleOrVec::Single(t)) => Some(**t),
+        Some(SingleOrVec::Vec(types)) => {
+            types.iter().find(|t| **t != InstanceType::Null).copied()
+        }
+        None => None,
+    }
+}
+
+fn coerce_field(value: &mut Value, schema: &Schema) {
+    let Some(target) = instance_type(schema) else {
+        return;
+    };
+
+    let coerced = match (&*value, target) {
+        (Value::String(s), InstanceType::Integer) => s.trim().parse::<i64>().ok().map(Value::from),
+        (Value::String(s), InstanceType::Number) => s.trim().parse::<f64>().ok().map(Value::from),
+        (Value::String(s), InstanceType::Boolean) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (Value::Number(n), InstanceType::String) => Some(Value::String(n.to_string())),
+        (Value::Bool(b), InstanceType::String) => Some(Value::String(b.to_string())),
+        _ => None,
+    };
+
+    if let Some(coerced) = coerced {
+        *value = coerced;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, JsonSchema)]
+    struct Example {
+        count: i32,
+        ratio: f64,
+        enabled: bool,
+        label: String,
+    }
+
+    #[test]
+    fn test_coerces_stringly_typed_primitives() {
+        let schema = schemars::schema_for!(Example);
+        let mut value = json!({
+            "count": "21",
+            "ratio": "1.5",
+            "enabled": "true",
+            "label": "already a string",
+        });
+
+        coerce_to_schema(&mut value, &schema);
+
+        assert_eq!(value["count"], json!(21));
+        assert_eq!(value["ratio"], json!(1.5));
+        assert_eq!(value["enabled"], json!(true));
+        assert_eq!(value["label"], json!("already a string"));
+
+        let parsed: Example = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.count, 21);
+        assert_eq!(parsed.label, "already a string");
+    }
+
+    #[test]
+    fn test_leaves_unparsable_strings_untouched() {
+        let schema = schemars::schema_for!(Example);
+        let mut value = json!({
+            "count": "not-a-number",
+            "ratio": 1.5,
+            "enabled": true,
+            "label": "ok",
+        });
+
+        coerce_to_schema(&mut value, &schema);
+
+        // Left as a string; serde will surface its own type-mismatch error.
+        assert_eq!(value["count"], json!("not-a-number"));
+    }
+
+    #[test]
+    fn test_coerces_numbers_and_bools_to_declared_strings() {
+        let schema = schemars::schema_for!(Example);
+        let mut value = json!({
+            "count": 21,
+            "ratio": 1.5,
+            "enabled": true,
+            "label": 42,
+        });
+
+        coerce_to_schema(&mut value, &schema);
+        assert_eq!(value["label"], json!("42"));
+    }
+
+    #[test]
+    fn test_non_object_value_is_left_alone() {
+        let schema = schemars::schema_for!(Example);
+        let mut value = json!([1, 2, 3]);
+        coerce_to_schema(&mut value, &schema);
+        assert_eq!(value, json!([1, 2, 3]));
+    }
+}
@@ -0,0 +1,361 @@
+//! Docker-backed integration fixtures for exercising a generated pforge
+//! server the way it actually runs in production - built into an image,
+//! started as a container, driven over its real `stdio` transport - rather
+//! than dispatching in-process against a [`crate::HandlerRegistry`].
+//!
+//! Every step shells out to the `docker` binary on `PATH`, the same way
+//! `pforge dev`'s rebuild step shells out to `cargo build`; nothing here
+//! talks to the Docker daemon directly.
+
+use crate::Error;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How assertion calls (and the readiness probe) reach the running
+/// container.
+#[derive(Debug, Clone, Copy)]
+pub enum ContainerTransport {
+    /// Attach to the container's stdin/stdout and speak the same
+    /// newline-delimited JSON-RPC [`crate::server::McpServer::run`]'s stdio
+    /// transport does. The only transport [`RunningContainer::call`]
+    /// actually drives.
+    Stdio,
+    /// A published TCP port. `sse`/`websocket` framing is out of scope for
+    /// this fixture, so only a connect-and-close readiness check is
+    /// supported on this transport - use `Stdio` to actually call tools.
+    Tcp { port: u16 },
+}
+
+/// Builds an ephemeral container fixture for a generated pforge server.
+/// Every setter takes and returns `self` so a fixture is assembled as
+/// `ContainerFixture::new(tag).mount_config(..).env(..).start()`.
+#[derive(Debug, Clone)]
+pub struct ContainerFixture {
+    image: String,
+    config_mount: Option<PathBuf>,
+    env: HashMap<String, String>,
+    transport: ContainerTransport,
+    readiness_probe: Option<(String, serde_json::Value)>,
+    readiness_timeout: Duration,
+}
+
+impl ContainerFixture {
+    /// Start building a fixture for `image`, an already-built (or
+    /// about-to-be-built via [`ContainerFixture::build_image`]) Docker image
+    /// tag.
+    pub fn new(image: impl Into<String>) -> Self {
+        Self {
+            image: image.into(),
+            config_mount: None,
+            env: HashMap::new(),
+            transport: ContainerTransport::Stdio,
+            readiness_probe: None,
+            readiness_timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Bind-mount `host_path` read-only at `/pforge.yaml` inside the
+    /// container, the path a generated server's Dockerfile is expected to
+    /// read its config from.
+    pub fn mount_config(mut self, host_path: impl Into<PathBuf>) -> Self {
+        self.config_mount = Some(host_path.into());
+        self
+    }
+
+    /// Set an environment variable inside the container.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn transport(mut self, transport: ContainerTransport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// A tool call that must succeed before [`ContainerFixture::start`]
+    /// returns; retried with a short fixed backoff until it succeeds or
+    /// `readiness_timeout` elapses. On [`ContainerTransport::Tcp`] the probe
+    /// is a connect to the published port instead of a tool call.
+    pub fn readiness_probe(mut self, tool: impl Into<String>, input: serde_json::Value) -> Self {
+        self.readiness_probe = Some((tool.into(), input));
+        self
+    }
+
+    pub fn readiness_timeout(mut self, timeout: Duration) -> Self {
+        self.readiness_timeout = timeout;
+        self
+    }
+
+    /// Build `image_tag` from the Dockerfile in `context_dir` via
+    /// `docker build`.
+    pub fn build_image(context_dir: &Path, image_tag: &str) -> crate::Result<()> {
+        let status = Command::new("docker")
+            .args(["build", "-t", image_tag, "."])
+            .current_dir(context_dir)
+            .status()
+            .map_err(Error::Io)?;
+
+        if !status.success() {
+            return Err(Error::Handler(format!(
+                "docker build failed with status: {}",
+                status
+            )));
+        }
+        Ok(())
+    }
+
+    /// Build the `docker run` argument vector for starting this fixture as
+    /// `name`, given the config mount (already canonicalized to
+    /// `absolute_config`, since canonicalizing requires touching the
+    /// filesystem and this function doesn't need to be fallible to be
+    /// tested). Kept separate from [`ContainerFixture::start`] so the
+    /// argument construction - env vars, the config mount, the published
+    /// port - can be unit-tested without actually invoking `docker`.
+    fn docker_run_args(&self, name: &str, absolute_config: Option<&str>) -> Vec<String> {
+        let mut args = vec![
+            "run".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            name.to_string(),
+            "-i".to_string(),
+        ];
+
+        if let Some(absolute) = absolute_config {
+            args.push("-v".to_string());
+            args.push(format!("{absolute}:/pforge.yaml:ro"));
+        }
+
+        let mut env_keys: Vec<&String> = self.env.keys().collect();
+        env_keys.sort_unstable();
+        for key in env_keys {
+            args.push("-e".to_string());
+            args.push(format!("{key}={}", self.env[key]));
+        }
+
+        if let ContainerTransport::Tcp { port } = self.transport {
+            args.push("-p".to_string());
+            args.push(format!("{port}:{port}"));
+        }
+
+        args.push(self.image.clone());
+        args
+    }
+
+    /// Start the container (`docker run --rm -i`), waiting for the
+    /// readiness probe to pass (if one was configured) before returning.
+    /// The returned [`RunningContainer`] stops and removes the container
+    /// when dropped.
+    pub fn start(&self) -> crate::Result<RunningContainer> {
+        let name = format!("pforge-fixture-{}", std::process::id());
+
+        let absolute_config = match &self.config_mount {
+            Some(config_path) => Some(
+                config_path
+                    .canonicalize()
+                    .map_err(Error::Io)?
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            None => None,
+        };
+        let args = self.docker_run_args(&name, absolute_config.as_deref());
+
+        let mut child = Command::new("docker")
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(Error::Io)?;
+
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take().map(BufReader::new);
+
+        let mut container = RunningContainer {
+            name,
+            transport: self.transport,
+            child,
+            stdin,
+            stdout,
+        };
+
+        if let Some((tool, input)) = &self.readiness_probe {
+            container.wait_ready(tool, input, self.readiness_timeout)?;
+        }
+
+        Ok(container)
+    }
+}
+
+/// A live container started by [`ContainerFixture::start`]. Stops and
+/// removes the container when dropped, so a test failure (panic or early
+/// return) can't leak it.
+pub struct RunningContainer {
+    name: String,
+    transport: ContainerTransport,
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: Option<BufReader<ChildStdout>>,
+}
+
+impl RunningContainer {
+    /// The `docker run --name` this container was started with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Dispatch one JSON-RPC request over the container's stdio transport
+    /// and return the decoded response envelope. Only meaningful when the
+    /// fixture was built with [`ContainerTransport::Stdio`].
+    pub fn call(&mut self, tool: &str, input: &serde_json::Value) -> crate::Result<serde_json::Value> {
+        match self.transport {
+            ContainerTransport::Stdio => self.call_stdio(tool, input),
+            ContainerTransport::Tcp { .. } => Err(Error::Handler(
+                "RunningContainer::call requires ContainerTransport::Stdio".to_string(),
+            )),
+        }
+    }
+
+    fn call_stdio(&mut self, tool: &str, input: &serde_json::Value) -> crate::Result<serde_json::Value> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| Error::Handler("container stdin is closed".to_string()))?;
+        let stdout = self
+            .stdout
+            .as_mut()
+            .ok_or_else(|| Error::Handler("container stdout is closed".to_string()))?;
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": tool,
+            "params": input,
+        });
+        writeln!(stdin, "{request}").map_err(Error::Io)?;
+        stdin.flush().map_err(Error::Io)?;
+
+        let mut line = String::new();
+        let bytes_read = stdout.read_line(&mut line).map_err(Error::Io)?;
+        if bytes_read == 0 {
+            return Err(Error::Handler(
+                "container closed stdout before responding".to_string(),
+            ));
+        }
+
+        let response: serde_json::Value = serde_json::from_str(line.trim())?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Handler(format!("container returned error: {error}")));
+        }
+        Ok(response.get("result").cloned().unwrap_or(serde_json::Value::Null))
+    }
+
+    fn wait_ready(
+        &mut self,
+        tool: &str,
+        input: &serde_json::Value,
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        let deadline = Instant::now() + timeout;
+        let mut last_err = None;
+
+        loop {
+            let attempt = match self.transport {
+                ContainerTransport::Stdio => self.call(tool, input).map(|_| ()),
+                ContainerTransport::Tcp { port } => std::net::TcpStream::connect(("127.0.0.1", port))
+                    .map(|_| ())
+                    .map_err(Error::Io),
+            };
+            match attempt {
+                Ok(()) => return Ok(()),
+                Err(e) => last_err = Some(e),
+            }
+
+            if Instant::now() >= deadline {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+
+        Err(last_err.unwrap_or_else(|| Error::Handler("readiness probe timed out".to_string())))
+    }
+}
+
+impl Drop for RunningContainer {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["stop", "-t", "1", &self.name])
+            .status();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docker_run_args_minimal() {
+        let fixture = ContainerFixture::new("pforge-example:latest");
+        let args = fixture.docker_run_args("pforge-fixture-1", None);
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "--rm",
+                "--name",
+                "pforge-fixture-1",
+                "-i",
+                "pforge-example:latest",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_docker_run_args_includes_config_mount() {
+        let fixture = ContainerFixture::new("pforge-example:latest");
+        let args = fixture.docker_run_args("pforge-fixture-1", Some("/abs/pforge.yaml"));
+        assert!(args.contains(&"-v".to_string()));
+        assert!(args.contains(&"/abs/pforge.yaml:/pforge.yaml:ro".to_string()));
+    }
+
+    #[test]
+    fn test_docker_run_args_includes_sorted_env_vars() {
+        let fixture = ContainerFixture::new("pforge-example:latest")
+            .env("ZEBRA", "1")
+            .env("APPLE", "2");
+        let args = fixture.docker_run_args("pforge-fixture-1", None);
+        let apple_idx = args.iter().position(|a| a == "APPLE=2").unwrap();
+        let zebra_idx = args.iter().position(|a| a == "ZEBRA=1").unwrap();
+        assert!(apple_idx < zebra_idx);
+    }
+
+    #[test]
+    fn test_docker_run_args_publishes_tcp_port() {
+        let fixture =
+            ContainerFixture::new("pforge-example:latest").transport(ContainerTransport::Tcp { port: 9000 });
+        let args = fixture.docker_run_args("pforge-fixture-1", None);
+        assert!(args.contains(&"-p".to_string()));
+        assert!(args.contains(&"9000:9000".to_string()));
+    }
+
+    #[test]
+    fn test_docker_run_args_stdio_transport_has_no_port_flag() {
+        let fixture = ContainerFixture::new("pforge-example:latest");
+        let args = fixture.docker_run_args("pforge-fixture-1", None);
+        assert!(!args.contains(&"-p".to_string()));
+    }
+
+    #[test]
+    fn test_fixture_defaults() {
+        let fixture = ContainerFixture::new("pforge-example:latest");
+        assert_eq!(fixture.readiness_timeout, Duration::from_secs(10));
+        assert!(fixture.readiness_probe.is_none());
+        assert!(fixture.config_mount.is_none());
+        assert!(matches!(fixture.transport, ContainerTransport::Stdio));
+    }
+}
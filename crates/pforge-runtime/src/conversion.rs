@@ -0,0 +1,381 @@
+//! Type coercion from raw (often string-typed) values to a tool's declared
+//! [`SimpleType`], driven by [`ParamType`]/[`Validation`] rather than ad hoc
+//! per-handler parsing.
+//!
+//! CLI arguments and HTTP query parameters always arrive as strings, but a
+//! tool's schema declares real types (`Integer`, `Float`, `Boolean`, ...).
+//! [`coerce_param`] bridges the two, consulting an explicit `coerce`
+//! override on `ParamType::Complex` when present and falling back to the
+//! conversion implied by the declared type otherwise.
+
+use crate::{Error, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use pforge_config::{ParamType, SimpleType, Validation};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// A single coercion strategy. Named variants are selected either from a
+/// schema's declared [`SimpleType`] or an explicit `coerce` name on
+/// `ParamType::Complex`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    /// Pass the value through unchanged.
+    AsIs,
+    /// Decode a base64 string into a byte array.
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    /// RFC3339 timestamp, normalized to UTC.
+    Timestamp,
+    /// Timestamp parsed with a caller-supplied `chrono` format, assumed UTC.
+    TimestampFmt(String),
+    /// Timestamp parsed with a caller-supplied `chrono` format that itself
+    /// carries a timezone offset.
+    TimestampTzFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(fmt) = s.strip_prefix("timestamp_tz|") {
+            return Ok(Conversion::TimestampTzFmt(fmt.to_string()));
+        }
+        if let Some(fmt) = s.strip_prefix("timestamp|") {
+            return Ok(Conversion::TimestampFmt(fmt.to_string()));
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "string" | "asis" => Ok(Conversion::AsIs),
+            "bytes" => Ok(Conversion::Bytes),
+            "int" | "integer" => Ok(Conversion::Integer),
+            "float" => Ok(Conversion::Float),
+            "bool" | "boolean" => Ok(Conversion::Boolean),
+            "timestamp" => Ok(Conversion::Timestamp),
+            other => Err(Error::Handler(format!("unknown conversion name: '{}'", other))),
+        }
+    }
+}
+
+impl From<SimpleType> for Conversion {
+    fn from(ty: SimpleType) -> Self {
+        match ty {
+            SimpleType::Integer => Conversion::Integer,
+            SimpleType::Float => Conversion::Float,
+            SimpleType::Boolean => Conversion::Boolean,
+            SimpleType::String | SimpleType::Array | SimpleType::Object => Conversion::AsIs,
+        }
+    }
+}
+
+/// Coerce `value` for `field` according to `param_type`'s declared type,
+/// honoring an explicit `coerce` override and `validation` range checks.
+/// Failures are reported as `Error::Handler` messages naming `field`.
+pub fn coerce_param(field: &str, param_type: &ParamType, value: &Value) -> Result<Value> {
+    let (ty, coerce, validation) = match param_type {
+        ParamType::Simple(ty) => (ty.clone(), None, None),
+        ParamType::Complex {
+            ty,
+            coerce,
+            validation,
+            ..
+        } => (ty.clone(), coerce.clone(), validation.as_ref()),
+    };
+
+    let conversion = match coerce {
+        Some(name) => Conversion::from_str(&name).map_err(|_| {
+            Error::Handler(format!("field '{}': unknown conversion '{}'", field, name))
+        })?,
+        None => Conversion::from(ty),
+    };
+
+    coerce_value(field, &conversion, value, validation)
+}
+
+fn coerce_value(
+    field: &str,
+    conversion: &Conversion,
+    value: &Value,
+    validation: Option<&Validation>,
+) -> Result<Value> {
+    match conversion {
+        Conversion::AsIs => Ok(value.clone()),
+        Conversion::Bytes => coerce_bytes(field, value),
+        Conversion::Integer => coerce_integer(field, value, validation),
+        Conversion::Float => coerce_float(field, value, validation),
+        Conversion::Boolean => coerce_boolean(field, value),
+        Conversion::Timestamp => coerce_timestamp(field, value, None),
+        Conversion::TimestampFmt(fmt) => coerce_timestamp(field, value, Some(fmt)),
+        Conversion::TimestampTzFmt(fmt) => coerce_timestamp_tz(field, value, fmt),
+    }
+}
+
+/// Name of `value`'s JSON type, for error messages that need to say what
+/// was actually found alongside what was expected.
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn as_str<'a>(field: &str, value: &'a Value) -> Result<&'a str> {
+    value.as_str().ok_or_else(|| {
+        Error::Handler(format!(
+            "field '{}': expected string, found {}",
+            field,
+            value_type_name(value)
+        ))
+    })
+}
+
+fn coerce_bytes(field: &str, value: &Value) -> Result<Value> {
+    use base64::Engine;
+    let s = as_str(field, value)?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(s)
+        .map_err(|e| Error::Handler(format!("field '{}': invalid base64: {}", field, e)))?;
+    Ok(Value::Array(bytes.into_iter().map(Value::from).collect()))
+}
+
+fn coerce_integer(field: &str, value: &Value, validation: Option<&Validation>) -> Result<Value> {
+    let n = match value {
+        Value::Number(n) => n.as_i64().ok_or_else(|| {
+            Error::Handler(format!("field '{}': expected integer, found number {}", field, n))
+        })?,
+        Value::String(s) => s.trim().parse::<i64>().map_err(|_| {
+            Error::Handler(format!(
+                "field '{}': expected integer, found string '{}'",
+                field, s
+            ))
+        })?,
+        other => {
+            return Err(Error::Handler(format!(
+                "field '{}': expected integer, found {}",
+                field,
+                value_type_name(other)
+            )))
+        }
+    };
+
+    check_range(field, n as f64, validation)?;
+    Ok(Value::from(n))
+}
+
+fn coerce_float(field: &str, value: &Value, validation: Option<&Validation>) -> Result<Value> {
+    let n = match value {
+        Value::Number(n) => n.as_f64().ok_or_else(|| {
+            Error::Handler(format!("field '{}': expected float, found number {}", field, n))
+        })?,
+        Value::String(s) => s.trim().parse::<f64>().map_err(|_| {
+            Error::Handler(format!(
+                "field '{}': expected float, found string '{}'",
+                field, s
+            ))
+        })?,
+        other => {
+            return Err(Error::Handler(format!(
+                "field '{}': expected float, found {}",
+                field,
+                value_type_name(other)
+            )))
+        }
+    };
+
+    check_range(field, n, validation)?;
+    Ok(Value::from(n))
+}
+
+fn check_range(field: &str, n: f64, validation: Option<&Validation>) -> Result<()> {
+    let Some(validation) = validation else {
+        return Ok(());
+    };
+
+    if let Some(min) = validation.min {
+        if n < min {
+            return Err(Error::Handler(format!(
+                "field '{}': {} is below minimum {}",
+                field, n, min
+            )));
+        }
+    }
+    if let Some(max) = validation.max {
+        if n > max {
+            return Err(Error::Handler(format!(
+                "field '{}': {} is above maximum {}",
+                field, n, max
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn coerce_boolean(field: &str, value: &Value) -> Result<Value> {
+    match value {
+        Value::Bool(b) => Ok(Value::Bool(*b)),
+        Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" => Ok(Value::Bool(false)),
+            other => Err(Error::Handler(format!(
+                "field '{}': expected boolean, found string '{}'",
+                field, other
+            ))),
+        },
+        Value::Number(n) if n.as_i64() == Some(1) => Ok(Value::Bool(true)),
+        Value::Number(n) if n.as_i64() == Some(0) => Ok(Value::Bool(false)),
+        other => Err(Error::Handler(format!(
+            "field '{}': expected boolean, found {}",
+            field,
+            value_type_name(other)
+        ))),
+    }
+}
+
+fn coerce_timestamp(field: &str, value: &Value, fmt: Option<&str>) -> Result<Value> {
+    let s = as_str(field, value)?;
+
+    let dt = match fmt {
+        None => DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| Error::Handler(format!("field '{}': invalid timestamp: {}", field, e)))?,
+        Some(fmt) => NaiveDateTime::parse_from_str(s, fmt)
+            .map(|naive| naive.and_utc())
+            .map_err(|e| Error::Handler(format!("field '{}': invalid timestamp: {}", field, e)))?,
+    };
+
+    Ok(Value::String(dt.to_rfc3339()))
+}
+
+fn coerce_timestamp_tz(field: &str, value: &Value, fmt: &str) -> Result<Value> {
+    let s = as_str(field, value)?;
+    let dt = DateTime::parse_from_str(s, fmt)
+        .map_err(|e| Error::Handler(format!("field '{}': invalid timestamp: {}", field, e)))?;
+    Ok(Value::String(dt.with_timezone(&Utc).to_rfc3339()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pforge_config::Validation;
+    use serde_json::json;
+
+    fn complex(ty: SimpleType, coerce: Option<&str>, validation: Option<Validation>) -> ParamType {
+        ParamType::Complex {
+            ty,
+            required: false,
+            default: None,
+            description: None,
+            validation,
+            coerce: coerce.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_conversion_from_str_names() {
+        assert_eq!(Conversion::from_str("string").unwrap(), Conversion::AsIs);
+        assert_eq!(Conversion::from_str("asis").unwrap(), Conversion::AsIs);
+        assert_eq!(Conversion::from_str("bytes").unwrap(), Conversion::Bytes);
+        assert_eq!(Conversion::from_str("int").unwrap(), Conversion::Integer);
+        assert_eq!(Conversion::from_str("integer").unwrap(), Conversion::Integer);
+        assert_eq!(Conversion::from_str("float").unwrap(), Conversion::Float);
+        assert_eq!(Conversion::from_str("bool").unwrap(), Conversion::Boolean);
+        assert_eq!(Conversion::from_str("boolean").unwrap(), Conversion::Boolean);
+        assert_eq!(Conversion::from_str("timestamp").unwrap(), Conversion::Timestamp);
+        assert_eq!(
+            Conversion::from_str("timestamp|%Y-%m-%d").unwrap(),
+            Conversion::TimestampFmt("%Y-%m-%d".to_string())
+        );
+        assert!(Conversion::from_str("nonsense").is_err());
+    }
+
+    #[test]
+    fn test_coerce_integer_from_string_with_range() {
+        let ty = complex(
+            SimpleType::Integer,
+            None,
+            Some(Validation {
+                min: Some(0.0),
+                max: Some(10.0),
+                pattern: None,
+                min_length: None,
+                max_length: None,
+            }),
+        );
+
+        assert_eq!(coerce_param("count", &ty, &json!("5")).unwrap(), json!(5));
+        assert!(coerce_param("count", &ty, &json!("100")).is_err());
+        assert!(coerce_param("count", &ty, &json!("not-a-number")).is_err());
+    }
+
+    #[test]
+    fn test_coerce_float_from_string() {
+        let ty = complex(SimpleType::Float, None, None);
+        assert_eq!(coerce_param("ratio", &ty, &json!("1.5")).unwrap(), json!(1.5));
+    }
+
+    #[test]
+    fn test_coerce_boolean_variants() {
+        let ty = ParamType::Simple(SimpleType::Boolean);
+        for (raw, expected) in [
+            ("true", true),
+            ("1", true),
+            ("yes", true),
+            ("false", false),
+            ("0", false),
+            ("no", false),
+        ] {
+            assert_eq!(coerce_param("flag", &ty, &json!(raw)).unwrap(), json!(expected));
+        }
+        assert!(coerce_param("flag", &ty, &json!("maybe")).is_err());
+    }
+
+    #[test]
+    fn test_coerce_timestamp_rfc3339() {
+        let ty = ParamType::Simple(SimpleType::String);
+        let value = coerce_param(
+            "created_at",
+            &complex(SimpleType::String, Some("timestamp"), None),
+            &json!("2024-01-15T10:30:00Z"),
+        )
+        .unwrap();
+        assert_eq!(value, json!("2024-01-15T10:30:00+00:00"));
+        let _ = ty; // Simple(String) is the default conversion target (AsIs)
+    }
+
+    #[test]
+    fn test_coerce_timestamp_with_custom_format() {
+        let ty = complex(SimpleType::String, Some("timestamp|%Y-%m-%d"), None);
+        let value = coerce_param("day", &ty, &json!("2024-01-15")).unwrap();
+        assert_eq!(value, json!("2024-01-15T00:00:00+00:00"));
+    }
+
+    #[test]
+    fn test_explicit_coerce_overrides_declared_type() {
+        // Declared type is Integer but an explicit `coerce: "bytes"` wins.
+        use base64::Engine;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(b"hi");
+        let ty = complex(SimpleType::Integer, Some("bytes"), None);
+        let value = coerce_param("payload", &ty, &json!(encoded)).unwrap();
+        assert_eq!(value, json!([104, 105]));
+    }
+
+    #[test]
+    fn test_error_names_the_field() {
+        let ty = ParamType::Simple(SimpleType::Integer);
+        let err = coerce_param("age", &ty, &json!("old")).unwrap_err();
+        assert!(err.to_string().contains("age"));
+    }
+
+    #[test]
+    fn test_error_names_expected_and_found_type() {
+        let ty = ParamType::Simple(SimpleType::Integer);
+        let err = coerce_param("age", &ty, &json!(true)).unwrap_err().to_string();
+        assert!(err.contains("expected integer"));
+        assert!(err.contains("found boolean"));
+    }
+}
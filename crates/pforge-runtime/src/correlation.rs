@@ -0,0 +1,149 @@
+//! Mnemonic correlation IDs for tracing a single request across the
+//! middleware chain and into telemetry output.
+//!
+//! Numeric or UUID correlation IDs are precise but unreadable in logs;
+//! `swift-falcon-0` is easy to eyeball when grepping output or diffing two
+//! terminal panes during a debugging session.
+
+use crate::middleware::{BeforeOutcome, Extensions, Middleware};
+use crate::{Error, Result};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const FIELD: &str = "_correlation_id";
+
+const ADJECTIVES: &[&str] = &[
+    "swift", "quiet", "brave", "calm", "eager", "lucky", "mellow", "nimble", "proud", "sunny",
+];
+
+const NOUNS: &[&str] = &[
+    "falcon", "otter", "comet", "maple", "harbor", "ember", "canyon", "ridge", "willow", "delta",
+];
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generate a human-readable correlation ID of the form `adjective-noun-n`,
+/// e.g. `nimble-falcon-7`. The trailing counter guarantees uniqueness within
+/// a process even if the word pair repeats.
+pub fn generate_correlation_id() -> String {
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let adjective = ADJECTIVES[(n as usize) % ADJECTIVES.len()];
+    let noun = NOUNS[(n as usize / ADJECTIVES.len()) % NOUNS.len()];
+    format!("{}-{}-{}", adjective, noun, n)
+}
+
+/// Extract the correlation ID from a request, if one was already assigned.
+pub fn correlation_id_of(request: &Value) -> Option<String> {
+    request
+        .get(FIELD)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Middleware that assigns a mnemonic correlation ID to every request that
+/// doesn't already carry one, then echoes it back on the response and prints
+/// it with any error to stderr (the error itself is returned unchanged), so
+/// downstream middleware (and log lines emitted by
+/// [`crate::middleware::LoggingMiddleware`]) can correlate the two.
+pub struct CorrelationIdMiddleware;
+
+impl CorrelationIdMiddleware {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn stamp_response(id: &str, mut response: Value) -> Value {
+        if let Value::Object(ref mut obj) = response {
+            obj.insert(FIELD.to_string(), Value::String(id.to_string()));
+        }
+        response
+    }
+}
+
+impl Default for CorrelationIdMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for CorrelationIdMiddleware {
+    async fn before(&self, mut request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        if correlation_id_of(&request).is_none() {
+            if let Value::Object(ref mut obj) = request {
+                obj.insert(FIELD.to_string(), Value::String(generate_correlation_id()));
+            }
+        }
+        Ok(BeforeOutcome::Continue(request))
+    }
+
+    async fn after(&self, request: Value, response: Value, _extensions: &Extensions) -> Result<Value> {
+        match correlation_id_of(&request) {
+            Some(id) => Ok(Self::stamp_response(&id, response)),
+            None => Ok(response),
+        }
+    }
+
+    async fn on_error(&self, request: Value, error: Error, _extensions: &Extensions) -> Result<Value> {
+        if let Some(id) = correlation_id_of(&request) {
+            eprintln!("[{}] error: {}", id, error);
+        }
+        Err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_generate_correlation_id_is_mnemonic_and_unique() {
+        let a = generate_correlation_id();
+        let b = generate_correlation_id();
+        assert_ne!(a, b);
+        assert!(a.chars().filter(|c| *c == '-').count() >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_assigns_id_when_missing() {
+        let middleware = CorrelationIdMiddleware::new();
+        let mut extensions = Extensions::new();
+        let request = match middleware
+            .before(json!({"input": 1}), &mut extensions)
+            .await
+            .unwrap()
+        {
+            BeforeOutcome::Continue(request) => request,
+            BeforeOutcome::ShortCircuit(_) => panic!("must not short-circuit"),
+        };
+        assert!(correlation_id_of(&request).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_preserves_existing_id() {
+        let middleware = CorrelationIdMiddleware::new();
+        let mut extensions = Extensions::new();
+        let request = match middleware
+            .before(json!({"_correlation_id": "given-id-0"}), &mut extensions)
+            .await
+            .unwrap()
+        {
+            BeforeOutcome::Continue(request) => request,
+            BeforeOutcome::ShortCircuit(_) => panic!("must not short-circuit"),
+        };
+        assert_eq!(correlation_id_of(&request).as_deref(), Some("given-id-0"));
+    }
+
+    #[tokio::test]
+    async fn test_stamps_response_with_request_id() {
+        let middleware = CorrelationIdMiddleware::new();
+        let extensions = Extensions::new();
+        let request = json!({"_correlation_id": "given-id-1"});
+        let response = middleware
+            .after(request, json!({"ok": true}), &extensions)
+            .await
+            .unwrap();
+        assert_eq!(response["_correlation_id"], "given-id-1");
+    }
+}
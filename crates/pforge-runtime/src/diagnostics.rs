@@ -0,0 +1,151 @@
+//! Structured validation diagnostics.
+//!
+//! When a handler's declared schema rejects an input, `serde_json`'s error
+//! is a single opaque message (e.g. "invalid type: string, expected i32 at
+//! line 1 column 12"). For a hand-edited JSON/YAML payload, a field-by-field
+//! diagnosis with an autofix suggestion is much faster to act on than
+//! re-reading the schema.
+
+use crate::coerce::instance_type;
+use schemars::schema::{InstanceType, RootSchema};
+use serde_json::Value;
+
+/// A single field-level validation problem, with an optional suggested fix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationDiagnostic {
+    pub field: String,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// Diagnose why `value` doesn't satisfy `schema`'s top-level object shape,
+/// producing one diagnostic per missing or mismatched field. Returns an
+/// empty vec if this shallow inspection finds nothing wrong (the original
+/// serde error should be surfaced in that case).
+pub fn diagnose(value: &Value, schema: &RootSchema) -> Vec<ValidationDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let Some(object_validation) = &schema.schema.object else {
+        return diagnostics;
+    };
+
+    let obj = value.as_object();
+
+    for required in &object_validation.required {
+        let missing = match obj {
+            Some(o) => !o.contains_key(required),
+            None => true,
+        };
+        if missing {
+            diagnostics.push(ValidationDiagnostic {
+                field: required.clone(),
+                message: "missing required field".to_string(),
+                suggestion: Some(format!("add a \"{}\" field to the input", required)),
+            });
+        }
+    }
+
+    if let Some(obj) = obj {
+        for (key, prop_schema) in &object_validation.properties {
+            let Some(field_value) = obj.get(key) else {
+                continue;
+            };
+            let Some(expected) = instance_type(prop_schema) else {
+                continue;
+            };
+            if !matches_instance_type(field_value, expected) {
+                diagnostics.push(ValidationDiagnostic {
+                    field: key.clone(),
+                    message: format!(
+                        "expected {:?}, got {}",
+                        expected,
+                        value_kind(field_value)
+                    ),
+                    suggestion: suggest_fix(field_value, expected),
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn matches_instance_type(value: &Value, expected: InstanceType) -> bool {
+    matches!(
+        (value, expected),
+        (Value::Null, InstanceType::Null)
+            | (Value::Bool(_), InstanceType::Boolean)
+            | (Value::Number(_), InstanceType::Integer)
+            | (Value::Number(_), InstanceType::Number)
+            | (Value::String(_), InstanceType::String)
+            | (Value::Array(_), InstanceType::Array)
+            | (Value::Object(_), InstanceType::Object)
+    )
+}
+
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn suggest_fix(value: &Value, expected: InstanceType) -> Option<String> {
+    match (value, expected) {
+        (Value::String(s), InstanceType::Integer | InstanceType::Number | InstanceType::Boolean) => {
+            Some(format!("remove the quotes: {} instead of \"{}\"", s, s))
+        }
+        (Value::Number(n), InstanceType::String) => Some(format!("quote the value: \"{}\"", n)),
+        (Value::Bool(b), InstanceType::String) => Some(format!("quote the value: \"{}\"", b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, JsonSchema)]
+    struct Example {
+        count: i32,
+        label: String,
+    }
+
+    #[test]
+    fn test_diagnoses_missing_required_field() {
+        let schema = schemars::schema_for!(Example);
+        let value = json!({"count": 1});
+
+        let diagnostics = diagnose(&value, &schema);
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.field == "label" && d.message.contains("missing")));
+    }
+
+    #[test]
+    fn test_diagnoses_type_mismatch_with_suggestion() {
+        let schema = schemars::schema_for!(Example);
+        let value = json!({"count": "not-a-number", "label": "ok"});
+
+        let diagnostics = diagnose(&value, &schema);
+        let count_diag = diagnostics.iter().find(|d| d.field == "count").unwrap();
+        assert!(count_diag.message.contains("Integer"));
+        assert_eq!(
+            count_diag.suggestion.as_deref(),
+            Some("remove the quotes: not-a-number instead of \"not-a-number\"")
+        );
+    }
+
+    #[test]
+    fn test_no_diagnostics_for_valid_value() {
+        let schema = schemars::schema_for!(Example);
+        let value = json!({"count": 1, "label": "ok"});
+        assert!(diagnose(&value, &schema).is_empty());
+    }
+}
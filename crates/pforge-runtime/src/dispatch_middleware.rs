@@ -0,0 +1,466 @@
+//! Middleware over [`crate::HandlerRegistry::dispatch`] itself.
+//!
+//! [`crate::middleware::Middleware`] wraps a single handler invocation once
+//! it's already been resolved to a `serde_json::Value` request/response.
+//! This is a level below that: it wraps the raw `(tool, params)` dispatch
+//! path so concerns like rate limiting or latency recording apply uniformly
+//! to every registered tool without each handler (or caller) opting in.
+
+use crate::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Middleware around the handler-registry dispatch path.
+#[async_trait::async_trait]
+pub trait DispatchMiddleware: Send + Sync {
+    /// Handle a dispatch, either short-circuiting or forwarding to `next`.
+    async fn handle(&self, tool: &str, params: &[u8], next: Next<'_>) -> Result<Vec<u8>>;
+}
+
+/// The remaining middleware chain, terminating in the real handler dispatch.
+pub struct Next<'a> {
+    pub(crate) middlewares: &'a [Arc<dyn DispatchMiddleware>],
+    pub(crate) registry: &'a crate::HandlerRegistry,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(middlewares: &'a [Arc<dyn DispatchMiddleware>], registry: &'a crate::HandlerRegistry) -> Self {
+        Self {
+            middlewares,
+            registry,
+        }
+    }
+
+    /// Invoke the next middleware in the chain, or the terminal handler
+    /// dispatch if this was the last one. Takes `&self` (rather than
+    /// consuming it) so a middleware like [`DispatchRetry`] can call it more
+    /// than once for the same dispatch.
+    pub async fn call(&self, tool: &str, params: &[u8]) -> Result<Vec<u8>> {
+        match self.middlewares.split_first() {
+            Some((middleware, rest)) => {
+                let next = Next::new(rest, self.registry);
+                middleware.handle(tool, params, next).await
+            }
+            None => self.registry.dispatch_raw(tool, params).await,
+        }
+    }
+}
+
+/// Enforces a per-tool (or default) deadline on dispatch, the
+/// [`DispatchMiddleware`] equivalent of [`crate::timeout::TimeoutMiddleware`]
+/// - unlike that one, this actually sits on [`crate::HandlerRegistry`]'s real
+/// dispatch path, so a `timeout_ms` the config declares has a runtime effect
+/// instead of being silently ignored.
+pub struct DispatchTimeout {
+    default_timeout: Option<Duration>,
+    per_tool: HashMap<String, Duration>,
+}
+
+impl DispatchTimeout {
+    pub fn new() -> Self {
+        Self {
+            default_timeout: None,
+            per_tool: HashMap::new(),
+        }
+    }
+
+    /// Set the deadline applied to tools with no tool-specific entry.
+    pub fn with_default(mut self, duration: Duration) -> Self {
+        self.default_timeout = Some(duration);
+        self
+    }
+
+    /// Override the deadline for one tool, e.g. from its declared
+    /// `ToolDef::Native { timeout_ms, .. }`.
+    pub fn with_tool_timeout_ms(mut self, tool: impl Into<String>, timeout_ms: u64) -> Self {
+        self.per_tool
+            .insert(tool.into(), Duration::from_millis(timeout_ms));
+        self
+    }
+
+    fn timeout_for(&self, tool: &str) -> Option<Duration> {
+        self.per_tool.get(tool).copied().or(self.default_timeout)
+    }
+}
+
+impl Default for DispatchTimeout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchMiddleware for DispatchTimeout {
+    async fn handle(&self, tool: &str, params: &[u8], next: Next<'_>) -> Result<Vec<u8>> {
+        match self.timeout_for(tool) {
+            Some(duration) => match tokio::time::timeout(duration, next.call(tool, params)).await {
+                Ok(result) => result,
+                Err(_) => Err(crate::Error::Timeout),
+            },
+            None => next.call(tool, params).await,
+        }
+    }
+}
+
+/// Retries a dispatch under `policy` on every retryable failure, the
+/// [`DispatchMiddleware`] equivalent of [`crate::timeout::RetryMiddleware`]
+/// - built on the same [`crate::timeout::retry_with_policy`] this runs on
+/// [`crate::HandlerRegistry`]'s real dispatch path rather than the unwired
+/// `middleware::Middleware` chain, re-invoking `next` (and therefore
+/// re-serializing `params` into a fresh `Handler::handle` call) per attempt.
+pub struct DispatchRetry {
+    policy: crate::timeout::RetryPolicy,
+}
+
+impl DispatchRetry {
+    pub fn new(policy: crate::timeout::RetryPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchMiddleware for DispatchRetry {
+    async fn handle(&self, tool: &str, params: &[u8], next: Next<'_>) -> Result<Vec<u8>> {
+        crate::timeout::retry_with_policy(&self.policy, || next.call(tool, params)).await
+    }
+}
+
+/// Per-tool token-bucket rate limiter. Each tool gets its own bucket with
+/// `capacity` tokens, refilling at `refill_per_sec` tokens/second.
+pub struct TokenBucketRateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: dashmap::DashMap<String, Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: std::time::Instant,
+}
+
+impl TokenBucketRateLimiter {
+    pub fn new(capacity: f64, refill_per_sec: f64) -> Self {
+        Self {
+            capacity,
+            refill_per_sec,
+            buckets: dashmap::DashMap::new(),
+        }
+    }
+
+    fn try_acquire(&self, tool: &str) -> bool {
+        let mut bucket = self.buckets.entry(tool.to_string()).or_insert_with(|| Bucket {
+            tokens: self.capacity,
+            last_refill: std::time::Instant::now(),
+        });
+
+        let now = std::time::Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchMiddleware for TokenBucketRateLimiter {
+    async fn handle(&self, tool: &str, params: &[u8], next: Next<'_>) -> Result<Vec<u8>> {
+        if self.try_acquire(tool) {
+            next.call(tool, params).await
+        } else {
+            Err(crate::Error::Handler(format!(
+                "rate limit exceeded for tool '{}'",
+                tool
+            )))
+        }
+    }
+}
+
+/// Records dispatch latency per tool into a [`crate::telemetry::MetricsCollector`].
+pub struct DispatchLatencyRecorder {
+    collector: crate::telemetry::MetricsCollector,
+}
+
+impl DispatchLatencyRecorder {
+    pub fn new(collector: crate::telemetry::MetricsCollector) -> Self {
+        Self { collector }
+    }
+
+    pub fn collector(&self) -> &crate::telemetry::MetricsCollector {
+        &self.collector
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchMiddleware for DispatchLatencyRecorder {
+    async fn handle(&self, tool: &str, params: &[u8], next: Next<'_>) -> Result<Vec<u8>> {
+        let start = std::time::Instant::now();
+        let result = next.call(tool, params).await;
+        self.collector
+            .record_request(tool, start.elapsed(), result.is_ok());
+        result
+    }
+}
+
+/// Checks a handler's serialized output against its declared
+/// `Handler::output_schema()` once dispatch succeeds, raising
+/// [`crate::Error::OutputValidation`] on drift instead of letting a
+/// malformed value propagate - most valuable for `pipeline` tools, where
+/// one step's output otherwise feeds later steps' `input` (via its
+/// `output_var`) unchecked. Reuses [`crate::diagnostics::diagnose`] (the same shallow,
+/// dependency-free schema check `HandlerEntryImpl::decode_input` already
+/// runs on input) rather than pulling in a full JSON Schema validator.
+pub struct OutputValidator;
+
+impl OutputValidator {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for OutputValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl DispatchMiddleware for OutputValidator {
+    async fn handle(&self, tool: &str, params: &[u8], next: Next<'_>) -> Result<Vec<u8>> {
+        let registry = next.registry;
+        let output = next.call(tool, params).await?;
+
+        if let Some(schema) = registry.get_output_schema(tool) {
+            let value: serde_json::Value = serde_json::from_slice(&output)?;
+            let diagnostics = crate::diagnostics::diagnose(&value, &schema);
+            if !diagnostics.is_empty() {
+                return Err(crate::Error::OutputValidation(diagnostics));
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Handler, HandlerRegistry};
+    use async_trait::async_trait;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct Input {
+        value: i32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct Output {
+        value: i32,
+    }
+
+    struct EchoHandler;
+
+    #[async_trait]
+    impl Handler for EchoHandler {
+        type Input = Input;
+        type Output = Output;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            Ok(Output { value: input.value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_allows_then_blocks() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+        registry.register_middleware(Arc::new(TokenBucketRateLimiter::new(1.0, 0.0)));
+
+        let input = serde_json::to_vec(&Input { value: 1 }).unwrap();
+
+        assert!(registry.dispatch("echo", &input).await.is_ok());
+        let second = registry.dispatch("echo", &input).await;
+        assert!(second.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_latency_recorder_records_tool() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+
+        let collector = crate::telemetry::MetricsCollector::new();
+        registry.register_middleware(Arc::new(DispatchLatencyRecorder::new(collector.clone())));
+
+        let input = serde_json::to_vec(&Input { value: 1 }).unwrap();
+        registry.dispatch("echo", &input).await.unwrap();
+
+        assert_eq!(collector.get_request_count("echo"), 1);
+    }
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct OutputWithExtraRequiredField {
+        value: i32,
+        extra: i32,
+    }
+
+    /// Declares a schema requiring a field its `handle` never actually
+    /// emits, simulating the schema/output drift [`OutputValidator`] exists
+    /// to catch.
+    struct DriftingHandler;
+
+    #[async_trait]
+    impl Handler for DriftingHandler {
+        type Input = Input;
+        type Output = Output;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            Ok(Output { value: input.value })
+        }
+
+        fn output_schema() -> schemars::schema::RootSchema {
+            schemars::schema_for!(OutputWithExtraRequiredField)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_output_validator_catches_schema_drift() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("drifting", DriftingHandler);
+        registry.register_middleware(Arc::new(OutputValidator::new()));
+
+        let input = serde_json::to_vec(&Input { value: 1 }).unwrap();
+        let result = registry.dispatch("drifting", &input).await;
+
+        match result.unwrap_err() {
+            Error::OutputValidation(diagnostics) => {
+                assert!(diagnostics.iter().any(|d| d.field == "extra"));
+            }
+            other => panic!("expected Error::OutputValidation, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_output_validator_passes_matching_output() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+        registry.register_middleware(Arc::new(OutputValidator::new()));
+
+        let input = serde_json::to_vec(&Input { value: 7 }).unwrap();
+        let result = registry.dispatch("echo", &input).await.unwrap();
+        let output: Output = serde_json::from_slice(&result).unwrap();
+        assert_eq!(output.value, 7);
+    }
+
+    #[tokio::test]
+    async fn test_empty_middleware_chain_still_dispatches() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+
+        let input = serde_json::to_vec(&Input { value: 5 }).unwrap();
+        let result = registry.dispatch("echo", &input).await.unwrap();
+        let output: Output = serde_json::from_slice(&result).unwrap();
+        assert_eq!(output.value, 5);
+    }
+
+    struct SlowHandler;
+
+    #[async_trait]
+    impl Handler for SlowHandler {
+        type Input = Input;
+        type Output = Output;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            Ok(Output { value: input.value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_timeout_aborts_slow_tool() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("slow", SlowHandler);
+        registry.register_middleware(Arc::new(
+            DispatchTimeout::new().with_tool_timeout_ms("slow", 1),
+        ));
+
+        let input = serde_json::to_vec(&Input { value: 1 }).unwrap();
+        let result = registry.dispatch("slow", &input).await;
+
+        assert!(matches!(result.unwrap_err(), Error::Timeout));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_timeout_passes_through_fast_tool() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+        registry.register_middleware(Arc::new(
+            DispatchTimeout::new().with_tool_timeout_ms("echo", 1_000),
+        ));
+
+        let input = serde_json::to_vec(&Input { value: 9 }).unwrap();
+        let result = registry.dispatch("echo", &input).await.unwrap();
+        let output: Output = serde_json::from_slice(&result).unwrap();
+        assert_eq!(output.value, 9);
+    }
+
+    /// Fails with a retryable error the first two calls, then succeeds -
+    /// exercises [`DispatchRetry`] retrying through the real dispatch path.
+    struct FlakyHandler {
+        remaining_failures: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Handler for FlakyHandler {
+        type Input = Input;
+        type Output = Output;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            if self
+                .remaining_failures
+                .fetch_update(
+                    std::sync::atomic::Ordering::SeqCst,
+                    std::sync::atomic::Ordering::SeqCst,
+                    |n| if n > 0 { Some(n - 1) } else { None },
+                )
+                .is_ok()
+            {
+                return Err(crate::Error::Handler("request timed out".to_string()));
+            }
+            Ok(Output { value: input.value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_retry_recovers_from_flaky_handler() {
+        let mut registry = HandlerRegistry::new();
+        registry.register(
+            "flaky",
+            FlakyHandler {
+                remaining_failures: std::sync::atomic::AtomicUsize::new(2),
+            },
+        );
+        registry.register_middleware(Arc::new(DispatchRetry::new(
+            crate::timeout::RetryPolicy::new(3).with_backoff(
+                std::time::Duration::from_millis(1),
+                std::time::Duration::from_millis(5),
+            ),
+        )));
+
+        let input = serde_json::to_vec(&Input { value: 3 }).unwrap();
+        let result = registry.dispatch("flaky", &input).await.unwrap();
+        let output: Output = serde_json::from_slice(&result).unwrap();
+        assert_eq!(output.value, 3);
+    }
+}
@@ -19,6 +19,158 @@ pub enum Error {
 
     #[error("Timeout error")]
     Timeout,
+
+    #[error("Decryption failed: {0}")]
+    Decryption(String),
+
+    #[error("Validation failed: {0:?}")]
+    Validation(Vec<crate::diagnostics::ValidationDiagnostic>),
+
+    /// Raised by [`crate::dispatch_middleware::OutputValidator`] when
+    /// `forge.validate_output: true` is set and a handler's serialized
+    /// output doesn't satisfy its own declared `Handler::output_schema()`.
+    #[error("Output validation failed: {0:?}")]
+    OutputValidation(Vec<crate::diagnostics::ValidationDiagnostic>),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Codec error: {0}")]
+    Codec(String),
+
+    /// Lets a `Handler` tag its own error with an explicit [`ErrorKind`]
+    /// instead of relying on [`Error::classify`]'s generic, text-based
+    /// mapping - e.g. `Error::Classified(ErrorKind::ClientError, "bad
+    /// input".into())` for a failure the handler already knows isn't worth
+    /// retrying.
+    #[error("{1}")]
+    Classified(ErrorKind, String),
+}
+
+impl Error {
+    /// Classify this error for retry purposes. [`crate::timeout::RetryPolicy`]
+    /// consults this (when no [`crate::timeout::RetryPolicy::retry_if`]
+    /// override is set) instead of pattern-matching error text itself, so
+    /// the mapping from error to retry behavior lives in one place.
+    ///
+    /// Handler implementors who want to tag their own failures directly
+    /// (rather than relying on this generic mapping) can return
+    /// `Error::Classified(kind, message)` from `Handler::handle`, which
+    /// always classifies as `kind`.
+    pub fn classify(&self) -> ErrorKind {
+        match self {
+            Error::Classified(kind, _) => *kind,
+            Error::Timeout => ErrorKind::Transient,
+            Error::Io(_) => ErrorKind::Transient,
+            Error::Http(msg) => classify_http_status(msg),
+            Error::Handler(msg) => {
+                if msg.contains("timeout") || msg.contains("timed out") {
+                    ErrorKind::Transient
+                } else if msg.contains("connection") || msg.contains("temporary") {
+                    ErrorKind::ServerError
+                } else {
+                    ErrorKind::Unrecoverable
+                }
+            }
+            Error::ToolNotFound(_)
+            | Error::Validation(_)
+            | Error::OutputValidation(_)
+            | Error::Unauthorized(_)
+            | Error::Serialization(_) => ErrorKind::ClientError,
+            Error::Decryption(_) | Error::Codec(_) => ErrorKind::Unrecoverable,
+        }
+    }
+}
+
+/// Classify an `Error::Http` message by its leading HTTP status code (e.g.
+/// `"503 Service Unavailable"`). A code that can't be parsed is treated as
+/// [`ErrorKind::Unrecoverable`] - safer than guessing it's retryable.
+fn classify_http_status(msg: &str) -> ErrorKind {
+    let code = msg.split_whitespace().next().and_then(|t| t.parse::<u16>().ok());
+    match code {
+        Some(429) => ErrorKind::Throttling,
+        Some(c) if (500..600).contains(&c) => ErrorKind::ServerError,
+        Some(c) if (400..500).contains(&c) => ErrorKind::ClientError,
+        _ => ErrorKind::Unrecoverable,
+    }
+}
+
+/// Coarse classification of an [`Error`] for retry decisions, returned by
+/// [`Error::classify`]. Keeps the retry subsystem's "should this retry, and
+/// how aggressively" logic driven by error semantics rather than string
+/// matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Likely transient (e.g. a timeout or I/O hiccup) - retry normally.
+    Transient,
+    /// The remote is explicitly asking callers to slow down (e.g. HTTP 429)
+    /// - retry, but with a longer, always-jittered backoff.
+    Throttling,
+    /// The remote is unhealthy (e.g. HTTP 5xx) - retry normally.
+    ServerError,
+    /// The request itself was invalid (e.g. HTTP 4xx, a validation error) -
+    /// never retry, since repeating it changes nothing.
+    ClientError,
+    /// Not worth retrying under any circumstance.
+    Unrecoverable,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_transient() {
+        assert_eq!(Error::Timeout.classify(), ErrorKind::Transient);
+        assert_eq!(
+            Error::Handler("request timed out".to_string()).classify(),
+            ErrorKind::Transient
+        );
+        assert_eq!(
+            Error::Io(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset")).classify(),
+            ErrorKind::Transient
+        );
+    }
+
+    #[test]
+    fn test_classify_http_by_status_code() {
+        assert_eq!(
+            Error::Http("429 Too Many Requests".to_string()).classify(),
+            ErrorKind::Throttling
+        );
+        assert_eq!(
+            Error::Http("503 Service Unavailable".to_string()).classify(),
+            ErrorKind::ServerError
+        );
+        assert_eq!(
+            Error::Http("404 Not Found".to_string()).classify(),
+            ErrorKind::ClientError
+        );
+        assert_eq!(
+            Error::Http("not a status code".to_string()).classify(),
+            ErrorKind::Unrecoverable
+        );
+    }
+
+    #[test]
+    fn test_classify_client_and_unrecoverable_never_retry_candidates() {
+        assert_eq!(
+            Error::Validation(vec![]).classify(),
+            ErrorKind::ClientError
+        );
+        assert_eq!(Error::Codec("bad frame".to_string()).classify(), ErrorKind::Unrecoverable);
+        assert_eq!(
+            Error::Handler("fatal error".to_string()).classify(),
+            ErrorKind::Unrecoverable
+        );
+    }
+
+    #[test]
+    fn test_classified_variant_uses_explicit_kind() {
+        let error = Error::Classified(ErrorKind::Throttling, "slow down".to_string());
+        assert_eq!(error.classify(), ErrorKind::Throttling);
+        assert_eq!(error.to_string(), "slow down");
+    }
+}
@@ -0,0 +1,138 @@
+//! HTTP/1.1 chunked transfer-encoding decoder.
+//!
+//! Used by [`super::http::HttpHandler`] when talking to upstreams that stream
+//! their response body instead of sending a `Content-Length`.
+
+use crate::{Error, Result};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+/// Read a chunked-encoded body to completion and return the reassembled bytes.
+///
+/// Handles chunk-size lines with `;`-delimited extensions (which are ignored)
+/// and stops at the terminating zero-length chunk, skipping any trailing
+/// headers that follow it.
+pub async fn decode_chunked_body<R>(reader: &mut R) -> Result<Vec<u8>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut body = Vec::new();
+
+    loop {
+        let chunk_size = read_chunk_size(reader).await?;
+
+        if chunk_size == 0 {
+            skip_trailing_headers(reader).await?;
+            break;
+        }
+
+        let mut chunk = vec![0u8; chunk_size];
+        reader
+            .read_exact(&mut chunk)
+            .await
+            .map_err(|e| Error::Http(format!("Failed to read chunk body: {}", e)))?;
+        body.extend_from_slice(&chunk);
+
+        // Each chunk is followed by a trailing CRLF.
+        consume_crlf(reader).await?;
+    }
+
+    Ok(body)
+}
+
+async fn read_chunk_size<R>(reader: &mut R) -> Result<usize>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .await
+        .map_err(|e| Error::Http(format!("Failed to read chunk size line: {}", e)))?;
+
+    // Strip chunk extensions (";name=value") and surrounding whitespace.
+    let size_str = line.trim().split(';').next().unwrap_or("").trim();
+
+    usize::from_str_radix(size_str, 16)
+        .map_err(|e| Error::Http(format!("Invalid chunk size '{}': {}", size_str, e)))
+}
+
+async fn consume_crlf<R>(reader: &mut R) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut crlf = [0u8; 2];
+    reader
+        .read_exact(&mut crlf)
+        .await
+        .map_err(|e| Error::Http(format!("Failed to read chunk trailer: {}", e)))?;
+    Ok(())
+}
+
+async fn skip_trailing_headers<R>(reader: &mut R) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        let n = reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| Error::Http(format!("Failed to read trailing header: {}", e)))?;
+
+        if n == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[tokio::test]
+    async fn test_decode_simple_chunked_body() {
+        let raw = b"4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        let mut reader = Cursor::new(&raw[..]);
+
+        let body = decode_chunked_body(&mut reader).await.unwrap();
+        assert_eq!(body, b"Wikipedia");
+    }
+
+    #[tokio::test]
+    async fn test_decode_chunked_with_extensions() {
+        let raw = b"4;foo=bar\r\nWiki\r\n0;last=true\r\n\r\n";
+        let mut reader = Cursor::new(&raw[..]);
+
+        let body = decode_chunked_body(&mut reader).await.unwrap();
+        assert_eq!(body, b"Wiki");
+    }
+
+    #[tokio::test]
+    async fn test_decode_chunked_with_trailing_headers() {
+        let raw = b"3\r\nfoo\r\n0\r\nX-Checksum: abc123\r\n\r\n";
+        let mut reader = Cursor::new(&raw[..]);
+
+        let body = decode_chunked_body(&mut reader).await.unwrap();
+        assert_eq!(body, b"foo");
+    }
+
+    #[tokio::test]
+    async fn test_decode_empty_body() {
+        let raw = b"0\r\n\r\n";
+        let mut reader = Cursor::new(&raw[..]);
+
+        let body = decode_chunked_body(&mut reader).await.unwrap();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_decode_invalid_chunk_size() {
+        let raw = b"zz\r\n";
+        let mut reader = Cursor::new(&raw[..]);
+
+        let result = decode_chunked_body(&mut reader).await;
+        assert!(result.is_err());
+    }
+}
@@ -1,10 +1,46 @@
+use crate::timeout::JitterStrategy;
 use crate::{Error, Result};
+use bytes::Bytes;
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+
+/// Size of each chunk [`CliHandler::execute_streaming`]'s stdout/stderr
+/// readers pull at a time - large enough to avoid one syscall per byte,
+/// small enough that output shows up promptly rather than batching into
+/// near-complete-output chunks.
+const CLI_STREAM_CHUNK_BYTES: usize = 8 * 1024;
+
+/// Backpressure bound on [`CliHandler::execute_streaming`]'s event channel -
+/// a slow consumer blocks the reader tasks rather than letting output
+/// buffer unboundedly in memory.
+const CLI_STREAM_CHANNEL_CAPACITY: usize = 64;
+
+/// One increment of [`CliHandler::execute_streaming`]'s output, in arrival
+/// order across both streams (stdout and stderr are read concurrently, so
+/// their relative interleaving in the stream reflects real arrival time,
+/// not round-robin fairness).
+#[derive(Debug)]
+pub enum CliStreamEvent {
+    Stdout(Bytes),
+    Stderr(Bytes),
+    /// The child exited with this code - the terminal event; nothing
+    /// follows it on a successful run.
+    Exit(i32),
+    /// The read loop hit [`CliHandler::timeout_ms`] before the child
+    /// exited; the child was killed. Also the terminal event.
+    Error(Error),
+}
 
 #[derive(Debug, Clone)]
 pub struct CliHandler {
@@ -14,6 +50,65 @@ pub struct CliHandler {
     pub env: HashMap<String, String>,
     pub timeout_ms: Option<u64>,
     pub stream: bool,
+    /// When set, [`CliHandler::execute_pty`] is the intended entry point -
+    /// the command runs attached to a pseudo-terminal of this size instead
+    /// of plain pipes, so it sees a tty (line-buffered, colorized,
+    /// `isatty()`-detecting output) the way it would from an interactive
+    /// shell. `None` (the `execute`/`execute_streaming` path) keeps the
+    /// plain pipe behavior.
+    pub pty: Option<PtySize>,
+    /// When set, the parent environment is cleared before spawning (via
+    /// `env_clear`) instead of inherited wholesale, and only the variables
+    /// named in `env_passthrough` are copied back in - giving deterministic,
+    /// secret-safe runs for CI-style and test-runner use cases. `self.env`
+    /// and `input.env` are layered on top either way.
+    pub clear_env: bool,
+    /// Parent environment variable names to copy back in when `clear_env`
+    /// is set. Ignored otherwise.
+    pub env_passthrough: Vec<String>,
+    /// When set, [`CliHandler::execute`] re-runs a failed attempt instead of
+    /// surfacing it immediately - for flaky external commands (a test
+    /// runner hitting a timing-dependent failure, a CLI tool that
+    /// occasionally flakes against a network dependency) where a clean
+    /// re-run is the expected recovery. `None` keeps the single-attempt
+    /// behavior.
+    pub retry: Option<CliRetryPolicy>,
+}
+
+/// Pseudo-terminal dimensions for [`CliHandler::execute_pty`] - mirrors
+/// `portable_pty::PtySize` but derives `Serialize`/`Deserialize`/`JsonSchema`
+/// so it can be carried the same way the rest of `CliHandler`'s config
+/// travels (tool definitions, generated registration code).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct PtySize {
+    pub rows: u16,
+    pub cols: u16,
+    #[serde(default)]
+    pub pixel_width: u16,
+    #[serde(default)]
+    pub pixel_height: u16,
+}
+
+impl Default for PtySize {
+    fn default() -> Self {
+        Self {
+            rows: 24,
+            cols: 80,
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+}
+
+impl From<PtySize> for portable_pty::PtySize {
+    fn from(size: PtySize) -> Self {
+        portable_pty::PtySize {
+            rows: size.rows,
+            cols: size.cols,
+            pixel_width: size.pixel_width,
+            pixel_height: size.pixel_height,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -22,6 +117,12 @@ pub struct CliInput {
     pub args: Vec<String>,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// Bytes written to the child's stdin before its output starts being
+    /// read. Only consumed by [`CliHandler::execute_pty`] - the plain
+    /// `execute`/`execute_streaming` paths don't pipe stdin at all, so
+    /// there's nothing for this to feed there.
+    #[serde(default)]
+    pub stdin: Vec<u8>,
 }
 
 #[derive(Debug, Serialize, JsonSchema)]
@@ -31,6 +132,110 @@ pub struct CliOutput {
     pub exit_code: i32,
 }
 
+/// What a single [`CliHandler::execute`] attempt produced, for
+/// [`CliRetryPolicy`]'s predicate to judge as retryable.
+#[derive(Debug)]
+pub enum CliAttemptOutcome<'a> {
+    /// The command ran to completion with this exit code.
+    ExitCode(i32),
+    /// The command failed to even spawn, or hit its per-attempt
+    /// [`CliHandler::timeout_ms`] budget.
+    SpawnError(&'a Error),
+}
+
+/// Retry configuration for [`CliHandler::execute`] - kept separate from the
+/// generic [`crate::timeout::RetryPolicy`] because CLI retryability hinges
+/// on the exit code of a *successful* spawn as much as on whether the spawn
+/// itself failed, which the generic `Error`-only classifier can't express.
+#[derive(Clone)]
+pub struct CliRetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+    jitter: JitterStrategy,
+    is_retryable: Arc<dyn Fn(&CliAttemptOutcome) -> bool + Send + Sync>,
+}
+
+impl std::fmt::Debug for CliRetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CliRetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CliRetryPolicy {
+    /// `max_attempts` total tries (1 means no retry). Defaults to a 100ms
+    /// base delay, 2x multiplier, 10s cap, full jitter, and "retry any
+    /// nonzero exit code or spawn error".
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: JitterStrategy::Full,
+            is_retryable: Arc::new(|outcome| match outcome {
+                CliAttemptOutcome::ExitCode(code) => *code != 0,
+                CliAttemptOutcome::SpawnError(_) => true,
+            }),
+        }
+    }
+
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: JitterStrategy) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Replace the default "retry any nonzero exit code or spawn error"
+    /// predicate - e.g. to retry only on a specific exit code a flaky
+    /// command is known to use for transient failures.
+    pub fn retry_if<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&CliAttemptOutcome) -> bool + Send + Sync + 'static,
+    {
+        self.is_retryable = Arc::new(f);
+        self
+    }
+
+    fn is_retryable(&self, outcome: &CliAttemptOutcome) -> bool {
+        (self.is_retryable)(outcome)
+    }
+
+    /// `base_delay * multiplier^attempt`, capped at `max_delay`, then
+    /// jittered per `self.jitter` - mirrors
+    /// [`crate::timeout::RetryPolicy::backoff_duration`].
+    /// [`JitterStrategy::Decorrelated`] has no previous-sleep state to work
+    /// from here, so it's treated the same as `None`.
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let capped = (self.base_delay.as_millis() as f64 * self.multiplier.powi(attempt as i32))
+            .min(self.max_delay.as_millis() as f64);
+
+        match self.jitter {
+            JitterStrategy::Full => Duration::from_millis((rand::random::<f64>() * capped) as u64),
+            JitterStrategy::None | JitterStrategy::Decorrelated => {
+                Duration::from_millis(capped as u64)
+            }
+        }
+    }
+}
+
 impl CliHandler {
     pub fn new(
         command: String,
@@ -47,10 +252,61 @@ impl CliHandler {
             env,
             timeout_ms,
             stream,
+            pty: None,
+            clear_env: false,
+            env_passthrough: Vec::new(),
+            retry: None,
         }
     }
 
+    /// Opt this handler into [`CliHandler::execute_pty`] with the given
+    /// pty dimensions, mirroring the `with_sink`/`with_resources` builder
+    /// pattern used elsewhere for optional add-on config.
+    pub fn with_pty(mut self, pty: PtySize) -> Self {
+        self.pty = Some(pty);
+        self
+    }
+
+    /// Opt into hermetic execution: the parent environment is cleared
+    /// before spawning and only `passthrough` is copied back in, ahead of
+    /// `self.env`/`input.env`.
+    pub fn with_clear_env(mut self, passthrough: Vec<String>) -> Self {
+        self.clear_env = true;
+        self.env_passthrough = passthrough;
+        self
+    }
+
+    /// Opt into re-running [`CliHandler::execute`] on a retryable failure,
+    /// per `policy`.
+    pub fn with_retry(mut self, policy: CliRetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
     pub async fn execute(&self, input: CliInput) -> Result<CliOutput> {
+        let Some(policy) = &self.retry else {
+            return self.execute_once(&input).await;
+        };
+
+        let mut attempt = 0u32;
+        loop {
+            let result = self.execute_once(&input).await;
+
+            let retryable = match &result {
+                Ok(output) => policy.is_retryable(&CliAttemptOutcome::ExitCode(output.exit_code)),
+                Err(err) => policy.is_retryable(&CliAttemptOutcome::SpawnError(err)),
+            };
+
+            attempt += 1;
+            if !retryable || attempt >= policy.max_attempts {
+                return result;
+            }
+
+            tokio::time::sleep(policy.delay_for(attempt - 1)).await;
+        }
+    }
+
+    async fn execute_once(&self, input: &CliInput) -> Result<CliOutput> {
         let mut cmd = Command::new(&self.command);
 
         // Add base args
@@ -64,6 +320,17 @@ impl CliHandler {
             cmd.current_dir(cwd);
         }
 
+        // Hermetic mode: drop the inherited environment first, then copy
+        // back only the allowlisted parent vars, before layering base/input.
+        if self.clear_env {
+            cmd.env_clear();
+            for key in &self.env_passthrough {
+                if let Ok(val) = std::env::var(key) {
+                    cmd.env(key, val);
+                }
+            }
+        }
+
         // Set environment variables (base + input)
         for (k, v) in &self.env {
             cmd.env(k, v);
@@ -100,6 +367,260 @@ impl CliHandler {
             exec_future.await
         }
     }
+
+    /// Spawn the command and stream its output as it arrives, instead of
+    /// buffering the whole run the way [`CliHandler::execute`] does - what
+    /// `stream: true` is meant to opt into. Stdout and stderr are read
+    /// concurrently in [`CLI_STREAM_CHUNK_BYTES`] chunks and forwarded onto
+    /// the returned stream as [`CliStreamEvent`]s, terminated by either
+    /// `Exit(code)` or, if [`CliHandler::timeout_ms`] elapses first,
+    /// `Error(Error::Timeout)` (after killing the child).
+    pub async fn execute_streaming(
+        &self,
+        input: CliInput,
+    ) -> Result<impl Stream<Item = CliStreamEvent>> {
+        let mut cmd = Command::new(&self.command);
+        cmd.args(&self.args);
+        cmd.args(&input.args);
+
+        if let Some(cwd) = &self.cwd {
+            cmd.current_dir(cwd);
+        }
+
+        if self.clear_env {
+            cmd.env_clear();
+            for key in &self.env_passthrough {
+                if let Ok(val) = std::env::var(key) {
+                    cmd.env(key, val);
+                }
+            }
+        }
+
+        for (k, v) in &self.env {
+            cmd.env(k, v);
+        }
+        for (k, v) in &input.env {
+            cmd.env(k, v);
+        }
+
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        // If the timeout below fires, the read loop (and the `child` it
+        // owns) is dropped mid-flight; `kill_on_drop` makes that drop
+        // actually terminate the process instead of leaving it running
+        // detached from anything that could reap it.
+        cmd.kill_on_drop(true);
+
+        let mut child = cmd.spawn().map_err(|e| {
+            Error::Handler(format!(
+                "Failed to spawn command '{}': {}",
+                self.command, e
+            ))
+        })?;
+
+        let mut stdout = child.stdout.take().expect("child spawned with piped stdout");
+        let mut stderr = child.stderr.take().expect("child spawned with piped stderr");
+
+        let (tx, rx) = tokio::sync::mpsc::channel(CLI_STREAM_CHANNEL_CAPACITY);
+        let timeout_ms = self.timeout_ms;
+
+        tokio::spawn(async move {
+            let read_loop = async {
+                let stdout_tx = tx.clone();
+                let stdout_task = tokio::spawn(async move {
+                    read_into_channel(&mut stdout, &stdout_tx, CliStreamEvent::Stdout).await;
+                });
+
+                let stderr_tx = tx.clone();
+                let stderr_task = tokio::spawn(async move {
+                    read_into_channel(&mut stderr, &stderr_tx, CliStreamEvent::Stderr).await;
+                });
+
+                let _ = tokio::join!(stdout_task, stderr_task);
+
+                let exit_code = match child.wait().await {
+                    Ok(status) => status.code().unwrap_or(-1),
+                    Err(_) => -1,
+                };
+                let _ = tx.send(CliStreamEvent::Exit(exit_code)).await;
+            };
+
+            match timeout_ms {
+                Some(ms) => {
+                    if timeout(Duration::from_millis(ms), read_loop).await.is_err() {
+                        let _ = tx.send(CliStreamEvent::Error(Error::Timeout)).await;
+                    }
+                }
+                None => read_loop.await,
+            }
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+
+    /// Like [`CliHandler::execute_streaming`], but the command runs attached
+    /// to a pseudo-terminal (sized from [`CliHandler::pty`], defaulting to
+    /// 80x24 if unset) instead of plain pipes. Combined stdout+stderr from
+    /// the pty master is forwarded as [`CliStreamEvent::Stdout`] chunks on
+    /// the returned stream - a pty has no separate stderr, the child sees
+    /// a single tty fd for both. `input.stdin` is written into the child
+    /// before streaming starts; the returned [`PtyHandle`] lets the caller
+    /// write more afterward and resize the pty mid-run.
+    pub async fn execute_pty(
+        &self,
+        input: CliInput,
+    ) -> Result<(impl Stream<Item = CliStreamEvent>, PtyHandle)> {
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(self.pty.unwrap_or_default().into())
+            .map_err(|e| Error::Handler(format!("failed to allocate pty: {e}")))?;
+
+        let mut cmd = CommandBuilder::new(&self.command);
+        cmd.args(&self.args);
+        cmd.args(&input.args);
+        if let Some(cwd) = &self.cwd {
+            cmd.cwd(cwd);
+        }
+        if self.clear_env {
+            cmd.env_clear();
+            for key in &self.env_passthrough {
+                if let Ok(val) = std::env::var(key) {
+                    cmd.env(key, val);
+                }
+            }
+        }
+        for (k, v) in self.env.iter().chain(input.env.iter()) {
+            cmd.env(k, v);
+        }
+
+        let child = pair.slave.spawn_command(cmd).map_err(|e| {
+            Error::Handler(format!(
+                "failed to spawn command '{}' in pty: {}",
+                self.command, e
+            ))
+        })?;
+        // The slave side belongs to the child now; holding onto it in the
+        // parent would keep the pty's other end open and the master reader
+        // would never see EOF once the child exits.
+        drop(pair.slave);
+
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| Error::Handler(format!("failed to clone pty reader: {e}")))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| Error::Handler(format!("failed to take pty writer: {e}")))?;
+
+        let handle = PtyHandle {
+            writer: Mutex::new(writer),
+            master: pair.master,
+        };
+        if !input.stdin.is_empty() {
+            handle.write_stdin(&input.stdin)?;
+        }
+
+        let child: Arc<Mutex<Box<dyn Child + Send + Sync>>> = Arc::new(Mutex::new(child));
+        let timed_out = Arc::new(AtomicBool::new(false));
+
+        let (tx, rx) = tokio::sync::mpsc::channel(CLI_STREAM_CHANNEL_CAPACITY);
+
+        let watchdog = self.timeout_ms.map(|ms| {
+            let child = child.clone();
+            let timed_out = timed_out.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(ms)).await;
+                timed_out.store(true, Ordering::SeqCst);
+                let _ = child.lock().expect("pty child mutex poisoned").kill();
+            })
+        });
+
+        tokio::task::spawn_blocking(move || {
+            let mut reader = reader;
+            let mut buf = vec![0u8; CLI_STREAM_CHUNK_BYTES];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let chunk = Bytes::copy_from_slice(&buf[..n]);
+                        if tx.blocking_send(CliStreamEvent::Stdout(chunk)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            let exit_code = match child.lock().expect("pty child mutex poisoned").wait() {
+                Ok(status) => status.exit_code() as i32,
+                Err(_) => -1,
+            };
+
+            if let Some(watchdog) = watchdog {
+                watchdog.abort();
+            }
+
+            let event = if timed_out.load(Ordering::SeqCst) {
+                CliStreamEvent::Error(Error::Timeout)
+            } else {
+                CliStreamEvent::Exit(exit_code)
+            };
+            let _ = tx.blocking_send(event);
+        });
+
+        Ok((ReceiverStream::new(rx), handle))
+    }
+}
+
+/// Returned alongside [`CliHandler::execute_pty`]'s event stream - the
+/// stream only carries output out, so writing into the child's stdin or
+/// resizing the pty mid-run has to go through this instead.
+pub struct PtyHandle {
+    writer: Mutex<Box<dyn Write + Send>>,
+    master: Box<dyn MasterPty + Send>,
+}
+
+impl PtyHandle {
+    /// Write bytes into the child's stdin - e.g. answering an interactive
+    /// prompt, or feeding a command to a REPL.
+    pub fn write_stdin(&self, bytes: &[u8]) -> Result<()> {
+        let mut writer = self.writer.lock().expect("pty writer mutex poisoned");
+        writer
+            .write_all(bytes)
+            .and_then(|_| writer.flush())
+            .map_err(|e| Error::Handler(format!("failed to write to pty: {e}")))
+    }
+
+    /// Resize the pty, e.g. in response to the caller's own terminal
+    /// being resized.
+    pub fn resize(&self, size: PtySize) -> Result<()> {
+        self.master
+            .resize(size.into())
+            .map_err(|e| Error::Handler(format!("failed to resize pty: {e}")))
+    }
+}
+
+/// Read `reader` to EOF in [`CLI_STREAM_CHUNK_BYTES`] chunks, wrapping each
+/// non-empty chunk in `event` and forwarding it on `tx`. Stops early,
+/// without error, if the receiver has gone away (the stream was dropped).
+async fn read_into_channel(
+    reader: &mut (impl tokio::io::AsyncRead + Unpin),
+    tx: &tokio::sync::mpsc::Sender<CliStreamEvent>,
+    event: impl Fn(Bytes) -> CliStreamEvent,
+) {
+    let mut buf = vec![0u8; CLI_STREAM_CHUNK_BYTES];
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) => break,
+            Ok(n) => {
+                if tx.send(event(Bytes::copy_from_slice(&buf[..n]))).await.is_err() {
+                    break;
+                }
+            }
+            Err(_) => break,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -140,6 +661,7 @@ mod tests {
         let input = CliInput {
             args: vec![],
             env: HashMap::new(),
+            stdin: vec![],
         };
 
         let result = handler.execute(input).await;
@@ -164,6 +686,7 @@ mod tests {
         let input = CliInput {
             args: vec!["test".to_string(), "message".to_string()],
             env: HashMap::new(),
+            stdin: vec![],
         };
 
         let result = handler.execute(input).await;
@@ -188,6 +711,7 @@ mod tests {
         let input = CliInput {
             args: vec![],
             env: HashMap::new(),
+            stdin: vec![],
         };
 
         let result = handler.execute(input).await;
@@ -209,6 +733,7 @@ mod tests {
         let input = CliInput {
             args: vec![],
             env: HashMap::new(),
+            stdin: vec![],
         };
 
         let result = handler.execute(input).await;
@@ -233,6 +758,7 @@ mod tests {
         let input = CliInput {
             args: vec![],
             env: HashMap::new(),
+            stdin: vec![],
         };
 
         let result = handler.execute(input).await;
@@ -241,4 +767,398 @@ mod tests {
         let output = result.unwrap();
         assert!(output.stdout.contains("test_value"));
     }
+
+    #[tokio::test]
+    async fn test_cli_handler_clear_env_drops_unallowed_parent_vars() {
+        std::env::set_var("PFORGE_TEST_HERMETIC_SECRET", "leaked_if_present");
+
+        let handler = CliHandler::new(
+            "sh".to_string(),
+            vec![
+                "-c".to_string(),
+                "echo \"[$PFORGE_TEST_HERMETIC_SECRET]\"".to_string(),
+            ],
+            None,
+            HashMap::new(),
+            None,
+            false,
+        )
+        .with_clear_env(vec![]);
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let result = handler.execute(input).await.unwrap();
+        std::env::remove_var("PFORGE_TEST_HERMETIC_SECRET");
+        assert_eq!(result.stdout.trim(), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_clear_env_passthrough_allowlist() {
+        std::env::set_var("PFORGE_TEST_HERMETIC_ALLOWED", "allowed_value");
+
+        let handler = CliHandler::new(
+            "sh".to_string(),
+            vec![
+                "-c".to_string(),
+                "echo $PFORGE_TEST_HERMETIC_ALLOWED".to_string(),
+            ],
+            None,
+            HashMap::new(),
+            None,
+            false,
+        )
+        .with_clear_env(vec!["PFORGE_TEST_HERMETIC_ALLOWED".to_string()]);
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let result = handler.execute(input).await.unwrap();
+        std::env::remove_var("PFORGE_TEST_HERMETIC_ALLOWED");
+        assert!(result.stdout.contains("allowed_value"));
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_retry_succeeds_after_transient_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("attempts");
+
+        // The first attempt finds no `marker`, creates it and fails; the
+        // second finds it and succeeds - exercises a retry after a failed
+        // attempt within a 3-attempt budget.
+        let handler = CliHandler::new(
+            "sh".to_string(),
+            vec![
+                "-c".to_string(),
+                format!(
+                    "[ -f {marker} ] && exit 0 || {{ touch {marker}; exit 1; }}",
+                    marker = marker.display()
+                ),
+            ],
+            None,
+            HashMap::new(),
+            None,
+            false,
+        )
+        .with_retry(CliRetryPolicy::new(3).with_backoff(
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        ));
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.exit_code, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_retry_exhausts_attempts_and_returns_last_failure() {
+        let handler = CliHandler::new(
+            "sh".to_string(),
+            vec!["-c".to_string(), "exit 7".to_string()],
+            None,
+            HashMap::new(),
+            None,
+            false,
+        )
+        .with_retry(CliRetryPolicy::new(2).with_backoff(
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        ));
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.exit_code, 7);
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_retry_retries_spawn_errors() {
+        let handler = CliHandler::new(
+            "nonexistent_command_that_should_fail".to_string(),
+            vec![],
+            None,
+            HashMap::new(),
+            None,
+            false,
+        )
+        .with_retry(CliRetryPolicy::new(2).with_backoff(
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        ));
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let result = handler.execute(input).await;
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::Handler(_)));
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_retry_custom_predicate_skips_unmatched_exit_code() {
+        let handler = CliHandler::new(
+            "sh".to_string(),
+            vec!["-c".to_string(), "exit 3".to_string()],
+            None,
+            HashMap::new(),
+            None,
+            false,
+        )
+        .with_retry(
+            CliRetryPolicy::new(5)
+                .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+                .retry_if(|outcome| matches!(outcome, CliAttemptOutcome::ExitCode(42))),
+        );
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        // Exit code 3 never matches the predicate, so the first attempt's
+        // result is returned immediately without retrying.
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.exit_code, 3);
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_retry_each_attempt_gets_its_own_timeout() {
+        let handler = CliHandler::new(
+            "sleep".to_string(),
+            vec!["2".to_string()],
+            None,
+            HashMap::new(),
+            Some(50),
+            false,
+        )
+        .with_retry(CliRetryPolicy::new(2).with_backoff(
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        ));
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let result = handler.execute(input).await;
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), Error::Timeout));
+    }
+
+    async fn collect_stream_events(
+        stream: impl Stream<Item = CliStreamEvent>,
+    ) -> Vec<CliStreamEvent> {
+        tokio::pin!(stream);
+        let mut events = Vec::new();
+        while let Some(event) = tokio_stream::StreamExt::next(&mut stream).await {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_execute_streaming_emits_stdout_and_exit() {
+        let handler = CliHandler::new(
+            "echo".to_string(),
+            vec!["hello".to_string()],
+            None,
+            HashMap::new(),
+            None,
+            true,
+        );
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let stream = handler.execute_streaming(input).await.unwrap();
+        let events = collect_stream_events(stream).await;
+
+        let stdout: Vec<u8> = events
+            .iter()
+            .filter_map(|e| match e {
+                CliStreamEvent::Stdout(chunk) => Some(chunk.to_vec()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert!(String::from_utf8_lossy(&stdout).contains("hello"));
+
+        assert!(matches!(events.last(), Some(CliStreamEvent::Exit(0))));
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_execute_streaming_times_out() {
+        let handler = CliHandler::new(
+            "sleep".to_string(),
+            vec!["2".to_string()],
+            None,
+            HashMap::new(),
+            Some(100),
+            true,
+        );
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let stream = handler.execute_streaming(input).await.unwrap();
+        let events = collect_stream_events(stream).await;
+
+        assert!(matches!(
+            events.last(),
+            Some(CliStreamEvent::Error(Error::Timeout))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_execute_streaming_invalid_command() {
+        let handler = CliHandler::new(
+            "nonexistent_command_that_should_fail".to_string(),
+            vec![],
+            None,
+            HashMap::new(),
+            None,
+            true,
+        );
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let result = handler.execute_streaming(input).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_execute_pty_emits_output_and_exit() {
+        let handler = CliHandler::new(
+            "echo".to_string(),
+            vec!["hello".to_string()],
+            None,
+            HashMap::new(),
+            None,
+            true,
+        )
+        .with_pty(PtySize::default());
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let (stream, _handle) = handler.execute_pty(input).await.unwrap();
+        let events = collect_stream_events(stream).await;
+
+        let stdout: Vec<u8> = events
+            .iter()
+            .filter_map(|e| match e {
+                CliStreamEvent::Stdout(chunk) => Some(chunk.to_vec()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert!(String::from_utf8_lossy(&stdout).contains("hello"));
+        assert!(matches!(events.last(), Some(CliStreamEvent::Exit(0))));
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_execute_pty_resize_and_write_stdin() {
+        let handler = CliHandler::new("cat".to_string(), vec![], None, HashMap::new(), None, true)
+            .with_pty(PtySize::default());
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let (_stream, handle) = handler.execute_pty(input).await.unwrap();
+        assert!(handle
+            .resize(PtySize {
+                rows: 40,
+                cols: 120,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .is_ok());
+        assert!(handle.write_stdin(b"hi\n").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_execute_pty_times_out() {
+        let handler = CliHandler::new(
+            "sleep".to_string(),
+            vec!["2".to_string()],
+            None,
+            HashMap::new(),
+            Some(100),
+            true,
+        )
+        .with_pty(PtySize::default());
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let (stream, _handle) = handler.execute_pty(input).await.unwrap();
+        let events = collect_stream_events(stream).await;
+
+        assert!(matches!(
+            events.last(),
+            Some(CliStreamEvent::Error(Error::Timeout))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cli_handler_execute_pty_invalid_command() {
+        let handler = CliHandler::new(
+            "nonexistent_command_that_should_fail".to_string(),
+            vec![],
+            None,
+            HashMap::new(),
+            None,
+            true,
+        )
+        .with_pty(PtySize::default());
+
+        let input = CliInput {
+            args: vec![],
+            env: HashMap::new(),
+            stdin: vec![],
+        };
+
+        let result = handler.execute_pty(input).await;
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,266 @@
+//! Native hashing/HMAC handler backed by the RustCrypto crates.
+//!
+//! Replaces the subprocess-based polyglot hashers (e.g. the Go bridge's
+//! `go_hash` example) with an in-process primitive so bridges aren't needed
+//! just to hash bytes.
+
+use crate::{Error, Result};
+use blake2::{Blake2b512, Blake2s256};
+use hmac::{Hmac, Mac};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use sha3::{Sha3_256, Sha3_512};
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CryptoAlgorithm {
+    Sha256,
+    Sha512,
+    Sha3_256,
+    Sha3_512,
+    Blake2s256,
+    Blake2b512,
+}
+
+#[derive(Debug, Clone)]
+pub struct CryptoHandler;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CryptoInput {
+    /// Hex-encoded data to hash or MAC
+    pub data: String,
+    pub algorithm: CryptoAlgorithm,
+    /// Hex-encoded HMAC key; when present the output is a keyed MAC rather
+    /// than a plain digest.
+    #[serde(default)]
+    pub hmac_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CryptoOutput {
+    pub hex: String,
+    pub base64: String,
+    pub length: usize,
+}
+
+impl CryptoHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub async fn execute(&self, input: CryptoInput) -> Result<CryptoOutput> {
+        let data = hex::decode(&input.data)
+            .map_err(|e| Error::Handler(format!("Invalid hex data: {}", e)))?;
+
+        let digest = match &input.hmac_key {
+            Some(key_hex) => {
+                let key = hex::decode(key_hex)
+                    .map_err(|e| Error::Handler(format!("Invalid hex HMAC key: {}", e)))?;
+                hmac_digest(input.algorithm, &key, &data)?
+            }
+            None => hash_digest(input.algorithm, &data),
+        };
+
+        Ok(CryptoOutput {
+            hex: hex::encode(&digest),
+            base64: base64_encode(&digest),
+            length: digest.len(),
+        })
+    }
+}
+
+impl Default for CryptoHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hash_digest(algorithm: CryptoAlgorithm, data: &[u8]) -> Vec<u8> {
+    match algorithm {
+        CryptoAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+        CryptoAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        CryptoAlgorithm::Sha3_256 => Sha3_256::digest(data).to_vec(),
+        CryptoAlgorithm::Sha3_512 => Sha3_512::digest(data).to_vec(),
+        CryptoAlgorithm::Blake2s256 => Blake2s256::digest(data).to_vec(),
+        CryptoAlgorithm::Blake2b512 => Blake2b512::digest(data).to_vec(),
+    }
+}
+
+fn hmac_digest(algorithm: CryptoAlgorithm, key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    fn run<D: hmac::digest::Update + hmac::digest::FixedOutput + hmac::digest::KeyInit + Clone>(
+        key: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let mut mac = <Hmac<D> as Mac>::new_from_slice(key)
+            .map_err(|e| Error::Handler(format!("Invalid HMAC key: {}", e)))?;
+        mac.update(data);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+
+    match algorithm {
+        CryptoAlgorithm::Sha256 => run::<Sha256>(key, data),
+        CryptoAlgorithm::Sha512 => run::<Sha512>(key, data),
+        CryptoAlgorithm::Sha3_256 => run::<Sha3_256>(key, data),
+        CryptoAlgorithm::Sha3_512 => run::<Sha3_512>(key, data),
+        _ => Err(Error::Handler(format!(
+            "HMAC is not supported for {:?}",
+            algorithm
+        ))),
+    }
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+/// A single Wycheproof-style test case.
+#[derive(Debug, Deserialize)]
+pub struct WycheproofCase {
+    #[serde(rename = "tcId")]
+    pub tc_id: u32,
+    #[serde(default)]
+    pub key: Option<String>,
+    pub msg: String,
+    #[serde(alias = "tag", alias = "digest")]
+    pub expected: String,
+    pub result: String,
+}
+
+/// A group of Wycheproof-style test cases for a single algorithm.
+#[derive(Debug, Deserialize)]
+pub struct WycheproofGroup {
+    pub algorithm: String,
+    #[serde(rename = "testCases")]
+    pub test_cases: Vec<WycheproofCase>,
+}
+
+/// Run a group of conformance vectors against [`CryptoHandler`], returning
+/// an error describing the first mismatch.
+pub async fn run_wycheproof_group(group: &WycheproofGroup) -> Result<()> {
+    let algorithm = parse_algorithm(&group.algorithm)?;
+    let handler = CryptoHandler::new();
+
+    for case in &group.test_cases {
+        let input = CryptoInput {
+            data: case.msg.clone(),
+            algorithm,
+            hmac_key: case.key.clone(),
+        };
+
+        let outcome = handler.execute(input).await;
+        let is_valid_case = case.result == "valid";
+
+        match (outcome, is_valid_case) {
+            (Ok(output), true) if output.hex.eq_ignore_ascii_case(&case.expected) => {}
+            (Ok(_), true) => {
+                return Err(Error::Handler(format!(
+                    "tcId {}: digest mismatch for valid vector",
+                    case.tc_id
+                )))
+            }
+            (Ok(output), false) if output.hex.eq_ignore_ascii_case(&case.expected) => {
+                return Err(Error::Handler(format!(
+                    "tcId {}: handler reproduced digest for an invalid vector",
+                    case.tc_id
+                )))
+            }
+            (Ok(_), false) => {}
+            (Err(_), true) => {
+                return Err(Error::Handler(format!(
+                    "tcId {}: handler rejected a valid vector",
+                    case.tc_id
+                )))
+            }
+            (Err(_), false) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_algorithm(name: &str) -> Result<CryptoAlgorithm> {
+    match name.to_ascii_uppercase().as_str() {
+        "SHA-256" | "SHA256" | "HMACSHA256" => Ok(CryptoAlgorithm::Sha256),
+        "SHA-512" | "SHA512" | "HMACSHA512" => Ok(CryptoAlgorithm::Sha512),
+        "SHA3-256" => Ok(CryptoAlgorithm::Sha3_256),
+        "SHA3-512" => Ok(CryptoAlgorithm::Sha3_512),
+        "BLAKE2S-256" => Ok(CryptoAlgorithm::Blake2s256),
+        "BLAKE2B-512" => Ok(CryptoAlgorithm::Blake2b512),
+        other => Err(Error::Handler(format!("Unknown algorithm: {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sha256_digest() {
+        let handler = CryptoHandler::new();
+        let input = CryptoInput {
+            data: hex::encode(b"abc"),
+            algorithm: CryptoAlgorithm::Sha256,
+            hmac_key: None,
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(
+            output.hex,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(output.length, 32);
+    }
+
+    #[tokio::test]
+    async fn test_hmac_sha256() {
+        let handler = CryptoHandler::new();
+        let input = CryptoInput {
+            data: hex::encode(b"hello"),
+            algorithm: CryptoAlgorithm::Sha256,
+            hmac_key: Some(hex::encode(b"secret")),
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.length, 32);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_hex_input() {
+        let handler = CryptoHandler::new();
+        let input = CryptoInput {
+            data: "not-hex".to_string(),
+            algorithm: CryptoAlgorithm::Sha256,
+            hmac_key: None,
+        };
+
+        assert!(handler.execute(input).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wycheproof_style_group() {
+        let group = WycheproofGroup {
+            algorithm: "SHA-256".to_string(),
+            test_cases: vec![
+                WycheproofCase {
+                    tc_id: 1,
+                    key: None,
+                    msg: hex::encode(b"abc"),
+                    expected: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+                        .to_string(),
+                    result: "valid".to_string(),
+                },
+                WycheproofCase {
+                    tc_id: 2,
+                    key: None,
+                    msg: hex::encode(b"abc"),
+                    expected: "00".repeat(32),
+                    result: "invalid".to_string(),
+                },
+            ],
+        };
+
+        run_wycheproof_group(&group).await.unwrap();
+    }
+}
@@ -0,0 +1,218 @@
+//! Built-in `health_check` tool handler, plus a couple of concrete
+//! [`HealthProbe`] implementations for the dependency classes
+//! `McpServer`-based servers commonly lean on: an external CLI binary (the
+//! `journalctl` requirement the `production-server` example's `log_stream`
+//! tool notes) and an HTTP endpoint (the same example's `api_fetch` tool).
+
+use crate::state::StateManager;
+use crate::telemetry::{HealthCheckOutcome, HealthProbe, HealthRegistry, HealthReport};
+use crate::{Handler, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Key a [`StateManagerProbe`] writes and reads back to confirm the backend
+/// round-trips, reserved so it never collides with application state.
+const STATE_PROBE_KEY: &str = "__pforge_health_check__";
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct HealthCheckInput {}
+
+pub type HealthCheckOutput = HealthReport;
+
+/// Runs every probe registered in a [`HealthRegistry`] and returns the
+/// aggregate report. Auto-registered by [`crate::server::McpServer`] under
+/// the tool name `health_check`.
+pub struct HealthCheckHandler {
+    registry: HealthRegistry,
+}
+
+impl HealthCheckHandler {
+    pub fn new(registry: HealthRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler for HealthCheckHandler {
+    type Input = HealthCheckInput;
+    type Output = HealthCheckOutput;
+    type Error = crate::Error;
+
+    async fn handle(&self, _input: Self::Input) -> Result<Self::Output> {
+        Ok(self.registry.run().await)
+    }
+}
+
+/// Checks that a command name resolves to an executable file somewhere on
+/// `PATH`, without actually invoking it - e.g. confirming `journalctl` is
+/// present before a `log_stream`-style CLI tool is relied on.
+pub struct CommandExistsProbe {
+    command: String,
+}
+
+impl CommandExistsProbe {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthProbe for CommandExistsProbe {
+    async fn check(&self) -> HealthCheckOutcome {
+        if command_exists(&self.command) {
+            HealthCheckOutcome::healthy()
+        } else {
+            HealthCheckOutcome::unhealthy(format!("'{}' not found on PATH", self.command))
+        }
+    }
+}
+
+fn command_exists(command: &str) -> bool {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return false;
+    };
+
+    std::env::split_paths(&path_var).any(|dir| {
+        let candidate = dir.join(command);
+        is_executable_file(&candidate)
+    })
+}
+
+fn is_executable_file(path: &Path) -> bool {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !meta.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+/// Checks that a [`StateManager`] backend is reachable by writing and
+/// reading back a reserved probe key.
+pub struct StateManagerProbe {
+    state: Arc<dyn StateManager>,
+}
+
+impl StateManagerProbe {
+    pub fn new(state: Arc<dyn StateManager>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthProbe for StateManagerProbe {
+    async fn check(&self) -> HealthCheckOutcome {
+        let write = self
+            .state
+            .set(STATE_PROBE_KEY, b"ok".to_vec(), Some(Duration::from_secs(5)))
+            .await;
+        if let Err(e) = write {
+            return HealthCheckOutcome::unhealthy(format!("state manager write failed: {}", e));
+        }
+
+        match self.state.get(STATE_PROBE_KEY).await {
+            Ok(Some(_)) => HealthCheckOutcome::healthy(),
+            Ok(None) => HealthCheckOutcome::degraded("state manager write did not persist"),
+            Err(e) => HealthCheckOutcome::unhealthy(format!("state manager read failed: {}", e)),
+        }
+    }
+}
+
+/// Checks that an HTTP endpoint is reachable - e.g. the upstream an
+/// `api_fetch`-style HTTP tool depends on. A non-2xx/3xx response is
+/// reported degraded rather than unhealthy, since the endpoint did respond.
+pub struct HttpReachabilityProbe {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl HttpReachabilityProbe {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self::with_timeout(endpoint, Duration::from_secs(5))
+    }
+
+    pub fn with_timeout(endpoint: impl Into<String>, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+        Self {
+            endpoint: endpoint.into(),
+            client,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthProbe for HttpReachabilityProbe {
+    async fn check(&self) -> HealthCheckOutcome {
+        match self.client.head(&self.endpoint).send().await {
+            Ok(response) if response.status().is_success() || response.status().is_redirection() => {
+                HealthCheckOutcome::healthy()
+            }
+            Ok(response) => {
+                HealthCheckOutcome::degraded(format!("{} returned {}", self.endpoint, response.status()))
+            }
+            Err(e) => HealthCheckOutcome::unhealthy(format!("{} unreachable: {}", self.endpoint, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health_check_handler_reports_empty_registry_healthy() {
+        let handler = HealthCheckHandler::new(HealthRegistry::new());
+        let report = handler.handle(HealthCheckInput::default()).await.unwrap();
+        assert_eq!(report.status, crate::telemetry::HealthStatus::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_command_exists_probe_finds_a_real_binary() {
+        // `sh` is reliably present wherever these tests run.
+        let probe = CommandExistsProbe::new("sh");
+        let outcome = probe.check().await;
+        assert_eq!(outcome.status, crate::telemetry::HealthStatus::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_command_exists_probe_reports_missing_binary() {
+        let probe = CommandExistsProbe::new("definitely-not-a-real-command-xyz");
+        let outcome = probe.check().await;
+        assert_eq!(outcome.status, crate::telemetry::HealthStatus::Unhealthy);
+    }
+
+    #[test]
+    fn test_command_exists_checks_executable_bit() {
+        assert!(!command_exists(""));
+        #[cfg(unix)]
+        {
+            let file = tempfile::NamedTempFile::new().unwrap();
+            assert!(!is_executable_file(file.path()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_state_manager_probe_healthy_for_working_backend() {
+        let state = Arc::new(crate::state::MemoryStateManager::new());
+        let probe = StateManagerProbe::new(state);
+        let outcome = probe.check().await;
+        assert_eq!(outcome.status, crate::telemetry::HealthStatus::Healthy);
+    }
+}
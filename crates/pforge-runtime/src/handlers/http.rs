@@ -1,8 +1,18 @@
 use crate::{Error, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::Rng;
 use reqwest::{Client, Method};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Refresh a cached OAuth2 token this far ahead of its actual expiry.
+const OAUTH2_EXPIRY_SKEW: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone)]
 pub struct HttpHandler {
@@ -11,6 +21,217 @@ pub struct HttpHandler {
     pub headers: HashMap<String, String>,
     pub auth: Option<AuthConfig>,
     client: Client,
+    timeout: Option<Duration>,
+    retry: RetryConfig,
+}
+
+/// Retry behavior for [`HttpHandler::execute`].
+///
+/// By default only a single attempt is made (`max_attempts: 1`); callers opt
+/// in to retries with [`HttpHandler::with_retry_config`].
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Apply full jitter (sleep a random duration in `[0, delay]`) rather
+    /// than sleeping the computed delay exactly.
+    pub jitter: bool,
+    /// Response status codes that should trigger a retry (e.g. 429, 502).
+    pub retryable_status_codes: Vec<u16>,
+    /// Retry non-idempotent methods (POST/PATCH) too. Off by default since
+    /// retrying a non-idempotent request can duplicate side effects.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retryable_status_codes: vec![429, 502, 503, 504],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+/// Build the shared reqwest client backed by rustls rather than a system TLS
+/// library, so operators can run without an OpenSSL install and can pin
+/// custom roots via `root_certs`.
+fn build_rustls_client(root_certs: &[Vec<u8>]) -> Client {
+    let mut builder = Client::builder()
+        .use_rustls_tls()
+        .gzip(true)
+        .brotli(true)
+        .deflate(true);
+
+    for der in root_certs {
+        if let Ok(cert) = reqwest::Certificate::from_der(der) {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// How many redirects a shared client will follow before giving up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedirectPolicy {
+    /// Never follow redirects.
+    None,
+    /// Follow up to `max_hops` redirects.
+    Limited(usize),
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::Limited(10)
+    }
+}
+
+/// Proxy to route outbound requests through, with optional basic auth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub url: String,
+    pub basic_auth: Option<(String, String)>,
+}
+
+/// Configuration for a shared, pooled `reqwest::Client` built once at
+/// startup and handed (cloned — `Client` is internally `Arc`-backed, so
+/// this is cheap) to every `HttpHandler` registered against the runtime,
+/// instead of each handler opening its own connection pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    pub pool_idle_timeout: Option<Duration>,
+    pub pool_max_idle_per_host: usize,
+    pub proxy: Option<ProxyConfig>,
+    pub redirect_policy: RedirectPolicy,
+    pub default_headers: HashMap<String, String>,
+    pub timeout: Option<Duration>,
+    pub danger_accept_invalid_certs: bool,
+    pub root_certs: Vec<Vec<u8>>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: usize::MAX,
+            proxy: None,
+            redirect_policy: RedirectPolicy::default(),
+            default_headers: HashMap::new(),
+            timeout: None,
+            danger_accept_invalid_certs: false,
+            root_certs: Vec::new(),
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Build the shared `reqwest::Client` described by this config.
+    pub fn build(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .use_rustls_tls()
+            .gzip(true)
+            .brotli(true)
+            .deflate(true)
+            .danger_accept_invalid_certs(self.danger_accept_invalid_certs)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host);
+
+        if let Some(idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(idle_timeout);
+        }
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        builder = match self.redirect_policy {
+            RedirectPolicy::None => builder.redirect(reqwest::redirect::Policy::none()),
+            RedirectPolicy::Limited(max_hops) => {
+                builder.redirect(reqwest::redirect::Policy::limited(max_hops))
+            }
+        };
+
+        if let Some(proxy_config) = &self.proxy {
+            let mut proxy = reqwest::Proxy::all(&proxy_config.url)
+                .map_err(|e| Error::Http(format!("invalid proxy URL: {}", e)))?;
+            if let Some((username, password)) = &proxy_config.basic_auth {
+                proxy = proxy.basic_auth(username, password);
+            }
+            builder = builder.proxy(proxy);
+        }
+
+        let mut default_headers = reqwest::header::HeaderMap::new();
+        for (k, v) in &self.default_headers {
+            let name = reqwest::header::HeaderName::from_bytes(k.as_bytes())
+                .map_err(|e| Error::Http(format!("invalid default header name '{}': {}", k, e)))?;
+            let value = reqwest::header::HeaderValue::from_str(v)
+                .map_err(|e| Error::Http(format!("invalid default header value for '{}': {}", k, e)))?;
+            default_headers.insert(name, value);
+        }
+        builder = builder.default_headers(default_headers);
+
+        for der in &self.root_certs {
+            if let Ok(cert) = reqwest::Certificate::from_der(der) {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        builder
+            .build()
+            .map_err(|e| Error::Http(format!("failed to build HTTP client: {}", e)))
+    }
+}
+
+/// Lazily builds and caches pooled `reqwest::Client`s keyed by
+/// [`HttpClientConfig`], so every `HttpHandler` registered against the same
+/// config (TLS, proxy, default headers) shares one set of keep-alive
+/// connections instead of opening its own pool.
+///
+/// `HttpClientConfig` has no `Hash` impl (it holds a `HashMap` and a
+/// `Vec<Vec<u8>>`), so the cache is a linear-scan `Vec` rather than a
+/// `HashMap` — a running server only ever registers a handful of distinct
+/// configs, so the scan cost is negligible.
+#[derive(Debug, Default)]
+pub struct HttpClientProvider {
+    cache: Mutex<Vec<(HttpClientConfig, Client)>>,
+}
+
+impl HttpClientProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the cached client for `config`, building and caching one if
+    /// this is the first request for it.
+    pub fn client_for(&self, config: &HttpClientConfig) -> Result<Client> {
+        let mut cache = self
+            .cache
+            .lock()
+            .map_err(|_| Error::Http("HTTP client cache lock poisoned".to_string()))?;
+
+        if let Some((_, client)) = cache.iter().find(|(cached, _)| cached == config) {
+            return Ok(client.clone());
+        }
+
+        let client = config.build()?;
+        cache.push((config.clone(), client.clone()));
+        Ok(client)
+    }
+
+    /// Build a new, uncached client for `config`.
+    ///
+    /// Use this when the caller's runtime context differs from the server's
+    /// own (e.g. a benchmark or test driving its own Tokio runtime), since a
+    /// client built against one runtime must not be reused from another.
+    pub fn fresh_client(&self, config: &HttpClientConfig) -> Result<Client> {
+        config.build()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -27,14 +248,106 @@ pub enum AuthConfig {
     Bearer { token: String },
     Basic { username: String, password: String },
     ApiKey { key: String, header: String },
+    /// SigV4 signing for AWS and AWS-compatible services (e.g. OpenSearch).
+    AwsSigV4 {
+        access_key: String,
+        secret_key: String,
+        session_token: Option<String>,
+        region: String,
+        service: String,
+    },
+    /// OAuth2 client-credentials grant. The fetched token is cached in
+    /// `cache` (shared across clones of this config) and refreshed
+    /// automatically once it nears expiry.
+    OAuth2ClientCredentials {
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        scopes: Vec<String>,
+        audience: Option<String>,
+        cache: OAuth2TokenCache,
+    },
+}
+
+impl AuthConfig {
+    /// Build OAuth2 client-credentials auth with a fresh, empty token cache.
+    pub fn oauth2_client_credentials(
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        scopes: Vec<String>,
+        audience: Option<String>,
+    ) -> Self {
+        AuthConfig::OAuth2ClientCredentials {
+            token_url,
+            client_id,
+            client_secret,
+            scopes,
+            audience,
+            cache: OAuth2TokenCache::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// Shared, lazily-populated cache for one OAuth2 client-credentials config.
+/// Cloning an `AuthConfig` clones the `Arc`, so all handlers built from the
+/// same config share one cached token.
+#[derive(Clone, Default)]
+pub struct OAuth2TokenCache(Arc<Mutex<Option<CachedToken>>>);
+
+impl std::fmt::Debug for OAuth2TokenCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OAuth2TokenCache(..)")
+    }
+}
+
+/// How `HttpInput.body` should be serialized onto the wire.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyKind {
+    /// Serialize `body` as a JSON document (the default).
+    #[default]
+    Json,
+    /// Serialize `body` (a flat string-keyed object) as
+    /// `application/x-www-form-urlencoded`.
+    Form,
+    /// Send `multipart` as `multipart/form-data`; `body` is ignored.
+    Multipart,
+}
+
+/// One field of a `multipart/form-data` body: a plain text value, or a file
+/// part supplied either by path (streamed from disk) or inline base64 bytes.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct MultipartField {
+    pub name: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub file_bytes_base64: Option<String>,
+    #[serde(default)]
+    pub filename: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
 pub struct HttpInput {
     #[serde(default)]
     pub body: Option<serde_json::Value>,
     #[serde(default)]
     pub query: HashMap<String, String>,
+    #[serde(default)]
+    pub body_kind: BodyKind,
+    #[serde(default)]
+    pub multipart: Vec<MultipartField>,
 }
 
 #[derive(Debug, Serialize, JsonSchema)]
@@ -42,6 +355,76 @@ pub struct HttpOutput {
     pub status: u16,
     pub body: serde_json::Value,
     pub headers: HashMap<String, String>,
+    /// The response's `Content-Type`, if present.
+    pub content_type: Option<String>,
+    /// Length in bytes of the decoded response body.
+    pub content_length: u64,
+}
+
+/// Failure building or sending a single request attempt. Kept separate
+/// from [`Error`] so `execute`'s retry loop can distinguish a genuinely
+/// retryable transport error from an unrecoverable local one (bad file
+/// path, invalid content type) without re-deriving that from a string.
+#[derive(Debug)]
+enum SendError {
+    Transport(reqwest::Error),
+    Io(std::io::Error),
+}
+
+impl From<reqwest::Error> for SendError {
+    fn from(e: reqwest::Error) -> Self {
+        SendError::Transport(e)
+    }
+}
+
+impl From<std::io::Error> for SendError {
+    fn from(e: std::io::Error) -> Self {
+        SendError::Io(e)
+    }
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Transport(e) => write!(f, "{}", e),
+            SendError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Build a `multipart/form-data` body from declared fields, streaming file
+/// parts from disk rather than buffering when a path is supplied.
+async fn build_multipart_form(
+    fields: &[MultipartField],
+) -> std::result::Result<reqwest::multipart::Form, SendError> {
+    let mut form = reqwest::multipart::Form::new();
+
+    for field in fields {
+        let mut part = if let Some(path) = &field.file_path {
+            reqwest::multipart::Part::file(path).await?
+        } else if let Some(b64) = &field.file_bytes_base64 {
+            use base64::Engine;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(b64)
+                .map_err(|e| {
+                    SendError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                })?;
+            reqwest::multipart::Part::bytes(bytes)
+        } else {
+            reqwest::multipart::Part::text(field.value.clone().unwrap_or_default())
+        };
+
+        if let Some(filename) = &field.filename {
+            part = part.file_name(filename.clone());
+        }
+        if let Some(content_type) = &field.content_type {
+            part = part.mime_str(content_type)?;
+        }
+
+        form = form.part(field.name.clone(), part);
+    }
+
+    Ok(form)
 }
 
 impl HttpHandler {
@@ -56,11 +439,233 @@ impl HttpHandler {
             method,
             headers,
             auth,
-            client: Client::new(),
+            client: build_rustls_client(&[]),
+            timeout: None,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Create a handler that pins a set of DER-encoded root certificates
+    /// instead of trusting the system root store.
+    pub fn with_root_certs(
+        endpoint: String,
+        method: HttpMethod,
+        headers: HashMap<String, String>,
+        auth: Option<AuthConfig>,
+        root_certs: Vec<Vec<u8>>,
+    ) -> Self {
+        Self {
+            endpoint,
+            method,
+            headers,
+            auth,
+            client: build_rustls_client(&root_certs),
+            timeout: None,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Create a handler that reuses a pre-built, pooled client (see
+    /// [`HttpClientConfig::build`]) instead of constructing its own, so
+    /// handlers registered against the same host share one connection pool.
+    pub fn with_client(
+        endpoint: String,
+        method: HttpMethod,
+        headers: HashMap<String, String>,
+        auth: Option<AuthConfig>,
+        client: Client,
+    ) -> Self {
+        Self {
+            endpoint,
+            method,
+            headers,
+            auth,
+            client,
+            timeout: None,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Apply a per-request timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Apply a retry policy for transient failures.
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// GET/PUT/DELETE are safe to retry without an explicit opt-in; POST and
+    /// PATCH may have non-idempotent side effects.
+    fn is_idempotent(&self) -> bool {
+        matches!(
+            self.method,
+            HttpMethod::Get | HttpMethod::Put | HttpMethod::Delete
+        )
+    }
+
+    /// Full-jitter exponential backoff: `delay = min(max_delay, base_delay * 2^attempt)`,
+    /// then (if jitter is enabled) a random duration in `[0, delay]`.
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .retry
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt));
+        let capped = exponential.min(self.retry.max_delay);
+
+        if !self.retry.jitter {
+            return capped;
+        }
+
+        let millis = capped.as_millis() as u64;
+        let jittered = if millis == 0 {
+            0
+        } else {
+            rand::thread_rng().gen_range(0..=millis)
+        };
+        Duration::from_millis(jittered)
+    }
+
+    /// Execute the request, aborting early if `cancel` fires before the
+    /// upstream responds. Integrates with [`crate::timeout::with_timeout`]
+    /// for the enclosing dispatch's own deadline.
+    pub async fn execute_cancellable(
+        &self,
+        input: HttpInput,
+        cancel: CancellationToken,
+    ) -> Result<HttpOutput> {
+        tokio::select! {
+            result = self.execute(input) => result,
+            _ = cancel.cancelled() => Err(Error::Http("request cancelled".to_string())),
         }
     }
 
+    /// Execute the request, retrying per [`RetryConfig`] on connection
+    /// failures and configured retryable status codes. If auth is OAuth2
+    /// client-credentials and the response is a 401, retries exactly once
+    /// more with a forcibly refreshed token.
     pub async fn execute(&self, input: HttpInput) -> Result<HttpOutput> {
+        let output = self.execute_attempts(&input, false).await?;
+
+        let is_oauth2 = matches!(self.auth, Some(AuthConfig::OAuth2ClientCredentials { .. }));
+        if output.status == 401 && is_oauth2 {
+            return self.execute_attempts(&input, true).await;
+        }
+
+        Ok(output)
+    }
+
+    async fn execute_attempts(
+        &self,
+        input: &HttpInput,
+        force_token_refresh: bool,
+    ) -> Result<HttpOutput> {
+        let attempts_allowed = if self.retry.retry_non_idempotent || self.is_idempotent() {
+            self.retry.max_attempts.max(1)
+        } else {
+            1
+        };
+
+        let mut attempt = 0u32;
+        loop {
+            match self.send_once(input, force_token_refresh).await {
+                Ok(output) => {
+                    let is_last = attempt + 1 >= attempts_allowed;
+                    let should_retry =
+                        !is_last && self.retry.retryable_status_codes.contains(&output.status);
+                    if !should_retry {
+                        return Ok(output);
+                    }
+
+                    let delay = output
+                        .headers
+                        .get("retry-after")
+                        .and_then(|v| parse_retry_after(v))
+                        .unwrap_or_else(|| self.backoff_delay(attempt));
+                    tokio::time::sleep(delay).await;
+                }
+                Err(SendError::Transport(e)) => {
+                    let is_last = attempt + 1 >= attempts_allowed;
+                    if is_last || !(e.is_connect() || e.is_timeout()) {
+                        return Err(Error::Http(format!("Request failed: {}", e)));
+                    }
+                    tokio::time::sleep(self.backoff_delay(attempt)).await;
+                }
+                Err(SendError::Io(e)) => {
+                    return Err(Error::Http(format!("Request body build failed: {}", e)));
+                }
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Return a cached OAuth2 access token, refreshing it if it's missing,
+    /// forced, or within [`OAUTH2_EXPIRY_SKEW`] of expiry.
+    #[allow(clippy::too_many_arguments)]
+    async fn get_oauth2_token(
+        &self,
+        token_url: &str,
+        client_id: &str,
+        client_secret: &str,
+        scopes: &[String],
+        audience: Option<&str>,
+        cache: &OAuth2TokenCache,
+        force_refresh: bool,
+    ) -> std::result::Result<String, SendError> {
+        if !force_refresh {
+            let cached = cache.0.lock().unwrap();
+            if let Some(token) = cached.as_ref() {
+                if token.expires_at > Instant::now() + OAUTH2_EXPIRY_SKEW {
+                    return Ok(token.access_token.clone());
+                }
+            }
+        }
+
+        let mut form = vec![
+            ("grant_type".to_string(), "client_credentials".to_string()),
+            ("client_id".to_string(), client_id.to_string()),
+            ("client_secret".to_string(), client_secret.to_string()),
+        ];
+        if !scopes.is_empty() {
+            form.push(("scope".to_string(), scopes.join(" ")));
+        }
+        if let Some(audience) = audience {
+            form.push(("audience".to_string(), audience.to_string()));
+        }
+
+        let response = self.client.post(token_url).form(&form).send().await?;
+        let body: serde_json::Value = response.json().await.map_err(SendError::Transport)?;
+
+        let access_token = body["access_token"]
+            .as_str()
+            .ok_or_else(|| {
+                SendError::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "OAuth2 token response missing access_token",
+                ))
+            })?
+            .to_string();
+        let expires_in = body["expires_in"].as_u64().unwrap_or(3600);
+
+        *cache.0.lock().unwrap() = Some(CachedToken {
+            access_token: access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(expires_in),
+        });
+
+        Ok(access_token)
+    }
+
+    /// Fire a single HTTP request with no retry logic. SigV4/OAuth2 auth
+    /// (if configured) is computed here, on the final bytes, so each retry
+    /// re-signs (or re-fetches a token) fresh rather than reusing stale auth.
+    async fn send_once(
+        &self,
+        input: &HttpInput,
+        force_token_refresh: bool,
+    ) -> std::result::Result<HttpOutput, SendError> {
         let method = match self.method {
             HttpMethod::Get => Method::GET,
             HttpMethod::Post => Method::POST,
@@ -69,7 +674,38 @@ impl HttpHandler {
             HttpMethod::Patch => Method::PATCH,
         };
 
-        let mut request = self.client.request(method, &self.endpoint);
+        let body_bytes = match input.body_kind {
+            BodyKind::Json => input
+                .body
+                .as_ref()
+                .map(|b| serde_json::to_vec(b).unwrap_or_default()),
+            BodyKind::Form | BodyKind::Multipart => None,
+        };
+
+        // Append the query string ourselves via `canonical_query_string`
+        // instead of going through reqwest's `.query()` (which encodes via
+        // `serde_urlencoded`/form-encoding - e.g. space as `+`, not `%20`).
+        // `sign_aws_v4` below signs this exact same canonical encoding, so
+        // a value containing a character the two schemes escape
+        // differently can no longer desync the signature from the request
+        // AWS actually receives.
+        let request_url = if input.query.is_empty() {
+            self.endpoint.clone()
+        } else {
+            let separator = if self.endpoint.contains('?') { '&' } else { '?' };
+            format!(
+                "{}{}{}",
+                self.endpoint,
+                separator,
+                canonical_query_string(&input.query)
+            )
+        };
+
+        let mut request = self.client.request(method.clone(), &request_url);
+
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
 
         // Add headers
         for (k, v) in &self.headers {
@@ -84,26 +720,88 @@ impl HttpHandler {
                     request.basic_auth(username, Some(password))
                 }
                 AuthConfig::ApiKey { key, header } => request.header(header, key),
+                AuthConfig::AwsSigV4 {
+                    access_key,
+                    secret_key,
+                    session_token,
+                    region,
+                    service,
+                } => {
+                    let sigv4 = sign_aws_v4(
+                        &method,
+                        &self.endpoint,
+                        &input.query,
+                        body_bytes.as_deref().unwrap_or(&[]),
+                        access_key,
+                        secret_key,
+                        session_token.as_deref(),
+                        region,
+                        service,
+                    );
+                    let mut request = request
+                        .header("x-amz-date", &sigv4.amz_date)
+                        .header("authorization", &sigv4.authorization);
+                    if let Some(token) = session_token {
+                        request = request.header("x-amz-security-token", token);
+                    }
+                    request
+                }
+                AuthConfig::OAuth2ClientCredentials {
+                    token_url,
+                    client_id,
+                    client_secret,
+                    scopes,
+                    audience,
+                    cache,
+                } => {
+                    let access_token = self
+                        .get_oauth2_token(
+                            token_url,
+                            client_id,
+                            client_secret,
+                            scopes,
+                            audience.as_deref(),
+                            cache,
+                            force_token_refresh,
+                        )
+                        .await?;
+                    request.bearer_auth(access_token)
+                }
             };
         }
 
-        // Add query parameters
-        if !input.query.is_empty() {
-            request = request.query(&input.query);
-        }
-
-        // Add body for non-GET requests
-        if let Some(body) = input.body {
-            request = request.json(&body);
+        // Add the body (for any method, when one is given), shaped per `body_kind`
+        match input.body_kind {
+            BodyKind::Json => {
+                if let Some(bytes) = body_bytes {
+                    request = request
+                        .header("content-type", "application/json")
+                        .body(bytes);
+                }
+            }
+            BodyKind::Form => {
+                if let Some(body) = &input.body {
+                    let form: HashMap<String, String> =
+                        serde_json::from_value(body.clone()).unwrap_or_default();
+                    request = request.form(&form);
+                }
+            }
+            BodyKind::Multipart => {
+                if !input.multipart.is_empty() {
+                    request = request.multipart(build_multipart_form(&input.multipart).await?);
+                }
+            }
         }
 
         // Execute request
-        let response = request
-            .send()
-            .await
-            .map_err(|e| Error::Http(format!("Request failed: {}", e)))?;
+        let response = request.send().await?;
 
         let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
 
         // Extract headers
         let mut headers = HashMap::new();
@@ -113,20 +811,179 @@ impl HttpHandler {
             }
         }
 
-        // Parse body as JSON (or empty object if fails)
-        let body = response
-            .json::<serde_json::Value>()
-            .await
-            .unwrap_or(serde_json::json!({}));
+        // Decompression (gzip/brotli/deflate) happens transparently inside
+        // reqwest; `bytes()` yields the already-decoded body either way.
+        let raw = response.bytes().await?;
+        let content_length = raw.len() as u64;
+        let body = decode_response_body(&raw, content_type.as_deref());
 
         Ok(HttpOutput {
             status,
             body,
             headers,
+            content_type,
+            content_length,
         })
     }
 }
 
+/// Decode a response body by content type: JSON is parsed, `text/*` is
+/// kept as a UTF-8 string, and anything else (or JSON/UTF-8 that fails to
+/// parse) is base64-encoded. Applied to both success and error responses
+/// so callers can inspect API error bodies instead of getting `{}`.
+fn decode_response_body(bytes: &[u8], content_type: Option<&str>) -> serde_json::Value {
+    if bytes.is_empty() {
+        return serde_json::json!({});
+    }
+
+    let is_json = content_type.map(|ct| ct.contains("json")).unwrap_or(false);
+    let is_text = content_type
+        .map(|ct| ct.starts_with("text/") || ct.contains("xml") || ct.contains("urlencoded"))
+        .unwrap_or(false);
+
+    if is_json || content_type.is_none() {
+        if let Ok(value) = serde_json::from_slice::<serde_json::Value>(bytes) {
+            return value;
+        }
+    }
+
+    if is_text {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return serde_json::Value::String(text.to_string());
+        }
+    }
+
+    use base64::Engine;
+    serde_json::Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+struct SigV4Headers {
+    authorization: String,
+    amz_date: String,
+}
+
+/// Sign a request with AWS Signature Version 4, per the canonical-request /
+/// string-to-sign / signing-key derivation in the SigV4 spec. Always
+/// re-computed from the final method/query/body, so callers must invoke
+/// this after those are finalized (and re-invoke it on every retry).
+#[allow(clippy::too_many_arguments)]
+fn sign_aws_v4(
+    method: &Method,
+    endpoint: &str,
+    query: &HashMap<String, String>,
+    body: &[u8],
+    access_key: &str,
+    secret_key: &str,
+    _session_token: Option<&str>,
+    region: &str,
+    service: &str,
+) -> SigV4Headers {
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date_stamp = now.format("%Y%m%d").to_string();
+
+    let url = reqwest::Url::parse(endpoint)
+        .unwrap_or_else(|_| reqwest::Url::parse("http://invalid.invalid").unwrap());
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let canonical_uri = match url.path() {
+        "" => "/".to_string(),
+        path => path.to_string(),
+    };
+
+    let canonical_query = canonical_query_string(query);
+    let canonical_headers = format!("host:{}\nx-amz-date:{}\n", host, amz_date);
+    let signed_headers = "host;x-amz-date";
+    let body_hash = hex::encode(Sha256::digest(body));
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method.as_str(),
+        canonical_uri,
+        canonical_query,
+        canonical_headers,
+        signed_headers,
+        body_hash
+    );
+
+    let credential_scope = format!("{}/{}/{}/aws4_request", date_stamp, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        credential_scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let signing_key = {
+        let k_date = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date_stamp.as_bytes());
+        let k_region = hmac_sha256(&k_date, region.as_bytes());
+        let k_service = hmac_sha256(&k_region, service.as_bytes());
+        hmac_sha256(&k_service, b"aws4_request")
+    };
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, credential_scope, signed_headers, signature
+    );
+
+    SigV4Headers {
+        authorization,
+        amz_date,
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Sorted, percent-encoded `k=v&...` canonical query string per the SigV4 spec.
+fn canonical_query_string(query: &HashMap<String, String>) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// RFC 3986 percent-encoding: escape everything except unreserved
+/// characters (`A-Za-z0-9-_.~`), as SigV4 canonicalization requires.
+fn uri_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Parse a `Retry-After` header value, either a number of seconds or an
+/// HTTP-date, into a sleep duration.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    httpdate::parse_http_date(value)
+        .ok()
+        .and_then(|when| when.duration_since(std::time::SystemTime::now()).ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,6 +1002,219 @@ mod tests {
         assert!(handler.auth.is_none());
     }
 
+    #[test]
+    fn test_http_client_config_default() {
+        let config = HttpClientConfig::default();
+        assert_eq!(config.pool_idle_timeout, Some(Duration::from_secs(90)));
+        assert!(config.proxy.is_none());
+        assert!(matches!(config.redirect_policy, RedirectPolicy::Limited(10)));
+    }
+
+    #[test]
+    fn test_http_client_config_builds_successfully() {
+        let config = HttpClientConfig::default();
+        assert!(config.build().is_ok());
+    }
+
+    #[test]
+    fn test_http_client_config_rejects_invalid_proxy_url() {
+        let config = HttpClientConfig {
+            proxy: Some(ProxyConfig {
+                url: "not a url".to_string(),
+                basic_auth: None,
+            }),
+            ..HttpClientConfig::default()
+        };
+        assert!(config.build().is_err());
+    }
+
+    #[test]
+    fn test_http_client_provider_caches_client_for_equal_configs() {
+        let provider = HttpClientProvider::new();
+        let config = HttpClientConfig::default();
+
+        assert!(provider.client_for(&config).is_ok());
+        assert!(provider.client_for(&config).is_ok());
+        assert_eq!(provider.cache.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_http_client_provider_caches_separately_per_distinct_config() {
+        let provider = HttpClientProvider::new();
+        let default_config = HttpClientConfig::default();
+        let proxied_config = HttpClientConfig {
+            proxy: Some(ProxyConfig {
+                url: "http://proxy.example:8080".to_string(),
+                basic_auth: None,
+            }),
+            ..HttpClientConfig::default()
+        };
+
+        assert!(provider.client_for(&default_config).is_ok());
+        assert!(provider.client_for(&proxied_config).is_ok());
+        assert_eq!(provider.cache.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_http_client_provider_fresh_client_does_not_populate_cache() {
+        let provider = HttpClientProvider::new();
+        let config = HttpClientConfig::default();
+
+        assert!(provider.fresh_client(&config).is_ok());
+        assert_eq!(provider.cache.lock().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_fetches_and_caches_token() {
+        let mut server = mockito::Server::new_async().await;
+        let token_mock = server
+            .mock("POST", "/token")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "grant_type".to_string(),
+                "client_credentials".to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"access_token": "tok-1", "expires_in": 3600}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let api_mock = server
+            .mock("GET", "/protected")
+            .match_header("authorization", "Bearer tok-1")
+            .with_status(200)
+            .with_body(r#"{"ok": true}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let handler = HttpHandler::new(
+            format!("{}/protected", server.url()),
+            HttpMethod::Get,
+            HashMap::new(),
+            Some(AuthConfig::oauth2_client_credentials(
+                format!("{}/token", server.url()),
+                "client-id".to_string(),
+                "client-secret".to_string(),
+                vec!["read".to_string()],
+                None,
+            )),
+        );
+
+        let input = HttpInput {
+            body: None,
+            query: HashMap::new(),
+            ..Default::default()
+        };
+
+        // First call fetches a token; second reuses the cached one, so the
+        // token endpoint should only be hit once.
+        assert_eq!(handler.execute(input.clone()).await.unwrap().status, 200);
+        assert_eq!(handler.execute(input).await.unwrap().status, 200);
+
+        token_mock.assert_async().await;
+        api_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_retries_once_on_401_with_fresh_token() {
+        let mut server = mockito::Server::new_async().await;
+        let token_mock = server
+            .mock("POST", "/token")
+            .with_status(200)
+            .with_body(r#"{"access_token": "tok-2", "expires_in": 3600}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let api_mock = server
+            .mock("GET", "/protected")
+            .match_header("authorization", "Bearer tok-2")
+            .with_status(401)
+            .with_body(r#"{"error": "expired"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let handler = HttpHandler::new(
+            format!("{}/protected", server.url()),
+            HttpMethod::Get,
+            HashMap::new(),
+            Some(AuthConfig::oauth2_client_credentials(
+                format!("{}/token", server.url()),
+                "client-id".to_string(),
+                "client-secret".to_string(),
+                vec![],
+                None,
+            )),
+        );
+
+        let input = HttpInput {
+            body: None,
+            query: HashMap::new(),
+            ..Default::default()
+        };
+
+        // A 401 with OAuth2 auth forces exactly one forced-refresh retry,
+        // so both the token and protected endpoints see two calls.
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.status, 401);
+
+        token_mock.assert_async().await;
+        api_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_with_client_reuses_shared_client() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/shared")
+            .with_status(200)
+            .with_body(r#"{"ok": true}"#)
+            .create_async()
+            .await;
+
+        let client = HttpClientConfig::default().build().unwrap();
+        let handler = HttpHandler::with_client(
+            format!("{}/shared", server.url()),
+            HttpMethod::Get,
+            HashMap::new(),
+            None,
+            client,
+        );
+
+        let input = HttpInput {
+            body: None,
+            query: HashMap::new(),
+            ..Default::default()
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.status, 200);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_cancellable_aborts_on_cancel() {
+        let handler = HttpHandler::new(
+            "http://10.255.255.1/unreachable".to_string(),
+            HttpMethod::Get,
+            HashMap::new(),
+            None,
+        );
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let input = HttpInput {
+            body: None,
+            query: HashMap::new(),
+            ..Default::default()
+        };
+
+        let result = handler.execute_cancellable(input, cancel).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("cancelled"));
+    }
+
     #[test]
     fn test_http_handler_new_with_auth() {
         let mut headers = HashMap::new();
@@ -193,6 +1263,8 @@ mod tests {
             status: 200,
             body: serde_json::json!({"result": "success"}),
             headers,
+            content_type: Some("application/json".to_string()),
+            content_length: 24,
         };
 
         let json = serde_json::to_string(&output).unwrap();
@@ -221,6 +1293,7 @@ mod tests {
         let input = HttpInput {
             body: None,
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -254,6 +1327,7 @@ mod tests {
         let input = HttpInput {
             body: Some(serde_json::json!({"key": "value"})),
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -288,7 +1362,11 @@ mod tests {
         query.insert("q".to_string(), "rust".to_string());
         query.insert("limit".to_string(), "10".to_string());
 
-        let input = HttpInput { body: None, query };
+        let input = HttpInput {
+            body: None,
+            query,
+            ..Default::default()
+        };
 
         let output = handler.execute(input).await.unwrap();
 
@@ -319,6 +1397,7 @@ mod tests {
         let input = HttpInput {
             body: None,
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -352,6 +1431,7 @@ mod tests {
         let input = HttpInput {
             body: None,
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -385,6 +1465,7 @@ mod tests {
         let input = HttpInput {
             body: None,
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -420,6 +1501,7 @@ mod tests {
         let input = HttpInput {
             body: None,
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -448,6 +1530,7 @@ mod tests {
         let input = HttpInput {
             body: Some(serde_json::json!({"data": "new_value"})),
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -477,6 +1560,7 @@ mod tests {
         let input = HttpInput {
             body: None,
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -505,6 +1589,7 @@ mod tests {
         let input = HttpInput {
             body: Some(serde_json::json!({"field": "value"})),
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let output = handler.execute(input).await.unwrap();
@@ -526,9 +1611,293 @@ mod tests {
         let input = HttpInput {
             body: None,
             query: HashMap::new(),
+            ..Default::default()
         };
 
         let result = handler.execute(input).await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_is_idempotent() {
+        let get = HttpHandler::new("http://x".to_string(), HttpMethod::Get, HashMap::new(), None);
+        let post = HttpHandler::new("http://x".to_string(), HttpMethod::Post, HashMap::new(), None);
+        assert!(get.is_idempotent());
+        assert!(!post.is_idempotent());
+    }
+
+    #[test]
+    fn test_backoff_delay_respects_max_delay() {
+        let handler = HttpHandler::new("http://x".to_string(), HttpMethod::Get, HashMap::new(), None)
+            .with_retry_config(RetryConfig {
+                max_attempts: 10,
+                base_delay: Duration::from_secs(1),
+                max_delay: Duration::from_secs(2),
+                jitter: false,
+                retryable_status_codes: vec![503],
+                retry_non_idempotent: false,
+            });
+
+        assert_eq!(handler.backoff_delay(10), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        assert_eq!(parse_retry_after("5"), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_parse_retry_after_invalid_is_none() {
+        assert_eq!(parse_retry_after("not-a-date"), None);
+    }
+
+    #[test]
+    fn test_uri_encode_leaves_unreserved_untouched() {
+        assert_eq!(uri_encode("abc-_.~123"), "abc-_.~123");
+        assert_eq!(uri_encode("a b"), "a%20b");
+        assert_eq!(uri_encode("a/b"), "a%2Fb");
+    }
+
+    #[test]
+    fn test_canonical_query_string_is_sorted_and_encoded() {
+        let mut query = HashMap::new();
+        query.insert("b".to_string(), "2".to_string());
+        query.insert("a".to_string(), "1 ".to_string());
+
+        assert_eq!(canonical_query_string(&query), "a=1%20&b=2");
+    }
+
+    #[test]
+    fn test_sign_aws_v4_produces_expected_shape() {
+        let mut query = HashMap::new();
+        query.insert("q".to_string(), "search".to_string());
+
+        let sigv4 = sign_aws_v4(
+            &Method::GET,
+            "https://search-domain.us-east-1.es.amazonaws.com/index/_search",
+            &query,
+            b"",
+            "AKIDEXAMPLE",
+            "secret",
+            None,
+            "us-east-1",
+            "es",
+        );
+
+        assert!(sigv4
+            .authorization
+            .starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+        assert!(sigv4
+            .authorization
+            .contains("/us-east-1/es/aws4_request, SignedHeaders=host;x-amz-date, Signature="));
+        assert_eq!(sigv4.amz_date.len(), "20260101T000000Z".len());
+    }
+
+    #[test]
+    fn test_decode_response_body_json() {
+        let body = decode_response_body(br#"{"a":1}"#, Some("application/json"));
+        assert_eq!(body, serde_json::json!({"a": 1}));
+    }
+
+    #[test]
+    fn test_decode_response_body_text() {
+        let body = decode_response_body(b"hello", Some("text/plain"));
+        assert_eq!(body, serde_json::Value::String("hello".to_string()));
+    }
+
+    #[test]
+    fn test_decode_response_body_binary_is_base64() {
+        let body = decode_response_body(&[0xff, 0x00, 0x10], Some("application/octet-stream"));
+        use base64::Engine;
+        let expected = base64::engine::general_purpose::STANDARD.encode([0xff, 0x00, 0x10]);
+        assert_eq!(body, serde_json::Value::String(expected));
+    }
+
+    #[test]
+    fn test_decode_response_body_empty_is_empty_object() {
+        assert_eq!(decode_response_body(b"", Some("application/json")), serde_json::json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_execute_preserves_non_2xx_body() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/error")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": "bad request"}"#)
+            .create_async()
+            .await;
+
+        let handler = HttpHandler::new(
+            format!("{}/error", server.url()),
+            HttpMethod::Get,
+            HashMap::new(),
+            None,
+        );
+
+        let input = HttpInput {
+            body: None,
+            query: HashMap::new(),
+            ..Default::default()
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.status, 400);
+        assert_eq!(output.body["error"], "bad request");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_retries_on_retryable_status_then_succeeds() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("GET", "/flaky")
+            .with_status(503)
+            .with_body(r#"{}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/flaky")
+            .with_status(200)
+            .with_body(r#"{"ok": true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let handler = HttpHandler::new(
+            format!("{}/flaky", server.url()),
+            HttpMethod::Get,
+            HashMap::new(),
+            None,
+        )
+        .with_retry_config(RetryConfig {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            jitter: false,
+            retryable_status_codes: vec![503],
+            retry_non_idempotent: false,
+        });
+
+        let input = HttpInput {
+            body: None,
+            query: HashMap::new(),
+            ..Default::default()
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.status, 200);
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_does_not_retry_non_idempotent_by_default() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/submit")
+            .with_status(503)
+            .with_body(r#"{}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let handler = HttpHandler::new(
+            format!("{}/submit", server.url()),
+            HttpMethod::Post,
+            HashMap::new(),
+            None,
+        )
+        .with_retry_config(RetryConfig {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            jitter: false,
+            retryable_status_codes: vec![503],
+            retry_non_idempotent: false,
+        });
+
+        let input = HttpInput {
+            body: Some(serde_json::json!({"key": "value"})),
+            query: HashMap::new(),
+            ..Default::default()
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.status, 503);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_form_body() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/form")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body(mockito::Matcher::UrlEncoded(
+                "name".to_string(),
+                "pforge".to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"ok": true}"#)
+            .create_async()
+            .await;
+
+        let handler = HttpHandler::new(
+            format!("{}/form", server.url()),
+            HttpMethod::Post,
+            HashMap::new(),
+            None,
+        );
+
+        let input = HttpInput {
+            body: Some(serde_json::json!({"name": "pforge"})),
+            body_kind: BodyKind::Form,
+            ..Default::default()
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.status, 200);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_multipart_body_with_inline_bytes() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/upload")
+            .with_status(200)
+            .with_body(r#"{"uploaded": true}"#)
+            .create_async()
+            .await;
+
+        let handler = HttpHandler::new(
+            format!("{}/upload", server.url()),
+            HttpMethod::Post,
+            HashMap::new(),
+            None,
+        );
+
+        use base64::Engine;
+        let input = HttpInput {
+            body_kind: BodyKind::Multipart,
+            multipart: vec![MultipartField {
+                name: "file".to_string(),
+                value: None,
+                file_path: None,
+                file_bytes_base64: Some(
+                    base64::engine::general_purpose::STANDARD.encode(b"hello world"),
+                ),
+                filename: Some("hello.txt".to_string()),
+                content_type: Some("text/plain".to_string()),
+            }],
+            ..Default::default()
+        };
+
+        let output = handler.execute(input).await.unwrap();
+        assert_eq!(output.status, 200);
+        mock.assert_async().await;
+    }
 }
@@ -0,0 +1,283 @@
+//! JSON-RPC 2.0 over HTTP, reusing [`HttpHandler`]'s auth/header/retry
+//! machinery for the transport and adding only the request/response
+//! envelope on top.
+
+use crate::handlers::http::{AuthConfig, HttpHandler, HttpInput, HttpMethod};
+use crate::{Error, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// One JSON-RPC call: a method name plus by-name (object) or by-position
+/// (array) params. `notify: true` sends it without an `id`, so no response
+/// is expected for it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonRpcCall {
+    pub method: String,
+    #[serde(default)]
+    pub params: Option<serde_json::Value>,
+    #[serde(default)]
+    pub notify: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum JsonRpcInput {
+    Single(JsonRpcCall),
+    Batch(Vec<JsonRpcCall>),
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum JsonRpcOutput {
+    Single(serde_json::Value),
+    Batch(Vec<serde_json::Value>),
+}
+
+/// JSON-RPC 2.0 client over HTTP. Wraps calls in the `{"jsonrpc":"2.0",…}`
+/// envelope, POSTs through an inner [`HttpHandler`], and unwraps
+/// `result`/`error` on the way back.
+pub struct JsonRpcHandler {
+    http: HttpHandler,
+    next_id: AtomicU64,
+}
+
+impl JsonRpcHandler {
+    pub fn new(
+        endpoint: String,
+        headers: HashMap<String, String>,
+        auth: Option<AuthConfig>,
+    ) -> Self {
+        Self {
+            http: HttpHandler::new(endpoint, HttpMethod::Post, headers, auth),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn envelope(&self, call: &JsonRpcCall) -> serde_json::Value {
+        let mut request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": call.method,
+        });
+        if let Some(params) = &call.params {
+            request["params"] = params.clone();
+        }
+        if !call.notify {
+            request["id"] = serde_json::json!(self.next_id.fetch_add(1, Ordering::SeqCst));
+        }
+        request
+    }
+
+    /// Pull `result` out of a single JSON-RPC response, mapping an `error`
+    /// object into `Error::Handler` with the code preserved in the message.
+    fn unwrap_response(response: &serde_json::Value) -> Result<serde_json::Value> {
+        if let Some(error) = response.get("error") {
+            let code = error.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+            let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("");
+            let data = error.get("data").cloned().unwrap_or(serde_json::Value::Null);
+            return Err(Error::Handler(format!(
+                "JSON-RPC error {}: {} (data: {})",
+                code, message, data
+            )));
+        }
+
+        Ok(response
+            .get("result")
+            .cloned()
+            .unwrap_or(serde_json::Value::Null))
+    }
+
+    pub async fn execute(&self, input: JsonRpcInput) -> Result<JsonRpcOutput> {
+        match input {
+            JsonRpcInput::Single(call) => {
+                let notify = call.notify;
+                let envelope = self.envelope(&call);
+
+                let output = self
+                    .http
+                    .execute(HttpInput {
+                        body: Some(envelope),
+                        ..Default::default()
+                    })
+                    .await?;
+
+                if notify {
+                    return Ok(JsonRpcOutput::Single(serde_json::Value::Null));
+                }
+                Self::unwrap_response(&output.body).map(JsonRpcOutput::Single)
+            }
+            JsonRpcInput::Batch(calls) => self.execute_batch(calls).await,
+        }
+    }
+
+    async fn execute_batch(&self, calls: Vec<JsonRpcCall>) -> Result<JsonRpcOutput> {
+        let envelopes: Vec<serde_json::Value> = calls.iter().map(|c| self.envelope(c)).collect();
+
+        if calls.iter().all(|c| c.notify) {
+            self.http
+                .execute(HttpInput {
+                    body: Some(serde_json::Value::Array(envelopes)),
+                    ..Default::default()
+                })
+                .await?;
+            return Ok(JsonRpcOutput::Batch(Vec::new()));
+        }
+
+        let output = self
+            .http
+            .execute(HttpInput {
+                body: Some(serde_json::Value::Array(envelopes.clone())),
+                ..Default::default()
+            })
+            .await?;
+
+        let responses = output.body.as_array().cloned().unwrap_or_default();
+        let by_id: HashMap<i64, serde_json::Value> = responses
+            .into_iter()
+            .filter_map(|r| r.get("id").and_then(|id| id.as_i64()).map(|id| (id, r)))
+            .collect();
+
+        let mut results = Vec::new();
+        for (call, envelope) in calls.iter().zip(envelopes.iter()) {
+            if call.notify {
+                continue;
+            }
+            let id = envelope.get("id").and_then(|id| id.as_i64()).unwrap_or(-1);
+            let response = by_id
+                .get(&id)
+                .ok_or_else(|| Error::Handler(format!("no JSON-RPC response for id {}", id)))?;
+            results.push(Self::unwrap_response(response)?);
+        }
+
+        Ok(JsonRpcOutput::Batch(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_single_call_unwraps_result() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/rpc")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"jsonrpc":"2.0","method":"add","params":[1,2],"id":1}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","result":3,"id":1}"#)
+            .create_async()
+            .await;
+
+        let handler = JsonRpcHandler::new(format!("{}/rpc", server.url()), HashMap::new(), None);
+        let output = handler
+            .execute(JsonRpcInput::Single(JsonRpcCall {
+                method: "add".to_string(),
+                params: Some(serde_json::json!([1, 2])),
+                notify: false,
+            }))
+            .await
+            .unwrap();
+
+        match output {
+            JsonRpcOutput::Single(value) => assert_eq!(value, serde_json::json!(3)),
+            JsonRpcOutput::Batch(_) => panic!("expected single response"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_single_call_maps_error_object() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/rpc")
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"method not found"},"id":1}"#)
+            .create_async()
+            .await;
+
+        let handler = JsonRpcHandler::new(format!("{}/rpc", server.url()), HashMap::new(), None);
+        let result = handler
+            .execute(JsonRpcInput::Single(JsonRpcCall {
+                method: "missing".to_string(),
+                params: None,
+                notify: false,
+            }))
+            .await;
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("-32601"));
+        assert!(err.to_string().contains("method not found"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_notification_has_no_id_and_no_result() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/rpc")
+            .match_body(mockito::Matcher::Regex(
+                r#"\{"jsonrpc":"2.0","method":"ping"\}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_body("")
+            .create_async()
+            .await;
+
+        let handler = JsonRpcHandler::new(format!("{}/rpc", server.url()), HashMap::new(), None);
+        let output = handler
+            .execute(JsonRpcInput::Single(JsonRpcCall {
+                method: "ping".to_string(),
+                params: None,
+                notify: true,
+            }))
+            .await
+            .unwrap();
+
+        match output {
+            JsonRpcOutput::Single(value) => assert_eq!(value, serde_json::Value::Null),
+            JsonRpcOutput::Batch(_) => panic!("expected single response"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_batch_correlates_responses_by_id() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/rpc")
+            .with_status(200)
+            .with_body(
+                r#"[{"jsonrpc":"2.0","result":"b","id":2},{"jsonrpc":"2.0","result":"a","id":1}]"#,
+            )
+            .create_async()
+            .await;
+
+        let handler = JsonRpcHandler::new(format!("{}/rpc", server.url()), HashMap::new(), None);
+        let output = handler
+            .execute(JsonRpcInput::Batch(vec![
+                JsonRpcCall {
+                    method: "first".to_string(),
+                    params: None,
+                    notify: false,
+                },
+                JsonRpcCall {
+                    method: "second".to_string(),
+                    params: None,
+                    notify: false,
+                },
+            ]))
+            .await
+            .unwrap();
+
+        match output {
+            JsonRpcOutput::Batch(results) => {
+                assert_eq!(results, vec![serde_json::json!("a"), serde_json::json!("b")]);
+            }
+            JsonRpcOutput::Single(_) => panic!("expected batch response"),
+        }
+        mock.assert_async().await;
+    }
+}
@@ -1,8 +1,17 @@
+pub mod chunked;
 pub mod cli;
+pub mod crypto;
+pub mod health;
 pub mod http;
+pub mod jsonrpc;
 pub mod pipeline;
 mod wrappers;
 
-pub use cli::CliHandler;
-pub use http::HttpHandler;
+pub use cli::{CliAttemptOutcome, CliHandler, CliRetryPolicy, CliStreamEvent, PtyHandle, PtySize};
+pub use crypto::CryptoHandler;
+pub use health::{
+    CommandExistsProbe, HealthCheckHandler, HttpReachabilityProbe, StateManagerProbe,
+};
+pub use http::{HttpClientConfig, HttpClientProvider, HttpHandler};
+pub use jsonrpc::JsonRpcHandler;
 pub use pipeline::PipelineHandler;
@@ -1,9 +1,27 @@
-use crate::{HandlerRegistry, Result};
+use crate::{Error, HandlerRegistry, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-
-#[derive(Debug, Clone)]
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Poll;
+use tokio::sync::RwLock;
+
+/// Runs a `ToolDef::Pipeline`'s steps in dependency order - independent
+/// steps concurrently, layer by layer (see [`PipelineHandler::execute`]) -
+/// through the same [`HandlerRegistry`] every other tool is dispatched
+/// through, piping each step's output into later steps' input via
+/// `variables`.
+///
+/// `registry` is the live registry the pipeline itself is registered in, so
+/// [`Handler::handle`](crate::Handler::handle) resolves each step's `tool`
+/// name against it at dispatch time rather than capturing specific handler
+/// references when the pipeline is constructed - a step can reference any
+/// tool registered before or after the pipeline itself.
+#[derive(Clone)]
 pub struct PipelineHandler {
+    pub(crate) registry: Arc<RwLock<HandlerRegistry>>,
     pub steps: Vec<PipelineStep>,
 }
 
@@ -43,91 +61,253 @@ pub struct StepResult {
 }
 
 impl PipelineHandler {
-    pub fn new(steps: Vec<PipelineStep>) -> Self {
-        Self { steps }
+    pub fn new(registry: Arc<RwLock<HandlerRegistry>>, steps: Vec<PipelineStep>) -> Self {
+        Self { registry, steps }
     }
 
+    /// Run every step, inferring data dependencies from `{{var}}` references
+    /// in each step's `input` and executing mutually independent steps
+    /// concurrently layer by layer (Kahn's algorithm), rather than strictly
+    /// one after another.
+    ///
+    /// `condition`s are evaluated once, up front, against the `variables`
+    /// the call started with, before the dependency graph is built - a
+    /// step's place in the graph has to be decided before anything runs,
+    /// so (unlike the old purely-sequential executor) a condition that
+    /// references another step's `output_var` sees it as unavailable
+    /// (falsy, same as any other missing variable) rather than that step's
+    /// actual result.
     pub async fn execute(
         &self,
         input: PipelineInput,
         registry: &HandlerRegistry,
     ) -> Result<PipelineOutput> {
         let mut variables = input.variables;
-        let mut results = Vec::new();
 
-        for step in &self.steps {
-            // Check condition if present
-            if let Some(condition) = &step.condition {
-                if !self.evaluate_condition(condition, &variables) {
+        let active: Vec<usize> = (0..self.steps.len())
+            .filter(|&i| match &self.steps[i].condition {
+                Some(condition) => self.evaluate_condition(condition, &variables),
+                None => true,
+            })
+            .collect();
+
+        let layers = self.layer_steps(&active)?;
+        let mut results: Vec<Option<StepResult>> = (0..self.steps.len()).map(|_| None).collect();
+
+        for layer in layers {
+            let mut prepared = Vec::with_capacity(layer.len());
+            for &i in &layer {
+                let step = &self.steps[i];
+                let step_input = if let Some(input_template) = &step.input {
+                    self.interpolate_variables(input_template, &variables)
+                } else {
+                    serde_json::json!({})
+                };
+                prepared.push(PreparedStep {
+                    index: i,
+                    tool: step.tool.as_str(),
+                    bytes: serde_json::to_vec(&step_input)?,
+                    output_var: step.output_var.as_deref(),
+                    error_policy: &step.error_policy,
+                });
+            }
+
+            let fail_fast: Vec<bool> = prepared
+                .iter()
+                .map(|p| *p.error_policy == ErrorPolicy::FailFast)
+                .collect();
+            let futures: Vec<Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + '_>>> =
+                prepared
+                    .iter()
+                    .map(|p| -> Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + '_>> {
+                        Box::pin(registry.dispatch(p.tool, &p.bytes))
+                    })
+                    .collect();
+
+            let outcomes = run_layer_concurrently(futures, fail_fast).await;
+
+            let mut fail_fast_error = None;
+            for (prepared_step, outcome) in prepared.iter().zip(outcomes) {
+                let Some(outcome) = outcome else {
+                    // Never dispatched: a sibling in this same layer hit a
+                    // FailFast error first and the rest of the layer was
+                    // dropped before it got a turn.
                     continue;
+                };
+
+                match outcome {
+                    Ok(output) => {
+                        let output_value: serde_json::Value = serde_json::from_slice(&output)?;
+                        if let Some(var_name) = prepared_step.output_var {
+                            variables.insert(var_name.to_string(), output_value.clone());
+                        }
+                        results[prepared_step.index] = Some(StepResult {
+                            tool: prepared_step.tool.to_string(),
+                            success: true,
+                            output: Some(output_value),
+                            error: None,
+                        });
+                    }
+                    Err(e) => {
+                        results[prepared_step.index] = Some(StepResult {
+                            tool: prepared_step.tool.to_string(),
+                            success: false,
+                            output: None,
+                            error: Some(format!(
+                                "step '{}' ({}): {}",
+                                prepared_step.tool, prepared_step.index, e
+                            )),
+                        });
+                        if *prepared_step.error_policy == ErrorPolicy::FailFast
+                            && fail_fast_error.is_none()
+                        {
+                            fail_fast_error = Some(e);
+                        }
+                    }
                 }
             }
 
-            // Interpolate input with variables
-            let step_input = if let Some(input_template) = &step.input {
-                self.interpolate_variables(input_template, &variables)
-            } else {
-                serde_json::json!({})
-            };
+            if let Some(e) = fail_fast_error {
+                return Err(e);
+            }
+        }
 
-            // Execute step
-            let step_result = match registry
-                .dispatch(&step.tool, &serde_json::to_vec(&step_input)?)
-                .await
-            {
-                Ok(output) => {
-                    let output_value: serde_json::Value = serde_json::from_slice(&output)?;
-
-                    // Store output in variable if specified
-                    if let Some(var_name) = &step.output_var {
-                        variables.insert(var_name.clone(), output_value.clone());
-                    }
+        Ok(PipelineOutput {
+            results: results.into_iter().flatten().collect(),
+            variables,
+        })
+    }
+
+    /// Partition `active` step indices into topologically-ordered layers of
+    /// mutually independent steps: step `i` depends on step `j` when `i`'s
+    /// `input` references a `{{var}}` that `j` (also active) declares as
+    /// its `output_var`. Every step in layer `N` depends only on steps in
+    /// layers `< N`, so each layer can dispatch concurrently once the
+    /// layers before it have run.
+    fn layer_steps(&self, active: &[usize]) -> Result<Vec<Vec<usize>>> {
+        let mut producer_of: HashMap<&str, usize> = HashMap::new();
+        for &i in active {
+            if let Some(var) = &self.steps[i].output_var {
+                producer_of.insert(var.as_str(), i);
+            }
+        }
+
+        let mut indegree: HashMap<usize, usize> = HashMap::new();
+        let mut dependents: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &i in active {
+            let mut referenced = HashSet::new();
+            if let Some(template) = &self.steps[i].input {
+                collect_referenced_vars(template, &mut referenced);
+            }
+
+            let deps: Vec<usize> = referenced
+                .into_iter()
+                .filter_map(|var| producer_of.get(var.as_str()).copied())
+                .filter(|&producer| producer != i)
+                .collect();
+
+            indegree.insert(i, deps.len());
+            for dep in deps {
+                dependents.entry(dep).or_default().push(i);
+            }
+        }
+
+        let mut remaining: HashSet<usize> = active.iter().copied().collect();
+        let mut layers = Vec::new();
+
+        while !remaining.is_empty() {
+            let mut layer: Vec<usize> = remaining
+                .iter()
+                .copied()
+                .filter(|i| indegree.get(i).copied().unwrap_or(0) == 0)
+                .collect();
+
+            if layer.is_empty() {
+                let mut stuck: Vec<&str> = remaining.iter().map(|&i| self.steps[i].tool.as_str()).collect();
+                stuck.sort_unstable();
+                return Err(Error::Handler(format!(
+                    "pipeline has a dependency cycle involving: {}",
+                    stuck.join(", ")
+                )));
+            }
 
-                    StepResult {
-                        tool: step.tool.clone(),
-                        success: true,
-                        output: Some(output_value),
-                        error: None,
+            // Deterministic, declaration-order layer contents regardless of
+            // `HashSet` iteration order above.
+            layer.sort_unstable();
+
+            for &i in &layer {
+                remaining.remove(&i);
+                if let Some(deps) = dependents.get(&i) {
+                    for &dependent in deps {
+                        if let Some(d) = indegree.get_mut(&dependent) {
+                            *d = d.saturating_sub(1);
+                        }
                     }
                 }
-                Err(e) => {
-                    let result = StepResult {
-                        tool: step.tool.clone(),
-                        success: false,
-                        output: None,
-                        error: Some(e.to_string()),
-                    };
-
-                    // Handle error based on policy
-                    if step.error_policy == ErrorPolicy::FailFast {
-                        results.push(result);
-                        return Err(e);
-                    }
+            }
 
-                    result
-                }
-            };
+            layers.push(layer);
+        }
+
+        Ok(layers)
+    }
 
-            results.push(step_result);
+    /// Resolve a dotted path like `step1.user.name` against `variables`: the
+    /// first segment selects the variable, every following segment indexes
+    /// into its value (object field or array index).
+    fn resolve_path(path: &str, variables: &HashMap<String, serde_json::Value>) -> Option<serde_json::Value> {
+        let mut segments = path.trim().split('.');
+        let mut current = variables.get(segments.next()?)?.clone();
+
+        for segment in segments {
+            current = match &current {
+                serde_json::Value::Object(map) => map.get(segment)?.clone(),
+                serde_json::Value::Array(arr) => arr.get(segment.parse::<usize>().ok()?)?.clone(),
+                _ => return None,
+            };
         }
 
-        Ok(PipelineOutput { results, variables })
+        Some(current)
     }
 
+    /// JS-style truthiness: `null`, `false`, `0`, `""`, and empty
+    /// arrays/objects are falsy; everything else is truthy.
+    fn is_truthy(value: &serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::Null => false,
+            serde_json::Value::Bool(b) => *b,
+            serde_json::Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(true),
+            serde_json::Value::String(s) => !s.is_empty(),
+            serde_json::Value::Array(a) => !a.is_empty(),
+            serde_json::Value::Object(o) => !o.is_empty(),
+        }
+    }
+
+    /// Evaluate a step's `condition`, a small boolean/comparison expression
+    /// over variable references and JSON literals - e.g. `status == "ok" &&
+    /// retries < 3`, or the plain-existence forms `"path"`/`"!path"` this
+    /// used to be limited to. A condition that fails to parse is treated as
+    /// false, same as any other way of asking about something that isn't
+    /// there - [`parse_condition`] never panics.
     fn evaluate_condition(
         &self,
         condition: &str,
         variables: &HashMap<String, serde_json::Value>,
     ) -> bool {
-        // Simple variable existence check for MVP
-        // Format: "variable_name" or "!variable_name"
-        if let Some(var_name) = condition.strip_prefix('!') {
-            !variables.contains_key(var_name)
-        } else {
-            variables.contains_key(condition)
+        match parse_condition(condition) {
+            Some(expr) => eval_condition_expr(&expr, variables),
+            None => false,
         }
     }
 
+    /// Substitute `{{path}}`/`{{path|default}}` placeholders in `template`
+    /// with values resolved from `variables` via [`Self::resolve_path`]. A
+    /// template that is *exactly* `"{{path}}"` (nothing else in the string)
+    /// is replaced with the resolved value as-is, so a step can select a
+    /// whole object or array, not just interpolate it into a string. When
+    /// `path` is undefined, `default` (if given) is substituted as a plain
+    /// string; with no default, the placeholder is left as literal text,
+    /// unchanged.
     #[allow(clippy::only_used_in_recursion)]
     fn interpolate_variables(
         &self,
@@ -136,15 +316,28 @@ impl PipelineHandler {
     ) -> serde_json::Value {
         match template {
             serde_json::Value::String(s) => {
-                // Replace {{var}} with variable value
-                let mut result = s.clone();
-                for (key, value) in variables {
-                    let pattern = format!("{{{{{}}}}}", key);
-                    if let Some(value_str) = value.as_str() {
-                        result = result.replace(&pattern, value_str);
-                    }
+                if let Some(inner) = whole_placeholder(s) {
+                    let (path, default) = split_path_default(inner);
+                    return Self::resolve_path(path, variables).unwrap_or_else(|| match default {
+                        Some(default) => serde_json::Value::String(default.to_string()),
+                        None => serde_json::Value::String(s.clone()),
+                    });
                 }
-                serde_json::Value::String(result)
+
+                let placeholder = placeholder_regex();
+                let result = placeholder.replace_all(s, |caps: &regex::Captures| {
+                    let path = &caps[1];
+                    let default = caps.get(2).map(|m| m.as_str());
+                    match Self::resolve_path(path, variables) {
+                        Some(serde_json::Value::String(s)) => s,
+                        Some(other) => other.to_string(),
+                        None => match default {
+                            Some(default) => default.to_string(),
+                            None => caps[0].to_string(),
+                        },
+                    }
+                });
+                serde_json::Value::String(result.into_owned())
             }
             serde_json::Value::Object(obj) => {
                 let mut new_obj = serde_json::Map::new();
@@ -165,10 +358,462 @@ impl PipelineHandler {
     }
 }
 
+/// `{{path}}`/`{{path|default}}` placeholder pattern shared by
+/// [`PipelineHandler::interpolate_variables`]. Capture group 2 (the
+/// default) is everything between `|` and the closing `}}`, verbatim - it's
+/// substituted as a literal string, not re-parsed as JSON.
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*([a-zA-Z0-9_.]+)\s*(?:\|\s*([^}]*?)\s*)?\}\}")
+        .expect("static placeholder regex is valid")
+}
+
+/// `Some(inner)` if `s` is nothing but a single `{{inner}}` placeholder,
+/// where `inner` is `path` or `path|default` - split further with
+/// [`split_path_default`].
+fn whole_placeholder(s: &str) -> Option<&str> {
+    let trimmed = s.trim();
+    trimmed.strip_prefix("{{")?.strip_suffix("}}").map(str::trim)
+}
+
+/// Split a placeholder's inner text on the first `|` into `(path,
+/// default)`, trimming whitespace on both sides; `default` is `None` when
+/// there's no `|` at all.
+fn split_path_default(inner: &str) -> (&str, Option<&str>) {
+    match inner.split_once('|') {
+        Some((path, default)) => (path.trim(), Some(default.trim())),
+        None => (inner.trim(), None),
+    }
+}
+
+/// One ready-to-dispatch step within a layer: the input has already been
+/// interpolated and serialized, so the only thing left to do is call
+/// `registry.dispatch` - the tool name, output variable and error policy
+/// are borrowed from `self.steps` rather than copied (only the serialized
+/// input is owned), since a layer's steps are built and consumed within
+/// the same `execute` call.
+struct PreparedStep<'s> {
+    index: usize,
+    tool: &'s str,
+    bytes: Vec<u8>,
+    output_var: Option<&'s str>,
+    error_policy: &'s ErrorPolicy,
+}
+
+/// The root variable name a dotted path like `step1.user.name` indexes
+/// into - the only part [`PipelineHandler::layer_steps`] cares about, since
+/// dependencies are tracked per-variable, not per-field.
+fn root_var(path: &str) -> &str {
+    path.trim().split('.').next().unwrap_or(path)
+}
+
+/// Recursively collect every `{{var...}}` placeholder's root variable name
+/// referenced anywhere in `template`, the same traversal
+/// [`PipelineHandler::interpolate_variables`] uses to substitute them.
+fn collect_referenced_vars(template: &serde_json::Value, vars: &mut HashSet<String>) {
+    match template {
+        serde_json::Value::String(s) => {
+            if let Some(inner) = whole_placeholder(s) {
+                let (path, _default) = split_path_default(inner);
+                vars.insert(root_var(path).to_string());
+                return;
+            }
+            for caps in placeholder_regex().captures_iter(s) {
+                vars.insert(root_var(&caps[1]).to_string());
+            }
+        }
+        serde_json::Value::Object(obj) => {
+            for v in obj.values() {
+                collect_referenced_vars(v, vars);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for v in arr {
+                collect_referenced_vars(v, vars);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A `condition` token, produced by [`tokenize_condition`].
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionToken {
+    /// A dotted variable reference, resolved via [`PipelineHandler::resolve_path`].
+    Path(String),
+    /// A JSON literal operand - `"quoted"` strings, numbers, and `true`/`false`.
+    Literal(serde_json::Value),
+    And,
+    Or,
+    Not,
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    LParen,
+    RParen,
+}
+
+/// Split a `condition` string into [`ConditionToken`]s. Returns `None` on
+/// anything it can't make sense of (an unterminated string, a stray
+/// character) rather than panicking - [`PipelineHandler::evaluate_condition`]
+/// treats that the same as a condition that evaluates to false.
+fn tokenize_condition(input: &str) -> Option<Vec<ConditionToken>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push(ConditionToken::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(ConditionToken::RParen);
+                i += 1;
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                tokens.push(ConditionToken::And);
+                i += 2;
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                tokens.push(ConditionToken::Or);
+                i += 2;
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(ConditionToken::Eq);
+                i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(ConditionToken::Ne);
+                i += 2;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(ConditionToken::Le);
+                i += 2;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(ConditionToken::Ge);
+                i += 2;
+            }
+            '<' => {
+                tokens.push(ConditionToken::Lt);
+                i += 1;
+            }
+            '>' => {
+                tokens.push(ConditionToken::Gt);
+                i += 1;
+            }
+            '!' => {
+                tokens.push(ConditionToken::Not);
+                i += 1;
+            }
+            '"' => {
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && chars[end] != '"' {
+                    end += 1;
+                }
+                if end >= chars.len() {
+                    return None;
+                }
+                let literal: String = chars[start..end].iter().collect();
+                tokens.push(ConditionToken::Literal(serde_json::Value::String(literal)));
+                i = end + 1;
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text.parse::<f64>().ok()?;
+                tokens.push(ConditionToken::Literal(serde_json::json!(number)));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "true" => ConditionToken::Literal(serde_json::Value::Bool(true)),
+                    "false" => ConditionToken::Literal(serde_json::Value::Bool(false)),
+                    _ => ConditionToken::Path(word),
+                });
+            }
+            _ => return None,
+        }
+    }
+
+    Some(tokens)
+}
+
+/// A value operand in a [`ConditionExpr`] - either a variable reference
+/// (resolved against `variables` at evaluation time) or a literal baked in
+/// at parse time.
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionValue {
+    Path(String),
+    Literal(serde_json::Value),
+}
+
+/// The AST [`parse_condition`] produces, evaluated by [`eval_condition_expr`].
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionExpr {
+    /// A bare operand used as a boolean, via the JS-style truthiness rules
+    /// of `PipelineHandler::is_truthy`.
+    Value(ConditionValue),
+    Not(Box<ConditionExpr>),
+    And(Box<ConditionExpr>, Box<ConditionExpr>),
+    Or(Box<ConditionExpr>, Box<ConditionExpr>),
+    Cmp(ConditionValue, ConditionToken, ConditionValue),
+}
+
+/// Recursive-descent parser over [`tokenize_condition`]'s output, for the
+/// grammar (loosest to tightest binding):
+/// `or := and ('||' and)*`, `and := unary ('&&' unary)*`,
+/// `unary := '!' unary | comparison`,
+/// `comparison := operand (('==' | '!=' | '<' | '>' | '<=' | '>=') operand)?`,
+/// `operand := '(' or ')' | literal | path`.
+struct ConditionParser<'t> {
+    tokens: &'t [ConditionToken],
+    pos: usize,
+}
+
+impl<'t> ConditionParser<'t> {
+    fn peek(&self) -> Option<&'t ConditionToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<&'t ConditionToken> {
+        let token = self.tokens.get(self.pos);
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn parse_or(&mut self) -> Option<ConditionExpr> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&ConditionToken::Or) {
+            self.advance();
+            let rhs = self.parse_and()?;
+            expr = ConditionExpr::Or(Box::new(expr), Box::new(rhs));
+        }
+        Some(expr)
+    }
+
+    fn parse_and(&mut self) -> Option<ConditionExpr> {
+        let mut expr = self.parse_unary()?;
+        while self.peek() == Some(&ConditionToken::And) {
+            self.advance();
+            let rhs = self.parse_unary()?;
+            expr = ConditionExpr::And(Box::new(expr), Box::new(rhs));
+        }
+        Some(expr)
+    }
+
+    fn parse_unary(&mut self) -> Option<ConditionExpr> {
+        if self.peek() == Some(&ConditionToken::Not) {
+            self.advance();
+            return Some(ConditionExpr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    /// `'(' or ')'`, wrapping a full boolean sub-expression, or a bare
+    /// comparison - the grouping `(a || b) && c` needs, distinct from
+    /// [`Self::parse_operand`], which only ever produces a single value.
+    fn parse_primary(&mut self) -> Option<ConditionExpr> {
+        if self.peek() == Some(&ConditionToken::LParen) {
+            self.advance();
+            let inner = self.parse_or()?;
+            if self.advance() != Some(&ConditionToken::RParen) {
+                return None;
+            }
+            return Some(inner);
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Option<ConditionExpr> {
+        let lhs = self.parse_operand()?;
+
+        let op = match self.peek() {
+            Some(
+                op @ (ConditionToken::Eq
+                | ConditionToken::Ne
+                | ConditionToken::Lt
+                | ConditionToken::Gt
+                | ConditionToken::Le
+                | ConditionToken::Ge),
+            ) => op.clone(),
+            _ => return Some(ConditionExpr::Value(lhs)),
+        };
+        self.advance();
+
+        let rhs = self.parse_operand()?;
+        Some(ConditionExpr::Cmp(lhs, op, rhs))
+    }
+
+    fn parse_operand(&mut self) -> Option<ConditionValue> {
+        match self.advance()? {
+            ConditionToken::Path(p) => Some(ConditionValue::Path(p.clone())),
+            ConditionToken::Literal(v) => Some(ConditionValue::Literal(v.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Parse `condition` into an evaluable [`ConditionExpr`], or `None` if it
+/// doesn't match the grammar (trailing tokens count as a parse failure too).
+fn parse_condition(condition: &str) -> Option<ConditionExpr> {
+    let tokens = tokenize_condition(condition)?;
+    let mut parser = ConditionParser { tokens: &tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if parser.pos != tokens.len() {
+        return None;
+    }
+    Some(expr)
+}
+
+/// Resolve a [`ConditionValue`] against `variables`: `None` ("undefined")
+/// for a path that doesn't resolve, `Some` otherwise.
+fn resolve_condition_value(
+    value: &ConditionValue,
+    variables: &HashMap<String, serde_json::Value>,
+) -> Option<serde_json::Value> {
+    match value {
+        ConditionValue::Path(path) => PipelineHandler::resolve_path(path, variables),
+        ConditionValue::Literal(v) => Some(v.clone()),
+    }
+}
+
+/// Evaluate a [`ConditionExpr`] against `variables`. An "undefined" operand
+/// (a path that doesn't resolve) makes every comparison false rather than
+/// panicking or short-circuiting the whole expression.
+fn eval_condition_expr(
+    expr: &ConditionExpr,
+    variables: &HashMap<String, serde_json::Value>,
+) -> bool {
+    match expr {
+        ConditionExpr::Value(value) => resolve_condition_value(value, variables)
+            .map(|v| PipelineHandler::is_truthy(&v))
+            .unwrap_or(false),
+        ConditionExpr::Not(inner) => !eval_condition_expr(inner, variables),
+        ConditionExpr::And(lhs, rhs) => {
+            eval_condition_expr(lhs, variables) && eval_condition_expr(rhs, variables)
+        }
+        ConditionExpr::Or(lhs, rhs) => {
+            eval_condition_expr(lhs, variables) || eval_condition_expr(rhs, variables)
+        }
+        ConditionExpr::Cmp(lhs, op, rhs) => {
+            match (
+                resolve_condition_value(lhs, variables),
+                resolve_condition_value(rhs, variables),
+            ) {
+                (Some(a), Some(b)) => compare_condition_values(&a, op, &b),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Apply a comparison operator to two resolved JSON values. `==`/`!=` use
+/// JSON structural equality; ordering operators only make sense for two
+/// numbers or two strings and are false for any other pairing (including a
+/// type mismatch), rather than panicking.
+fn compare_condition_values(
+    a: &serde_json::Value,
+    op: &ConditionToken,
+    b: &serde_json::Value,
+) -> bool {
+    match op {
+        ConditionToken::Eq => a == b,
+        ConditionToken::Ne => a != b,
+        ConditionToken::Lt | ConditionToken::Gt | ConditionToken::Le | ConditionToken::Ge => {
+            let ordering = match (a.as_f64(), b.as_f64()) {
+                (Some(x), Some(y)) => x.partial_cmp(&y),
+                _ => match (a.as_str(), b.as_str()) {
+                    (Some(x), Some(y)) => Some(x.cmp(y)),
+                    _ => None,
+                },
+            };
+            match (ordering, op) {
+                (Some(std::cmp::Ordering::Less), ConditionToken::Lt | ConditionToken::Le) => true,
+                (Some(std::cmp::Ordering::Greater), ConditionToken::Gt | ConditionToken::Ge) => true,
+                (Some(std::cmp::Ordering::Equal), ConditionToken::Le | ConditionToken::Ge) => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Poll every not-yet-finished future in `futures` each time any of them
+/// wakes, the non-`'static` analogue of `futures::future::join_all` (a
+/// dependency on a whole extra crate for one combinator isn't worth it
+/// here). Returns `None` at a future's slot if the layer was abandoned
+/// before that future got a chance to run.
+///
+/// Under `ErrorPolicy::FailFast` (`fail_fast[i]`), a future resolving to
+/// `Err` stops the whole layer immediately: the remaining pending futures
+/// are dropped (cancelled) rather than awaited to completion, since a
+/// fail-fast pipeline has already decided to abort.
+async fn run_layer_concurrently(
+    mut futures: Vec<Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + '_>>>,
+    fail_fast: Vec<bool>,
+) -> Vec<Option<Result<Vec<u8>>>> {
+    let mut outcomes: Vec<Option<Result<Vec<u8>>>> = (0..futures.len()).map(|_| None).collect();
+    let mut done = vec![false; futures.len()];
+
+    std::future::poll_fn(move |cx| {
+        let mut all_done = true;
+        for idx in 0..futures.len() {
+            if done[idx] {
+                continue;
+            }
+            match futures[idx].as_mut().poll(cx) {
+                Poll::Ready(result) => {
+                    done[idx] = true;
+                    let should_abort = result.is_err() && fail_fast[idx];
+                    outcomes[idx] = Some(result);
+                    if should_abort {
+                        return Poll::Ready(std::mem::take(&mut outcomes));
+                    }
+                }
+                Poll::Pending => all_done = false,
+            }
+        }
+
+        if all_done {
+            Poll::Ready(std::mem::take(&mut outcomes))
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn empty_registry() -> Arc<RwLock<HandlerRegistry>> {
+        Arc::new(RwLock::new(HandlerRegistry::new()))
+    }
+
     #[test]
     fn test_pipeline_handler_new() {
         let steps = vec![PipelineStep {
@@ -179,7 +824,7 @@ mod tests {
             error_policy: ErrorPolicy::FailFast,
         }];
 
-        let handler = PipelineHandler::new(steps);
+        let handler = PipelineHandler::new(empty_registry(), steps);
         assert_eq!(handler.steps.len(), 1);
         assert_eq!(handler.steps[0].tool, "test_tool");
     }
@@ -193,7 +838,7 @@ mod tests {
 
     #[test]
     fn test_evaluate_condition_exists() {
-        let handler = PipelineHandler::new(vec![]);
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
         let mut vars = HashMap::new();
         vars.insert("key".to_string(), serde_json::json!("value"));
 
@@ -203,7 +848,7 @@ mod tests {
 
     #[test]
     fn test_evaluate_condition_not_exists() {
-        let handler = PipelineHandler::new(vec![]);
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
         let mut vars = HashMap::new();
         vars.insert("key".to_string(), serde_json::json!("value"));
 
@@ -211,9 +856,87 @@ mod tests {
         assert!(handler.evaluate_condition("!missing", &vars));
     }
 
+    #[test]
+    fn test_evaluate_condition_falsy_field() {
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
+        let mut vars = HashMap::new();
+        vars.insert("step1".to_string(), serde_json::json!({"ok": false, "count": 0}));
+
+        assert!(!handler.evaluate_condition("step1.ok", &vars));
+        assert!(!handler.evaluate_condition("step1.count", &vars));
+        assert!(handler.evaluate_condition("!step1.ok", &vars));
+    }
+
+    #[test]
+    fn test_evaluate_condition_comparisons() {
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
+        let mut vars = HashMap::new();
+        vars.insert("status".to_string(), serde_json::json!("ok"));
+        vars.insert("retries".to_string(), serde_json::json!(2));
+
+        assert!(handler.evaluate_condition(r#"status == "ok""#, &vars));
+        assert!(!handler.evaluate_condition(r#"status == "fail""#, &vars));
+        assert!(handler.evaluate_condition("retries < 3", &vars));
+        assert!(!handler.evaluate_condition("retries >= 3", &vars));
+        assert!(handler.evaluate_condition(r#"status == "ok" && retries < 3"#, &vars));
+        assert!(!handler.evaluate_condition(r#"status == "ok" && retries > 3"#, &vars));
+        assert!(handler.evaluate_condition(r#"status == "fail" || retries < 3"#, &vars));
+        assert!(handler.evaluate_condition(r#"!(status == "fail") && retries <= 2"#, &vars));
+    }
+
+    #[test]
+    fn test_evaluate_condition_undefined_variable_makes_comparisons_false() {
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
+        let vars = HashMap::new();
+
+        assert!(!handler.evaluate_condition("missing == 1", &vars));
+        assert!(!handler.evaluate_condition("missing != 1", &vars));
+        assert!(!handler.evaluate_condition("missing < 1", &vars));
+    }
+
+    #[test]
+    fn test_evaluate_condition_malformed_expression_is_false() {
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
+        let vars = HashMap::new();
+
+        assert!(!handler.evaluate_condition("status ==", &vars));
+        assert!(!handler.evaluate_condition("(status == \"ok\"", &vars));
+    }
+
+    #[test]
+    fn test_interpolate_variables_default_fallback() {
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
+        let mut vars = HashMap::new();
+        vars.insert("name".to_string(), serde_json::json!("Alice"));
+
+        let present = handler.interpolate_variables(
+            &serde_json::json!("Hi {{name|Anonymous}}"),
+            &vars,
+        );
+        assert_eq!(present, serde_json::json!("Hi Alice"));
+
+        let missing = handler.interpolate_variables(
+            &serde_json::json!("Hi {{nickname|Anonymous}}"),
+            &vars,
+        );
+        assert_eq!(missing, serde_json::json!("Hi Anonymous"));
+
+        let missing_no_default = handler.interpolate_variables(
+            &serde_json::json!("Hi {{nickname}}"),
+            &vars,
+        );
+        assert_eq!(missing_no_default, serde_json::json!("Hi {{nickname}}"));
+
+        let whole_default = handler.interpolate_variables(&serde_json::json!("{{nickname|Bob}}"), &vars);
+        assert_eq!(whole_default, serde_json::json!("Bob"));
+
+        let whole_value = handler.interpolate_variables(&serde_json::json!("{{name|Bob}}"), &vars);
+        assert_eq!(whole_value, serde_json::json!("Alice"));
+    }
+
     #[test]
     fn test_interpolate_variables_string() {
-        let handler = PipelineHandler::new(vec![]);
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
         let mut vars = HashMap::new();
         vars.insert("name".to_string(), serde_json::json!("Alice"));
 
@@ -225,7 +948,7 @@ mod tests {
 
     #[test]
     fn test_interpolate_variables_object() {
-        let handler = PipelineHandler::new(vec![]);
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
         let mut vars = HashMap::new();
         vars.insert("user".to_string(), serde_json::json!("Bob"));
 
@@ -237,7 +960,7 @@ mod tests {
 
     #[test]
     fn test_interpolate_variables_array() {
-        let handler = PipelineHandler::new(vec![]);
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
         let mut vars = HashMap::new();
         vars.insert("item".to_string(), serde_json::json!("test"));
 
@@ -250,7 +973,7 @@ mod tests {
 
     #[test]
     fn test_interpolate_variables_no_match() {
-        let handler = PipelineHandler::new(vec![]);
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
         let vars = HashMap::new();
 
         let template = serde_json::json!("Hello {{missing}}!");
@@ -259,6 +982,23 @@ mod tests {
         assert_eq!(result, serde_json::json!("Hello {{missing}}!"));
     }
 
+    #[test]
+    fn test_interpolate_variables_nested_field_selects_value() {
+        let handler = PipelineHandler::new(empty_registry(), vec![]);
+        let mut vars = HashMap::new();
+        vars.insert(
+            "step1".to_string(),
+            serde_json::json!({"user": {"name": "Carol"}, "tags": ["a", "b"]}),
+        );
+
+        let renamed = handler.interpolate_variables(&serde_json::json!("{{step1.user.name}}"), &vars);
+        assert_eq!(renamed, serde_json::json!("Carol"));
+
+        // A whole-string placeholder preserves the resolved value's type.
+        let selected = handler.interpolate_variables(&serde_json::json!("{{step1.tags}}"), &vars);
+        assert_eq!(selected, serde_json::json!(["a", "b"]));
+    }
+
     #[test]
     fn test_pipeline_input_deserialization() {
         let json = r#"{"variables": {"key": "value"}}"#;
@@ -321,13 +1061,16 @@ mod tests {
         registry.register("test_tool", TestHandler);
 
         // Create pipeline with one step
-        let handler = PipelineHandler::new(vec![PipelineStep {
-            tool: "test_tool".to_string(),
-            input: Some(serde_json::json!({"value": "hello"})),
-            output_var: Some("result".to_string()),
-            condition: None,
-            error_policy: ErrorPolicy::FailFast,
-        }]);
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![PipelineStep {
+                tool: "test_tool".to_string(),
+                input: Some(serde_json::json!({"value": "hello"})),
+                output_var: Some("result".to_string()),
+                condition: None,
+                error_policy: ErrorPolicy::FailFast,
+            }],
+        );
 
         let input = PipelineInput {
             variables: HashMap::new(),
@@ -340,19 +1083,112 @@ mod tests {
         assert!(output.variables.contains_key("result"));
     }
 
+    #[tokio::test]
+    async fn test_pipeline_execute_chains_step_output_into_next_input() {
+        use crate::{Handler, HandlerRegistry};
+        use schemars::JsonSchema;
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct FetchInput {}
+
+        #[derive(Debug, serde::Serialize, JsonSchema)]
+        struct FetchOutput {
+            user: serde_json::Value,
+        }
+
+        struct FetchHandler;
+
+        #[async_trait::async_trait]
+        impl Handler for FetchHandler {
+            type Input = FetchInput;
+            type Output = FetchOutput;
+            type Error = crate::Error;
+
+            async fn handle(&self, _input: Self::Input) -> crate::Result<Self::Output> {
+                Ok(FetchOutput {
+                    user: serde_json::json!({"name": "Dave"}),
+                })
+            }
+        }
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct GreetInput {
+            name: String,
+        }
+
+        #[derive(Debug, serde::Serialize, JsonSchema)]
+        struct GreetOutput {
+            message: String,
+        }
+
+        struct GreetHandler;
+
+        #[async_trait::async_trait]
+        impl Handler for GreetHandler {
+            type Input = GreetInput;
+            type Output = GreetOutput;
+            type Error = crate::Error;
+
+            async fn handle(&self, input: Self::Input) -> crate::Result<Self::Output> {
+                Ok(GreetOutput {
+                    message: format!("Hello, {}!", input.name),
+                })
+            }
+        }
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("fetch", FetchHandler);
+        registry.register("greet", GreetHandler);
+
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![
+                PipelineStep {
+                    tool: "fetch".to_string(),
+                    input: Some(serde_json::json!({})),
+                    output_var: Some("fetched".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+                PipelineStep {
+                    tool: "greet".to_string(),
+                    input: Some(serde_json::json!({"name": "{{fetched.user.name}}"})),
+                    output_var: Some("greeting".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+            ],
+        );
+
+        let input = PipelineInput {
+            variables: HashMap::new(),
+        };
+
+        let output = handler.execute(input, &registry).await.unwrap();
+
+        assert_eq!(output.results.len(), 2);
+        assert_eq!(
+            output.variables["greeting"]["message"],
+            "Hello, Dave!"
+        );
+    }
+
     #[tokio::test]
     async fn test_pipeline_execute_with_condition_skip() {
         use crate::HandlerRegistry;
 
         let registry = HandlerRegistry::new();
 
-        let handler = PipelineHandler::new(vec![PipelineStep {
-            tool: "nonexistent".to_string(),
-            input: None,
-            output_var: None,
-            condition: Some("missing_var".to_string()),
-            error_policy: ErrorPolicy::FailFast,
-        }]);
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![PipelineStep {
+                tool: "nonexistent".to_string(),
+                input: None,
+                output_var: None,
+                condition: Some("missing_var".to_string()),
+                error_policy: ErrorPolicy::FailFast,
+            }],
+        );
 
         let input = PipelineInput {
             variables: HashMap::new(),
@@ -370,22 +1206,25 @@ mod tests {
 
         let registry = HandlerRegistry::new();
 
-        let handler = PipelineHandler::new(vec![
-            PipelineStep {
-                tool: "nonexistent1".to_string(),
-                input: None,
-                output_var: None,
-                condition: None,
-                error_policy: ErrorPolicy::Continue,
-            },
-            PipelineStep {
-                tool: "nonexistent2".to_string(),
-                input: None,
-                output_var: None,
-                condition: None,
-                error_policy: ErrorPolicy::Continue,
-            },
-        ]);
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![
+                PipelineStep {
+                    tool: "nonexistent1".to_string(),
+                    input: None,
+                    output_var: None,
+                    condition: None,
+                    error_policy: ErrorPolicy::Continue,
+                },
+                PipelineStep {
+                    tool: "nonexistent2".to_string(),
+                    input: None,
+                    output_var: None,
+                    condition: None,
+                    error_policy: ErrorPolicy::Continue,
+                },
+            ],
+        );
 
         let input = PipelineInput {
             variables: HashMap::new(),
@@ -405,22 +1244,25 @@ mod tests {
 
         let registry = HandlerRegistry::new();
 
-        let handler = PipelineHandler::new(vec![
-            PipelineStep {
-                tool: "nonexistent1".to_string(),
-                input: None,
-                output_var: None,
-                condition: None,
-                error_policy: ErrorPolicy::FailFast,
-            },
-            PipelineStep {
-                tool: "nonexistent2".to_string(),
-                input: None,
-                output_var: None,
-                condition: None,
-                error_policy: ErrorPolicy::FailFast,
-            },
-        ]);
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![
+                PipelineStep {
+                    tool: "nonexistent1".to_string(),
+                    input: None,
+                    output_var: None,
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+                PipelineStep {
+                    tool: "nonexistent2".to_string(),
+                    input: None,
+                    output_var: None,
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+            ],
+        );
 
         let input = PipelineInput {
             variables: HashMap::new(),
@@ -431,4 +1273,315 @@ mod tests {
         // Should fail on first error
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_pipeline_handle_resolves_tool_from_live_registry() {
+        use crate::Handler;
+        use schemars::JsonSchema;
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct PingInput {}
+
+        #[derive(Debug, serde::Serialize, JsonSchema)]
+        struct PingOutput {
+            pong: bool,
+        }
+
+        struct PingHandler;
+
+        #[async_trait::async_trait]
+        impl Handler for PingHandler {
+            type Input = PingInput;
+            type Output = PingOutput;
+            type Error = crate::Error;
+
+            async fn handle(&self, _input: Self::Input) -> crate::Result<Self::Output> {
+                Ok(PingOutput { pong: true })
+            }
+        }
+
+        let registry = Arc::new(RwLock::new(HandlerRegistry::new()));
+        let handler = PipelineHandler::new(
+            registry.clone(),
+            vec![PipelineStep {
+                tool: "ping".to_string(),
+                input: Some(serde_json::json!({})),
+                output_var: Some("pinged".to_string()),
+                condition: None,
+                error_policy: ErrorPolicy::FailFast,
+            }],
+        );
+
+        // `ping` is registered only after the pipeline itself - this only
+        // works if the pipeline resolves the tool name at dispatch time.
+        registry.write().await.register("ping", PingHandler);
+
+        let output = handler
+            .handle(PipelineInput {
+                variables: HashMap::new(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.results.len(), 1);
+        assert!(output.results[0].success);
+        assert_eq!(output.variables["pinged"]["pong"], true);
+    }
+
+    #[tokio::test]
+    async fn test_independent_steps_run_concurrently() {
+        use crate::Handler;
+        use schemars::JsonSchema;
+        use std::time::Duration;
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct SleepInput {}
+
+        #[derive(Debug, serde::Serialize, JsonSchema)]
+        struct SleepOutput {
+            slept: bool,
+        }
+
+        struct SleepHandler;
+
+        #[async_trait::async_trait]
+        impl Handler for SleepHandler {
+            type Input = SleepInput;
+            type Output = SleepOutput;
+            type Error = crate::Error;
+
+            async fn handle(&self, _input: Self::Input) -> crate::Result<Self::Output> {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(SleepOutput { slept: true })
+            }
+        }
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("sleep", SleepHandler);
+
+        // Three independent steps, none referencing another's output_var,
+        // so they should all land in a single layer and run concurrently.
+        let steps: Vec<PipelineStep> = (0..3)
+            .map(|i| PipelineStep {
+                tool: "sleep".to_string(),
+                input: Some(serde_json::json!({})),
+                output_var: Some(format!("slept_{}", i)),
+                condition: None,
+                error_policy: ErrorPolicy::FailFast,
+            })
+            .collect();
+
+        let handler = PipelineHandler::new(empty_registry(), steps);
+        let start = std::time::Instant::now();
+        let output = handler
+            .execute(
+                PipelineInput {
+                    variables: HashMap::new(),
+                },
+                &registry,
+            )
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(output.results.len(), 3);
+        // Sequential execution would take >=150ms; concurrent execution of
+        // three 50ms steps should comfortably finish in well under that.
+        assert!(
+            elapsed < Duration::from_millis(140),
+            "expected concurrent execution, took {:?}",
+            elapsed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dependency_chain_still_resolves_in_order_when_parallelized() {
+        use crate::{Handler, HandlerRegistry};
+        use schemars::JsonSchema;
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct FetchInput {}
+
+        #[derive(Debug, serde::Serialize, JsonSchema)]
+        struct FetchOutput {
+            user: serde_json::Value,
+        }
+
+        struct FetchHandler;
+
+        #[async_trait::async_trait]
+        impl Handler for FetchHandler {
+            type Input = FetchInput;
+            type Output = FetchOutput;
+            type Error = crate::Error;
+
+            async fn handle(&self, _input: Self::Input) -> crate::Result<Self::Output> {
+                Ok(FetchOutput {
+                    user: serde_json::json!({"name": "Erin"}),
+                })
+            }
+        }
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct GreetInput {
+            name: String,
+        }
+
+        #[derive(Debug, serde::Serialize, JsonSchema)]
+        struct GreetOutput {
+            message: String,
+        }
+
+        struct GreetHandler;
+
+        #[async_trait::async_trait]
+        impl Handler for GreetHandler {
+            type Input = GreetInput;
+            type Output = GreetOutput;
+            type Error = crate::Error;
+
+            async fn handle(&self, input: Self::Input) -> crate::Result<Self::Output> {
+                Ok(GreetOutput {
+                    message: format!("Hello, {}!", input.name),
+                })
+            }
+        }
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("fetch", FetchHandler);
+        registry.register("greet", GreetHandler);
+
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![
+                // Declared out of dependency order - `greet` depends on
+                // `fetch`'s output, but is written first.
+                PipelineStep {
+                    tool: "greet".to_string(),
+                    input: Some(serde_json::json!({"name": "{{fetched.user.name}}"})),
+                    output_var: Some("greeting".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+                PipelineStep {
+                    tool: "fetch".to_string(),
+                    input: Some(serde_json::json!({})),
+                    output_var: Some("fetched".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+            ],
+        );
+
+        let output = handler
+            .execute(
+                PipelineInput {
+                    variables: HashMap::new(),
+                },
+                &registry,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output.variables["greeting"]["message"], "Hello, Erin!");
+    }
+
+    #[tokio::test]
+    async fn test_cyclic_dependency_is_rejected() {
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![
+                PipelineStep {
+                    tool: "a".to_string(),
+                    input: Some(serde_json::json!({"x": "{{b_out}}"})),
+                    output_var: Some("a_out".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+                PipelineStep {
+                    tool: "b".to_string(),
+                    input: Some(serde_json::json!({"x": "{{a_out}}"})),
+                    output_var: Some("b_out".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+            ],
+        );
+
+        let registry = HandlerRegistry::new();
+        let result = handler
+            .execute(
+                PipelineInput {
+                    variables: HashMap::new(),
+                },
+                &registry,
+            )
+            .await;
+
+        assert!(result.is_err());
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("cycle"));
+        assert!(message.contains("a, b"));
+    }
+
+    #[tokio::test]
+    async fn test_fail_fast_in_one_layer_short_circuits_remaining_layers() {
+        use crate::{Handler, HandlerRegistry};
+        use schemars::JsonSchema;
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct NoInput {}
+
+        #[derive(Debug, serde::Serialize, JsonSchema)]
+        struct NoOutput {}
+
+        struct FailingHandler;
+
+        #[async_trait::async_trait]
+        impl Handler for FailingHandler {
+            type Input = NoInput;
+            type Output = NoOutput;
+            type Error = crate::Error;
+
+            async fn handle(&self, _input: Self::Input) -> crate::Result<Self::Output> {
+                Err(crate::Error::Handler("boom".to_string()))
+            }
+        }
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("failing", FailingHandler);
+
+        let handler = PipelineHandler::new(
+            empty_registry(),
+            vec![
+                PipelineStep {
+                    tool: "failing".to_string(),
+                    input: Some(serde_json::json!({})),
+                    output_var: Some("first".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+                // Depends on `first`, so it lands in a second layer that
+                // must never run once the first layer fails fast.
+                PipelineStep {
+                    tool: "failing".to_string(),
+                    input: Some(serde_json::json!({"x": "{{first}}"})),
+                    output_var: Some("second".to_string()),
+                    condition: None,
+                    error_policy: ErrorPolicy::FailFast,
+                },
+            ],
+        );
+
+        let result = handler
+            .execute(
+                PipelineInput {
+                    variables: HashMap::new(),
+                },
+                &registry,
+            )
+            .await;
+
+        assert!(result.is_err());
+    }
 }
@@ -1,6 +1,9 @@
 // Handler trait implementations for CLI and HTTP handlers
 use crate::handlers::cli::{CliHandler, CliInput, CliOutput};
+use crate::handlers::crypto::{CryptoHandler, CryptoInput, CryptoOutput};
 use crate::handlers::http::{HttpHandler, HttpInput, HttpOutput};
+use crate::handlers::jsonrpc::{JsonRpcHandler, JsonRpcInput, JsonRpcOutput};
+use crate::handlers::pipeline::{PipelineHandler, PipelineInput, PipelineOutput};
 use crate::{Error, Handler, Result};
 use async_trait::async_trait;
 
@@ -27,3 +30,45 @@ impl Handler for HttpHandler {
         self.execute(input).await
     }
 }
+
+// JSON-RPC Handler Wrapper
+#[async_trait]
+impl Handler for JsonRpcHandler {
+    type Input = JsonRpcInput;
+    type Output = JsonRpcOutput;
+    type Error = Error;
+
+    async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+        self.execute(input).await
+    }
+}
+
+// Crypto Handler Wrapper
+#[async_trait]
+impl Handler for CryptoHandler {
+    type Input = CryptoInput;
+    type Output = CryptoOutput;
+    type Error = Error;
+
+    async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+        self.execute(input).await
+    }
+}
+
+// Pipeline Handler Wrapper
+//
+// Unlike the other wrappers, `execute` needs the live registry (so each
+// step's tool name resolves at dispatch time), so `handle` reads the
+// registry this pipeline was registered into rather than calling
+// `execute` directly with no arguments.
+#[async_trait]
+impl Handler for PipelineHandler {
+    type Input = PipelineInput;
+    type Output = PipelineOutput;
+    type Error = Error;
+
+    async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+        let registry = self.registry.read().await;
+        self.execute(input, &registry).await
+    }
+}
@@ -65,36 +65,99 @@
 //! - **Middleware**: Composable request/response processing chain
 //! - **MCP protocol**: Full support for resources, prompts, and tools
 
+pub mod auth;
+pub mod client;
+pub mod codec;
+pub mod coerce;
+pub mod container;
+pub mod conversion;
+pub mod correlation;
+pub mod diagnostics;
+pub mod dispatch_middleware;
 pub mod error;
 pub mod handler;
 pub mod handlers;
 pub mod middleware;
 pub mod prompt;
+pub mod protocol;
 pub mod recovery;
 pub mod registry;
 pub mod resource;
 pub mod server;
 pub mod state;
 pub mod telemetry;
+pub mod testkit;
 pub mod timeout;
 pub mod transport;
 
-pub use error::{Error, Result};
+pub use auth::{
+    bearer_token_from_headers, build_authenticator, identity_of, stamp_identity, Authenticator,
+    Credentials, Identity, NoneAuthenticator, StaticTokenAuthenticator,
+};
+pub use client::{AsyncClient, ClientRetryPolicy, LoopbackClient, SyncClient};
+pub use codec::{Codec, JsonCodec, WireFormat};
+#[cfg(feature = "bincode")]
+pub use codec::BincodeCodec;
+#[cfg(feature = "cbor")]
+pub use codec::CborCodec;
+#[cfg(feature = "msgpack")]
+pub use codec::MessagePackCodec;
+#[cfg(feature = "postcard")]
+pub use codec::PostcardCodec;
+pub use coerce::coerce_to_schema;
+pub use container::{ContainerFixture, ContainerTransport, RunningContainer};
+pub use conversion::{coerce_param, Conversion};
+pub use correlation::{correlation_id_of, generate_correlation_id, CorrelationIdMiddleware};
+pub use diagnostics::{diagnose, ValidationDiagnostic};
+pub use dispatch_middleware::{
+    DispatchLatencyRecorder, DispatchMiddleware, DispatchRetry, DispatchTimeout, Next,
+    OutputValidator, TokenBucketRateLimiter,
+};
+pub use error::{Error, ErrorKind, Result};
 pub use handler::Handler;
-pub use handlers::{CliHandler, HttpHandler, PipelineHandler};
-pub use middleware::{LoggingMiddleware, Middleware, MiddlewareChain, ValidationMiddleware};
-pub use prompt::{PromptManager, PromptMetadata};
+pub use handlers::{
+    CliAttemptOutcome, CliHandler, CliRetryPolicy, CliStreamEvent, CommandExistsProbe,
+    CryptoHandler, HealthCheckHandler, HttpClientConfig, HttpClientProvider, HttpHandler,
+    HttpReachabilityProbe, JsonRpcHandler, PipelineHandler, PtyHandle, PtySize, StateManagerProbe,
+};
+// `middleware::Next` is not re-exported at the crate root - it would clash
+// with `dispatch_middleware::Next`, the analogous continuation type one
+// layer down at the raw dispatch path. Reach it via `middleware::Next`.
+pub use middleware::{
+    AuthMiddleware, BeforeOutcome, CacheMiddleware, LoggingMiddleware, Middleware,
+    MiddlewareChain, ValidationMiddleware,
+};
+pub use prompt::{PromptDiagnostic, PromptManager, PromptMetadata, Severity};
+pub use protocol::{
+    build_capability_manifest, negotiate_protocol_version, protocol_version_of,
+    stamp_protocol_version, CapabilityManifest, ToolCapability, VersionNegotiation,
+};
 pub use recovery::{
-    CircuitBreaker, CircuitBreakerConfig, CircuitState, ErrorTracker, FallbackHandler,
-    RecoveryMiddleware,
+    CircuitBreaker, CircuitBreakerConfig, CircuitState, ErrorTracker, FailureDetectionMode,
+    FallbackHandler, RecoveryMiddleware,
 };
 pub use registry::HandlerRegistry;
 pub use resource::{ResourceHandler, ResourceManager};
 pub use server::McpServer;
-pub use state::{MemoryStateManager, SledStateManager, StateManager};
+#[cfg(feature = "postgres")]
+pub use state::PostgresStateManager;
+#[cfg(feature = "redis")]
+pub use state::RedisStateManager;
+pub use state::{
+    MemoryStateManager, RedbStateManager, SledStateManager, StateBackend, StateManager,
+};
 pub use telemetry::{
-    ComponentHealth, HealthCheck, HealthStatus, MetricsCollector, TelemetryMiddleware,
+    init_tracing_with_console, serve_metrics, AdminMetrics, ComponentHealth, ComponentReport,
+    HealthCheck, HealthCheckOutcome, HealthProbe, HealthRegistry, HealthReport, HealthStatus,
+    MetricsCollector, OtlpConfig, OtlpHttpSink, OtlpResource, ResourceCollector, Stopwatch,
+    TelemetryEvent, TelemetryMiddleware, TelemetrySink,
+};
+pub use testkit::{
+    format_summary, run_suite, run_suite_against_container, CaseResult, RunOptions, SuiteReport,
+    TestCase, TestSuite,
 };
 pub use timeout::{
-    retry_with_policy, with_timeout, RetryMiddleware, RetryPolicy, TimeoutMiddleware,
+    retry_with_breaker, retry_with_policy, with_timeout, JitterStrategy, RetryBudget,
+    RetryMiddleware, RetryPolicy, TimeoutMiddleware,
 };
+pub use transport::{create_transport, create_transport_with_config};
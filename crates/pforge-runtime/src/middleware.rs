@@ -1,28 +1,145 @@
+use crate::state::StateManager;
 use crate::{Error, Result};
 use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Type-keyed, request-scoped storage threaded through `before`, `after`,
+/// and `on_error` for a single [`MiddlewareChain::execute`] call - modeled
+/// on actix-web's `HttpRequest` extensions / salvo's `Request::extensions`.
+/// A middleware can stash something in its own `before` (a start `Instant`,
+/// a resolved identity) and read it back in its own `after`/`on_error`, or a
+/// downstream middleware can read what an upstream one stashed. Each
+/// middleware should use a private type as its key to avoid colliding with
+/// another middleware's entry.
+#[derive(Default)]
+pub struct Extensions {
+    values: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value, returning the previous one of the same type, if any.
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|prev| prev.downcast::<T>().ok())
+            .map(|prev| *prev)
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.values
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut())
+    }
+
+    pub fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .and_then(|prev| prev.downcast::<T>().ok())
+            .map(|prev| *prev)
+    }
+}
+
+/// A handler (or the rest of the middleware chain), type-erased so [`Next`]
+/// doesn't need to carry a generic parameter through every recursive layer.
+type DynHandler<'a> = dyn Fn(Value) -> Pin<Box<dyn Future<Output = Result<Value>> + Send + 'a>>
+    + Send
+    + Sync
+    + 'a;
+
+/// Handle to "the rest of the chain" passed to [`Middleware::around`] -
+/// modeled on axum's `middleware::Next`, but callable more than once:
+/// invoking it a second time re-runs every inner `around` hook and the
+/// handler from scratch, which is what lets a retry middleware retry
+/// through `around` instead of only through `on_error`.
+pub struct Next<'a> {
+    middlewares: &'a [Arc<dyn Middleware>],
+    handler: &'a DynHandler<'a>,
+}
+
+impl<'a> Next<'a> {
+    fn new(middlewares: &'a [Arc<dyn Middleware>], handler: &'a DynHandler<'a>) -> Self {
+        Self {
+            middlewares,
+            handler,
+        }
+    }
+
+    /// Run the rest of the chain with `request`, wrapping the handler in
+    /// whatever the next middleware's `around` hook wants to do with it.
+    pub fn call(&self, request: Value) -> Pin<Box<dyn Future<Output = Result<Value>> + Send + 'a>> {
+        match self.middlewares.split_first() {
+            None => (self.handler)(request),
+            Some((middleware, rest)) => {
+                let middleware = middleware.clone();
+                let next = Next::new(rest, self.handler);
+                Box::pin(async move { middleware.around(request, next).await })
+            }
+        }
+    }
+}
+
+/// What a [`Middleware::before`] hook decided to do with a request.
+#[derive(Debug, Clone)]
+pub enum BeforeOutcome {
+    /// Carry on to the next `before` hook (and eventually the handler) with
+    /// this, possibly modified, request.
+    Continue(Value),
+    /// Skip every remaining `before` hook and the handler entirely, and
+    /// answer with this response straight away - the same "answer before
+    /// the route" pattern a CORS rejection or a cache hit uses. The
+    /// `after` phase still runs in reverse order, same as a normal
+    /// handler-produced response.
+    ShortCircuit(Value),
+}
 
 /// Middleware trait for request/response processing
 #[async_trait::async_trait]
 pub trait Middleware: Send + Sync {
-    /// Process request before handler execution
-    /// Returns modified request or error
-    async fn before(&self, request: Value) -> Result<Value> {
-        Ok(request)
+    /// Process request before handler execution. Returns the (possibly
+    /// modified) request to continue with, or a response to short-circuit
+    /// the chain with instead of invoking the handler. `extensions` is
+    /// shared for the whole `execute` call - stash something here to read
+    /// it back in this middleware's own `after`/`on_error`, or a later one.
+    async fn before(&self, request: Value, extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        let _ = extensions;
+        Ok(BeforeOutcome::Continue(request))
     }
 
     /// Process response after handler execution
     /// Returns modified response or error
-    async fn after(&self, request: Value, response: Value) -> Result<Value> {
-        let _ = request;
+    async fn after(&self, request: Value, response: Value, extensions: &Extensions) -> Result<Value> {
+        let _ = (request, extensions);
         Ok(response)
     }
 
     /// Handle errors from handler or downstream middleware
-    async fn on_error(&self, request: Value, error: Error) -> Result<Value> {
-        let _ = request;
+    async fn on_error(&self, request: Value, error: Error, extensions: &Extensions) -> Result<Value> {
+        let _ = (request, extensions);
         Err(error)
     }
+
+    /// Wrap the rest of the chain and the handler itself, e.g. to enforce a
+    /// deadline or retry on failure. Unlike `before`/`after`, `around` can
+    /// observe the handler's own latency and invoke `next` more than once.
+    /// The default simply calls through with no wrapping.
+    async fn around(&self, request: Value, next: Next<'_>) -> Result<Value> {
+        next.call(request).await
+    }
 }
 
 /// Middleware chain manages ordered middleware execution
@@ -45,29 +162,58 @@ impl MiddlewareChain {
     /// Execute middleware chain around a handler
     pub async fn execute<F, Fut>(&self, mut request: Value, handler: F) -> Result<Value>
     where
-        F: FnOnce(Value) -> Fut,
-        Fut: std::future::Future<Output = Result<Value>>,
+        F: Fn(Value) -> Fut + Send + Sync,
+        Fut: Future<Output = Result<Value>> + Send,
     {
-        // Execute "before" phase in order
+        // Shared for the whole call, so `before` can stash something that
+        // this middleware's own `after`/`on_error` (or a later middleware's)
+        // reads back.
+        let mut extensions = Extensions::new();
+
+        // Execute "before" phase in order, stopping early if a middleware
+        // short-circuits the chain with a final response.
+        let mut short_circuited = None;
         for middleware in &self.middlewares {
-            request = middleware.before(request).await?;
+            match middleware.before(request, &mut extensions).await? {
+                BeforeOutcome::Continue(next) => request = next,
+                BeforeOutcome::ShortCircuit(response) => {
+                    short_circuited = Some(response);
+                    break;
+                }
+            }
         }
 
-        // Execute handler
-        let result = handler(request.clone()).await;
+        // Execute handler, unless a middleware already answered for us. The
+        // handler call itself is wrapped by every middleware's `around`
+        // hook, outermost first.
+        let result = match short_circuited {
+            Some(response) => Ok(response),
+            None => {
+                let boxed_handler: Box<DynHandler> = Box::new(move |req| Box::pin(handler(req)));
+                let next = Next::new(&self.middlewares, &*boxed_handler);
+                next.call(request.clone()).await
+            }
+        };
 
-        // Execute "after" phase in reverse order or "on_error" if failed
+        // Execute "after" phase in reverse order or "on_error" if failed.
+        // A short-circuited response still runs through every `after` hook,
+        // same as a normal handler-produced one.
         match result {
             Ok(mut response) => {
                 for middleware in self.middlewares.iter().rev() {
-                    response = middleware.after(request.clone(), response).await?;
+                    response = middleware
+                        .after(request.clone(), response, &extensions)
+                        .await?;
                 }
                 Ok(response)
             }
             Err(error) => {
                 let mut current_error = error;
                 for middleware in self.middlewares.iter().rev() {
-                    match middleware.on_error(request.clone(), current_error).await {
+                    match middleware
+                        .on_error(request.clone(), current_error, &extensions)
+                        .await
+                    {
                         Ok(recovery_response) => return Ok(recovery_response),
                         Err(new_error) => current_error = new_error,
                     }
@@ -95,27 +241,34 @@ impl LoggingMiddleware {
     }
 }
 
+/// Private extension key: the `Instant` this middleware saw the request at,
+/// stashed in `before` and read back in this middleware's own `after`.
+struct RequestStartedAt(std::time::Instant);
+
 #[async_trait::async_trait]
 impl Middleware for LoggingMiddleware {
-    async fn before(&self, request: Value) -> Result<Value> {
+    async fn before(&self, request: Value, extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        extensions.insert(RequestStartedAt(std::time::Instant::now()));
         eprintln!(
             "[{}] Request: {}",
             self.tag,
             serde_json::to_string(&request).unwrap_or_default()
         );
-        Ok(request)
+        Ok(BeforeOutcome::Continue(request))
     }
 
-    async fn after(&self, _request: Value, response: Value) -> Result<Value> {
+    async fn after(&self, _request: Value, response: Value, extensions: &Extensions) -> Result<Value> {
+        let elapsed = extensions.get::<RequestStartedAt>().map(|s| s.0.elapsed());
         eprintln!(
-            "[{}] Response: {}",
+            "[{}] Response ({:?}): {}",
             self.tag,
+            elapsed,
             serde_json::to_string(&response).unwrap_or_default()
         );
         Ok(response)
     }
 
-    async fn on_error(&self, _request: Value, error: Error) -> Result<Value> {
+    async fn on_error(&self, _request: Value, error: Error, _extensions: &Extensions) -> Result<Value> {
         eprintln!("[{}] Error: {}", self.tag, error);
         Err(error)
     }
@@ -134,7 +287,7 @@ impl ValidationMiddleware {
 
 #[async_trait::async_trait]
 impl Middleware for ValidationMiddleware {
-    async fn before(&self, request: Value) -> Result<Value> {
+    async fn before(&self, request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
         if let Value::Object(obj) = &request {
             for field in &self.required_fields {
                 if !obj.contains_key(field) {
@@ -142,7 +295,7 @@ impl Middleware for ValidationMiddleware {
                 }
             }
         }
-        Ok(request)
+        Ok(BeforeOutcome::Continue(request))
     }
 }
 
@@ -175,15 +328,87 @@ where
     BeforeFn: Fn(Value) -> Result<Value> + Send + Sync,
     AfterFn: Fn(Value) -> Result<Value> + Send + Sync,
 {
-    async fn before(&self, request: Value) -> Result<Value> {
-        (self.before_fn)(request)
+    async fn before(&self, request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        Ok(BeforeOutcome::Continue((self.before_fn)(request)?))
     }
 
-    async fn after(&self, _request: Value, response: Value) -> Result<Value> {
+    async fn after(&self, _request: Value, response: Value, _extensions: &Extensions) -> Result<Value> {
         (self.after_fn)(response)
     }
 }
 
+/// Cache middleware - short-circuits on a cache hit, keyed by the request's
+/// serialized JSON, and stores the handler's response for next time.
+pub struct CacheMiddleware {
+    state: Arc<dyn StateManager>,
+    ttl: Option<Duration>,
+    prefix: String,
+}
+
+impl CacheMiddleware {
+    pub fn new(state: Arc<dyn StateManager>, ttl: Option<Duration>) -> Self {
+        Self {
+            state,
+            ttl,
+            prefix: "middleware:cache:".to_string(),
+        }
+    }
+
+    fn cache_key(&self, request: &Value) -> String {
+        format!(
+            "{}{}",
+            self.prefix,
+            serde_json::to_string(request).unwrap_or_default()
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for CacheMiddleware {
+    async fn before(&self, request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        let key = self.cache_key(&request);
+        if let Some(cached) = self.state.get(&key).await? {
+            if let Ok(response) = serde_json::from_slice::<Value>(&cached) {
+                return Ok(BeforeOutcome::ShortCircuit(response));
+            }
+        }
+        Ok(BeforeOutcome::Continue(request))
+    }
+
+    async fn after(&self, request: Value, response: Value, _extensions: &Extensions) -> Result<Value> {
+        let key = self.cache_key(&request);
+        let encoded = serde_json::to_vec(&response).unwrap_or_default();
+        self.state.set(&key, encoded, self.ttl).await?;
+        Ok(response)
+    }
+}
+
+/// Auth middleware - rejects requests that have no stamped identity
+/// (see [`crate::auth::identity_of`]) without ever invoking the handler.
+/// On success, stashes the resolved subject into `extensions` as
+/// [`ResolvedIdentity`] so later middleware can read who the caller is
+/// without re-parsing the request.
+pub struct AuthMiddleware;
+
+/// Extension key carrying the subject [`AuthMiddleware`] resolved, for
+/// later middleware to read back out of `extensions`.
+pub struct ResolvedIdentity(pub String);
+
+#[async_trait::async_trait]
+impl Middleware for AuthMiddleware {
+    async fn before(&self, request: Value, extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        match crate::auth::identity_of(&request) {
+            Some(subject) => {
+                extensions.insert(ResolvedIdentity(subject));
+                Ok(BeforeOutcome::Continue(request))
+            }
+            None => Ok(BeforeOutcome::ShortCircuit(serde_json::json!({
+                "error": "unauthenticated",
+            }))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,14 +420,14 @@ mod tests {
 
     #[async_trait::async_trait]
     impl Middleware for TestMiddleware {
-        async fn before(&self, mut request: Value) -> Result<Value> {
+        async fn before(&self, mut request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
             if let Value::Object(ref mut obj) = request {
                 obj.insert(format!("{}_before", self.tag), Value::Bool(true));
             }
-            Ok(request)
+            Ok(BeforeOutcome::Continue(request))
         }
 
-        async fn after(&self, _request: Value, mut response: Value) -> Result<Value> {
+        async fn after(&self, _request: Value, mut response: Value, _extensions: &Extensions) -> Result<Value> {
             if let Value::Object(ref mut obj) = response {
                 obj.insert(format!("{}_after", self.tag), Value::Bool(true));
             }
@@ -243,12 +468,14 @@ mod tests {
 
         // Valid request
         let valid_request = json!({"name": "Alice", "age": 30});
-        let result = middleware.before(valid_request).await;
+        let mut extensions = Extensions::new();
+        let result = middleware.before(valid_request, &mut extensions).await;
         assert!(result.is_ok());
+        assert!(matches!(result.unwrap(), BeforeOutcome::Continue(_)));
 
         // Invalid request - missing field
         let invalid_request = json!({"name": "Alice"});
-        let result = middleware.before(invalid_request).await;
+        let result = middleware.before(invalid_request, &mut extensions).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -276,11 +503,18 @@ mod tests {
         );
 
         let request = json!({"name": "alice"});
-        let transformed = middleware.before(request).await.unwrap();
+        let mut extensions = Extensions::new();
+        let transformed = match middleware.before(request, &mut extensions).await.unwrap() {
+            BeforeOutcome::Continue(req) => req,
+            BeforeOutcome::ShortCircuit(_) => panic!("expected Continue"),
+        };
         assert_eq!(transformed["name"], "ALICE");
 
         let response = json!({});
-        let transformed = middleware.after(json!({}), response).await.unwrap();
+        let transformed = middleware
+            .after(json!({}), response, &extensions)
+            .await
+            .unwrap();
         assert_eq!(transformed["transformed"], true);
     }
 
@@ -290,7 +524,12 @@ mod tests {
 
         #[async_trait::async_trait]
         impl Middleware for RecoveryMiddleware {
-            async fn on_error(&self, _request: Value, error: Error) -> Result<Value> {
+            async fn on_error(
+                &self,
+                _request: Value,
+                error: Error,
+                _extensions: &Extensions,
+            ) -> Result<Value> {
                 // Attempt to recover from specific errors
                 if error.to_string().contains("recoverable") {
                     Ok(json!({"recovered": true}))
@@ -356,4 +595,216 @@ mod tests {
 
         assert_eq!(result["result"], 11);
     }
+
+    #[tokio::test]
+    async fn test_short_circuit_skips_handler() {
+        struct RejectMiddleware;
+
+        #[async_trait::async_trait]
+        impl Middleware for RejectMiddleware {
+            async fn before(
+                &self,
+                _request: Value,
+                _extensions: &mut Extensions,
+            ) -> Result<BeforeOutcome> {
+                Ok(BeforeOutcome::ShortCircuit(json!({"rejected": true})))
+            }
+        }
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(RejectMiddleware));
+
+        let result = chain
+            .execute(json!({}), |_| async {
+                panic!("handler must not run when a middleware short-circuits")
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result["rejected"], true);
+    }
+
+    #[tokio::test]
+    async fn test_short_circuit_still_runs_after_in_reverse_order() {
+        let mut chain = MiddlewareChain::new();
+
+        chain.add(Arc::new(TestMiddleware {
+            tag: "outer".to_string(),
+        }));
+        chain.add(Arc::new(TestMiddleware {
+            tag: "inner".to_string(),
+        }));
+
+        struct RejectMiddleware;
+
+        #[async_trait::async_trait]
+        impl Middleware for RejectMiddleware {
+            async fn before(
+                &self,
+                _request: Value,
+                _extensions: &mut Extensions,
+            ) -> Result<BeforeOutcome> {
+                Ok(BeforeOutcome::ShortCircuit(json!({})))
+            }
+        }
+        chain.add(Arc::new(RejectMiddleware));
+
+        let result = chain
+            .execute(json!({}), |_| async {
+                panic!("handler must not run when a middleware short-circuits")
+            })
+            .await
+            .unwrap();
+
+        // "after" still fires for every middleware, innermost first, even
+        // though the request never reached the handler.
+        assert!(result["inner_after"].as_bool().unwrap_or(false));
+        assert!(result["outer_after"].as_bool().unwrap_or(false));
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_rejects_unidentified_request() {
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(AuthMiddleware));
+
+        let result = chain
+            .execute(json!({}), |_| async {
+                panic!("handler must not run without a stamped identity")
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result["error"], "unauthenticated");
+    }
+
+    #[tokio::test]
+    async fn test_auth_middleware_passes_identified_request() {
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(AuthMiddleware));
+
+        let identity = crate::auth::Identity {
+            subject: "alice".to_string(),
+        };
+        let request = crate::auth::stamp_identity(&identity, json!({}));
+
+        let result = chain
+            .execute(request, |_| async { Ok(json!({"ok": true})) })
+            .await
+            .unwrap();
+
+        assert_eq!(result["ok"], true);
+    }
+
+    #[tokio::test]
+    async fn test_extensions_stashed_by_one_middleware_are_read_by_a_later_middleware() {
+        // `AuthMiddleware::before` stashes the resolved identity in
+        // extensions; a downstream middleware (here, a stand-in for
+        // something like an audit-log middleware) reads it back in its own
+        // `after`, without the identity ever round-tripping through the
+        // request/response JSON.
+        struct EchoesResolvedIdentity;
+
+        #[async_trait::async_trait]
+        impl Middleware for EchoesResolvedIdentity {
+            async fn after(
+                &self,
+                _request: Value,
+                mut response: Value,
+                extensions: &Extensions,
+            ) -> Result<Value> {
+                if let Some(identity) = extensions.get::<ResolvedIdentity>() {
+                    if let Value::Object(ref mut obj) = response {
+                        obj.insert("resolved_by".to_string(), json!(identity.0));
+                    }
+                }
+                Ok(response)
+            }
+        }
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(AuthMiddleware));
+        chain.add(Arc::new(EchoesResolvedIdentity));
+
+        let identity = crate::auth::Identity {
+            subject: "alice".to_string(),
+        };
+        let request = crate::auth::stamp_identity(&identity, json!({}));
+
+        let result = chain
+            .execute(request, |_| async { Ok(json!({"ok": true})) })
+            .await
+            .unwrap();
+
+        assert_eq!(result["resolved_by"], "alice");
+    }
+
+    #[tokio::test]
+    async fn test_cache_middleware_short_circuits_on_hit() {
+        use crate::state::MemoryStateManager;
+
+        let state: Arc<dyn StateManager> = Arc::new(MemoryStateManager::new());
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(CacheMiddleware::new(state, None)));
+
+        let request = json!({"q": "hello"});
+
+        let result = chain
+            .execute(request.clone(), |_| async { Ok(json!({"answer": 42})) })
+            .await
+            .unwrap();
+        assert_eq!(result["answer"], 42);
+
+        // Second call with the same request should be served from cache
+        // without the handler running at all.
+        let result = chain
+            .execute(request, |_| async {
+                panic!("handler must not run on a cache hit")
+            })
+            .await
+            .unwrap();
+        assert_eq!(result["answer"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_around_wraps_handler_and_nests_outermost_first() {
+        struct MarkingMiddleware {
+            tag: &'static str,
+        }
+
+        #[async_trait::async_trait]
+        impl Middleware for MarkingMiddleware {
+            async fn around(&self, request: Value, next: Next<'_>) -> Result<Value> {
+                let mut response = next.call(request).await?;
+                if let Value::Object(ref mut obj) = response {
+                    obj.insert(
+                        "order".to_string(),
+                        Value::Array(
+                            obj.get("order")
+                                .and_then(Value::as_array)
+                                .cloned()
+                                .unwrap_or_default()
+                                .into_iter()
+                                .chain(std::iter::once(Value::String(self.tag.to_string())))
+                                .collect(),
+                        ),
+                    );
+                }
+                Ok(response)
+            }
+        }
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(MarkingMiddleware { tag: "outer" }));
+        chain.add(Arc::new(MarkingMiddleware { tag: "inner" }));
+
+        let result = chain
+            .execute(json!({}), |_| async { Ok(json!({})) })
+            .await
+            .unwrap();
+
+        // The outer middleware's own append happens after the inner one's,
+        // since it wraps `next.call` and only gets to mutate the response
+        // on the way back out - outermost wraps all inner `around` hooks.
+        assert_eq!(result["order"], json!(["inner", "outer"]));
+    }
 }
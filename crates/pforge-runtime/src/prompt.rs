@@ -1,23 +1,112 @@
+use crate::conversion::coerce_param;
 use crate::{Error, Result};
+use handlebars::Handlebars;
 use pforge_config::{ParamType, PromptDef};
+use regex::Regex;
 use serde_json::Value;
 use std::collections::HashMap;
 
-/// Prompt manager handles prompt rendering with template interpolation
+/// Prompt manager handles prompt rendering with template interpolation.
+///
+/// Templates are compiled once by [`handlebars`] at `register` time and
+/// cached under the prompt's name, so in addition to plain `{{variable}}`
+/// substitution they can use Handlebars conditionals (`{{#if}}`), iteration
+/// (`{{#each}}`), and `{{> other_prompt}}` partials referencing other
+/// registered prompts - call [`PromptManager::validate_partials`] once
+/// after registering a batch of prompts to check those references resolve
+/// and don't cycle. [`PromptManager::render`] stops at the first problem;
+/// [`PromptManager::render_checked`] instead collects every one it finds
+/// into a `Vec<PromptDiagnostic>`.
 pub struct PromptManager {
     prompts: HashMap<String, PromptEntry>,
+    handlebars: Handlebars<'static>,
 }
 
 struct PromptEntry {
     description: String,
-    template: String,
     arguments: HashMap<String, ParamType>,
+    /// Names of other prompts this one references via `{{> name}}`, e.g.
+    /// `["footer"]` for a template containing `{{> footer}}`.
+    partial_refs: Vec<String>,
+    /// Raw template source, kept alongside the compiled Handlebars template
+    /// so [`PromptManager::render_checked`] can report byte offsets for
+    /// unresolved placeholders.
+    template: String,
+}
+
+/// Names referenced by `{{> name}}` partial includes in `template` -
+/// Handlebars' own syntax for "render another registered template here".
+fn partial_references(template: &str) -> Vec<String> {
+    let re = Regex::new(r"\{\{>\s*([a-zA-Z0-9_-]+)\s*\}\}")
+        .expect("static partial-reference regex is valid");
+    re.captures_iter(template)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// Byte offset (of the whole `{{...}}` token) and variable name of each
+/// plain `{{variable}}` reference in `template`. Block helpers
+/// (`{{#if}}`, `{{/if}}`), partials (`{{> name}}`), and the iteration
+/// builtins (`{{@index}}`) all start with a character outside
+/// `[a-zA-Z0-9_.]`, so they don't match and aren't reported here. A dotted
+/// path like `{{user.name}}` is reported under its leading segment
+/// (`user`), since that's the key looked up in the argument map.
+fn template_variable_references(template: &str) -> Vec<(usize, String)> {
+    let re = Regex::new(r"\{\{\s*([a-zA-Z0-9_.]+)\s*\}\}")
+        .expect("static variable-reference regex is valid");
+    re.captures_iter(template)
+        .map(|c| {
+            let whole = c.get(0).expect("capture 0 is always the whole match");
+            let path = &c[1];
+            let root = path.split('.').next().unwrap_or(path).to_string();
+            (whole.start(), root)
+        })
+        .collect()
+}
+
+/// 1-indexed (line, column) of `byte_offset` within `template`.
+fn line_col(template: &str, byte_offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut line_start = 0;
+    for (i, ch) in template[..byte_offset].char_indices() {
+        if ch == '\n' {
+            line += 1;
+            line_start = i + 1;
+        }
+    }
+    (line, byte_offset - line_start + 1)
+}
+
+/// Severity of a [`PromptDiagnostic`] collected by
+/// [`PromptManager::render_checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single problem found while rendering a prompt in diagnostic mode. See
+/// [`PromptManager::render_checked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptDiagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// 1-indexed (line, column) into the template source, for diagnostics
+    /// that point at a specific place in the template (e.g. an unresolved
+    /// placeholder) rather than at the argument map as a whole.
+    pub location: Option<(usize, usize)>,
 }
 
 impl PromptManager {
     pub fn new() -> Self {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(true);
+        // Prompts are plain text, not HTML, so don't escape quotes/ampersands.
+        handlebars.register_escape_fn(handlebars::no_escape);
+
         Self {
             prompts: HashMap::new(),
+            handlebars,
         }
     }
 
@@ -30,18 +119,91 @@ impl PromptManager {
             )));
         }
 
+        // Registering the raw template as a Handlebars partial under the
+        // prompt's own name is what makes `{{> name}}` in another prompt
+        // resolve to it - Handlebars renders whatever's registered under
+        // that name at render time, so this doesn't require `name` to
+        // already exist yet.
+        self.handlebars
+            .register_partial(&def.name, &def.template)
+            .map_err(|e| {
+                Error::Handler(format!("Prompt '{}': invalid template: {}", def.name, e))
+            })?;
+
+        let partial_refs = partial_references(&def.template);
+
         self.prompts.insert(
             def.name.clone(),
             PromptEntry {
                 description: def.description,
-                template: def.template,
                 arguments: def.arguments,
+                partial_refs,
+                template: def.template,
             },
         );
 
         Ok(())
     }
 
+    /// Check that every `{{> name}}` partial reference among registered
+    /// prompts points at a prompt that actually exists, and that no cycle
+    /// exists among them (`a` includes `b` includes `a`). Run this once
+    /// after registering a batch of prompts, the same way
+    /// `pforge_config::validator` checks pipeline-to-pipeline references
+    /// only after the whole config is parsed.
+    pub fn validate_partials(&self) -> Result<()> {
+        for (name, entry) in &self.prompts {
+            for referenced in &entry.partial_refs {
+                if !self.prompts.contains_key(referenced) {
+                    return Err(Error::Handler(format!(
+                        "Prompt '{}' references unknown partial '{}'",
+                        name, referenced
+                    )));
+                }
+            }
+        }
+
+        let mut visited = std::collections::HashSet::new();
+        for name in self.prompts.keys() {
+            let mut stack = Vec::new();
+            if let Some(cycle) = self.find_partial_cycle(name, &mut stack, &mut visited) {
+                return Err(Error::Handler(format!(
+                    "cycle detected in prompt partial references: {}",
+                    cycle.join(" -> ")
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_partial_cycle(
+        &self,
+        name: &str,
+        stack: &mut Vec<String>,
+        visited: &mut std::collections::HashSet<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(pos) = stack.iter().position(|n| n == name) {
+            let mut cycle: Vec<String> = stack[pos..].to_vec();
+            cycle.push(name.to_string());
+            return Some(cycle);
+        }
+        if visited.contains(name) {
+            return None;
+        }
+
+        stack.push(name.to_string());
+        let result = self.prompts.get(name).and_then(|entry| {
+            entry
+                .partial_refs
+                .iter()
+                .find_map(|referenced| self.find_partial_cycle(referenced, stack, visited))
+        });
+        stack.pop();
+        visited.insert(name.to_string());
+        result
+    }
+
     /// Render a prompt with given arguments
     pub fn render(&self, name: &str, args: HashMap<String, Value>) -> Result<String> {
         let entry = self
@@ -49,11 +211,117 @@ impl PromptManager {
             .get(name)
             .ok_or_else(|| Error::Handler(format!("Prompt '{}' not found", name)))?;
 
+        // Fill in any argument the caller omitted but whose schema declares
+        // a `default`, before validation - so a required-with-default
+        // argument is satisfied, and interpolation never sees it missing.
+        let args = apply_defaults(entry, args);
+
         // Validate arguments
         self.validate_arguments(entry, &args)?;
 
-        // Perform template interpolation
-        self.interpolate(&entry.template, &args)
+        // Coerce string-typed arguments (CLI args, query params, ...) to
+        // their declared SimpleType before interpolation.
+        let args = coerce_arguments(entry, args)?;
+
+        // Perform template interpolation, reusing the parsed template
+        // Handlebars cached under `name` when the prompt was registered
+        // (rather than re-parsing the raw template string on every render).
+        self.interpolate(name, &args)
+    }
+
+    /// Render `name` like [`Self::render`], but never stop at the first
+    /// problem: every missing required argument, unresolved
+    /// `{{placeholder}}` (with its line/column in the template), argument
+    /// coercion failure, and undeclared argument the caller passed is
+    /// collected into one `Vec<PromptDiagnostic>` instead. Returns `Ok` with
+    /// the rendered template when nothing of `Error` severity was found
+    /// (`Warning`-only diagnostics, e.g. an undeclared argument, don't block
+    /// rendering).
+    pub fn render_checked(
+        &self,
+        name: &str,
+        args: HashMap<String, Value>,
+    ) -> std::result::Result<String, Vec<PromptDiagnostic>> {
+        let Some(entry) = self.prompts.get(name) else {
+            return Err(vec![PromptDiagnostic {
+                severity: Severity::Error,
+                message: format!("Prompt '{}' not found", name),
+                location: None,
+            }]);
+        };
+
+        let args = apply_defaults(entry, args);
+        let mut diagnostics = Vec::new();
+
+        for (arg_name, param_type) in &entry.arguments {
+            let is_required = matches!(param_type, ParamType::Complex { required: true, .. });
+            if is_required && !args.contains_key(arg_name) {
+                diagnostics.push(PromptDiagnostic {
+                    severity: Severity::Error,
+                    message: format!("Required argument '{}' not provided", arg_name),
+                    location: None,
+                });
+            }
+        }
+
+        for arg_name in args.keys() {
+            if !entry.arguments.contains_key(arg_name) {
+                diagnostics.push(PromptDiagnostic {
+                    severity: Severity::Warning,
+                    message: format!(
+                        "Argument '{}' is not declared in the prompt schema",
+                        arg_name
+                    ),
+                    location: None,
+                });
+            }
+        }
+
+        let mut coerced = HashMap::with_capacity(args.len());
+        for (arg_name, value) in &args {
+            match entry.arguments.get(arg_name) {
+                Some(param_type) => match coerce_param(arg_name, param_type, value) {
+                    Ok(v) => {
+                        coerced.insert(arg_name.clone(), v);
+                    }
+                    Err(e) => diagnostics.push(PromptDiagnostic {
+                        severity: Severity::Error,
+                        message: e.to_string(),
+                        location: None,
+                    }),
+                },
+                None => {
+                    coerced.insert(arg_name.clone(), value.clone());
+                }
+            }
+        }
+
+        for (offset, var) in template_variable_references(&entry.template) {
+            if var == "this" || coerced.contains_key(&var) {
+                continue;
+            }
+            diagnostics.push(PromptDiagnostic {
+                severity: Severity::Error,
+                message: format!("Unresolved placeholder '{{{{{}}}}}'", var),
+                location: Some(line_col(&entry.template, offset)),
+            });
+        }
+
+        if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+            return Err(diagnostics);
+        }
+
+        match self.interpolate(name, &coerced) {
+            Ok(rendered) => Ok(rendered),
+            Err(e) => {
+                diagnostics.push(PromptDiagnostic {
+                    severity: Severity::Error,
+                    message: e.to_string(),
+                    location: None,
+                });
+                Err(diagnostics)
+            }
+        }
     }
 
     /// Get prompt metadata
@@ -86,47 +354,16 @@ impl PromptManager {
             }
         }
 
-        // Type validation could be added here
         Ok(())
     }
 
-    /// Interpolate template with argument values
-    /// Supports {{variable}} syntax
-    fn interpolate(&self, template: &str, args: &HashMap<String, Value>) -> Result<String> {
-        let mut result = template.to_string();
-
-        for (key, value) in args {
-            let placeholder = format!("{{{{{}}}}}", key);
-            let replacement = match value {
-                Value::String(s) => s.clone(),
-                Value::Number(n) => n.to_string(),
-                Value::Bool(b) => b.to_string(),
-                Value::Null => String::new(),
-                _ => serde_json::to_string(value)
-                    .map_err(|e| Error::Handler(format!("Failed to serialize value: {}", e)))?,
-            };
-
-            result = result.replace(&placeholder, &replacement);
-        }
-
-        // Check for unresolved placeholders
-        if result.contains("{{") && result.contains("}}") {
-            // Extract unresolved variable names for better error message
-            let unresolved: Vec<&str> = result
-                .split("{{")
-                .skip(1)
-                .filter_map(|s| s.split("}}").next())
-                .collect();
-
-            if !unresolved.is_empty() {
-                return Err(Error::Handler(format!(
-                    "Unresolved template variables: {}",
-                    unresolved.join(", ")
-                )));
-            }
-        }
-
-        Ok(result)
+    /// Render the template Handlebars cached under `name` (at `register`
+    /// time) with argument values, so prompts can use `{{variable}}` as
+    /// well as `{{#if}}`/`{{#each}}`/`{{> partial}}`.
+    fn interpolate(&self, name: &str, args: &HashMap<String, Value>) -> Result<String> {
+        self.handlebars
+            .render(name, args)
+            .map_err(|e| Error::Handler(format!("Unresolved template variables: {}", e)))
     }
 }
 
@@ -143,6 +380,42 @@ pub struct PromptMetadata {
     pub arguments: HashMap<String, ParamType>,
 }
 
+/// Inject each schema-declared `default` for an argument the caller omitted,
+/// leaving any value the caller did provide untouched.
+fn apply_defaults(entry: &PromptEntry, mut args: HashMap<String, Value>) -> HashMap<String, Value> {
+    for (name, param_type) in &entry.arguments {
+        if args.contains_key(name) {
+            continue;
+        }
+        if let ParamType::Complex {
+            default: Some(default),
+            ..
+        } = param_type
+        {
+            args.insert(name.clone(), default.clone());
+        }
+    }
+    args
+}
+
+/// Coerce each argument to the `SimpleType` its prompt declares (e.g. a CLI
+/// caller's `"30"` becomes the integer `30`), leaving arguments the prompt
+/// doesn't declare untouched.
+fn coerce_arguments(
+    entry: &PromptEntry,
+    args: HashMap<String, Value>,
+) -> Result<HashMap<String, Value>> {
+    args.into_iter()
+        .map(|(name, value)| {
+            let coerced = match entry.arguments.get(&name) {
+                Some(param_type) => coerce_param(&name, param_type, &value)?,
+                None => value,
+            };
+            Ok((name, coerced))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,6 +491,7 @@ mod tests {
                 default: None,
                 description: None,
                 validation: None,
+                coerce: None,
             },
         );
 
@@ -277,6 +551,7 @@ mod tests {
                 default: None,
                 description: Some("User name".to_string()),
                 validation: None,
+                coerce: None,
             },
         );
 
@@ -294,6 +569,132 @@ mod tests {
         assert!(metadata.arguments.contains_key("name"));
     }
 
+    #[test]
+    fn test_default_injected_when_argument_omitted() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "tone".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::String,
+                required: false,
+                default: Some(json!("friendly")),
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Tone: {{tone}}".to_string(),
+            arguments,
+        };
+
+        manager.register(def).unwrap();
+
+        let result = manager.render("greeting", HashMap::new()).unwrap();
+        assert_eq!(result, "Tone: friendly");
+    }
+
+    #[test]
+    fn test_caller_value_overrides_default() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "tone".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::String,
+                required: false,
+                default: Some(json!("friendly")),
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Tone: {{tone}}".to_string(),
+            arguments,
+        };
+
+        manager.register(def).unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("tone".to_string(), json!("formal"));
+        let result = manager.render("greeting", args).unwrap();
+        assert_eq!(result, "Tone: formal");
+    }
+
+    #[test]
+    fn test_required_with_default_is_satisfied() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "tone".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::String,
+                required: true,
+                default: Some(json!("friendly")),
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Tone: {{tone}}".to_string(),
+            arguments,
+        };
+
+        manager.register(def).unwrap();
+
+        let result = manager.render("greeting", HashMap::new());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_required_without_default_still_errors() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "tone".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::String,
+                required: true,
+                default: None,
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Tone: {{tone}}".to_string(),
+            arguments,
+        };
+
+        manager.register(def).unwrap();
+
+        let result = manager.render("greeting", HashMap::new());
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Required argument"));
+    }
+
     #[test]
     fn test_complex_value_interpolation() {
         let mut manager = PromptManager::new();
@@ -315,4 +716,302 @@ mod tests {
         let result = manager.render("test", args).unwrap();
         assert_eq!(result, "String: hello, Number: 42, Bool: true");
     }
+
+    #[test]
+    fn test_conditional_section() {
+        let mut manager = PromptManager::new();
+
+        let def = PromptDef {
+            name: "test".to_string(),
+            description: "Test".to_string(),
+            template: "{{#if verbose}}Details: {{detail}}{{/if}}Done".to_string(),
+            arguments: HashMap::new(),
+        };
+        manager.register(def).unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("verbose".to_string(), json!(true));
+        args.insert("detail".to_string(), json!("extra info"));
+        assert_eq!(
+            manager.render("test", args).unwrap(),
+            "Details: extra infoDone"
+        );
+
+        let mut args = HashMap::new();
+        args.insert("verbose".to_string(), json!(false));
+        assert_eq!(manager.render("test", args).unwrap(), "Done");
+    }
+
+    #[test]
+    fn test_each_loop() {
+        let mut manager = PromptManager::new();
+
+        let def = PromptDef {
+            name: "test".to_string(),
+            description: "Test".to_string(),
+            template: "{{#each examples}}[{{@index}}:{{this}}]{{/each}}".to_string(),
+            arguments: HashMap::new(),
+        };
+        manager.register(def).unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("examples".to_string(), json!(["a", "b", "c"]));
+        assert_eq!(manager.render("test", args).unwrap(), "[0:a][1:b][2:c]");
+    }
+
+    #[test]
+    fn test_partial_reference_renders_other_prompt() {
+        let mut manager = PromptManager::new();
+
+        manager
+            .register(PromptDef {
+                name: "footer".to_string(),
+                description: "Footer".to_string(),
+                template: "-- sent by pforge".to_string(),
+                arguments: HashMap::new(),
+            })
+            .unwrap();
+        manager
+            .register(PromptDef {
+                name: "email".to_string(),
+                description: "Email".to_string(),
+                template: "Hi {{name}}!\n{{> footer}}".to_string(),
+                arguments: HashMap::new(),
+            })
+            .unwrap();
+
+        manager.validate_partials().unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("name".to_string(), json!("Alice"));
+        assert_eq!(
+            manager.render("email", args).unwrap(),
+            "Hi Alice!\n-- sent by pforge"
+        );
+    }
+
+    #[test]
+    fn test_validate_partials_catches_unknown_reference() {
+        let mut manager = PromptManager::new();
+
+        manager
+            .register(PromptDef {
+                name: "email".to_string(),
+                description: "Email".to_string(),
+                template: "Hi {{name}}\n{{> missing_footer}}".to_string(),
+                arguments: HashMap::new(),
+            })
+            .unwrap();
+
+        let err = manager.validate_partials().unwrap_err();
+        assert!(err.to_string().contains("missing_footer"));
+    }
+
+    #[test]
+    fn test_validate_partials_catches_cycle() {
+        let mut manager = PromptManager::new();
+
+        manager
+            .register(PromptDef {
+                name: "a".to_string(),
+                description: "A".to_string(),
+                template: "{{> b}}".to_string(),
+                arguments: HashMap::new(),
+            })
+            .unwrap();
+        manager
+            .register(PromptDef {
+                name: "b".to_string(),
+                description: "B".to_string(),
+                template: "{{> a}}".to_string(),
+                arguments: HashMap::new(),
+            })
+            .unwrap();
+
+        let err = manager.validate_partials().unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn test_render_checked_collects_missing_required_and_unresolved_placeholder() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "name".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::String,
+                required: true,
+                default: None,
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Hello, {{name}}! Welcome to {{location}}.".to_string(),
+            arguments,
+        };
+        manager.register(def).unwrap();
+
+        let diagnostics = manager.render_checked("greeting", HashMap::new()).unwrap_err();
+
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error && d.message.contains("Required argument")));
+        let placeholder = diagnostics
+            .iter()
+            .find(|d| d.message.contains("location"))
+            .expect("unresolved 'location' placeholder should be reported");
+        assert_eq!(placeholder.severity, Severity::Error);
+        assert!(placeholder.location.is_some());
+    }
+
+    #[test]
+    fn test_render_checked_reports_coercion_failure() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "age".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::Integer,
+                required: true,
+                default: None,
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "test".to_string(),
+            description: "Test".to_string(),
+            template: "Age: {{age}}".to_string(),
+            arguments,
+        };
+        manager.register(def).unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("age".to_string(), json!("not-a-number"));
+
+        let diagnostics = manager.render_checked("test", args).unwrap_err();
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error && d.message.contains("age")));
+    }
+
+    #[test]
+    fn test_render_checked_warns_on_unknown_argument_but_still_renders() {
+        let mut manager = PromptManager::new();
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Hello, {{name}}!".to_string(),
+            arguments: HashMap::new(),
+        };
+        manager.register(def).unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("name".to_string(), json!("Alice"));
+        args.insert("extra".to_string(), json!("unused"));
+
+        let rendered = manager.render_checked("greeting", args).unwrap();
+        assert_eq!(rendered, "Hello, Alice!");
+    }
+
+    #[test]
+    fn test_render_checked_unknown_argument_warning_alongside_real_error() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "name".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::String,
+                required: true,
+                default: None,
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Hello, {{name}}!".to_string(),
+            arguments,
+        };
+        manager.register(def).unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("extra".to_string(), json!("unused"));
+
+        let diagnostics = manager.render_checked("greeting", args).unwrap_err();
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Warning && d.message.contains("extra")));
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error && d.message.contains("Required argument")));
+    }
+
+    #[test]
+    fn test_render_checked_reports_line_and_column_of_placeholder() {
+        let mut manager = PromptManager::new();
+
+        let def = PromptDef {
+            name: "test".to_string(),
+            description: "Test".to_string(),
+            template: "line one\nline two {{missing}}".to_string(),
+            arguments: HashMap::new(),
+        };
+        manager.register(def).unwrap();
+
+        let diagnostics = manager.render_checked("test", HashMap::new()).unwrap_err();
+        let placeholder = diagnostics
+            .iter()
+            .find(|d| d.message.contains("missing"))
+            .unwrap();
+        assert_eq!(placeholder.location, Some((2, 10)));
+    }
+
+    #[test]
+    fn test_render_checked_happy_path_has_no_diagnostics() {
+        let mut manager = PromptManager::new();
+
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "name".to_string(),
+            ParamType::Complex {
+                ty: SimpleType::String,
+                required: true,
+                default: None,
+                description: None,
+                validation: None,
+                coerce: None,
+            },
+        );
+
+        let def = PromptDef {
+            name: "greeting".to_string(),
+            description: "Greeting".to_string(),
+            template: "Hello, {{name}}!".to_string(),
+            arguments,
+        };
+        manager.register(def).unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("name".to_string(), json!("Alice"));
+
+        assert_eq!(
+            manager.render_checked("greeting", args).unwrap(),
+            "Hello, Alice!"
+        );
+    }
 }
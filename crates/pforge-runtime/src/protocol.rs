@@ -0,0 +1,257 @@
+//! MCP handshake: protocol version negotiation and capability advertisement.
+//!
+//! The first JSON-RPC call a client makes on a connection is conventionally
+//! `initialize`, carrying the protocol version it speaks. The server here
+//! either accepts that version, downgrades to the newest version it itself
+//! supports (when the client asked for something newer), or rejects the
+//! connection with a structured list of what it does support. The response
+//! also carries a capability manifest - derived from `forge.tools`,
+//! `forge.resources`, and `forge.prompts` - so a client can feature-detect
+//! before issuing its first real call.
+
+use pforge_config::{ForgeConfig, ToolDef};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Oldest protocol version this server still understands.
+pub const MIN_PROTOCOL_VERSION: &str = "2024-11-05";
+/// Newest protocol version this server speaks.
+pub const MAX_PROTOCOL_VERSION: &str = "2025-03-26";
+/// The two endpoints of the accepted range, reported back to a client whose
+/// requested version was rejected by [`negotiate_protocol_version`] (any
+/// version between them, inclusive, is accepted as-is - no downgrade
+/// needed).
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[MIN_PROTOCOL_VERSION, MAX_PROTOCOL_VERSION];
+
+/// Outcome of [`negotiate_protocol_version`] for a version the server is
+/// willing to speak.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionNegotiation {
+    /// The client's requested version is directly supported.
+    Accepted(String),
+    /// The client asked for a version newer than this server supports; the
+    /// server is falling back to the newest version it speaks.
+    Downgraded {
+        requested: String,
+        negotiated: String,
+    },
+}
+
+impl VersionNegotiation {
+    /// The version the server will actually speak for this connection.
+    pub fn version(&self) -> &str {
+        match self {
+            VersionNegotiation::Accepted(v) => v,
+            VersionNegotiation::Downgraded { negotiated, .. } => negotiated,
+        }
+    }
+}
+
+/// Negotiate a protocol version against [`SUPPORTED_PROTOCOL_VERSIONS`].
+/// Returns the list of supported versions as the error when `requested` is
+/// older than anything this server understands.
+pub fn negotiate_protocol_version(
+    requested: &str,
+) -> std::result::Result<VersionNegotiation, Vec<String>> {
+    if requested >= MIN_PROTOCOL_VERSION && requested <= MAX_PROTOCOL_VERSION {
+        return Ok(VersionNegotiation::Accepted(requested.to_string()));
+    }
+
+    if requested > MAX_PROTOCOL_VERSION {
+        return Ok(VersionNegotiation::Downgraded {
+            requested: requested.to_string(),
+            negotiated: MAX_PROTOCOL_VERSION.to_string(),
+        });
+    }
+
+    Err(SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .map(|v| v.to_string())
+        .collect())
+}
+
+/// Whether a single registered tool supports streaming output.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCapability {
+    pub name: String,
+    pub streaming: bool,
+}
+
+/// What a client can feature-detect before issuing its first call.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityManifest {
+    pub tools: Vec<ToolCapability>,
+    pub resources: Vec<String>,
+    pub prompts: Vec<String>,
+}
+
+/// Derive the capability manifest advertised by `initialize` from the
+/// server's configuration.
+pub fn build_capability_manifest(config: &ForgeConfig) -> CapabilityManifest {
+    let tools = config
+        .tools
+        .iter()
+        .map(|tool| ToolCapability {
+            name: tool.name().to_string(),
+            streaming: matches!(tool, ToolDef::Cli { stream: true, .. }),
+        })
+        .collect();
+
+    let resources = config
+        .resources
+        .iter()
+        .map(|r| r.uri_template.clone())
+        .collect();
+
+    let prompts = config.prompts.iter().map(|p| p.name.clone()).collect();
+
+    CapabilityManifest {
+        tools,
+        resources,
+        prompts,
+    }
+}
+
+const FIELD: &str = "_protocol_version";
+
+/// Stamp the negotiated protocol version onto a request, mirroring
+/// [`crate::auth::stamp_identity`], so handlers/middleware can adapt
+/// behavior to the version in effect for the connection.
+pub fn stamp_protocol_version(version: &str, mut request: Value) -> Value {
+    if let Value::Object(ref mut obj) = request {
+        obj.insert(FIELD.to_string(), Value::String(version.to_string()));
+    }
+    request
+}
+
+/// Recover the protocol version stamped by [`stamp_protocol_version`], if any.
+pub fn protocol_version_of(request: &Value) -> Option<String> {
+    request
+        .get(FIELD)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pforge_config::{
+        ForgeMetadata, HandlerRef, HttpMethod, OptimizationLevel, PromptDef, ResourceDef,
+        ResourceOperation, TransportTuning, TransportType,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_negotiate_exact_match_is_accepted() {
+        let result = negotiate_protocol_version(MIN_PROTOCOL_VERSION).unwrap();
+        assert_eq!(
+            result,
+            VersionNegotiation::Accepted(MIN_PROTOCOL_VERSION.to_string())
+        );
+    }
+
+    #[test]
+    fn test_negotiate_mid_range_version_is_accepted() {
+        let result = negotiate_protocol_version("2025-01-01").unwrap();
+        assert_eq!(
+            result,
+            VersionNegotiation::Accepted("2025-01-01".to_string())
+        );
+    }
+
+    #[test]
+    fn test_negotiate_newer_than_supported_downgrades() {
+        let result = negotiate_protocol_version("2099-01-01").unwrap();
+        assert_eq!(result.version(), MAX_PROTOCOL_VERSION);
+        assert!(matches!(result, VersionNegotiation::Downgraded { .. }));
+    }
+
+    #[test]
+    fn test_negotiate_older_than_supported_is_rejected() {
+        let err = negotiate_protocol_version("2020-01-01").unwrap_err();
+        assert_eq!(
+            err,
+            vec![
+                MIN_PROTOCOL_VERSION.to_string(),
+                MAX_PROTOCOL_VERSION.to_string()
+            ]
+        );
+    }
+
+    fn test_config() -> ForgeConfig {
+        ForgeConfig {
+            forge: ForgeMetadata {
+                name: "test".to_string(),
+                version: "1.0.0".to_string(),
+                transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
+                optimization: OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
+            },
+            tools: vec![
+                ToolDef::Cli {
+                    name: "streamed".to_string(),
+                    description: "Streams".to_string(),
+                    command: "tail".to_string(),
+                    args: vec![],
+                    cwd: None,
+                    env: HashMap::new(),
+                    stream: true,
+                },
+                ToolDef::Http {
+                    name: "fetch".to_string(),
+                    description: "Fetches".to_string(),
+                    endpoint: "https://example.com".to_string(),
+                    method: HttpMethod::Get,
+                    headers: HashMap::new(),
+                    auth: None,
+                },
+            ],
+            resources: vec![ResourceDef {
+                uri_template: "file:///{path}".to_string(),
+                handler: HandlerRef {
+                    path: "handlers::file".to_string(),
+                    inline: None,
+                },
+                supports: vec![ResourceOperation::Read],
+            }],
+            prompts: vec![PromptDef {
+                name: "greeting".to_string(),
+                description: "Greets".to_string(),
+                template: "Hello, {{name}}!".to_string(),
+                arguments: HashMap::new(),
+            }],
+            aliases: HashMap::new(),
+            state: None,
+            auth: None,
+        }
+    }
+
+    #[test]
+    fn test_build_capability_manifest() {
+        let manifest = build_capability_manifest(&test_config());
+
+        assert_eq!(manifest.tools.len(), 2);
+        assert!(manifest
+            .tools
+            .iter()
+            .any(|t| t.name == "streamed" && t.streaming));
+        assert!(manifest
+            .tools
+            .iter()
+            .any(|t| t.name == "fetch" && !t.streaming));
+        assert_eq!(manifest.resources, vec!["file:///{path}".to_string()]);
+        assert_eq!(manifest.prompts, vec!["greeting".to_string()]);
+    }
+
+    #[test]
+    fn test_stamp_and_read_protocol_version() {
+        let request = stamp_protocol_version(MAX_PROTOCOL_VERSION, serde_json::json!({"a": 1}));
+        assert_eq!(
+            protocol_version_of(&request).as_deref(),
+            Some(MAX_PROTOCOL_VERSION)
+        );
+    }
+}
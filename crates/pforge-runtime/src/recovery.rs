@@ -1,7 +1,8 @@
+use crate::middleware::{BeforeOutcome, Extensions};
 use crate::{Error, Middleware, Result};
 use serde_json::Value;
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
@@ -13,6 +14,107 @@ pub enum CircuitState {
     HalfOpen, // Testing if service recovered
 }
 
+impl CircuitState {
+    /// Numeric encoding used by [`RecoveryMiddleware::export_prometheus`]:
+    /// `0` closed, `1` open, `2` half-open. A gauge needs a number, and this
+    /// is the ordering operators scanning a dashboard expect - "how open is
+    /// this thing" increasing left to right.
+    fn as_metric_code(self) -> u8 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::Open => 1,
+            CircuitState::HalfOpen => 2,
+        }
+    }
+}
+
+/// How a `CircuitBreaker` decides it's time to trip from `Closed` to `Open`.
+#[derive(Debug, Clone)]
+pub enum FailureDetectionMode {
+    /// Trip after `failure_threshold` consecutive failures; any success
+    /// resets the counter. Misses intermittent-but-sustained degradation,
+    /// since occasional successes keep resetting the count.
+    Consecutive,
+    /// Trip when, over a rolling `window` divided into `num_buckets` equal
+    /// slots, at least `min_requests` calls were recorded and the failure
+    /// rate is >= `error_rate_threshold`. Tolerates occasional successes
+    /// amid sustained, stochastic failures.
+    SlidingWindow {
+        window: Duration,
+        num_buckets: usize,
+        min_requests: usize,
+        error_rate_threshold: f64,
+    },
+}
+
+impl Default for FailureDetectionMode {
+    fn default() -> Self {
+        FailureDetectionMode::Consecutive
+    }
+}
+
+/// Ring buffer of per-bucket (success, failure) counts covering a rolling
+/// time window, backing `FailureDetectionMode::SlidingWindow`. Buckets are
+/// addressed by generation (elapsed time / bucket width) and lazily zeroed
+/// when a slot is revisited after wrapping around, so there's no background
+/// task advancing or pruning it.
+struct SlidingWindowCounters {
+    start: Instant,
+    bucket_width_nanos: u128,
+    num_buckets: usize,
+    buckets: Mutex<Vec<(u64, usize, usize)>>,
+}
+
+impl SlidingWindowCounters {
+    fn new(window: Duration, num_buckets: usize) -> Self {
+        let num_buckets = num_buckets.max(1);
+        let bucket_width_nanos = (window.as_nanos() / num_buckets as u128).max(1);
+        Self {
+            start: Instant::now(),
+            bucket_width_nanos,
+            num_buckets,
+            buckets: Mutex::new(vec![(0, 0, 0); num_buckets]),
+        }
+    }
+
+    fn current_generation(&self) -> u64 {
+        (self.start.elapsed().as_nanos() / self.bucket_width_nanos) as u64
+    }
+
+    fn record(&self, success: bool) {
+        let generation = self.current_generation();
+        let idx = (generation as usize) % self.num_buckets;
+        let mut buckets = self.buckets.lock().unwrap();
+        let (bucket_generation, successes, failures) = &mut buckets[idx];
+        if *bucket_generation != generation {
+            *bucket_generation = generation;
+            *successes = 0;
+            *failures = 0;
+        }
+        if success {
+            *successes += 1;
+        } else {
+            *failures += 1;
+        }
+    }
+
+    /// Total (successes, failures) across buckets still inside the window.
+    fn totals(&self) -> (usize, usize) {
+        let generation = self.current_generation();
+        let num_buckets = self.num_buckets as u64;
+        self.buckets.lock().unwrap().iter().fold(
+            (0, 0),
+            |(successes, failures), (bucket_generation, s, f)| {
+                if generation.saturating_sub(*bucket_generation) < num_buckets {
+                    (successes + s, failures + f)
+                } else {
+                    (successes, failures)
+                }
+            },
+        )
+    }
+}
+
 /// Circuit breaker configuration
 #[derive(Debug, Clone)]
 pub struct CircuitBreakerConfig {
@@ -22,6 +124,14 @@ pub struct CircuitBreakerConfig {
     pub timeout: Duration,
     /// Number of successes needed to close circuit
     pub success_threshold: usize,
+    /// How failures are detected; defaults to the original consecutive-count
+    /// behavior driven by `failure_threshold`.
+    pub failure_detection: FailureDetectionMode,
+    /// Trial calls admitted at once while `HalfOpen`; the rest are rejected
+    /// until one of these probes resolves. Bounds a recovering backend to a
+    /// handful of probes instead of a thundering herd the instant the
+    /// timeout elapses.
+    pub half_open_max_concurrent: usize,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -30,32 +140,59 @@ impl Default for CircuitBreakerConfig {
             failure_threshold: 5,
             timeout: Duration::from_secs(60),
             success_threshold: 2,
+            failure_detection: FailureDetectionMode::default(),
+            half_open_max_concurrent: 1,
         }
     }
 }
 
+/// All circuit-breaker state that must change together atomically. Prior to
+/// this, state/failure_count/success_count/last_failure_time were separate
+/// locks, which left a TOCTOU gap: concurrent callers could each read `Open`,
+/// independently decide the timeout had elapsed, and all flip to `HalfOpen`
+/// at once, flooding a recovering backend with probes. Guarding everything
+/// with one lock makes "check state, maybe transition, admit a probe" a
+/// single atomic step.
+struct Inner {
+    state: CircuitState,
+    failure_count: usize,
+    success_count: usize,
+    last_failure_time: Option<Instant>,
+    /// Half-open trial calls currently admitted and not yet resolved.
+    half_open_in_flight: usize,
+}
+
 /// Circuit breaker for fault tolerance
 pub struct CircuitBreaker {
     config: CircuitBreakerConfig,
-    state: Arc<RwLock<CircuitState>>,
-    failure_count: Arc<AtomicUsize>,
-    success_count: Arc<AtomicUsize>,
-    last_failure_time: Arc<RwLock<Option<Instant>>>,
+    inner: Mutex<Inner>,
+    sliding_window: Option<SlidingWindowCounters>,
 }
 
 impl CircuitBreaker {
     pub fn new(config: CircuitBreakerConfig) -> Self {
+        let sliding_window = match &config.failure_detection {
+            FailureDetectionMode::Consecutive => None,
+            FailureDetectionMode::SlidingWindow {
+                window, num_buckets, ..
+            } => Some(SlidingWindowCounters::new(*window, *num_buckets)),
+        };
+
         Self {
             config,
-            state: Arc::new(RwLock::new(CircuitState::Closed)),
-            failure_count: Arc::new(AtomicUsize::new(0)),
-            success_count: Arc::new(AtomicUsize::new(0)),
-            last_failure_time: Arc::new(RwLock::new(None)),
+            inner: Mutex::new(Inner {
+                state: CircuitState::Closed,
+                failure_count: 0,
+                success_count: 0,
+                last_failure_time: None,
+                half_open_in_flight: 0,
+            }),
+            sliding_window,
         }
     }
 
     pub async fn get_state(&self) -> CircuitState {
-        *self.state.read().await
+        self.inner.lock().unwrap().state
     }
 
     pub async fn call<F, Fut, T>(&self, operation: F) -> Result<T>
@@ -63,23 +200,8 @@ impl CircuitBreaker {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T>>,
     {
-        // Check if we should attempt the operation
-        let current_state = self.get_state().await;
-
-        if current_state == CircuitState::Open {
-            // Check if timeout has elapsed
-            if let Some(last_failure) = *self.last_failure_time.read().await {
-                if last_failure.elapsed() >= self.config.timeout {
-                    // Transition to half-open
-                    *self.state.write().await = CircuitState::HalfOpen;
-                    self.success_count.store(0, Ordering::SeqCst);
-                } else {
-                    return Err(Error::Handler("Circuit breaker is OPEN".to_string()));
-                }
-            }
-        }
+        self.admit()?;
 
-        // Attempt the operation
         match operation().await {
             Ok(result) => {
                 self.on_success().await;
@@ -92,52 +214,152 @@ impl CircuitBreaker {
         }
     }
 
-    async fn on_success(&self) {
-        let state = self.get_state().await;
-
-        match state {
+    /// Atomically check the circuit state and, if necessary, transition
+    /// `Open` -> `HalfOpen` or admit a bounded `HalfOpen` probe, all under a
+    /// single lock so concurrent callers can't race each other into the same
+    /// decision twice.
+    fn admit(&self) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+
+        match inner.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => {
+                let elapsed = inner
+                    .last_failure_time
+                    .map(|t| t.elapsed() >= self.config.timeout)
+                    .unwrap_or(false);
+                if elapsed {
+                    inner.state = CircuitState::HalfOpen;
+                    inner.success_count = 0;
+                    inner.half_open_in_flight = 1;
+                    Ok(())
+                } else {
+                    Err(Error::Handler("Circuit breaker is OPEN".to_string()))
+                }
+            }
             CircuitState::HalfOpen => {
-                let successes = self.success_count.fetch_add(1, Ordering::SeqCst) + 1;
-                if successes >= self.config.success_threshold {
-                    *self.state.write().await = CircuitState::Closed;
-                    self.failure_count.store(0, Ordering::SeqCst);
-                    self.success_count.store(0, Ordering::SeqCst);
+                if inner.half_open_in_flight < self.config.half_open_max_concurrent {
+                    inner.half_open_in_flight += 1;
+                    Ok(())
+                } else {
+                    Err(Error::Handler(
+                        "Circuit breaker is HALF_OPEN (probing)".to_string(),
+                    ))
                 }
             }
-            CircuitState::Closed => {
-                // Reset failure count on success
-                self.failure_count.store(0, Ordering::SeqCst);
+        }
+    }
+
+    async fn on_success(&self) {
+        let state = {
+            let mut inner = self.inner.lock().unwrap();
+            match inner.state {
+                CircuitState::HalfOpen => {
+                    inner.half_open_in_flight = inner.half_open_in_flight.saturating_sub(1);
+                    inner.success_count += 1;
+                    if inner.success_count >= self.config.success_threshold {
+                        inner.state = CircuitState::Closed;
+                        inner.failure_count = 0;
+                        inner.success_count = 0;
+                        inner.half_open_in_flight = 0;
+                    }
+                    None
+                }
+                CircuitState::Closed => {
+                    if matches!(self.config.failure_detection, FailureDetectionMode::Consecutive) {
+                        inner.failure_count = 0;
+                        None
+                    } else {
+                        Some(CircuitState::Closed)
+                    }
+                }
+                _ => None,
             }
-            _ => {}
+        };
+
+        if state == Some(CircuitState::Closed) {
+            if let Some(window) = &self.sliding_window {
+                window.record(true);
+            }
+            self.maybe_trip_from_window().await;
         }
     }
 
     async fn on_failure(&self) {
-        let state = self.get_state().await;
-
-        match state {
-            CircuitState::Closed => {
-                let failures = self.failure_count.fetch_add(1, Ordering::SeqCst) + 1;
-                if failures >= self.config.failure_threshold {
-                    *self.state.write().await = CircuitState::Open;
-                    *self.last_failure_time.write().await = Some(Instant::now());
+        let state = {
+            let mut inner = self.inner.lock().unwrap();
+            match inner.state {
+                CircuitState::Closed => {
+                    if matches!(self.config.failure_detection, FailureDetectionMode::Consecutive) {
+                        inner.failure_count += 1;
+                        if inner.failure_count >= self.config.failure_threshold {
+                            inner.state = CircuitState::Open;
+                            inner.last_failure_time = Some(Instant::now());
+                        }
+                        None
+                    } else {
+                        Some(CircuitState::Closed)
+                    }
+                }
+                CircuitState::HalfOpen => {
+                    // Any failure in half-open state immediately opens circuit
+                    inner.state = CircuitState::Open;
+                    inner.last_failure_time = Some(Instant::now());
+                    inner.failure_count = self.config.failure_threshold;
+                    inner.half_open_in_flight = 0;
+                    None
                 }
+                _ => None,
             }
-            CircuitState::HalfOpen => {
-                // Any failure in half-open state immediately opens circuit
-                *self.state.write().await = CircuitState::Open;
-                *self.last_failure_time.write().await = Some(Instant::now());
-                self.failure_count
-                    .store(self.config.failure_threshold, Ordering::SeqCst);
+        };
+
+        if state == Some(CircuitState::Closed) {
+            if let Some(window) = &self.sliding_window {
+                window.record(false);
+            }
+            self.maybe_trip_from_window().await;
+        }
+    }
+
+    fn trip(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.state = CircuitState::Open;
+        inner.last_failure_time = Some(Instant::now());
+    }
+
+    /// In `SlidingWindow` mode, check the rolling error rate and trip the
+    /// circuit if it's breached `min_requests`/`error_rate_threshold`.
+    async fn maybe_trip_from_window(&self) {
+        let FailureDetectionMode::SlidingWindow {
+            min_requests,
+            error_rate_threshold,
+            ..
+        } = &self.config.failure_detection
+        else {
+            return;
+        };
+        let Some(window) = &self.sliding_window else {
+            return;
+        };
+
+        let (successes, failures) = window.totals();
+        let total = successes + failures;
+        if total >= *min_requests {
+            let error_rate = failures as f64 / total as f64;
+            if error_rate >= *error_rate_threshold {
+                self.trip();
             }
-            _ => {}
         }
     }
 
     pub fn get_stats(&self) -> CircuitBreakerStats {
+        let inner = self.inner.lock().unwrap();
+        let window_totals = self.sliding_window.as_ref().map(|w| w.totals());
         CircuitBreakerStats {
-            failure_count: self.failure_count.load(Ordering::SeqCst),
-            success_count: self.success_count.load(Ordering::SeqCst),
+            failure_count: inner.failure_count,
+            success_count: inner.success_count,
+            window_requests: window_totals.map(|(s, f)| s + f),
+            window_failures: window_totals.map(|(_, f)| f),
         }
     }
 }
@@ -146,6 +368,12 @@ impl CircuitBreaker {
 pub struct CircuitBreakerStats {
     pub failure_count: usize,
     pub success_count: usize,
+    /// Total calls within the current rolling window, when running in
+    /// `FailureDetectionMode::SlidingWindow`; `None` in `Consecutive` mode.
+    pub window_requests: Option<usize>,
+    /// Failures within the current rolling window, when running in
+    /// `FailureDetectionMode::SlidingWindow`; `None` in `Consecutive` mode.
+    pub window_failures: Option<usize>,
 }
 
 /// Fallback handler for error recovery
@@ -175,26 +403,32 @@ where
     }
 }
 
-/// Error tracking for monitoring and debugging
+/// Error tracking for monitoring and debugging, partitioned per tool so one
+/// noisy tool's errors don't drown out visibility into the rest.
 pub struct ErrorTracker {
     total_errors: Arc<AtomicU64>,
-    errors_by_type: Arc<RwLock<std::collections::HashMap<String, u64>>>,
+    errors_by_tool_and_type:
+        Arc<RwLock<std::collections::HashMap<String, std::collections::HashMap<String, u64>>>>,
 }
 
 impl ErrorTracker {
     pub fn new() -> Self {
         Self {
             total_errors: Arc::new(AtomicU64::new(0)),
-            errors_by_type: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            errors_by_tool_and_type: Arc::new(RwLock::new(std::collections::HashMap::new())),
         }
     }
 
-    pub async fn track_error(&self, error: &Error) {
+    pub async fn track_error(&self, tool: &str, error: &Error) {
         self.total_errors.fetch_add(1, Ordering::SeqCst);
 
         let error_type = self.classify_error(error);
-        let mut errors = self.errors_by_type.write().await;
-        *errors.entry(error_type).or_insert(0) += 1;
+        let mut by_tool = self.errors_by_tool_and_type.write().await;
+        *by_tool
+            .entry(tool.to_string())
+            .or_default()
+            .entry(error_type)
+            .or_insert(0) += 1;
     }
 
     fn classify_error(&self, error: &Error) -> String {
@@ -216,13 +450,15 @@ impl ErrorTracker {
         self.total_errors.load(Ordering::SeqCst)
     }
 
-    pub async fn errors_by_type(&self) -> std::collections::HashMap<String, u64> {
-        self.errors_by_type.read().await.clone()
+    pub async fn errors_by_tool_and_type(
+        &self,
+    ) -> std::collections::HashMap<String, std::collections::HashMap<String, u64>> {
+        self.errors_by_tool_and_type.read().await.clone()
     }
 
     pub async fn reset(&self) {
         self.total_errors.store(0, Ordering::SeqCst);
-        self.errors_by_type.write().await.clear();
+        self.errors_by_tool_and_type.write().await.clear();
     }
 }
 
@@ -232,28 +468,203 @@ impl Default for ErrorTracker {
     }
 }
 
-/// Recovery middleware - integrates circuit breaker and fallback
+/// Tool key for requests with no `"tool"` field, so untagged callers still
+/// get a working (shared) breaker and error bucket instead of being
+/// rejected outright.
+const UNSCOPED_TOOL: &str = "_unscoped";
+
+fn tool_key(request: &Value) -> &str {
+    request
+        .get("tool")
+        .and_then(Value::as_str)
+        .unwrap_or(UNSCOPED_TOOL)
+}
+
+/// Recovery middleware - integrates circuit breaking, error tracking, and
+/// fallback.
+///
+/// Breakers and error counts are keyed per tool name (read from a `"tool"`
+/// field on the request `Value`, when present) rather than shared globally.
+/// This is bulkhead isolation: one misbehaving tool trips and sheds load on
+/// itself without tripping the breaker for every other tool on the server,
+/// and operators get per-tool failure visibility instead of one blended
+/// count.
 pub struct RecoveryMiddleware {
-    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    circuit_breaker_config: Option<CircuitBreakerConfig>,
+    circuit_breakers: dashmap::DashMap<String, Arc<CircuitBreaker>>,
     error_tracker: Arc<ErrorTracker>,
 }
 
 impl RecoveryMiddleware {
     pub fn new() -> Self {
         Self {
-            circuit_breaker: None,
+            circuit_breaker_config: None,
+            circuit_breakers: dashmap::DashMap::new(),
             error_tracker: Arc::new(ErrorTracker::new()),
         }
     }
 
+    /// Configure the circuit breaker every tool gets. Breakers are created
+    /// lazily per tool, on first use, from a clone of this config - not
+    /// eagerly for every tool name up front.
     pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
-        self.circuit_breaker = Some(Arc::new(CircuitBreaker::new(config)));
+        self.circuit_breaker_config = Some(config);
         self
     }
 
     pub fn error_tracker(&self) -> Arc<ErrorTracker> {
         self.error_tracker.clone()
     }
+
+    fn breaker_for(&self, tool: &str) -> Option<Arc<CircuitBreaker>> {
+        let config = self.circuit_breaker_config.clone()?;
+        Some(
+            self.circuit_breakers
+                .entry(tool.to_string())
+                .or_insert_with(|| Arc::new(CircuitBreaker::new(config)))
+                .clone(),
+        )
+    }
+
+    /// Render `error_tracker` and every per-tool circuit breaker as
+    /// Prometheus text, following the same hand-rolled `# HELP`/`# TYPE`
+    /// convention as [`crate::telemetry::MetricsCollector::export_prometheus`].
+    /// Values are computed fresh from the live `ErrorTracker`/`CircuitBreaker`
+    /// state on every call rather than a separately maintained counter set -
+    /// `before`/`after`/`on_error` already keep that state current, so a
+    /// scrape just renders what's already there.
+    pub async fn export_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP pforge_recovery_errors_total Total errors tracked across all tools\n");
+        out.push_str("# TYPE pforge_recovery_errors_total counter\n");
+        out.push_str(&format!(
+            "pforge_recovery_errors_total {}\n",
+            self.error_tracker.total_errors()
+        ));
+
+        out.push_str(
+            "# HELP pforge_recovery_errors_by_type_total Errors tracked per tool and error type\n",
+        );
+        out.push_str("# TYPE pforge_recovery_errors_by_type_total counter\n");
+        for (tool, by_type) in self.error_tracker.errors_by_tool_and_type().await {
+            for (error_type, count) in by_type {
+                out.push_str(&format!(
+                    "pforge_recovery_errors_by_type_total{{tool=\"{}\",error_type=\"{}\"}} {}\n",
+                    tool, error_type, count
+                ));
+            }
+        }
+
+        let breakers: Vec<(String, Arc<CircuitBreaker>)> = self
+            .circuit_breakers
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+
+        out.push_str(
+            "# HELP pforge_circuit_breaker_state Circuit breaker state (0=closed, 1=open, 2=half_open)\n",
+        );
+        out.push_str("# TYPE pforge_circuit_breaker_state gauge\n");
+        for (tool, cb) in &breakers {
+            out.push_str(&format!(
+                "pforge_circuit_breaker_state{{tool=\"{}\"}} {}\n",
+                tool,
+                cb.get_state().await.as_metric_code()
+            ));
+        }
+
+        out.push_str(
+            "# HELP pforge_circuit_breaker_success_count Successes counted toward closing a half-open circuit\n",
+        );
+        out.push_str("# TYPE pforge_circuit_breaker_success_count gauge\n");
+        for (tool, cb) in &breakers {
+            out.push_str(&format!(
+                "pforge_circuit_breaker_success_count{{tool=\"{}\"}} {}\n",
+                tool,
+                cb.get_stats().success_count
+            ));
+        }
+
+        out.push_str(
+            "# HELP pforge_circuit_breaker_failure_count Failures counted toward the current trip/probe decision\n",
+        );
+        out.push_str("# TYPE pforge_circuit_breaker_failure_count gauge\n");
+        for (tool, cb) in &breakers {
+            out.push_str(&format!(
+                "pforge_circuit_breaker_failure_count{{tool=\"{}\"}} {}\n",
+                tool,
+                cb.get_stats().failure_count
+            ));
+        }
+
+        out.push_str(
+            "# HELP pforge_circuit_breaker_error_rate Rolling error rate within the current sliding window (sliding-window mode only)\n",
+        );
+        out.push_str("# TYPE pforge_circuit_breaker_error_rate gauge\n");
+        for (tool, cb) in &breakers {
+            let stats = cb.get_stats();
+            if let (Some(requests), Some(failures)) = (stats.window_requests, stats.window_failures) {
+                if requests > 0 {
+                    out.push_str(&format!(
+                        "pforge_circuit_breaker_error_rate{{tool=\"{}\"}} {}\n",
+                        tool,
+                        failures as f64 / requests as f64
+                    ));
+                }
+            }
+        }
+
+        #[cfg(feature = "runtime-metrics")]
+        {
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                out.push_str("# HELP pforge_tokio_worker_threads Tokio runtime worker thread count\n");
+                out.push_str("# TYPE pforge_tokio_worker_threads gauge\n");
+                out.push_str(&format!(
+                    "pforge_tokio_worker_threads {}\n",
+                    handle.metrics().num_workers()
+                ));
+            }
+        }
+
+        out
+    }
+
+    /// JSON-shaped counterpart to [`Self::export_prometheus`], used by
+    /// [`crate::telemetry::AdminMetrics::export_json`] to fold circuit
+    /// breaker and error-tracker state into the combined admin snapshot.
+    pub async fn export_json(&self) -> Value {
+        let by_tool = self.error_tracker.errors_by_tool_and_type().await;
+
+        let breakers: Vec<(String, Arc<CircuitBreaker>)> = self
+            .circuit_breakers
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+
+        let mut circuit_breakers = serde_json::Map::new();
+        for (tool, cb) in &breakers {
+            let state = cb.get_state().await;
+            let stats = cb.get_stats();
+            circuit_breakers.insert(
+                tool.clone(),
+                serde_json::json!({
+                    "state": format!("{:?}", state),
+                    "state_code": state.as_metric_code(),
+                    "failure_count": stats.failure_count,
+                    "success_count": stats.success_count,
+                    "window_requests": stats.window_requests,
+                    "window_failures": stats.window_failures,
+                }),
+            );
+        }
+
+        serde_json::json!({
+            "total_errors": self.error_tracker.total_errors(),
+            "errors_by_tool_and_type": by_tool,
+            "circuit_breakers": circuit_breakers,
+        })
+    }
 }
 
 impl Default for RecoveryMiddleware {
@@ -264,34 +675,38 @@ impl Default for RecoveryMiddleware {
 
 #[async_trait::async_trait]
 impl Middleware for RecoveryMiddleware {
-    async fn before(&self, request: Value) -> Result<Value> {
+    async fn before(&self, request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        let tool = tool_key(&request);
         // Check circuit breaker before processing
-        if let Some(cb) = &self.circuit_breaker {
+        if let Some(cb) = self.breaker_for(tool) {
             let state = cb.get_state().await;
             if state == CircuitState::Open {
-                return Err(Error::Handler(
-                    "Circuit breaker is OPEN - service unavailable".to_string(),
-                ));
+                return Err(Error::Handler(format!(
+                    "Circuit breaker is OPEN for tool '{}' - service unavailable",
+                    tool
+                )));
             }
         }
-        Ok(request)
+        Ok(BeforeOutcome::Continue(request))
     }
 
-    async fn on_error(&self, _request: Value, error: Error) -> Result<Value> {
-        // Track the error
-        self.error_tracker.track_error(&error).await;
+    async fn on_error(&self, request: Value, error: Error, _extensions: &Extensions) -> Result<Value> {
+        let tool = tool_key(&request);
 
-        // Record failure in circuit breaker
-        if let Some(cb) = &self.circuit_breaker {
+        // Track the error, scoped to this tool
+        self.error_tracker.track_error(tool, &error).await;
+
+        // Record failure in this tool's circuit breaker
+        if let Some(cb) = self.breaker_for(tool) {
             cb.on_failure().await;
         }
 
         Err(error)
     }
 
-    async fn after(&self, _request: Value, response: Value) -> Result<Value> {
-        // Record success in circuit breaker
-        if let Some(cb) = &self.circuit_breaker {
+    async fn after(&self, request: Value, response: Value, _extensions: &Extensions) -> Result<Value> {
+        // Record success in this tool's circuit breaker
+        if let Some(cb) = self.breaker_for(tool_key(&request)) {
             cb.on_success().await;
         }
 
@@ -309,6 +724,8 @@ mod tests {
             failure_threshold: 3,
             timeout: Duration::from_secs(1),
             success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
         };
 
         let cb = CircuitBreaker::new(config);
@@ -332,6 +749,8 @@ mod tests {
             failure_threshold: 2,
             timeout: Duration::from_millis(100),
             success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
         };
 
         let cb = CircuitBreaker::new(config);
@@ -363,6 +782,8 @@ mod tests {
             failure_threshold: 1,
             timeout: Duration::from_secs(60),
             success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
         };
 
         let cb = CircuitBreaker::new(config);
@@ -385,28 +806,50 @@ mod tests {
     async fn test_error_tracker() {
         let tracker = ErrorTracker::new();
 
-        // Track different errors
+        // Track different errors, all for the same tool
         tracker
-            .track_error(&Error::Handler("timeout error".to_string()))
+            .track_error("alpha", &Error::Handler("timeout error".to_string()))
             .await;
         tracker
-            .track_error(&Error::Handler("timeout error".to_string()))
+            .track_error("alpha", &Error::Handler("timeout error".to_string()))
             .await;
         tracker
-            .track_error(&Error::Handler("connection error".to_string()))
+            .track_error("alpha", &Error::Handler("connection error".to_string()))
             .await;
         tracker
-            .track_error(&Error::Handler("other error".to_string()))
+            .track_error("alpha", &Error::Handler("other error".to_string()))
             .await;
 
         assert_eq!(tracker.total_errors(), 4);
 
-        let by_type = tracker.errors_by_type().await;
+        let by_tool = tracker.errors_by_tool_and_type().await;
+        let by_type = &by_tool["alpha"];
         assert_eq!(by_type.get("timeout"), Some(&2));
         assert_eq!(by_type.get("connection"), Some(&1));
         assert_eq!(by_type.get("handler_error"), Some(&1));
     }
 
+    #[tokio::test]
+    async fn test_error_tracker_partitions_by_tool() {
+        let tracker = ErrorTracker::new();
+
+        tracker
+            .track_error("alpha", &Error::Handler("timeout error".to_string()))
+            .await;
+        tracker
+            .track_error("beta", &Error::Handler("timeout error".to_string()))
+            .await;
+        tracker
+            .track_error("beta", &Error::Handler("timeout error".to_string()))
+            .await;
+
+        assert_eq!(tracker.total_errors(), 3);
+
+        let by_tool = tracker.errors_by_tool_and_type().await;
+        assert_eq!(by_tool["alpha"].get("timeout"), Some(&1));
+        assert_eq!(by_tool["beta"].get("timeout"), Some(&2));
+    }
+
     #[tokio::test]
     async fn test_fallback_handler() {
         let fallback = FallbackHandler::new(|error: Error| async move {
@@ -429,16 +872,21 @@ mod tests {
             failure_threshold: 2,
             timeout: Duration::from_secs(60),
             success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
         };
 
         let middleware = RecoveryMiddleware::new().with_circuit_breaker(config);
         let tracker = middleware.error_tracker();
 
+        let extensions = Extensions::new();
+
         // Simulate failures
         let _ = middleware
             .on_error(
                 serde_json::json!({}),
                 Error::Handler("test error".to_string()),
+                &extensions,
             )
             .await;
 
@@ -446,6 +894,7 @@ mod tests {
             .on_error(
                 serde_json::json!({}),
                 Error::Handler("test error".to_string()),
+                &extensions,
             )
             .await;
 
@@ -453,7 +902,253 @@ mod tests {
         assert_eq!(tracker.total_errors(), 2);
 
         // Circuit should be open, before hook should fail
-        let result = middleware.before(serde_json::json!({})).await;
+        let mut extensions = Extensions::new();
+        let result = middleware.before(serde_json::json!({}), &mut extensions).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_recovery_middleware_bulkhead_isolates_tools() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
+        };
+
+        let middleware = RecoveryMiddleware::new().with_circuit_breaker(config);
+        let tracker = middleware.error_tracker();
+
+        // Trip the breaker for "flaky" only.
+        let extensions = Extensions::new();
+        for _ in 0..2 {
+            let _ = middleware
+                .on_error(
+                    serde_json::json!({"tool": "flaky"}),
+                    Error::Handler("boom".to_string()),
+                    &extensions,
+                )
+                .await;
+        }
+
+        // "flaky" is rejected...
+        let mut flaky_extensions = Extensions::new();
+        let flaky_result = middleware
+            .before(serde_json::json!({"tool": "flaky"}), &mut flaky_extensions)
+            .await;
+        assert!(flaky_result.is_err());
+
+        // ...but an unrelated tool is unaffected, since each tool gets its
+        // own breaker rather than sharing one global one.
+        let mut healthy_extensions = Extensions::new();
+        let healthy_result = middleware
+            .before(serde_json::json!({"tool": "healthy"}), &mut healthy_extensions)
+            .await;
+        assert!(healthy_result.is_ok());
+
+        let by_tool = tracker.errors_by_tool_and_type().await;
+        assert_eq!(by_tool["flaky"].get("handler_error"), Some(&2));
+        assert!(!by_tool.contains_key("healthy"));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_sliding_window_trips_on_sustained_error_rate() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 100, // unused in sliding-window mode
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::SlidingWindow {
+                window: Duration::from_secs(10),
+                num_buckets: 10,
+                min_requests: 4,
+                error_rate_threshold: 0.5,
+            },
+            half_open_max_concurrent: 1,
+        };
+
+        let cb = CircuitBreaker::new(config);
+
+        // Intermittent failures: a consecutive-count breaker would never
+        // trip here since no two failures are adjacent, but the error rate
+        // (2/4 = 0.5) meets the threshold.
+        let _ = cb.call(|| async { Ok::<_, Error>(1) }).await;
+        let _ = cb
+            .call(|| async { Err::<(), _>(Error::Handler("boom".to_string())) })
+            .await;
+        let _ = cb.call(|| async { Ok::<_, Error>(1) }).await;
+        let _ = cb
+            .call(|| async { Err::<(), _>(Error::Handler("boom".to_string())) })
+            .await;
+
+        assert_eq!(cb.get_state().await, CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_sliding_window_stays_closed_below_min_requests() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 100,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::SlidingWindow {
+                window: Duration::from_secs(10),
+                num_buckets: 10,
+                min_requests: 10,
+                error_rate_threshold: 0.5,
+            },
+            half_open_max_concurrent: 1,
+        };
+
+        let cb = CircuitBreaker::new(config);
+
+        // All failures, but fewer than `min_requests` total calls so far.
+        for _ in 0..3 {
+            let _ = cb
+                .call(|| async { Err::<(), _>(Error::Handler("boom".to_string())) })
+                .await;
+        }
+
+        assert_eq!(cb.get_state().await, CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_bounds_concurrent_half_open_probes() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            timeout: Duration::from_millis(50),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
+        };
+
+        let cb = CircuitBreaker::new(config);
+
+        // Open the circuit.
+        let _ = cb
+            .call(|| async { Err::<(), _>(Error::Handler("test error".to_string())) })
+            .await;
+        assert_eq!(cb.get_state().await, CircuitState::Open);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // First admit() transitions Open -> HalfOpen and takes the one
+        // available probe slot; it's released only once the operation
+        // future completes, so a second admit() while it's still pending
+        // must be rejected rather than piling onto the same recovering
+        // backend.
+        assert!(cb.admit().is_ok());
+        assert_eq!(cb.get_state().await, CircuitState::HalfOpen);
+
+        let second = cb.admit();
+        assert!(second.is_err());
+        assert!(second
+            .unwrap_err()
+            .to_string()
+            .contains("Circuit breaker is HALF_OPEN (probing)"));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_sliding_window_stats_report_window_totals() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 100,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::SlidingWindow {
+                window: Duration::from_secs(10),
+                num_buckets: 10,
+                min_requests: 100,
+                error_rate_threshold: 0.9,
+            },
+            half_open_max_concurrent: 1,
+        };
+
+        let cb = CircuitBreaker::new(config);
+        let _ = cb.call(|| async { Ok::<_, Error>(1) }).await;
+        let _ = cb
+            .call(|| async { Err::<(), _>(Error::Handler("boom".to_string())) })
+            .await;
+
+        let stats = cb.get_stats();
+        assert_eq!(stats.window_requests, Some(2));
+        assert_eq!(stats.window_failures, Some(1));
+    }
+
+    #[test]
+    fn test_circuit_state_metric_codes() {
+        assert_eq!(CircuitState::Closed.as_metric_code(), 0);
+        assert_eq!(CircuitState::Open.as_metric_code(), 1);
+        assert_eq!(CircuitState::HalfOpen.as_metric_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_export_prometheus_includes_errors_and_circuit_state() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
+        };
+
+        let middleware = RecoveryMiddleware::new().with_circuit_breaker(config);
+
+        let extensions = Extensions::new();
+        let _ = middleware
+            .on_error(
+                serde_json::json!({"tool": "flaky"}),
+                Error::Handler("timeout error".to_string()),
+                &extensions,
+            )
+            .await;
+
+        let text = middleware.export_prometheus().await;
+
+        assert!(text.contains("# TYPE pforge_recovery_errors_total counter"));
+        assert!(text.contains("pforge_recovery_errors_total 1"));
+        assert!(text.contains(
+            "pforge_recovery_errors_by_type_total{tool=\"flaky\",error_type=\"timeout\"} 1"
+        ));
+        assert!(text.contains("pforge_circuit_breaker_state{tool=\"flaky\"} 1"));
+        assert!(text.contains("pforge_circuit_breaker_failure_count{tool=\"flaky\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_export_prometheus_reports_window_error_rate_in_sliding_window_mode() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 100,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::SlidingWindow {
+                window: Duration::from_secs(10),
+                num_buckets: 10,
+                min_requests: 100,
+                error_rate_threshold: 0.9,
+            },
+            half_open_max_concurrent: 1,
+        };
+
+        let middleware = RecoveryMiddleware::new().with_circuit_breaker(config);
+
+        let mut extensions = Extensions::new();
+        let _ = middleware
+            .before(serde_json::json!({"tool": "noisy"}), &mut extensions)
+            .await;
+        let _ = middleware
+            .after(
+                serde_json::json!({"tool": "noisy"}),
+                serde_json::json!({}),
+                &extensions,
+            )
+            .await;
+        let _ = middleware
+            .on_error(
+                serde_json::json!({"tool": "noisy"}),
+                Error::Handler("boom".to_string()),
+                &extensions,
+            )
+            .await;
+
+        let text = middleware.export_prometheus().await;
+        assert!(text.contains("pforge_circuit_breaker_error_rate{tool=\"noisy\"} 0.5"));
+    }
 }
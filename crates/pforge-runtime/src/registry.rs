@@ -1,10 +1,11 @@
-use crate::{Error, Handler, Result};
+use crate::dispatch_middleware::{DispatchMiddleware, Next};
+use crate::{Error, Handler, Result, WireFormat};
 use rustc_hash::FxHashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
-type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+pub(crate) type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 /// Zero-overhead handler registry with O(1) average-case lookup.
 ///
@@ -58,12 +59,23 @@ type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 /// ```
 pub struct HandlerRegistry {
     handlers: FxHashMap<String, Arc<dyn HandlerEntry>>,
+    middlewares: Vec<Arc<dyn DispatchMiddleware>>,
 }
 
 trait HandlerEntry: Send + Sync {
     /// Direct dispatch without dynamic allocation
     fn dispatch(&self, params: &[u8]) -> BoxFuture<'static, Result<Vec<u8>>>;
 
+    /// Dispatch using a negotiated, non-default wire format. JSON still goes
+    /// through [`HandlerEntry::dispatch`] and its schema-coercing decode
+    /// path; the other formats are strongly typed on the wire already, so
+    /// there's nothing to coerce.
+    fn dispatch_with_format(
+        &self,
+        params: &[u8],
+        format: WireFormat,
+    ) -> BoxFuture<'static, Result<Vec<u8>>>;
+
     /// Get schema metadata
     fn input_schema(&self) -> schemars::schema::RootSchema;
     fn output_schema(&self) -> schemars::schema::RootSchema;
@@ -71,12 +83,61 @@ trait HandlerEntry: Send + Sync {
 
 struct HandlerEntryImpl<H: Handler> {
     handler: Arc<H>,
+    /// The tool's declared `params:` schema from its YAML config, if any.
+    /// When present, each field's `coerce_param` conversion runs before the
+    /// generic schema-based coercion, giving the config-driven `Conversion`
+    /// types (e.g. `Timestamp`) a chance that schemars-based coercion can't
+    /// express on its own.
+    params: Option<pforge_config::ParamSchema>,
 }
 
 impl<H: Handler> HandlerEntryImpl<H> {
     fn new(handler: H) -> Self {
         Self {
             handler: Arc::new(handler),
+            params: None,
+        }
+    }
+
+    fn with_params(handler: H, params: pforge_config::ParamSchema) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            params: Some(params),
+        }
+    }
+}
+
+impl<H: Handler> HandlerEntryImpl<H> {
+    /// Parse `params` as JSON, run the declared `params:` conversions (if
+    /// any) field by field, coerce remaining primitive mismatches against
+    /// the handler's declared input schema, then deserialize into `H::Input`.
+    fn decode_input(&self, params: &[u8]) -> Result<H::Input> {
+        let mut value: serde_json::Value = serde_json::from_slice(params)?;
+
+        if let Some(param_schema) = &self.params {
+            if let Some(obj) = value.as_object_mut() {
+                for (field, param_type) in &param_schema.fields {
+                    if let Some(raw) = obj.get(field) {
+                        let coerced = crate::conversion::coerce_param(field, param_type, raw)?;
+                        obj.insert(field.clone(), coerced);
+                    }
+                }
+            }
+        }
+
+        let schema = H::input_schema();
+        crate::coerce::coerce_to_schema(&mut value, &schema);
+
+        match serde_json::from_value(value.clone()) {
+            Ok(input) => Ok(input),
+            Err(e) => {
+                let diagnostics = crate::diagnostics::diagnose(&value, &schema);
+                if diagnostics.is_empty() {
+                    Err(e.into())
+                } else {
+                    Err(Error::Validation(diagnostics))
+                }
+            }
         }
     }
 }
@@ -88,9 +149,9 @@ where
     H::Output: 'static,
 {
     fn dispatch(&self, params: &[u8]) -> BoxFuture<'static, Result<Vec<u8>>> {
-        let input: H::Input = match serde_json::from_slice(params) {
+        let input: H::Input = match self.decode_input(params) {
             Ok(input) => input,
-            Err(e) => return Box::pin(async move { Err(e.into()) }),
+            Err(e) => return Box::pin(async move { Err(e) }),
         };
 
         let handler = self.handler.clone();
@@ -100,6 +161,27 @@ where
         })
     }
 
+    fn dispatch_with_format(
+        &self,
+        params: &[u8],
+        format: WireFormat,
+    ) -> BoxFuture<'static, Result<Vec<u8>>> {
+        if format == WireFormat::Json {
+            return self.dispatch(params);
+        }
+
+        let input: H::Input = match format.decode(params) {
+            Ok(input) => input,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+
+        let handler = self.handler.clone();
+        Box::pin(async move {
+            let output = handler.handle(input).await.map_err(Into::into)?;
+            format.encode(&output)
+        })
+    }
+
     fn input_schema(&self) -> schemars::schema::RootSchema {
         H::input_schema()
     }
@@ -114,9 +196,15 @@ impl HandlerRegistry {
     pub fn new() -> Self {
         Self {
             handlers: FxHashMap::default(),
+            middlewares: Vec::new(),
         }
     }
 
+    /// Register dispatch middleware, appended to the end of the chain.
+    pub fn register_middleware(&mut self, middleware: Arc<dyn DispatchMiddleware>) {
+        self.middlewares.push(middleware);
+    }
+
     /// Register a handler with a name
     pub fn register<H>(&mut self, name: impl Into<String>, handler: H)
     where
@@ -128,17 +216,74 @@ impl HandlerRegistry {
         self.handlers.insert(name.into(), Arc::new(entry));
     }
 
+    /// Register a handler alongside its tool's declared `params:` schema, so
+    /// each field's `coerce` conversion (see [`crate::conversion::Conversion`])
+    /// runs on dispatch before the handler ever sees the value - the same
+    /// typed-params-from-YAML story [`crate::prompt::PromptManager`] already
+    /// gives prompt arguments, extended to tool dispatch.
+    pub fn register_with_params<H>(
+        &mut self,
+        name: impl Into<String>,
+        handler: H,
+        params: pforge_config::ParamSchema,
+    ) where
+        H: Handler,
+        H::Input: 'static,
+        H::Output: 'static,
+    {
+        let entry = HandlerEntryImpl::with_params(handler, params);
+        self.handlers.insert(name.into(), Arc::new(entry));
+    }
+
     /// Check if handler exists
     pub fn has_handler(&self, name: &str) -> bool {
         self.handlers.contains_key(name)
     }
 
-    /// Dispatch to a handler by name
+    /// Dispatch to a handler by name, running it through any registered
+    /// dispatch middleware. With no middleware registered this is the
+    /// original zero-overhead `<1μs` hot path.
     #[inline(always)]
     pub async fn dispatch(&self, tool: &str, params: &[u8]) -> Result<Vec<u8>> {
+        if self.middlewares.is_empty() {
+            return self.dispatch_raw(tool, params).await;
+        }
+
+        Next::new(&self.middlewares, self).call(tool, params).await
+    }
+
+    /// Dispatch directly to the resolved handler, bypassing middleware. This
+    /// is the terminal step [`Next`] calls once the chain is exhausted.
+    pub(crate) async fn dispatch_raw(&self, tool: &str, params: &[u8]) -> Result<Vec<u8>> {
         match self.handlers.get(tool) {
             Some(handler) => handler.dispatch(params).await,
-            None => Err(Error::ToolNotFound(tool.to_string())),
+            None => Err(self.tool_not_found(tool)),
+        }
+    }
+
+    /// Dispatch to a handler using a negotiated wire format instead of the
+    /// default JSON, e.g. when a transport or tool config picked MessagePack
+    /// for throughput. Bypasses dispatch middleware, same as `dispatch_raw`.
+    pub async fn dispatch_with_format(
+        &self,
+        tool: &str,
+        params: &[u8],
+        format: WireFormat,
+    ) -> Result<Vec<u8>> {
+        match self.handlers.get(tool) {
+            Some(handler) => handler.dispatch_with_format(params, format).await,
+            None => Err(self.tool_not_found(tool)),
+        }
+    }
+
+    /// Build the [`Error::ToolNotFound`] for a missing `tool`, appending a
+    /// "did you mean" suggestion (à la cargo's subcommand dispatcher) when a
+    /// registered name is close enough by Levenshtein distance to plausibly
+    /// be what the caller meant to type.
+    fn tool_not_found(&self, tool: &str) -> Error {
+        match closest_match(tool, self.handlers.keys().map(String::as_str)) {
+            Some(candidate) => Error::ToolNotFound(format!("{tool} (did you mean '{candidate}'?)")),
+            None => Error::ToolNotFound(tool.to_string()),
         }
     }
 
@@ -169,6 +314,42 @@ impl Default for HandlerRegistry {
     }
 }
 
+/// Find the registered name closest to `query` by Levenshtein distance,
+/// within a threshold generous enough to catch typos but not so generous
+/// that an unrelated name gets suggested.
+fn closest_match<'a>(query: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let threshold = (query.chars().count() / 2).max(2);
+
+    candidates
+        .map(|candidate| (levenshtein_distance(query, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Classic Wagner-Fischer edit distance between two strings, counted in
+/// chars rather than bytes so it behaves sensibly on non-ASCII tool names.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+    let mut curr_row = vec![0usize; b.len() + 1];
+
+    for (i, a_ch) in a.iter().enumerate() {
+        curr_row[0] = i + 1;
+        for (j, b_ch) in b.iter().enumerate() {
+            let substitution_cost = if a_ch == b_ch { 0 } else { 1 };
+            curr_row[j + 1] = (prev_row[j + 1] + 1)
+                .min(curr_row[j] + 1)
+                .min(prev_row[j] + substitution_cost);
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    prev_row[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,6 +439,20 @@ mod tests {
         assert!(matches!(result.unwrap_err(), crate::Error::ToolNotFound(_)));
     }
 
+    #[tokio::test]
+    async fn test_registry_dispatch_coerces_stringly_typed_fields() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("test", TestHandler);
+
+        // `value` declared as i32 but arrives as a JSON string.
+        let input_bytes = br#"{"value": "21"}"#;
+        let result = registry.dispatch("test", input_bytes).await;
+        assert!(result.is_ok());
+
+        let output: TestOutput = serde_json::from_slice(&result.unwrap()).unwrap();
+        assert_eq!(output.result, 42);
+    }
+
     #[tokio::test]
     async fn test_registry_dispatch_invalid_input() {
         let mut registry = HandlerRegistry::new();
@@ -268,6 +463,21 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_registry_dispatch_reports_structured_diagnostics() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("test", TestHandler);
+
+        // Missing the required `value` field entirely.
+        let result = registry.dispatch("test", b"{}").await;
+        match result.unwrap_err() {
+            crate::Error::Validation(diagnostics) => {
+                assert!(diagnostics.iter().any(|d| d.field == "value"));
+            }
+            other => panic!("expected Validation error, got {:?}", other),
+        }
+    }
+
     #[tokio::test]
     async fn test_registry_dispatch_handler_error() {
         let mut registry = HandlerRegistry::new();
@@ -351,4 +561,130 @@ mod tests {
             "Output schema should have object"
         );
     }
+
+    /// Every compiled-in wire format dispatches the same handler to the
+    /// same result, matching the per-format split used elsewhere (e.g.
+    /// `codec::tests`).
+    #[tokio::test]
+    async fn test_dispatch_with_format_conformance_matrix() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("test", TestHandler);
+
+        let formats = [
+            WireFormat::Json,
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack,
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor,
+            #[cfg(feature = "bincode")]
+            WireFormat::Bincode,
+            #[cfg(feature = "postcard")]
+            WireFormat::Postcard,
+        ];
+
+        for format in formats {
+            let params = format.encode(&TestInput { value: 21 }).unwrap();
+            let result_bytes = registry
+                .dispatch_with_format("test", &params, format)
+                .await
+                .unwrap();
+            let result: TestOutput = format.decode(&result_bytes).unwrap();
+            assert_eq!(result.result, 42, "format {:?} failed to dispatch", format);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_with_format_unknown_tool() {
+        let registry = HandlerRegistry::new();
+        let result = registry
+            .dispatch_with_format("missing", b"{}", WireFormat::Json)
+            .await;
+        assert!(matches!(result, Err(Error::ToolNotFound(_))));
+    }
+
+    fn int_params() -> pforge_config::ParamSchema {
+        let mut fields = std::collections::HashMap::new();
+        fields.insert(
+            "value".to_string(),
+            pforge_config::ParamType::Complex {
+                ty: pforge_config::SimpleType::Integer,
+                required: true,
+                default: None,
+                description: None,
+                validation: None,
+                coerce: Some("int".to_string()),
+            },
+        );
+        pforge_config::ParamSchema { fields }
+    }
+
+    #[tokio::test]
+    async fn test_registry_register_with_params_coerces_declared_conversion() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_with_params("test", TestHandler, int_params());
+
+        // `value` declared i32 in the handler but a stringly-typed param
+        // schema conversion still coerces it before the generic schema pass.
+        let result = registry.dispatch("test", br#"{"value": "21"}"#).await;
+        assert!(result.is_ok());
+        let output: TestOutput = serde_json::from_slice(&result.unwrap()).unwrap();
+        assert_eq!(output.result, 42);
+    }
+
+    #[tokio::test]
+    async fn test_registry_register_with_params_reports_conversion_failure() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_with_params("test", TestHandler, int_params());
+
+        let result = registry.dispatch("test", br#"{"value": "not-a-number"}"#).await;
+        match result.unwrap_err() {
+            Error::Handler(msg) => assert!(msg.contains("value")),
+            other => panic!("expected Error::Handler, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_unknown_tool_suggests_close_match() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("greet", TestHandler);
+
+        let result = registry.dispatch("great", b"{}").await;
+        match result.unwrap_err() {
+            Error::ToolNotFound(msg) => {
+                assert!(msg.contains("great"));
+                assert!(msg.contains("did you mean 'greet'?"));
+            }
+            other => panic!("expected Error::ToolNotFound, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_unknown_tool_omits_suggestion_when_nothing_close() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("greet", TestHandler);
+
+        let result = registry.dispatch("totally_unrelated_name", b"{}").await;
+        match result.unwrap_err() {
+            Error::ToolNotFound(msg) => assert!(!msg.contains("did you mean")),
+            other => panic!("expected Error::ToolNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_levenshtein_distance_basic_cases() {
+        assert_eq!(levenshtein_distance("", ""), 0);
+        assert_eq!(levenshtein_distance("kitten", "kitten"), 0);
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("greet", "great"), 1);
+    }
+
+    #[test]
+    fn test_closest_match_picks_nearest_within_threshold() {
+        let candidates = ["build", "test", "bench"];
+        assert_eq!(
+            closest_match("buidl", candidates.into_iter()),
+            Some("build")
+        );
+        assert_eq!(closest_match("xyzzy", candidates.into_iter()), None);
+    }
 }
@@ -5,6 +5,8 @@ use pforge_config::{ResourceDef, ResourceOperation};
 use regex::Regex;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 
 /// Resource handler trait for read/write/subscribe operations
 #[async_trait::async_trait]
@@ -32,34 +34,132 @@ pub trait ResourceHandler: Send + Sync {
     }
 }
 
+/// The kind of filesystem/resource change a [`ResourceChangeEvent`] reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// A single resource change, as published through [`ResourceManager::publish`]
+/// and delivered to subscribers via [`ResourceSubscription`]/[`ResourceChangeStream`].
+#[derive(Debug, Clone)]
+pub struct ResourceChangeEvent {
+    pub uri: String,
+    pub kind: ChangeKind,
+}
+
+/// Build the MCP `notifications/resources/updated` payload for `event`, for
+/// the server's event loop to forward over its transport.
+pub fn resources_updated_notification(event: &ResourceChangeEvent) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/resources/updated",
+        "params": { "uri": event.uri },
+    })
+}
+
+/// A live subscription to changes on one URI, handed back by
+/// [`ResourceManager::subscribe`].
+pub struct ResourceSubscription {
+    uri: String,
+    inner: broadcast::Receiver<ResourceChangeEvent>,
+}
+
+impl ResourceSubscription {
+    /// Wait for the next change to this subscription's URI. Events for
+    /// other resources are skipped, and a lagged receiver (the channel
+    /// overflowed before we drained it) resumes at the next available event
+    /// rather than erroring.
+    pub async fn recv(&mut self) -> Option<ResourceChangeEvent> {
+        loop {
+            match self.inner.recv().await {
+                Ok(event) if event.uri == self.uri => return Some(event),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// A firehose of every resource change across the manager, for the server's
+/// main event loop to poll via `tokio::select!` alongside transport I/O.
+pub struct ResourceChangeStream {
+    inner: broadcast::Receiver<ResourceChangeEvent>,
+}
+
+impl ResourceChangeStream {
+    pub async fn recv(&mut self) -> Option<ResourceChangeEvent> {
+        loop {
+            match self.inner.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
 /// Resource manager handles URI matching and dispatch
 pub struct ResourceManager {
     resources: Vec<ResourceEntry>,
+    changes: broadcast::Sender<ResourceChangeEvent>,
 }
 
 struct ResourceEntry {
     uri_template: String,
     pattern: Regex,
-    param_names: Vec<String>,
+    vars: Vec<TemplateVar>,
     supports: Vec<ResourceOperation>,
     handler: Arc<dyn ResourceHandler>,
 }
 
+/// The RFC 6570 expansion operator a [`TemplateVar`] was declared with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateOperator {
+    /// `{var}` -- a plain path segment.
+    Simple,
+    /// `{+var}` -- reserved expansion; matches `/` and other reserved chars.
+    Reserved,
+    /// `{#var}` -- fragment expansion, prefixed with a literal `#`.
+    Fragment,
+    /// `{?a,b}` -- starts a query string: `?a=...&b=...`.
+    Query,
+    /// `{&a,b}` -- continues an existing query string: `&a=...&b=...`.
+    QueryContinuation,
+}
+
+impl TemplateOperator {
+    fn is_query_like(self) -> bool {
+        matches!(self, TemplateOperator::Query | TemplateOperator::QueryContinuation)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TemplateVar {
+    name: String,
+    operator: TemplateOperator,
+}
+
 impl ResourceManager {
     pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(256);
         Self {
             resources: Vec::new(),
+            changes,
         }
     }
 
     /// Register a resource with URI template matching
     pub fn register(&mut self, def: ResourceDef, handler: Arc<dyn ResourceHandler>) -> Result<()> {
-        let (pattern, param_names) = Self::compile_uri_template(&def.uri_template)?;
+        let (pattern, vars) = Self::compile_uri_template(&def.uri_template)?;
 
         self.resources.push(ResourceEntry {
             uri_template: def.uri_template,
             pattern,
-            param_names,
+            vars,
             supports: def.supports,
             handler,
         });
@@ -67,20 +167,57 @@ impl ResourceManager {
         Ok(())
     }
 
-    /// Match URI and extract parameters (internal use)
+    /// Reconstruct a concrete URI from a registered `uri_template` and
+    /// `params`, the inverse of the matching `match_uri` performs.
+    pub fn expand(&self, uri_template: &str, params: &HashMap<String, String>) -> Result<String> {
+        if !self.resources.iter().any(|e| e.uri_template == uri_template) {
+            return Err(Error::Handler(format!(
+                "No registered template: {}",
+                uri_template
+            )));
+        }
+
+        Ok(expand_template(uri_template, params))
+    }
+
+    /// Match URI and extract parameters (internal use). Path-level
+    /// variables (`{var}`, `{+var}`, `{#var}`) come from the compiled
+    /// regex; query-style variables (`{?a,b}`, `{&a,b}`) are parsed from
+    /// the URI's `?...` suffix instead.
     fn match_uri(&self, uri: &str) -> Option<(&ResourceEntry, HashMap<String, String>)> {
+        let (path, query) = split_uri_query(uri);
+
         for entry in &self.resources {
-            if let Some(captures) = entry.pattern.captures(uri) {
-                let mut params = HashMap::new();
+            let Some(captures) = entry.pattern.captures(path) else {
+                continue;
+            };
 
-                for (i, name) in entry.param_names.iter().enumerate() {
-                    if let Some(value) = captures.get(i + 1) {
-                        params.insert(name.clone(), value.as_str().to_string());
-                    }
+            let mut params = HashMap::new();
+            let mut capture_idx = 1;
+
+            for var in &entry.vars {
+                if var.operator.is_query_like() {
+                    continue;
                 }
+                if let Some(value) = captures.get(capture_idx) {
+                    params.insert(var.name.clone(), value.as_str().to_string());
+                }
+                capture_idx += 1;
+            }
 
-                return Some((entry, params));
+            if let Some(query) = query {
+                for (key, value) in parse_query_string(query) {
+                    let declared = entry
+                        .vars
+                        .iter()
+                        .any(|v| v.operator.is_query_like() && v.name == key);
+                    if declared {
+                        params.insert(key, value);
+                    }
+                }
             }
+
+            return Some((entry, params));
         }
 
         None
@@ -118,8 +255,11 @@ impl ResourceManager {
         entry.handler.write(uri, params, content).await
     }
 
-    /// Subscribe to resource changes
-    pub async fn subscribe(&self, uri: &str) -> Result<()> {
+    /// Subscribe to resource changes. Invokes the matched handler's
+    /// `subscribe` hook (so it can, e.g., arm an upstream watch) and returns
+    /// a [`ResourceSubscription`] that receives every subsequent
+    /// [`ResourceChangeEvent`] published for this URI.
+    pub async fn subscribe(&self, uri: &str) -> Result<ResourceSubscription> {
         let (entry, params) = self
             .match_uri(uri)
             .ok_or_else(|| Error::Handler(format!("No resource matches URI: {}", uri)))?;
@@ -131,44 +271,113 @@ impl ResourceManager {
             )));
         }
 
-        entry.handler.subscribe(uri, params).await
+        entry.handler.subscribe(uri, params).await?;
+
+        Ok(ResourceSubscription {
+            uri: uri.to_string(),
+            inner: self.changes.subscribe(),
+        })
     }
 
-    /// Compile URI template to regex pattern
-    /// Example: "file:///{path}" -> r"^file:///(.+)$" with param_names = ["path"]
-    /// Uses non-greedy matching to handle multiple parameters correctly
-    fn compile_uri_template(template: &str) -> Result<(Regex, Vec<String>)> {
+    /// Publish a change notification for `uri` to all current subscribers.
+    /// Returns normally even if nobody is listening yet -- that's a normal
+    /// state, not a failure.
+    pub fn publish(&self, uri: &str, kind: ChangeKind) {
+        let _ = self.changes.send(ResourceChangeEvent {
+            uri: uri.to_string(),
+            kind,
+        });
+    }
+
+    /// A stream of every change across all resources, independent of any
+    /// single [`ResourceManager::subscribe`] call.
+    pub fn change_stream(&self) -> ResourceChangeStream {
+        ResourceChangeStream {
+            inner: self.changes.subscribe(),
+        }
+    }
+
+    /// Compile an RFC 6570 (Level 2/3 subset) URI template to a regex
+    /// pattern plus its declared variables.
+    ///
+    /// Supports bare `{var}` (path segment or greedy-to-end), `{+var}`
+    /// (reserved expansion -- matches `/` and other reserved chars),
+    /// `{#var}` (fragment, prefixed with a literal `#`), `{?a,b}` (starts a
+    /// query string) and `{&a,b}` (continues one), and comma-separated
+    /// variable lists within one expression. Query-style variables (`?`/`&`)
+    /// contribute no capture group -- they're matched against the URI's
+    /// `?...` suffix by [`ResourceManager::match_uri`] instead.
+    fn compile_uri_template(template: &str) -> Result<(Regex, Vec<TemplateVar>)> {
         let mut pattern = String::from("^");
-        let mut param_names = Vec::new();
+        let mut vars = Vec::new();
         let mut chars = template.chars().peekable();
 
         while let Some(ch) = chars.next() {
             if ch == '{' {
-                // Extract parameter name
-                let mut param_name = String::new();
+                let mut expr = String::new();
                 while let Some(&next_ch) = chars.peek() {
                     if next_ch == '}' {
-                        chars.next(); // consume '}'
+                        chars.next();
                         break;
                     }
-                    param_name.push(chars.next().unwrap());
+                    expr.push(chars.next().unwrap());
                 }
 
-                if param_name.is_empty() {
+                if expr.is_empty() {
+                    return Err(Error::Handler(
+                        "Empty parameter expression in URI template".to_string(),
+                    ));
+                }
+
+                let (operator, body) = match expr.chars().next().unwrap() {
+                    '+' => (TemplateOperator::Reserved, &expr[1..]),
+                    '#' => (TemplateOperator::Fragment, &expr[1..]),
+                    '?' => (TemplateOperator::Query, &expr[1..]),
+                    '&' => (TemplateOperator::QueryContinuation, &expr[1..]),
+                    _ => (TemplateOperator::Simple, expr.as_str()),
+                };
+
+                let names: Vec<&str> = body.split(',').map(|s| s.trim()).collect();
+                if names.iter().any(|n| n.is_empty()) {
                     return Err(Error::Handler(
                         "Empty parameter name in URI template".to_string(),
                     ));
                 }
 
-                param_names.push(param_name);
+                if operator.is_query_like() {
+                    for name in names {
+                        vars.push(TemplateVar {
+                            name: name.to_string(),
+                            operator,
+                        });
+                    }
+                    continue;
+                }
 
-                // Check what comes after the parameter
-                // If there's a '/' after, match non-greedy up to next '/'
-                // Otherwise, match greedy to end
-                if chars.peek() == Some(&'/') {
-                    pattern.push_str("([^/]+)"); // Segment matching
-                } else {
-                    pattern.push_str("(.+)"); // Greedy path matching
+                if operator == TemplateOperator::Fragment {
+                    pattern.push('#');
+                }
+
+                for (i, name) in names.iter().enumerate() {
+                    vars.push(TemplateVar {
+                        name: name.to_string(),
+                        operator,
+                    });
+                    if i > 0 {
+                        pattern.push(',');
+                    }
+
+                    // A single {var} immediately followed by '/' matches
+                    // just that segment; everything else (reserved
+                    // expansion, multi-variable lists, or trailing
+                    // position) matches greedily to the end.
+                    let single_segment =
+                        operator == TemplateOperator::Simple && names.len() == 1 && chars.peek() == Some(&'/');
+                    if single_segment {
+                        pattern.push_str("([^/]+)");
+                    } else {
+                        pattern.push_str("(.+)");
+                    }
                 }
             } else {
                 // Escape regex special characters
@@ -184,7 +393,7 @@ impl ResourceManager {
         let regex = Regex::new(&pattern)
             .map_err(|e| Error::Handler(format!("Invalid URI template regex: {}", e)))?;
 
-        Ok((regex, param_names))
+        Ok((regex, vars))
     }
 
     /// List all registered resource templates
@@ -202,6 +411,233 @@ impl Default for ResourceManager {
     }
 }
 
+/// Split a URI into its path and (if present) query-string suffix, e.g.
+/// `"file:///a?b=c"` -> `("file:///a", Some("b=c"))`.
+fn split_uri_query(uri: &str) -> (&str, Option<&str>) {
+    match uri.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (uri, None),
+    }
+}
+
+/// Parse an `a=b&c=d` query string into ordered key/value pairs. A key with
+/// no `=value` is recorded with an empty value.
+fn parse_query_string(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (pair.to_string(), String::new()),
+        })
+        .collect()
+}
+
+/// Percent-encode `value` per RFC 3986. Unreserved characters
+/// (`ALPHA` / `DIGIT` / `-` / `.` / `_` / `~`) always pass through
+/// unescaped; when `allow_reserved` is set, the reserved set (`:/?#[]@!$&'()*+,;=`)
+/// is left unescaped too, matching the `{+var}`/`{#var}` "reserved
+/// expansion" operators of RFC 6570, which are documented to permit those
+/// characters through unencoded. Everything else is escaped as `%XX`.
+fn percent_encode(value: &str, allow_reserved: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        let unreserved = byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~');
+        let reserved = allow_reserved
+            && matches!(
+                byte,
+                b':' | b'/'
+                    | b'?'
+                    | b'#'
+                    | b'['
+                    | b']'
+                    | b'@'
+                    | b'!'
+                    | b'$'
+                    | b'&'
+                    | b'\''
+                    | b'('
+                    | b')'
+                    | b'*'
+                    | b'+'
+                    | b','
+                    | b';'
+                    | b'='
+            );
+        if unreserved || reserved {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Reconstruct a URI from a template and a set of resolved parameter
+/// values, the inverse of [`ResourceManager::match_uri`]. Path-level
+/// variables (`{var}`, `{+var}`, `{#var}`) are substituted in place,
+/// percent-encoded per RFC 3986 (`{+var}`/`{#var}` additionally leave
+/// reserved characters unescaped, per their RFC 6570 "reserved expansion"
+/// semantics); query-level variables (`{?a,b}`, `{&a,b}`) are rendered as
+/// a `?a=...&b=...` suffix, with both keys and values percent-encoded,
+/// built from whichever of their names are present in `params`. Missing
+/// path variables are substituted with an empty string.
+fn expand_template(template: &str, params: &HashMap<String, String>) -> String {
+    let mut output = String::new();
+    let mut query_pairs: Vec<(String, String)> = Vec::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '{' {
+            output.push(ch);
+            continue;
+        }
+
+        let mut expr = String::new();
+        while let Some(&next_ch) = chars.peek() {
+            if next_ch == '}' {
+                chars.next();
+                break;
+            }
+            expr.push(chars.next().unwrap());
+        }
+
+        let (operator, body) = match expr.chars().next() {
+            Some('+') => (TemplateOperator::Reserved, &expr[1..]),
+            Some('#') => (TemplateOperator::Fragment, &expr[1..]),
+            Some('?') => (TemplateOperator::Query, &expr[1..]),
+            Some('&') => (TemplateOperator::QueryContinuation, &expr[1..]),
+            _ => (TemplateOperator::Simple, expr.as_str()),
+        };
+
+        let names = body.split(',').map(|s| s.trim());
+
+        if operator.is_query_like() {
+            for name in names {
+                if let Some(value) = params.get(name) {
+                    query_pairs.push((name.to_string(), value.clone()));
+                }
+            }
+            continue;
+        }
+
+        let allow_reserved = matches!(
+            operator,
+            TemplateOperator::Reserved | TemplateOperator::Fragment
+        );
+
+        if operator == TemplateOperator::Fragment {
+            output.push('#');
+        }
+        for name in names {
+            if let Some(value) = params.get(name) {
+                output.push_str(&percent_encode(value, allow_reserved));
+            }
+        }
+    }
+
+    if !query_pairs.is_empty() {
+        output.push('?');
+        let rendered: Vec<String> = query_pairs
+            .iter()
+            .map(|(k, v)| format!("{}={}", percent_encode(k, false), percent_encode(v, false)))
+            .collect();
+        output.push_str(&rendered.join("&"));
+    }
+
+    output
+}
+
+/// Watches the directories implied by every registered `file://`
+/// `uri_template` and [`ResourceManager::publish`]es a change whenever a
+/// matching path is created, modified, or removed, debounced so a burst of
+/// OS events for one edit (e.g. an editor's write-then-rename) collapses
+/// into a single notification.
+pub struct FileWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl FileWatcher {
+    /// Start watching every `file://`-templated resource registered on
+    /// `manager`. Events on the same path within `debounce` are coalesced.
+    pub fn watch(manager: Arc<ResourceManager>, debounce: Duration) -> Result<Self> {
+        use notify::Watcher;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let _ = tx.send(res);
+        })
+        .map_err(|e| Error::Handler(format!("failed to create file watcher: {}", e)))?;
+
+        let dirs: Vec<std::path::PathBuf> = manager
+            .resources
+            .iter()
+            .filter(|e| e.uri_template.starts_with("file://"))
+            .filter_map(|e| file_template_dir(&e.uri_template))
+            .collect();
+
+        for dir in &dirs {
+            watcher
+                .watch(dir, notify::RecursiveMode::Recursive)
+                .map_err(|e| Error::Handler(format!("failed to watch {}: {}", dir.display(), e)))?;
+        }
+
+        tokio::task::spawn_blocking(move || {
+            let mut last_seen: HashMap<std::path::PathBuf, Instant> = HashMap::new();
+
+            for res in rx {
+                let Ok(event) = res else { continue };
+                let kind = match event.kind {
+                    notify::EventKind::Create(_) => ChangeKind::Created,
+                    notify::EventKind::Remove(_) => ChangeKind::Removed,
+                    notify::EventKind::Modify(_) => ChangeKind::Modified,
+                    _ => continue,
+                };
+
+                for path in event.paths {
+                    let now = Instant::now();
+                    if let Some(last) = last_seen.get(&path) {
+                        if now.duration_since(*last) < debounce {
+                            continue;
+                        }
+                    }
+                    last_seen.insert(path.clone(), now);
+
+                    manager.publish(&format!("file://{}", path.display()), kind.clone());
+                }
+            }
+        });
+
+        Ok(Self {
+            _watcher: watcher,
+        })
+    }
+}
+
+/// The directory a `file://` URI template lives under, e.g.
+/// `file:///data/{name}.json` -> `/data`, `file:///data/{path}` -> `/data`.
+fn file_template_dir(template: &str) -> Option<std::path::PathBuf> {
+    let static_prefix = template.split('{').next().unwrap_or(template);
+    let path_part = static_prefix.strip_prefix("file://")?;
+
+    // A bare `{var}` segment right after the scheme (e.g. `file:///{path}`)
+    // leaves no fixed directory to scope a watch to - `path_part` is just
+    // `/`. Returning it as-is would make `FileWatcher::watch` recursively
+    // watch the filesystem root. Treat that as "no watchable directory"
+    // instead.
+    if path_part == "/" {
+        return None;
+    }
+
+    let path = std::path::Path::new(path_part);
+
+    if path_part.ends_with('/') {
+        Some(path.to_path_buf())
+    } else {
+        path.parent().map(|p| p.to_path_buf())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,12 +660,18 @@ mod tests {
         ) -> Result<()> {
             Ok(())
         }
+
+        async fn subscribe(&self, _uri: &str, _params: HashMap<String, String>) -> Result<()> {
+            Ok(())
+        }
     }
 
     #[test]
     fn test_uri_template_compilation() {
-        let (pattern, params) = ResourceManager::compile_uri_template("file:///{path}").unwrap();
-        assert_eq!(params, vec!["path"]);
+        let (pattern, vars) = ResourceManager::compile_uri_template("file:///{path}").unwrap();
+        let names: Vec<&str> = vars.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["path"]);
+        assert!(vars.iter().all(|v| v.operator == TemplateOperator::Simple));
 
         let captures = pattern.captures("file:///home/user/test.txt").unwrap();
         assert_eq!(captures.get(1).unwrap().as_str(), "home/user/test.txt");
@@ -237,15 +679,122 @@ mod tests {
 
     #[test]
     fn test_uri_template_multiple_params() {
-        let (pattern, params) =
+        let (pattern, vars) =
             ResourceManager::compile_uri_template("api://{service}/{resource}").unwrap();
-        assert_eq!(params, vec!["service", "resource"]);
+        let names: Vec<&str> = vars.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["service", "resource"]);
 
         let captures = pattern.captures("api://users/profile").unwrap();
         assert_eq!(captures.get(1).unwrap().as_str(), "users");
         assert_eq!(captures.get(2).unwrap().as_str(), "profile");
     }
 
+    #[test]
+    fn test_uri_template_reserved_expansion_matches_slashes() {
+        let (pattern, vars) = ResourceManager::compile_uri_template("file://{+path}").unwrap();
+        assert_eq!(vars[0].operator, TemplateOperator::Reserved);
+
+        let captures = pattern.captures("file://a/b/c.txt").unwrap();
+        assert_eq!(captures.get(1).unwrap().as_str(), "a/b/c.txt");
+    }
+
+    #[test]
+    fn test_uri_template_fragment() {
+        let (pattern, vars) = ResourceManager::compile_uri_template("doc://readme{#section}").unwrap();
+        assert_eq!(vars[0].operator, TemplateOperator::Fragment);
+
+        let captures = pattern.captures("doc://readme#install").unwrap();
+        assert_eq!(captures.get(1).unwrap().as_str(), "install");
+    }
+
+    #[test]
+    fn test_uri_template_query_vars_contribute_no_capture_group() {
+        let (pattern, vars) =
+            ResourceManager::compile_uri_template("search://results{?q,lang}").unwrap();
+        assert_eq!(pattern.captures_len(), 1); // whole-match group only, no captures
+        assert_eq!(vars.len(), 2);
+        assert!(vars.iter().all(|v| v.operator == TemplateOperator::Query));
+    }
+
+    #[test]
+    fn test_expand_template_substitutes_path_and_query_vars() {
+        let mut params = HashMap::new();
+        params.insert("service".to_string(), "users".to_string());
+        params.insert("resource".to_string(), "profile".to_string());
+        let uri = expand_template("api://{service}/{resource}", &params);
+        assert_eq!(uri, "api://users/profile");
+
+        let mut params = HashMap::new();
+        params.insert("q".to_string(), "rust".to_string());
+        params.insert("lang".to_string(), "en".to_string());
+        let uri = expand_template("search://results{?q,lang}", &params);
+        assert_eq!(uri, "search://results?q=rust&lang=en");
+    }
+
+    #[test]
+    fn test_expand_template_percent_encodes_query_pairs() {
+        let mut params = HashMap::new();
+        params.insert("q".to_string(), "a&admin=1".to_string());
+        params.insert("lang".to_string(), "en us".to_string());
+        let uri = expand_template("search://results{?q,lang}", &params);
+        assert_eq!(uri, "search://results?q=a%26admin%3D1&lang=en%20us");
+    }
+
+    #[test]
+    fn test_expand_template_percent_encodes_simple_path_vars() {
+        let mut params = HashMap::new();
+        params.insert("resource".to_string(), "a/b c".to_string());
+        let uri = expand_template("api://{resource}", &params);
+        assert_eq!(uri, "api://a%2Fb%20c");
+    }
+
+    #[test]
+    fn test_expand_template_reserved_operator_leaves_reserved_chars_unescaped() {
+        let mut params = HashMap::new();
+        params.insert("path".to_string(), "a/b c".to_string());
+        let uri = expand_template("file://{+path}", &params);
+        assert_eq!(uri, "file://a/b%20c");
+    }
+
+    #[tokio::test]
+    async fn test_manager_expand_round_trips_through_match_uri() {
+        let mut manager = ResourceManager::new();
+        let def = ResourceDef {
+            uri_template: "search://results{?q,lang}".to_string(),
+            handler: HandlerRef {
+                path: "test::handler".to_string(),
+                inline: None,
+            },
+            supports: vec![ResourceOperation::Read],
+        };
+        manager
+            .register(
+                def,
+                Arc::new(TestResourceHandler {
+                    read_response: Vec::new(),
+                }),
+            )
+            .unwrap();
+
+        let mut params = HashMap::new();
+        params.insert("q".to_string(), "rust".to_string());
+        params.insert("lang".to_string(), "en".to_string());
+        let uri = manager
+            .expand("search://results{?q,lang}", &params)
+            .unwrap();
+
+        let (_, matched_params) = manager.match_uri(&uri).unwrap();
+        assert_eq!(matched_params.get("q").unwrap(), "rust");
+        assert_eq!(matched_params.get("lang").unwrap(), "en");
+    }
+
+    #[test]
+    fn test_expand_unregistered_template_errors() {
+        let manager = ResourceManager::new();
+        let result = manager.expand("file:///{path}", &HashMap::new());
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_resource_registration_and_matching() {
         let mut manager = ResourceManager::new();
@@ -319,4 +868,109 @@ mod tests {
             .to_string()
             .contains("does not support write"));
     }
+
+    #[tokio::test]
+    async fn test_subscribe_requires_support() {
+        let mut manager = ResourceManager::new();
+
+        let def = ResourceDef {
+            uri_template: "file:///{path}".to_string(),
+            handler: HandlerRef {
+                path: "test::handler".to_string(),
+                inline: None,
+            },
+            supports: vec![ResourceOperation::Read],
+        };
+
+        manager
+            .register(
+                def,
+                Arc::new(TestResourceHandler {
+                    read_response: b"test".to_vec(),
+                }),
+            )
+            .unwrap();
+
+        let result = manager.subscribe("file:///test.txt").await;
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("does not support subscribe"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_receives_published_changes_for_matching_uri_only() {
+        let mut manager = ResourceManager::new();
+
+        let def = ResourceDef {
+            uri_template: "file:///{path}".to_string(),
+            handler: HandlerRef {
+                path: "test::handler".to_string(),
+                inline: None,
+            },
+            supports: vec![ResourceOperation::Subscribe],
+        };
+
+        manager
+            .register(
+                def,
+                Arc::new(TestResourceHandler {
+                    read_response: b"test".to_vec(),
+                }),
+            )
+            .unwrap();
+
+        let mut subscription = manager.subscribe("file:///test.txt").await.unwrap();
+
+        manager.publish("file:///other.txt", ChangeKind::Modified);
+        manager.publish("file:///test.txt", ChangeKind::Modified);
+
+        let event = subscription.recv().await.unwrap();
+        assert_eq!(event.uri, "file:///test.txt");
+        assert_eq!(event.kind, ChangeKind::Modified);
+    }
+
+    #[tokio::test]
+    async fn test_change_stream_sees_every_published_event() {
+        let manager = ResourceManager::new();
+        let mut stream = manager.change_stream();
+
+        manager.publish("file:///a.txt", ChangeKind::Created);
+        manager.publish("file:///b.txt", ChangeKind::Removed);
+
+        assert_eq!(stream.recv().await.unwrap().uri, "file:///a.txt");
+        assert_eq!(stream.recv().await.unwrap().kind, ChangeKind::Removed);
+    }
+
+    #[test]
+    fn test_resources_updated_notification_shape() {
+        let event = ResourceChangeEvent {
+            uri: "file:///test.txt".to_string(),
+            kind: ChangeKind::Modified,
+        };
+        let notification = resources_updated_notification(&event);
+        assert_eq!(notification["method"], "notifications/resources/updated");
+        assert_eq!(notification["params"]["uri"], "file:///test.txt");
+    }
+
+    #[test]
+    fn test_file_template_dir() {
+        assert_eq!(
+            file_template_dir("file:///data/{path}"),
+            Some(std::path::PathBuf::from("/data"))
+        );
+        assert_eq!(
+            file_template_dir("file:///data/{name}.json"),
+            Some(std::path::PathBuf::from("/data"))
+        );
+    }
+
+    #[test]
+    fn test_file_template_dir_root_level_var_has_no_fixed_directory() {
+        // `file:///{path}` has no static directory component to scope a
+        // watch to - watching `/` would blow past inotify limits and flood
+        // change notifications for unrelated directories.
+        assert_eq!(file_template_dir("file:///{path}"), None);
+    }
 }
@@ -1,27 +1,104 @@
-use crate::{Error, HandlerRegistry, Result};
-use pforge_config::ForgeConfig;
-use std::sync::Arc;
+use crate::auth::{bearer_token_from_headers, build_authenticator, stamp_identity, Authenticator};
+use crate::dispatch_middleware::{DispatchLatencyRecorder, DispatchTimeout, OutputValidator};
+use crate::protocol::{self, stamp_protocol_version, CapabilityManifest};
+use crate::telemetry::{AdminMetrics, HealthRegistry};
+use crate::{Error, HandlerRegistry, PromptManager, Result};
+use pforge_config::{ForgeConfig, TransportType};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::RwLock;
 
+/// Default bind address for the `sse` and `websocket` transports, mirroring
+/// the placeholder endpoints [`crate::transport::create_transport`] already
+/// hardcodes for their client-side counterparts.
+const DEFAULT_BIND_ADDR: &str = "127.0.0.1:8080";
+
 /// MCP Server implementation
 pub struct McpServer {
     config: ForgeConfig,
     registry: Arc<RwLock<HandlerRegistry>>,
+    prompts: Arc<RwLock<PromptManager>>,
+    authenticator: Arc<dyn Authenticator>,
+    http_clients: crate::handlers::http::HttpClientProvider,
+    admin_metrics: AdminMetrics,
+    health: HealthRegistry,
 }
 
 impl McpServer {
     /// Create a new MCP server from configuration
     pub fn new(config: ForgeConfig) -> Self {
+        let authenticator = build_authenticator(config.auth.as_ref());
         Self {
             config,
             registry: Arc::new(RwLock::new(HandlerRegistry::new())),
+            prompts: Arc::new(RwLock::new(PromptManager::new())),
+            authenticator,
+            http_clients: crate::handlers::http::HttpClientProvider::new(),
+            admin_metrics: AdminMetrics::new(),
+            health: HealthRegistry::new(),
         }
     }
 
-    /// Register all handlers from configuration
+    /// The health probe registry backing the built-in `health_check` tool.
+    /// Handlers and backends register named probes here (state manager
+    /// roundtrips, CLI dependency checks, HTTP reachability checks) so
+    /// operators can wire readiness/liveness gates without hand-rolling
+    /// probes.
+    pub fn health_registry(&self) -> &HealthRegistry {
+        &self.health
+    }
+
+    /// Replace the default (empty) admin metrics surface - e.g. to attach a
+    /// [`crate::recovery::RecoveryMiddleware`] so circuit-breaker and error
+    /// state show up in the `pforge/metrics` snapshot and the `/metrics`
+    /// HTTP endpoint alongside request counts.
+    pub fn with_admin_metrics(mut self, admin_metrics: AdminMetrics) -> Self {
+        self.admin_metrics = admin_metrics;
+        self
+    }
+
+    /// The admin metrics surface this server records invocations into and
+    /// exposes via `pforge/metrics`.
+    pub fn admin_metrics(&self) -> &AdminMetrics {
+        &self.admin_metrics
+    }
+
+    /// Register all tool handlers and prompts from configuration
     pub async fn register_handlers(&self) -> Result<()> {
+        self.register_prompts().await?;
+
         let mut registry = self.registry.write().await;
 
+        registry.register_middleware(Arc::new(DispatchLatencyRecorder::new(
+            self.admin_metrics.collector.as_ref().clone(),
+        )));
+
+        if self.config.forge.validate_output {
+            registry.register_middleware(Arc::new(OutputValidator::new()));
+        }
+
+        let mut dispatch_timeout = DispatchTimeout::new();
+        for tool in &self.config.tools {
+            if let pforge_config::ToolDef::Native {
+                name,
+                timeout_ms: Some(timeout_ms),
+                ..
+            } = tool
+            {
+                dispatch_timeout = dispatch_timeout.with_tool_timeout_ms(name.clone(), *timeout_ms);
+            }
+        }
+        registry.register_middleware(Arc::new(dispatch_timeout));
+
+        registry.register(
+            "health_check",
+            crate::handlers::health::HealthCheckHandler::new(self.health.clone()),
+        );
+
         for tool in &self.config.tools {
             match tool {
                 pforge_config::ToolDef::Native { name, .. } => {
@@ -61,7 +138,8 @@ impl McpServer {
                     ..
                 } => {
                     use crate::handlers::http::{
-                        AuthConfig as HttpAuthConfig, HttpHandler, HttpMethod as HandlerHttpMethod,
+                        AuthConfig as HttpAuthConfig, HttpClientConfig, HttpHandler,
+                        HttpMethod as HandlerHttpMethod,
                     };
 
                     let handler_method = match method {
@@ -90,17 +168,39 @@ impl McpServer {
                         }
                     });
 
-                    let handler = HttpHandler::new(
+                    let client = self.http_clients.client_for(&HttpClientConfig::default())?;
+                    let handler = HttpHandler::with_client(
                         endpoint.clone(),
                         handler_method,
                         headers.clone(),
                         handler_auth,
+                        client,
                     );
                     registry.register(name, handler);
                     eprintln!("Registered HTTP handler: {}", name);
                 }
-                pforge_config::ToolDef::Pipeline { name, .. } => {
-                    eprintln!("Note: Pipeline handler '{}' pending implementation", name);
+                pforge_config::ToolDef::Pipeline { name, steps, .. } => {
+                    use crate::handlers::pipeline::{
+                        ErrorPolicy as HandlerErrorPolicy, PipelineHandler, PipelineStep,
+                    };
+
+                    let handler_steps = steps
+                        .iter()
+                        .map(|step| PipelineStep {
+                            tool: step.tool.clone(),
+                            input: step.input.clone(),
+                            output_var: step.output_var.clone(),
+                            condition: step.condition.clone(),
+                            error_policy: match step.error_policy {
+                                pforge_config::ErrorPolicy::FailFast => HandlerErrorPolicy::FailFast,
+                                pforge_config::ErrorPolicy::Continue => HandlerErrorPolicy::Continue,
+                            },
+                        })
+                        .collect();
+
+                    let handler = PipelineHandler::new(self.registry.clone(), handler_steps);
+                    registry.register(name, handler);
+                    eprintln!("Registered pipeline handler: {}", name);
                 }
             }
         }
@@ -108,7 +208,20 @@ impl McpServer {
         Ok(())
     }
 
-    /// Run the MCP server
+    /// Register prompt definitions from configuration into the Handlebars-backed
+    /// [`PromptManager`], so they're available alongside tool handlers.
+    async fn register_prompts(&self) -> Result<()> {
+        let mut prompts = self.prompts.write().await;
+        for prompt in &self.config.prompts {
+            prompts.register(prompt.clone())?;
+            eprintln!("Registered prompt: {}", prompt.name);
+        }
+        prompts.validate_partials()?;
+        Ok(())
+    }
+
+    /// Run the MCP server: register handlers, then drive the transport
+    /// declared in `forge.transport` until it's interrupted.
     pub async fn run(&self) -> Result<()> {
         eprintln!(
             "Starting MCP server: {} v{}",
@@ -117,19 +230,217 @@ impl McpServer {
         eprintln!("Transport: {:?}", self.config.forge.transport);
         eprintln!("Tools registered: {}", self.config.tools.len());
 
-        // Register handlers
         self.register_handlers().await?;
 
-        // TODO: Implement actual MCP protocol loop
-        // For now, just keep the server alive
-        eprintln!("\n⚠ MCP protocol loop not yet implemented");
-        eprintln!("Server configuration loaded and handlers registered successfully");
-        eprintln!("Press Ctrl+C to exit");
+        let manifest = Arc::new(protocol::build_capability_manifest(&self.config));
 
-        // Wait indefinitely (will be replaced with actual MCP loop)
-        tokio::signal::ctrl_c().await.map_err(Error::Io)?;
+        match self.config.forge.transport {
+            TransportType::Stdio => self.run_stdio(manifest).await,
+            TransportType::Sse => self.run_sse(DEFAULT_BIND_ADDR, manifest).await,
+            TransportType::WebSocket => self.run_websocket(DEFAULT_BIND_ADDR, manifest).await,
+        }
+    }
+
+    /// The reserved JSON-RPC method name serving [`AdminMetrics::export_json`]
+    /// directly, without a registered handler - available on every
+    /// transport (stdio included, where there's no separate HTTP port for
+    /// [`crate::telemetry::serve_metrics`]) since it's handled in
+    /// [`dispatch_jsonrpc`] the same way `initialize` is.
+    const METRICS_METHOD: &'static str = "pforge/metrics";
+
+    /// Serve newline-delimited JSON-RPC requests over stdin/stdout until
+    /// stdin closes, enforcing `slow_request_timeout_ms` per request. The
+    /// first `initialize` call negotiates a protocol version for the rest of
+    /// the session; see [`dispatch_jsonrpc`].
+    async fn run_stdio(&self, manifest: Arc<CapabilityManifest>) -> Result<()> {
+        eprintln!("Reading JSON-RPC requests from stdin (one per line)");
+
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let slow_request_timeout_ms = self.config.forge.slow_request_timeout_ms;
+        let negotiated_version: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+        while let Some(line) = lines.next_line().await.map_err(Error::Io)? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let stamped = stamp_protocol_version_on_raw(&line, &negotiated_version);
+            let response = dispatch_jsonrpc_bounded(
+                &self.registry,
+                &manifest,
+                &negotiated_version,
+                &stamped,
+                slow_request_timeout_ms,
+                &in_flight,
+                &self.admin_metrics,
+            )
+            .await;
+            stdout.write_all(response.as_bytes()).await.map_err(Error::Io)?;
+            stdout.write_all(b"\n").await.map_err(Error::Io)?;
+            stdout.flush().await.map_err(Error::Io)?;
+        }
 
-        eprintln!("\nShutting down...");
+        eprintln!("\nStdin closed, shutting down...");
+        Ok(())
+    }
+
+    /// Accept WebSocket connections on `addr`; each connection frames
+    /// inbound text messages as a JSON-RPC request routed through the
+    /// shared registry, writing the response back on the same socket.
+    /// Wrapped in TLS when `PFORGE_TLS_CERT`/`PFORGE_TLS_KEY` are set. Stops
+    /// accepting new connections on Ctrl+C and waits up to
+    /// `shutdown_timeout_ms` for in-flight dispatches to drain before
+    /// returning.
+    async fn run_websocket(&self, addr: &str, manifest: Arc<CapabilityManifest>) -> Result<()> {
+        let listener = TcpListener::bind(addr).await.map_err(Error::Io)?;
+        eprintln!("WebSocket transport listening on {}", addr);
+        let acceptor = load_tls_acceptor()?;
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let slow_request_timeout_ms = self.config.forge.slow_request_timeout_ms;
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, peer) = match accepted {
+                        Ok(pair) => pair,
+                        Err(e) => {
+                            eprintln!("websocket accept error: {}", e);
+                            continue;
+                        }
+                    };
+
+                    let registry = self.registry.clone();
+                    let authenticator = self.authenticator.clone();
+                    let manifest = manifest.clone();
+                    let acceptor = acceptor.clone();
+                    let in_flight = in_flight.clone();
+                    let admin_metrics = self.admin_metrics.clone();
+                    tokio::spawn(async move {
+                        let result = match acceptor {
+                            Some(acceptor) => match acceptor.accept(stream).await {
+                                Ok(tls_stream) => {
+                                    handle_websocket_connection(
+                                        tls_stream,
+                                        registry,
+                                        authenticator,
+                                        manifest,
+                                        slow_request_timeout_ms,
+                                        in_flight,
+                                        admin_metrics,
+                                    )
+                                    .await
+                                }
+                                Err(e) => Err(Error::Handler(format!("TLS handshake failed: {}", e))),
+                            },
+                            None => {
+                                handle_websocket_connection(
+                                    stream,
+                                    registry,
+                                    authenticator,
+                                    manifest,
+                                    slow_request_timeout_ms,
+                                    in_flight,
+                                    admin_metrics,
+                                )
+                                .await
+                            }
+                        };
+                        if let Err(e) = result {
+                            eprintln!("websocket connection {} error: {}", peer, e);
+                        }
+                    });
+                }
+                _ = tokio::signal::ctrl_c() => {
+                    eprintln!("\nShutdown signal received, no longer accepting new connections");
+                    break;
+                }
+            }
+        }
+
+        wait_for_drain(&in_flight, self.config.forge.shutdown_timeout_ms).await;
+        Ok(())
+    }
+
+    /// Accept SSE connections on `addr`: `GET` opens a long-lived
+    /// `text/event-stream` that receives pushed responses, `POST` submits a
+    /// JSON-RPC request whose response is pushed onto the matching `GET`
+    /// stream (identified by the `session` query parameter the initial
+    /// `endpoint` event advertises). Wrapped in TLS when
+    /// `PFORGE_TLS_CERT`/`PFORGE_TLS_KEY` are set. Stops accepting new
+    /// connections on Ctrl+C and waits up to `shutdown_timeout_ms` for
+    /// in-flight dispatches to drain before returning.
+    async fn run_sse(&self, addr: &str, manifest: Arc<CapabilityManifest>) -> Result<()> {
+        let listener = TcpListener::bind(addr).await.map_err(Error::Io)?;
+        eprintln!("SSE transport listening on {}", addr);
+        let acceptor = load_tls_acceptor()?;
+        let sessions: SseSessions = Arc::new(RwLock::new(std::collections::HashMap::new()));
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let slow_request_timeout_ms = self.config.forge.slow_request_timeout_ms;
+
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, peer) = match accepted {
+                        Ok(pair) => pair,
+                        Err(e) => {
+                            eprintln!("sse accept error: {}", e);
+                            continue;
+                        }
+                    };
+
+                    let registry = self.registry.clone();
+                    let authenticator = self.authenticator.clone();
+                    let manifest = manifest.clone();
+                    let sessions = sessions.clone();
+                    let acceptor = acceptor.clone();
+                    let in_flight = in_flight.clone();
+                    let admin_metrics = self.admin_metrics.clone();
+                    tokio::spawn(async move {
+                        let result = match acceptor {
+                            Some(acceptor) => match acceptor.accept(stream).await {
+                                Ok(tls_stream) => {
+                                    handle_sse_connection(
+                                        tls_stream,
+                                        registry,
+                                        authenticator,
+                                        manifest,
+                                        sessions,
+                                        slow_request_timeout_ms,
+                                        in_flight,
+                                        admin_metrics,
+                                    )
+                                    .await
+                                }
+                                Err(e) => Err(Error::Handler(format!("TLS handshake failed: {}", e))),
+                            },
+                            None => {
+                                handle_sse_connection(
+                                    stream,
+                                    registry,
+                                    authenticator,
+                                    manifest,
+                                    sessions,
+                                    slow_request_timeout_ms,
+                                    in_flight,
+                                    admin_metrics,
+                                )
+                                .await
+                            }
+                        };
+                        if let Err(e) = result {
+                            eprintln!("sse connection {} error: {}", peer, e);
+                        }
+                    });
+                }
+                _ = tokio::signal::ctrl_c() => {
+                    eprintln!("\nShutdown signal received, no longer accepting new connections");
+                    break;
+                }
+            }
+        }
+
+        wait_for_drain(&in_flight, self.config.forge.shutdown_timeout_ms).await;
         Ok(())
     }
 
@@ -137,12 +448,594 @@ impl McpServer {
     pub fn registry(&self) -> Arc<RwLock<HandlerRegistry>> {
         self.registry.clone()
     }
+
+    /// Get the prompt manager (for testing)
+    pub fn prompts(&self) -> Arc<RwLock<PromptManager>> {
+        self.prompts.clone()
+    }
+}
+
+/// Dispatch one JSON-RPC 2.0 request (`{"jsonrpc":"2.0","method":...,
+/// "params":...,"id":...}`) to `registry` and render the response (or
+/// error) envelope as a string. Shared by every transport so stdio,
+/// WebSocket, and SSE all speak the exact same wire format.
+///
+/// The `initialize` method is handled here directly rather than going
+/// through `registry`: it negotiates a protocol version (recording it in
+/// `negotiated_version` for the caller to stamp onto subsequent requests via
+/// [`stamp_protocol_version_on_raw`]) and returns `manifest` so the client
+/// can feature-detect before issuing its first real call.
+async fn dispatch_jsonrpc(
+    registry: &Arc<RwLock<HandlerRegistry>>,
+    manifest: &CapabilityManifest,
+    negotiated_version: &Mutex<Option<String>>,
+    raw: &str,
+    admin_metrics: &AdminMetrics,
+) -> String {
+    let request: Value = match serde_json::from_str(raw) {
+        Ok(v) => v,
+        Err(e) => return jsonrpc_error(Value::Null, -32700, &format!("Parse error: {}", e)),
+    };
+
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+
+    let method = match request.get("method").and_then(Value::as_str) {
+        Some(m) => m,
+        None => return jsonrpc_error(id, -32600, "Invalid Request: missing method"),
+    };
+
+    if method == "initialize" {
+        return handle_initialize(id, &request, manifest, negotiated_version);
+    }
+
+    if method == McpServer::METRICS_METHOD {
+        return jsonrpc_result(id, admin_metrics.export_json().await);
+    }
+
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let params_bytes = match serde_json::to_vec(&params) {
+        Ok(b) => b,
+        Err(e) => return jsonrpc_error(id, -32603, &format!("Internal error: {}", e)),
+    };
+
+    let dispatch_result = { registry.read().await.dispatch(method, &params_bytes).await };
+
+    match dispatch_result {
+        Ok(result_bytes) => {
+            let result: Value = serde_json::from_slice(&result_bytes).unwrap_or_else(|_| {
+                Value::String(String::from_utf8_lossy(&result_bytes).into_owned())
+            });
+            jsonrpc_result(id, result)
+        }
+        Err(e) => jsonrpc_error(id, -32000, &e.to_string()),
+    }
+}
+
+fn jsonrpc_result(id: Value, result: Value) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result,
+    })
+    .to_string()
+}
+
+/// Negotiate the protocol version an `initialize` call requested, record it
+/// in `negotiated_version`, and respond with the negotiated version plus the
+/// server's capability manifest. Rejects with a structured error listing
+/// [`protocol::SUPPORTED_PROTOCOL_VERSIONS`] when the requested version is
+/// older than anything this server understands.
+fn handle_initialize(
+    id: Value,
+    request: &Value,
+    manifest: &CapabilityManifest,
+    negotiated_version: &Mutex<Option<String>>,
+) -> String {
+    let requested = request
+        .get("params")
+        .and_then(|p| p.get("protocolVersion"))
+        .and_then(Value::as_str)
+        .unwrap_or(protocol::MAX_PROTOCOL_VERSION);
+
+    match protocol::negotiate_protocol_version(requested) {
+        Ok(negotiation) => {
+            let version = negotiation.version().to_string();
+            *negotiated_version.lock().unwrap() = Some(version.clone());
+            jsonrpc_result(
+                id,
+                serde_json::json!({
+                    "protocolVersion": version,
+                    "capabilities": manifest,
+                }),
+            )
+        }
+        Err(supported) => jsonrpc_error(
+            id,
+            -32600,
+            &format!(
+                "Unsupported protocol version '{}'; supported versions: {}",
+                requested,
+                supported.join(", ")
+            ),
+        ),
+    }
+}
+
+/// Stamp the connection's currently negotiated protocol version (if any)
+/// onto an incoming JSON-RPC request, so [`dispatch_jsonrpc`] and any
+/// middleware downstream of it can read it via
+/// [`crate::protocol::protocol_version_of`]. Falls through to the original
+/// text unchanged if no version has been negotiated yet or it doesn't parse.
+fn stamp_protocol_version_on_raw(raw: &str, negotiated_version: &Mutex<Option<String>>) -> String {
+    let version = match negotiated_version.lock().unwrap().clone() {
+        Some(v) => v,
+        None => return raw.to_string(),
+    };
+
+    match serde_json::from_str::<Value>(raw) {
+        Ok(value) => stamp_protocol_version(&version, value).to_string(),
+        Err(_) => raw.to_string(),
+    }
+}
+
+/// Tracks how many dispatches are currently in flight so a shutting-down
+/// transport knows when it's safe to stop waiting. Decrements automatically
+/// when the guard returned by [`InFlightGuard::enter`] drops, so an early
+/// return or panic inside a handler can't leak the count.
+struct InFlightGuard(Arc<AtomicU64>);
+
+impl InFlightGuard {
+    fn enter(counter: &Arc<AtomicU64>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter.clone())
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Wait for `in_flight` to reach zero, up to `timeout_ms`, logging whether
+/// every request drained cleanly or the deadline forced an exit.
+async fn wait_for_drain(in_flight: &Arc<AtomicU64>, timeout_ms: u64) {
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(timeout_ms);
+
+    while in_flight.load(Ordering::SeqCst) > 0 {
+        if tokio::time::Instant::now() >= deadline {
+            eprintln!(
+                "Shutdown timeout of {}ms elapsed with {} request(s) still in flight; forcing exit",
+                timeout_ms,
+                in_flight.load(Ordering::SeqCst)
+            );
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    eprintln!("All in-flight requests drained, shutting down cleanly");
+}
+
+/// [`dispatch_jsonrpc`], wrapped with in-flight accounting and an optional
+/// per-request timeout that returns a timeout error instead of letting a
+/// stuck handler block the connection (and shutdown) forever.
+#[allow(clippy::too_many_arguments)]
+async fn dispatch_jsonrpc_bounded(
+    registry: &Arc<RwLock<HandlerRegistry>>,
+    manifest: &CapabilityManifest,
+    negotiated_version: &Mutex<Option<String>>,
+    raw: &str,
+    slow_request_timeout_ms: Option<u64>,
+    in_flight: &Arc<AtomicU64>,
+    admin_metrics: &AdminMetrics,
+) -> String {
+    let _guard = InFlightGuard::enter(in_flight);
+
+    match slow_request_timeout_ms {
+        Some(ms) => {
+            match tokio::time::timeout(
+                Duration::from_millis(ms),
+                dispatch_jsonrpc(registry, manifest, negotiated_version, raw, admin_metrics),
+            )
+            .await
+            {
+                Ok(response) => response,
+                Err(_) => jsonrpc_error(
+                    request_id(raw),
+                    -32001,
+                    &format!("Request exceeded slow_request_timeout of {}ms", ms),
+                ),
+            }
+        }
+        None => dispatch_jsonrpc(registry, manifest, negotiated_version, raw, admin_metrics).await,
+    }
+}
+
+/// Best-effort extraction of a JSON-RPC request's `id`, for error envelopes
+/// built without going through the normal parse path (e.g. on timeout).
+fn request_id(raw: &str) -> Value {
+    serde_json::from_str::<Value>(raw)
+        .ok()
+        .and_then(|v| v.get("id").cloned())
+        .unwrap_or(Value::Null)
+}
+
+fn jsonrpc_error(id: Value, code: i64, message: &str) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+
+/// Build a [`tokio_rustls::TlsAcceptor`] from `PFORGE_TLS_CERT` /
+/// `PFORGE_TLS_KEY` (PEM paths), if both are set. Transports fall back to
+/// plaintext when either is absent, matching the "optional" TLS
+/// termination the request asked for without adding new `ForgeConfig`
+/// fields that every call site constructing one would need to learn about.
+fn load_tls_acceptor() -> Result<Option<tokio_rustls::TlsAcceptor>> {
+    let (cert_path, key_path) = match (
+        std::env::var("PFORGE_TLS_CERT"),
+        std::env::var("PFORGE_TLS_KEY"),
+    ) {
+        (Ok(cert), Ok(key)) => (cert, key),
+        _ => return Ok(None),
+    };
+
+    let cert_file = std::fs::File::open(&cert_path).map_err(Error::Io)?;
+    let certs = rustls_pemfile::certs(&mut std::io::BufReader::new(cert_file))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::Io)?;
+
+    let key_file = std::fs::File::open(&key_path).map_err(Error::Io)?;
+    let key = rustls_pemfile::private_key(&mut std::io::BufReader::new(key_file))
+        .map_err(Error::Io)?
+        .ok_or_else(|| Error::Handler(format!("no private key found in {}", key_path)))?;
+
+    let tls_config = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| Error::Handler(format!("invalid TLS certificate/key: {}", e)))?;
+
+    Ok(Some(tokio_rustls::TlsAcceptor::from(Arc::new(tls_config))))
+}
+
+/// Accept a WebSocket connection, authenticating it from the `Authorization`
+/// header of its upgrade request before the message loop starts. Rejected
+/// connections are closed with a policy-violation close frame and never see
+/// a single JSON-RPC dispatch.
+#[allow(clippy::too_many_arguments)]
+async fn handle_websocket_connection<S>(
+    stream: S,
+    registry: Arc<RwLock<HandlerRegistry>>,
+    authenticator: Arc<dyn Authenticator>,
+    manifest: Arc<CapabilityManifest>,
+    slow_request_timeout_ms: Option<u64>,
+    in_flight: Arc<AtomicU64>,
+    admin_metrics: AdminMetrics,
+) -> Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let handshake_headers: Arc<std::sync::Mutex<Vec<String>>> =
+        Arc::new(std::sync::Mutex::new(Vec::new()));
+    let captured = handshake_headers.clone();
+    let callback =
+        move |request: &tokio_tungstenite::tungstenite::handshake::server::Request,
+              response: tokio_tungstenite::tungstenite::handshake::server::Response| {
+            let headers = request
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    format!("{}: {}", name, value.to_str().unwrap_or_default())
+                })
+                .collect();
+            *captured.lock().unwrap() = headers;
+            Ok(response)
+        };
+
+    let mut ws = tokio_tungstenite::accept_hdr_async(stream, callback)
+        .await
+        .map_err(|e| Error::Handler(format!("WebSocket handshake failed: {}", e)))?;
+
+    let credentials = bearer_token_from_headers(&handshake_headers.lock().unwrap());
+    let identity = match authenticator.authenticate(&credentials).await {
+        Ok(identity) => identity,
+        Err(e) => {
+            let _ = ws
+                .send(Message::Close(Some(CloseFrame {
+                    code: CloseCode::Policy,
+                    reason: e.to_string().into(),
+                })))
+                .await;
+            return Err(e);
+        }
+    };
+
+    let negotiated_version: Mutex<Option<String>> = Mutex::new(None);
+
+    while let Some(message) = ws.next().await {
+        let message =
+            message.map_err(|e| Error::Handler(format!("WebSocket stream error: {}", e)))?;
+
+        match message {
+            Message::Text(text) => {
+                let stamped = stamp_identity_on_raw(&text, &identity);
+                let stamped = stamp_protocol_version_on_raw(&stamped, &negotiated_version);
+                let response = dispatch_jsonrpc_bounded(
+                    &registry,
+                    &manifest,
+                    &negotiated_version,
+                    &stamped,
+                    slow_request_timeout_ms,
+                    &in_flight,
+                    &admin_metrics,
+                )
+                .await;
+                ws.send(Message::Text(response))
+                    .await
+                    .map_err(|e| Error::Handler(format!("WebSocket send error: {}", e)))?;
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse `raw` as a JSON-RPC request, stamp the authenticated caller onto it
+/// via [`stamp_identity`], and re-serialize. Falls through to the original
+/// text unchanged if it doesn't parse; [`dispatch_jsonrpc`] reports the parse
+/// error itself rather than having it masked here.
+fn stamp_identity_on_raw(raw: &str, identity: &crate::auth::Identity) -> String {
+    match serde_json::from_str::<Value>(raw) {
+        Ok(value) => stamp_identity(identity, value).to_string(),
+        Err(_) => raw.to_string(),
+    }
+}
+
+/// One pending SSE `GET` stream: the channel its matching `POST` pushes
+/// responses onto, and the protocol version (if any) negotiated by an
+/// `initialize` call on that same session, shared so every `POST` against
+/// the session sees it.
+struct SseSession {
+    sender: tokio::sync::mpsc::UnboundedSender<String>,
+    negotiated_version: Arc<Mutex<Option<String>>>,
+}
+
+/// Pending SSE `GET` streams, keyed by the session id handed out in each
+/// stream's initial `endpoint` event, so a matching `POST` can push its
+/// response onto the right connection.
+type SseSessions = Arc<RwLock<std::collections::HashMap<String, SseSession>>>;
+
+static SSE_SESSION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_sse_connection<S>(
+    stream: S,
+    registry: Arc<RwLock<HandlerRegistry>>,
+    authenticator: Arc<dyn Authenticator>,
+    manifest: Arc<CapabilityManifest>,
+    sessions: SseSessions,
+    slow_request_timeout_ms: Option<u64>,
+    in_flight: Arc<AtomicU64>,
+    admin_metrics: AdminMetrics,
+) -> Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut reader, request_line, headers) = read_http_request_head(stream).await?;
+    let (peek_method, peek_path) = parse_request_line(&request_line)?;
+
+    // `GET /metrics` is served directly as a plain HTTP response rather
+    // than opened as an SSE stream like every other `GET` below, giving
+    // SSE deployments the same admin endpoint the side-channel
+    // `telemetry::serve_metrics` listener exposes, on the transport's own
+    // port.
+    if peek_method == "GET" && peek_path.starts_with("/metrics") {
+        let body = if peek_path.contains("format=json") {
+            admin_metrics.export_json().await.to_string()
+        } else {
+            admin_metrics.export_prometheus().await
+        };
+        let content_type = if peek_path.contains("format=json") {
+            "application/json"
+        } else {
+            "text/plain; version=0.0.4"
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+            content_type,
+            body.len(),
+            body
+        );
+        reader
+            .write_all(response.as_bytes())
+            .await
+            .map_err(Error::Io)?;
+        return Ok(());
+    }
+
+    let credentials = bearer_token_from_headers(&headers);
+    let identity = match authenticator.authenticate(&credentials).await {
+        Ok(identity) => identity,
+        Err(e) => {
+            let body = format!("{{\"error\":\"{}\"}}", e);
+            let response = format!(
+                "HTTP/1.1 401 Unauthorized\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = reader.write_all(response.as_bytes()).await;
+            return Ok(());
+        }
+    };
+
+    let content_length: usize = headers
+        .iter()
+        .find_map(|h| {
+            let (name, value) = h.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse().unwrap_or(0))
+        })
+        .unwrap_or(0);
+
+    let (method, path) = (peek_method, peek_path);
+
+    if method == "GET" {
+        let session_id = format!(
+            "sse-{}",
+            SSE_SESSION_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        sessions.write().await.insert(
+            session_id.clone(),
+            SseSession {
+                sender: tx,
+                negotiated_version: Arc::new(Mutex::new(None)),
+            },
+        );
+
+        reader
+            .write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                  Content-Type: text/event-stream\r\n\
+                  Cache-Control: no-cache\r\n\
+                  Connection: keep-alive\r\n\r\n",
+            )
+            .await
+            .map_err(Error::Io)?;
+        reader
+            .write_all(format!("event: endpoint\ndata: /rpc?session={}\n\n", session_id).as_bytes())
+            .await
+            .map_err(Error::Io)?;
+
+        while let Some(payload) = rx.recv().await {
+            let frame = format!("event: message\ndata: {}\n\n", payload);
+            if reader.write_all(frame.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+
+        sessions.write().await.remove(&session_id);
+        return Ok(());
+    }
+
+    let session_id = path
+        .split_once('?')
+        .and_then(|(_, query)| query.split('&').find_map(|kv| kv.strip_prefix("session=")));
+
+    let negotiated_version = match session_id {
+        Some(session_id) => sessions
+            .read()
+            .await
+            .get(session_id)
+            .map(|session| session.negotiated_version.clone()),
+        None => None,
+    }
+    .unwrap_or_else(|| Arc::new(Mutex::new(None)));
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await.map_err(Error::Io)?;
+    let text = stamp_identity_on_raw(&String::from_utf8_lossy(&body), &identity);
+    let text = stamp_protocol_version_on_raw(&text, &negotiated_version);
+    let response = dispatch_jsonrpc_bounded(
+        &registry,
+        &manifest,
+        &negotiated_version,
+        &text,
+        slow_request_timeout_ms,
+        &in_flight,
+        &admin_metrics,
+    )
+    .await;
+
+    let delivered = match session_id {
+        Some(session_id) => match sessions.read().await.get(session_id) {
+            Some(session) => session.sender.send(response.clone()).is_ok(),
+            None => false,
+        },
+        None => false,
+    };
+
+    let ack = if delivered {
+        "{\"status\":\"accepted\"}"
+    } else {
+        response.as_str()
+    };
+    let http_response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        ack.len(),
+        ack
+    );
+    reader
+        .write_all(http_response.as_bytes())
+        .await
+        .map_err(Error::Io)?;
+
+    Ok(())
+}
+
+/// Read the request line and header block (up to the blank line) of an
+/// HTTP/1.1 request, returning the reader (buffered and positioned at the
+/// start of the body, still writable since [`BufReader`] passes `AsyncWrite`
+/// straight through to `S`) alongside the parsed lines.
+///
+/// `pub(crate)` so [`crate::telemetry::serve_metrics`] can reuse it for the
+/// `/metrics` endpoint instead of hand-rolling its own HTTP/1.1 parsing.
+pub(crate) async fn read_http_request_head<S>(
+    stream: S,
+) -> Result<(BufReader<S>, String, Vec<String>)>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await.map_err(Error::Io)?;
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        let bytes_read = reader.read_line(&mut line).await.map_err(Error::Io)?;
+        if bytes_read == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        headers.push(line);
+    }
+
+    Ok((reader, request_line, headers))
+}
+
+pub(crate) fn parse_request_line(line: &str) -> Result<(String, String)> {
+    let mut parts = line.trim_end().split_whitespace();
+    let method = parts
+        .next()
+        .ok_or_else(|| Error::Handler("malformed HTTP request line".to_string()))?
+        .to_string();
+    let path = parts
+        .next()
+        .ok_or_else(|| Error::Handler("malformed HTTP request line".to_string()))?
+        .to_string();
+    Ok((method, path))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pforge_config::{ForgeMetadata, ParamSchema, ToolDef, TransportType};
+    use async_trait::async_trait;
+    use pforge_config::{ForgeMetadata, ParamSchema, ToolDef, TransportTuning, TransportType};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
 
     fn create_test_config() -> ForgeConfig {
         ForgeConfig {
@@ -150,12 +1043,18 @@ mod tests {
                 name: "test-server".to_string(),
                 version: "0.1.0".to_string(),
                 transport: TransportType::Stdio,
+                transport_tuning: TransportTuning::default(),
                 optimization: pforge_config::OptimizationLevel::Debug,
+                shutdown_timeout_ms: 30_000,
+                slow_request_timeout_ms: None,
+                validate_output: false,
             },
             tools: vec![],
             resources: vec![],
             prompts: vec![],
+            aliases: HashMap::new(),
             state: None,
+            auth: None,
         }
     }
 
@@ -228,6 +1127,22 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_register_handlers_registers_output_validator_when_enabled() {
+        let mut config = create_test_config();
+        config.forge.validate_output = true;
+
+        let server = McpServer::new(config);
+        let result = server.register_handlers().await;
+
+        assert!(result.is_ok());
+        let registry = server.registry.read().await;
+        let input = serde_json::to_vec(&serde_json::json!({})).unwrap();
+        // health_check takes no input, so a schema-matching dispatch through
+        // the (now registered) OutputValidator middleware still succeeds.
+        assert!(registry.dispatch("health_check", &input).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_registry_access() {
         let config = create_test_config();
@@ -255,6 +1170,24 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_register_handlers_registers_prompts() {
+        let mut config = create_test_config();
+        config.prompts.push(pforge_config::PromptDef {
+            name: "greeting".to_string(),
+            description: "A greeting prompt".to_string(),
+            template: "Hello, {{name}}!".to_string(),
+            arguments: std::collections::HashMap::new(),
+        });
+
+        let server = McpServer::new(config);
+        server.register_handlers().await.unwrap();
+
+        let prompts = server.prompts();
+        let prompts = prompts.read().await;
+        assert_eq!(prompts.list_prompts(), vec!["greeting"]);
+    }
+
     #[tokio::test]
     async fn test_server_with_multiple_tools() {
         let mut config = create_test_config();
@@ -284,4 +1217,248 @@ mod tests {
         let result = server.register_handlers().await;
         assert!(result.is_ok());
     }
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct DoubleInput {
+        value: i32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct DoubleOutput {
+        result: i32,
+    }
+
+    struct DoubleHandler;
+
+    #[async_trait]
+    impl crate::Handler for DoubleHandler {
+        type Input = DoubleInput;
+        type Output = DoubleOutput;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            Ok(DoubleOutput {
+                result: input.value * 2,
+            })
+        }
+    }
+
+    fn registry_with_double_handler() -> Arc<RwLock<HandlerRegistry>> {
+        let mut registry = HandlerRegistry::new();
+        registry.register("double", DoubleHandler);
+        Arc::new(RwLock::new(registry))
+    }
+
+    fn test_manifest() -> CapabilityManifest {
+        CapabilityManifest {
+            tools: vec![],
+            resources: vec![],
+            prompts: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_jsonrpc_routes_to_registered_handler() {
+        let registry = registry_with_double_handler();
+        let manifest = test_manifest();
+        let negotiated_version = Mutex::new(None);
+        let admin_metrics = AdminMetrics::new();
+        let request = r#"{"jsonrpc":"2.0","method":"double","params":{"value":21},"id":1}"#;
+
+        let response: Value = serde_json::from_str(
+            &dispatch_jsonrpc(
+                &registry,
+                &manifest,
+                &negotiated_version,
+                request,
+                &admin_metrics,
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["result"], 42);
+        assert!(response.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_jsonrpc_unknown_method_errors() {
+        let registry = registry_with_double_handler();
+        let manifest = test_manifest();
+        let negotiated_version = Mutex::new(None);
+        let admin_metrics = AdminMetrics::new();
+        let request = r#"{"jsonrpc":"2.0","method":"missing","params":{},"id":"abc"}"#;
+
+        let response: Value = serde_json::from_str(
+            &dispatch_jsonrpc(
+                &registry,
+                &manifest,
+                &negotiated_version,
+                request,
+                &admin_metrics,
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert_eq!(response["id"], "abc");
+        assert!(response.get("error").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_jsonrpc_parse_error() {
+        let registry = registry_with_double_handler();
+        let manifest = test_manifest();
+        let negotiated_version = Mutex::new(None);
+        let admin_metrics = AdminMetrics::new();
+
+        let response: Value = serde_json::from_str(
+            &dispatch_jsonrpc(
+                &registry,
+                &manifest,
+                &negotiated_version,
+                "not json",
+                &admin_metrics,
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert_eq!(response["error"]["code"], -32700);
+        assert_eq!(response["id"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_jsonrpc_missing_method_is_invalid_request() {
+        let registry = registry_with_double_handler();
+        let manifest = test_manifest();
+        let negotiated_version = Mutex::new(None);
+        let admin_metrics = AdminMetrics::new();
+        let request = r#"{"jsonrpc":"2.0","params":{},"id":2}"#;
+
+        let response: Value = serde_json::from_str(
+            &dispatch_jsonrpc(
+                &registry,
+                &manifest,
+                &negotiated_version,
+                request,
+                &admin_metrics,
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert_eq!(response["error"]["code"], -32600);
+    }
+
+    #[test]
+    fn test_parse_request_line() {
+        let (method, path) = parse_request_line("POST /rpc?session=sse-0 HTTP/1.1\r\n").unwrap();
+        assert_eq!(method, "POST");
+        assert_eq!(path, "/rpc?session=sse-0");
+    }
+
+    #[test]
+    fn test_parse_request_line_malformed() {
+        assert!(parse_request_line("\r\n").is_err());
+    }
+
+    struct SlowHandler;
+
+    #[async_trait]
+    impl crate::Handler for SlowHandler {
+        type Input = DoubleInput;
+        type Output = DoubleOutput;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(DoubleOutput {
+                result: input.value,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_jsonrpc_bounded_times_out_slow_request() {
+        let mut registry = HandlerRegistry::new();
+        registry.register("slow", SlowHandler);
+        let registry = Arc::new(RwLock::new(registry));
+        let manifest = test_manifest();
+        let negotiated_version = Mutex::new(None);
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let admin_metrics = AdminMetrics::new();
+
+        let request = r#"{"jsonrpc":"2.0","method":"slow","params":{"value":1},"id":7}"#;
+        let response: Value = serde_json::from_str(
+            &dispatch_jsonrpc_bounded(
+                &registry,
+                &manifest,
+                &negotiated_version,
+                request,
+                Some(5),
+                &in_flight,
+                &admin_metrics,
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert_eq!(response["error"]["code"], -32001);
+        assert_eq!(response["id"], 7);
+        assert_eq!(in_flight.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_jsonrpc_bounded_within_timeout_succeeds() {
+        let registry = registry_with_double_handler();
+        let manifest = test_manifest();
+        let negotiated_version = Mutex::new(None);
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let admin_metrics = AdminMetrics::new();
+
+        let request = r#"{"jsonrpc":"2.0","method":"double","params":{"value":5},"id":1}"#;
+        let response: Value = serde_json::from_str(
+            &dispatch_jsonrpc_bounded(
+                &registry,
+                &manifest,
+                &negotiated_version,
+                request,
+                Some(5_000),
+                &in_flight,
+                &admin_metrics,
+            )
+            .await,
+        )
+        .unwrap();
+
+        assert_eq!(response["result"]["result"], 10);
+        assert_eq!(in_flight.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_drain_returns_once_in_flight_hits_zero() {
+        let in_flight = Arc::new(AtomicU64::new(1));
+
+        let counter = in_flight.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            counter.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        let start = tokio::time::Instant::now();
+        wait_for_drain(&in_flight, 5_000).await;
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(in_flight.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_drain_gives_up_after_timeout() {
+        let in_flight = Arc::new(AtomicU64::new(1));
+        wait_for_drain(&in_flight, 10).await;
+        // Forces exit rather than hanging forever; the counter is left
+        // non-zero since nothing actually drained.
+        assert_eq!(in_flight.load(Ordering::SeqCst), 1);
+    }
 }
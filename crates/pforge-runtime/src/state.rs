@@ -1,6 +1,11 @@
 use crate::{Error, Result};
 use async_trait::async_trait;
-use std::time::Duration;
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// State management trait
 #[async_trait]
@@ -16,41 +21,258 @@ pub trait StateManager: Send + Sync {
 
     /// Check if key exists
     async fn exists(&self, key: &str) -> Result<bool>;
+
+    /// Every stored key beginning with `prefix`, with its value - listing
+    /// namespace-style state (e.g. all `session:*` entries) that single-key
+    /// lookups can't do. Expired entries are excluded. No generic default
+    /// is possible (the trait has no way to enumerate keys), so every
+    /// backend implements this against whatever native scan it has.
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>>;
+
+    /// Look up several keys at once. Default implementation issues one
+    /// `get` per key sequentially; backends with a native batch fetch
+    /// (Redis `MGET`, Postgres `WHERE key = ANY(...)`) override this.
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<(String, Option<Vec<u8>>)>> {
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            results.push((key.clone(), self.get(key).await?));
+        }
+        Ok(results)
+    }
+
+    /// Write several entries at once. Default implementation issues one
+    /// `set` per item sequentially; backends that can batch the writes
+    /// into a single transaction or pipeline override this.
+    async fn set_many(&self, items: Vec<(String, Vec<u8>, Option<Duration>)>) -> Result<()> {
+        for (key, value, ttl) in items {
+            self.set(&key, value, ttl).await?;
+        }
+        Ok(())
+    }
+}
+
+/// On-disk envelope for a stored value, CBOR-encoded before being written to
+/// an embedded store (and, when encryption is enabled, before being sealed).
+///
+/// `expires_at_millis` is an absolute deadline (Unix epoch millis) rather
+/// than the TTL duration itself, so an on-disk entry remains correctly
+/// expirable after a process restart regardless of how long the process
+/// was down.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEntry {
+    value: Vec<u8>,
+    expires_at_millis: Option<u64>,
+}
+
+impl StoredEntry {
+    fn new(value: Vec<u8>, ttl: Option<Duration>) -> Self {
+        Self {
+            value,
+            expires_at_millis: ttl.map(|d| now_millis() + d.as_millis() as u64),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at_millis
+            .is_some_and(|deadline| now_millis() >= deadline)
+    }
 }
 
-/// Sled-backed state manager
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// How often the background reaper tasks sweep [`SledStateManager`] and
+/// [`MemoryStateManager`] for expired-but-not-yet-read entries. Lazy
+/// expiry on `get`/`exists` is what keeps correctness independent of this
+/// running at all - this just keeps dead keys from accumulating in stores
+/// that are rarely read back.
+const REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+const NONCE_LEN: usize = 12;
+
+/// Sled-backed state manager.
+///
+/// Values are serialized as CBOR rather than JSON for compactness. When
+/// constructed with an encryption key via [`SledStateManager::with_encryption_key`],
+/// every entry is additionally sealed with ChaCha20-Poly1305: a fresh random
+/// nonce is generated per write and prepended to the ciphertext, and the
+/// requested key is authenticated as associated data so an entry written
+/// under one key cannot be swapped in under another.
 pub struct SledStateManager {
     db: sled::Db,
+    cipher: Option<ChaCha20Poly1305>,
+    /// Periodic sweep for expired entries, so a store that's rarely read
+    /// back doesn't just accumulate dead keys forever. Aborted on `Drop` -
+    /// lazy expiry on `get`/`exists` is what actually guarantees
+    /// correctness, this is cleanup only.
+    reaper: tokio::task::JoinHandle<()>,
 }
 
 impl SledStateManager {
     pub fn new(path: &str) -> Result<Self> {
         let db = sled::open(path).map_err(|e| Error::Handler(format!("Sled open failed: {}", e)))?;
-        Ok(Self { db })
+        let reaper = spawn_sled_reaper(db.clone(), None);
+        Ok(Self {
+            db,
+            cipher: None,
+            reaper,
+        })
+    }
+
+    /// Construct a manager that seals every value at rest with
+    /// ChaCha20-Poly1305 under `encryption_key`.
+    pub fn with_encryption_key(path: &str, encryption_key: [u8; 32]) -> Result<Self> {
+        let db = sled::open(path).map_err(|e| Error::Handler(format!("Sled open failed: {}", e)))?;
+        let cipher = ChaCha20Poly1305::new((&encryption_key).into());
+        let reaper = spawn_sled_reaper(db.clone(), Some(cipher.clone()));
+        Ok(Self {
+            db,
+            cipher: Some(cipher),
+            reaper,
+        })
+    }
+
+    fn encode_entry(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<Vec<u8>> {
+        let entry = StoredEntry::new(value, ttl);
+        let plaintext = serde_cbor::to_vec(&entry)
+            .map_err(|e| Error::Handler(format!("CBOR encode failed: {}", e)))?;
+
+        let Some(cipher) = &self.cipher else {
+            return Ok(plaintext);
+        };
+
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                Payload {
+                    msg: &plaintext,
+                    aad: key.as_bytes(),
+                },
+            )
+            .map_err(|_| Error::Decryption("failed to seal entry".to_string()))?;
+
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce_bytes);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    fn decode_entry(&self, key: &str, raw: Vec<u8>) -> Result<StoredEntry> {
+        decode_sled_entry(self.cipher.as_ref(), key, raw)
+    }
+}
+
+/// Shared by [`SledStateManager::decode_entry`] and the reaper task spawned
+/// by [`spawn_sled_reaper`], which needs to decode entries without holding
+/// a `&SledStateManager` (it outlives any single borrow of one).
+fn decode_sled_entry(
+    cipher: Option<&ChaCha20Poly1305>,
+    key: &str,
+    raw: Vec<u8>,
+) -> Result<StoredEntry> {
+    let plaintext = match cipher {
+        Some(cipher) => {
+            if raw.len() < NONCE_LEN {
+                return Err(Error::Decryption("entry shorter than nonce".to_string()));
+            }
+            let (nonce_bytes, ciphertext) = raw.split_at(NONCE_LEN);
+            cipher
+                .decrypt(
+                    Nonce::from_slice(nonce_bytes),
+                    Payload {
+                        msg: ciphertext,
+                        aad: key.as_bytes(),
+                    },
+                )
+                .map_err(|_| {
+                    Error::Decryption(
+                        "authentication failed: wrong key or corrupted entry".to_string(),
+                    )
+                })?
+        }
+        None => raw,
+    };
+
+    serde_cbor::from_slice(&plaintext)
+        .map_err(|e| Error::Handler(format!("CBOR decode failed: {}", e)))
+}
+
+/// Periodically scans every key in `db` and removes entries whose
+/// `StoredEntry::is_expired` is true, so deleted-by-TTL data doesn't just
+/// sit on disk until something happens to `get` it. Entries that fail to
+/// decode (e.g. sealed under a different key than `cipher`) are left alone
+/// - that's not this task's problem to diagnose.
+fn spawn_sled_reaper(
+    db: sled::Db,
+    cipher: Option<ChaCha20Poly1305>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REAP_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let expired: Vec<sled::IVec> = db
+                .iter()
+                .filter_map(|item| item.ok())
+                .filter_map(|(key, raw)| {
+                    let key_str = String::from_utf8_lossy(&key).into_owned();
+                    let entry = decode_sled_entry(cipher.as_ref(), &key_str, raw.to_vec()).ok()?;
+                    entry.is_expired().then_some(key)
+                })
+                .collect();
+
+            for key in expired {
+                let _ = db.remove(key);
+            }
+        }
+    })
+}
+
+impl Drop for SledStateManager {
+    fn drop(&mut self) {
+        self.reaper.abort();
     }
 }
 
 #[async_trait]
 impl StateManager for SledStateManager {
     async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
-        let value = self
+        let raw = self
             .db
             .get(key)
             .map_err(|e| Error::Handler(format!("Sled get failed: {}", e)))?;
 
-        Ok(value.map(|v| v.to_vec()))
+        match raw {
+            Some(bytes) => {
+                let entry = self.decode_entry(key, bytes.to_vec())?;
+                if entry.is_expired() {
+                    self.delete(key).await?;
+                    Ok(None)
+                } else {
+                    Ok(Some(entry.value))
+                }
+            }
+            None => Ok(None),
+        }
     }
 
-    async fn set(&self, key: &str, value: Vec<u8>, _ttl: Option<Duration>) -> Result<()> {
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let encoded = self.encode_entry(key, value, ttl)?;
+
         self.db
-            .insert(key, value)
+            .insert(key, encoded)
             .map_err(|e| Error::Handler(format!("Sled insert failed: {}", e)))?;
 
         self.db
             .flush()
             .map_err(|e| Error::Handler(format!("Sled flush failed: {}", e)))?;
 
-        // TODO: Implement TTL with background task
         Ok(())
     }
 
@@ -62,24 +284,533 @@ impl StateManager for SledStateManager {
     }
 
     async fn exists(&self, key: &str) -> Result<bool> {
-        let exists = self
+        // Route through `get` rather than `contains_key` so an
+        // already-expired-but-not-yet-reaped entry reports absent and gets
+        // lazily swept, instead of reporting a phantom hit.
+        Ok(self.get(key).await?.is_some())
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let mut results = Vec::new();
+        for item in self.db.scan_prefix(prefix) {
+            let (key, raw) =
+                item.map_err(|e| Error::Handler(format!("Sled scan failed: {}", e)))?;
+            let key_str = String::from_utf8_lossy(&key).into_owned();
+            let entry = self.decode_entry(&key_str, raw.to_vec())?;
+            if !entry.is_expired() {
+                results.push((key_str, entry.value));
+            }
+        }
+        Ok(results)
+    }
+
+    async fn set_many(&self, items: Vec<(String, Vec<u8>, Option<Duration>)>) -> Result<()> {
+        for (key, value, ttl) in items {
+            let encoded = self.encode_entry(&key, value, ttl)?;
+            self.db
+                .insert(key, encoded)
+                .map_err(|e| Error::Handler(format!("Sled insert failed: {}", e)))?;
+        }
+        // One flush for the whole batch, rather than one per item the way
+        // `set` does it - that's the actual win of a "batch write" here.
+        self.db
+            .flush()
+            .map_err(|e| Error::Handler(format!("Sled flush failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// `redb`-backed state manager: an alternative embedded on-disk store to
+/// [`SledStateManager`] for deployments that prefer redb's single-file,
+/// ACID-transactional storage engine. Shares the same CBOR `StoredEntry`
+/// envelope and TTL semantics.
+pub struct RedbStateManager {
+    db: redb::Database,
+}
+
+const STATE_TABLE: redb::TableDefinition<&str, &[u8]> = redb::TableDefinition::new("pforge_state");
+
+impl RedbStateManager {
+    pub fn new(path: &str) -> Result<Self> {
+        let db =
+            redb::Database::create(path).map_err(|e| Error::Handler(format!("redb open failed: {}", e)))?;
+        Ok(Self { db })
+    }
+}
+
+#[async_trait]
+impl StateManager for RedbStateManager {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .map_err(|e| Error::Handler(format!("redb begin_read failed: {}", e)))?;
+        let table = match read_txn.open_table(STATE_TABLE) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(Error::Handler(format!("redb open_table failed: {}", e))),
+        };
+
+        let raw = table
+            .get(key)
+            .map_err(|e| Error::Handler(format!("redb get failed: {}", e)))?
+            .map(|v| v.value().to_vec());
+        drop(table);
+        drop(read_txn);
+
+        match raw {
+            Some(bytes) => {
+                let entry: StoredEntry = serde_cbor::from_slice(&bytes)
+                    .map_err(|e| Error::Handler(format!("CBOR decode failed: {}", e)))?;
+                if entry.is_expired() {
+                    self.delete(key).await?;
+                    Ok(None)
+                } else {
+                    Ok(Some(entry.value))
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let entry = StoredEntry::new(value, ttl);
+        let encoded = serde_cbor::to_vec(&entry)
+            .map_err(|e| Error::Handler(format!("CBOR encode failed: {}", e)))?;
+
+        let write_txn = self
+            .db
+            .begin_write()
+            .map_err(|e| Error::Handler(format!("redb begin_write failed: {}", e)))?;
+        {
+            let mut table = write_txn
+                .open_table(STATE_TABLE)
+                .map_err(|e| Error::Handler(format!("redb open_table failed: {}", e)))?;
+            table
+                .insert(key, encoded.as_slice())
+                .map_err(|e| Error::Handler(format!("redb insert failed: {}", e)))?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| Error::Handler(format!("redb commit failed: {}", e)))?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .map_err(|e| Error::Handler(format!("redb begin_write failed: {}", e)))?;
+        {
+            let mut table = write_txn
+                .open_table(STATE_TABLE)
+                .map_err(|e| Error::Handler(format!("redb open_table failed: {}", e)))?;
+            table
+                .remove(key)
+                .map_err(|e| Error::Handler(format!("redb remove failed: {}", e)))?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| Error::Handler(format!("redb commit failed: {}", e)))?;
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.get(key).await?.is_some())
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let read_txn = self
             .db
-            .contains_key(key)
-            .map_err(|e| Error::Handler(format!("Sled contains_key failed: {}", e)))?;
-        Ok(exists)
+            .begin_read()
+            .map_err(|e| Error::Handler(format!("redb begin_read failed: {}", e)))?;
+        let table = match read_txn.open_table(STATE_TABLE) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(Error::Handler(format!("redb open_table failed: {}", e))),
+        };
+
+        let mut results = Vec::new();
+        let range = table
+            .range(prefix..)
+            .map_err(|e| Error::Handler(format!("redb range failed: {}", e)))?;
+        for item in range {
+            let (k, v) = item.map_err(|e| Error::Handler(format!("redb range item failed: {}", e)))?;
+            let key_str = k.value().to_string();
+            // Keys are stored in lexicographic order, so once one no longer
+            // starts with `prefix` nothing further in the range will either.
+            if !key_str.starts_with(prefix) {
+                break;
+            }
+            let entry: StoredEntry = serde_cbor::from_slice(v.value())
+                .map_err(|e| Error::Handler(format!("CBOR decode failed: {}", e)))?;
+            if !entry.is_expired() {
+                results.push((key_str, entry.value));
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Redis-backed state manager, for deployments that already run a shared
+/// Redis/Valkey instance and want `StateManager` entries visible across
+/// multiple pforge server processes rather than pinned to one disk. TTL is
+/// enforced by Redis itself via `SET ... EX` in whole seconds (the TTL is
+/// rounded down, with a minimum of one second), so there's no
+/// lazy-expiry bookkeeping needed on the read path like the embedded stores.
+/// Connections are pooled via `deadpool` rather than opened per call, since
+/// a shared Redis instance is exactly the case where connection setup cost
+/// would otherwise dominate a simple `get`/`set`.
+#[cfg(feature = "redis")]
+pub struct RedisStateManager {
+    pool: deadpool_redis::Pool,
+}
+
+#[cfg(feature = "redis")]
+impl RedisStateManager {
+    pub fn new(redis_url: &str, pool_size: usize) -> Result<Self> {
+        let mut config = deadpool_redis::Config::from_url(redis_url);
+        config.pool = Some(deadpool_redis::PoolConfig::new(pool_size));
+        let pool = config
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .map_err(|e| Error::Handler(format!("Redis pool init failed: {}", e)))?;
+        Ok(Self { pool })
+    }
+
+    async fn connection(&self) -> Result<deadpool_redis::Connection> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| Error::Handler(format!("Redis pool checkout failed: {}", e)))
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl StateManager for RedisStateManager {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        use redis::AsyncCommands;
+        let mut conn = self.connection().await?;
+        conn.get(key)
+            .await
+            .map_err(|e| Error::Handler(format!("Redis GET failed: {}", e)))
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        use redis::AsyncCommands;
+        let mut conn = self.connection().await?;
+        match ttl {
+            Some(ttl) => {
+                let _: () = conn
+                    .set_ex(key, value, ttl.as_secs().max(1))
+                    .await
+                    .map_err(|e| Error::Handler(format!("Redis SET EX failed: {}", e)))?;
+            }
+            None => {
+                let _: () = conn
+                    .set(key, value)
+                    .await
+                    .map_err(|e| Error::Handler(format!("Redis SET failed: {}", e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        use redis::AsyncCommands;
+        let mut conn = self.connection().await?;
+        let _: () = conn
+            .del(key)
+            .await
+            .map_err(|e| Error::Handler(format!("Redis DEL failed: {}", e)))?;
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        use redis::AsyncCommands;
+        let mut conn = self.connection().await?;
+        conn.exists(key)
+            .await
+            .map_err(|e| Error::Handler(format!("Redis EXISTS failed: {}", e)))
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        use redis::AsyncCommands;
+        let mut conn = self.connection().await?;
+        // `KEYS` is O(N) over the whole keyspace and blocks the server
+        // while it runs - fine for the namespace-sized scans this is meant
+        // for, but a deployment with a huge keyspace should favor `SCAN`.
+        let keys: Vec<String> = conn
+            .keys(format!("{prefix}*"))
+            .await
+            .map_err(|e| Error::Handler(format!("Redis KEYS failed: {}", e)))?;
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let values: Vec<Option<Vec<u8>>> = conn
+            .mget(&keys)
+            .await
+            .map_err(|e| Error::Handler(format!("Redis MGET failed: {}", e)))?;
+        Ok(keys
+            .into_iter()
+            .zip(values)
+            .filter_map(|(k, v)| v.map(|v| (k, v)))
+            .collect())
+    }
+
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<(String, Option<Vec<u8>>)>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        use redis::AsyncCommands;
+        let mut conn = self.connection().await?;
+        let values: Vec<Option<Vec<u8>>> = conn
+            .mget(keys)
+            .await
+            .map_err(|e| Error::Handler(format!("Redis MGET failed: {}", e)))?;
+        Ok(keys.iter().cloned().zip(values).collect())
+    }
+
+    async fn set_many(&self, items: Vec<(String, Vec<u8>, Option<Duration>)>) -> Result<()> {
+        use redis::AsyncCommands;
+        let mut conn = self.connection().await?;
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for (key, value, ttl) in &items {
+            match ttl {
+                Some(ttl) => {
+                    pipe.set_ex(key, value, ttl.as_secs().max(1));
+                }
+                None => {
+                    pipe.set(key, value);
+                }
+            }
+        }
+        let _: () = pipe
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| Error::Handler(format!("Redis pipeline SET failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Postgres-backed state manager, for deployments that want shared state on
+/// infrastructure they already run a database on rather than standing up a
+/// Redis instance just for this. Values live in a single `kv` table; TTL is
+/// enforced natively via the nullable `expires_at` column rather than
+/// lazy-expiry bookkeeping like the embedded stores use.
+#[cfg(feature = "postgres")]
+pub struct PostgresStateManager {
+    pool: deadpool_postgres::Pool,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresStateManager {
+    pub fn new(url: &str, pool_size: usize) -> Result<Self> {
+        let pg_config: tokio_postgres::Config = url
+            .parse()
+            .map_err(|e| Error::Handler(format!("Postgres URL parse failed: {}", e)))?;
+        let manager_config = deadpool_postgres::ManagerConfig {
+            recycling_method: deadpool_postgres::RecyclingMethod::Fast,
+        };
+        let manager =
+            deadpool_postgres::Manager::from_config(pg_config, tokio_postgres::NoTls, manager_config);
+        let pool = deadpool_postgres::Pool::builder(manager)
+            .max_size(pool_size)
+            .build()
+            .map_err(|e| Error::Handler(format!("Postgres pool init failed: {}", e)))?;
+        Ok(Self { pool })
+    }
+
+    async fn connection(&self) -> Result<deadpool_postgres::Client> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres pool checkout failed: {}", e)))
+    }
+
+    /// Create the `kv` table if it doesn't already exist. Idempotent, so
+    /// [`StateBackend::connect`] can call it unconditionally every time it
+    /// builds a `Postgres` backend.
+    async fn ensure_schema(&self) -> Result<()> {
+        let conn = self.connection().await?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS kv (
+                key TEXT PRIMARY KEY,
+                value BYTEA NOT NULL,
+                expires_at TIMESTAMPTZ NULL
+            )",
+            &[],
+        )
+        .await
+        .map_err(|e| Error::Handler(format!("Postgres schema init failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl StateManager for PostgresStateManager {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let conn = self.connection().await?;
+        let row = conn
+            .query_opt(
+                "SELECT value FROM kv WHERE key = $1 AND (expires_at IS NULL OR expires_at > now())",
+                &[&key],
+            )
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres SELECT failed: {}", e)))?;
+        Ok(row.map(|row| row.get::<_, Vec<u8>>("value")))
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let conn = self.connection().await?;
+        let expires_at = ttl.map(|ttl| SystemTime::now() + ttl);
+        conn.execute(
+            "INSERT INTO kv (key, value, expires_at) VALUES ($1, $2, $3)
+             ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at",
+            &[&key, &value, &expires_at],
+        )
+        .await
+        .map_err(|e| Error::Handler(format!("Postgres UPSERT failed: {}", e)))?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let conn = self.connection().await?;
+        conn.execute("DELETE FROM kv WHERE key = $1", &[&key])
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres DELETE failed: {}", e)))?;
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.get(key).await?.is_some())
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let conn = self.connection().await?;
+        let pattern = format!("{}%", escape_like_pattern(prefix));
+        let rows = conn
+            .query(
+                "SELECT key, value FROM kv WHERE key LIKE $1 AND (expires_at IS NULL OR expires_at > now())",
+                &[&pattern],
+            )
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres scan failed: {}", e)))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect())
+    }
+
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<(String, Option<Vec<u8>>)>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.connection().await?;
+        let rows = conn
+            .query(
+                "SELECT key, value FROM kv WHERE key = ANY($1) AND (expires_at IS NULL OR expires_at > now())",
+                &[&keys],
+            )
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres batch SELECT failed: {}", e)))?;
+        let mut found: std::collections::HashMap<String, Vec<u8>> = rows
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect();
+        Ok(keys.iter().map(|k| (k.clone(), found.remove(k))).collect())
+    }
+
+    async fn set_many(&self, items: Vec<(String, Vec<u8>, Option<Duration>)>) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let txn = conn
+            .transaction()
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres begin transaction failed: {}", e)))?;
+        for (key, value, ttl) in &items {
+            let expires_at = (*ttl).map(|ttl| SystemTime::now() + ttl);
+            txn.execute(
+                "INSERT INTO kv (key, value, expires_at) VALUES ($1, $2, $3)
+                 ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at",
+                &[key, value, &expires_at],
+            )
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres batch UPSERT failed: {}", e)))?;
+        }
+        txn.commit()
+            .await
+            .map_err(|e| Error::Handler(format!("Postgres commit failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Escape `LIKE` wildcards in a literal prefix before appending pforge's
+/// own trailing `%`, so a key containing `%` or `_` is matched literally
+/// rather than as a pattern.
+#[cfg(feature = "postgres")]
+fn escape_like_pattern(prefix: &str) -> String {
+    prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Selects which [`StateManager`] backend a deployment runs against, so
+/// handlers can be pointed at shared state purely through config rather
+/// than swapping which concrete type they construct.
+#[derive(Debug, Clone)]
+pub enum StateBackend {
+    Memory,
+    Sled {
+        path: String,
+    },
+    #[cfg(feature = "redis")]
+    Redis {
+        url: String,
+        pool_size: usize,
+    },
+    #[cfg(feature = "postgres")]
+    Postgres {
+        url: String,
+        pool_size: usize,
+    },
+}
+
+impl StateBackend {
+    /// Build the backend's `StateManager` and hand it back type-erased,
+    /// since callers generally want "a state manager", not the specific
+    /// concrete type this variant happens to be.
+    pub async fn connect(&self) -> Result<Arc<dyn StateManager>> {
+        match self {
+            StateBackend::Memory => Ok(Arc::new(MemoryStateManager::new())),
+            StateBackend::Sled { path } => Ok(Arc::new(SledStateManager::new(path)?)),
+            #[cfg(feature = "redis")]
+            StateBackend::Redis { url, pool_size } => {
+                Ok(Arc::new(RedisStateManager::new(url, *pool_size)?))
+            }
+            #[cfg(feature = "postgres")]
+            StateBackend::Postgres { url, pool_size } => {
+                let manager = PostgresStateManager::new(url, *pool_size)?;
+                manager.ensure_schema().await?;
+                Ok(Arc::new(manager))
+            }
+        }
     }
 }
 
 /// In-memory state manager for testing
 pub struct MemoryStateManager {
-    store: dashmap::DashMap<String, Vec<u8>>,
+    store: std::sync::Arc<dashmap::DashMap<String, (Vec<u8>, Option<std::time::Instant>)>>,
+    /// Periodic sweep for expired entries - the same belt-and-suspenders
+    /// cleanup `SledStateManager` does alongside lazy expiry on
+    /// `get`/`exists`.
+    reaper: tokio::task::JoinHandle<()>,
 }
 
 impl MemoryStateManager {
     pub fn new() -> Self {
-        Self {
-            store: dashmap::DashMap::new(),
-        }
+        let store = std::sync::Arc::new(dashmap::DashMap::new());
+        let reaper = spawn_memory_reaper(store.clone());
+        Self { store, reaper }
     }
 }
 
@@ -89,15 +820,48 @@ impl Default for MemoryStateManager {
     }
 }
 
+impl Drop for MemoryStateManager {
+    fn drop(&mut self) {
+        self.reaper.abort();
+    }
+}
+
+/// Periodically walks the `DashMap` and removes entries past their
+/// deadline - the same belt-and-suspenders cleanup [`spawn_sled_reaper`]
+/// does for [`SledStateManager`].
+fn spawn_memory_reaper(
+    store: std::sync::Arc<dashmap::DashMap<String, (Vec<u8>, Option<std::time::Instant>)>>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REAP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = std::time::Instant::now();
+            store.retain(|_, (_, deadline)| !deadline.is_some_and(|d| now >= d));
+        }
+    })
+}
+
 #[async_trait]
 impl StateManager for MemoryStateManager {
     async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
-        Ok(self.store.get(key).map(|v| v.clone()))
+        let Some(entry) = self.store.get(key) else {
+            return Ok(None);
+        };
+        let (value, deadline) = entry.clone();
+        drop(entry);
+
+        if deadline.is_some_and(|d| std::time::Instant::now() >= d) {
+            self.store.remove(key);
+            Ok(None)
+        } else {
+            Ok(Some(value))
+        }
     }
 
-    async fn set(&self, key: &str, value: Vec<u8>, _ttl: Option<Duration>) -> Result<()> {
-        self.store.insert(key.to_string(), value);
-        // TODO: Implement TTL with tokio::time
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let deadline = ttl.map(|d| std::time::Instant::now() + d);
+        self.store.insert(key.to_string(), (value, deadline));
         Ok(())
     }
 
@@ -107,7 +871,18 @@ impl StateManager for MemoryStateManager {
     }
 
     async fn exists(&self, key: &str) -> Result<bool> {
-        Ok(self.store.contains_key(key))
+        Ok(self.get(key).await?.is_some())
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let now = std::time::Instant::now();
+        Ok(self
+            .store
+            .iter()
+            .filter(|entry| entry.key().starts_with(prefix))
+            .filter(|entry| !entry.value().1.is_some_and(|d| now >= d))
+            .map(|entry| (entry.key().clone(), entry.value().0.clone()))
+            .collect())
     }
 }
 
@@ -150,4 +925,215 @@ mod tests {
         let value = state.get("key1").await.unwrap();
         assert_eq!(value, Some(b"value1".to_vec()));
     }
+
+    #[tokio::test]
+    async fn test_sled_state_encrypted_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let key = [7u8; 32];
+        let state =
+            SledStateManager::with_encryption_key(temp_dir.path().to_str().unwrap(), key).unwrap();
+
+        state.set("key1", b"value1".to_vec(), None).await.unwrap();
+        let value = state.get("key1").await.unwrap();
+        assert_eq!(value, Some(b"value1".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_sled_state_encrypted_rejects_wrong_key() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().to_str().unwrap();
+
+        let writer = SledStateManager::with_encryption_key(path, [1u8; 32]).unwrap();
+        writer.set("key1", b"value1".to_vec(), None).await.unwrap();
+        drop(writer);
+
+        let reader = SledStateManager::with_encryption_key(path, [2u8; 32]).unwrap();
+        let err = reader.get("key1").await.unwrap_err();
+        assert!(matches!(err, Error::Decryption(_)));
+    }
+
+    #[tokio::test]
+    async fn test_sled_state_encrypted_entry_bound_to_key_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state =
+            SledStateManager::with_encryption_key(temp_dir.path().to_str().unwrap(), [3u8; 32])
+                .unwrap();
+
+        state.set("key1", b"value1".to_vec(), None).await.unwrap();
+        let sealed = state.db.get("key1").unwrap().unwrap().to_vec();
+
+        // Swapping the ciphertext under a different key name must fail AAD
+        // authentication rather than silently decrypting.
+        let err = state.decode_entry("key2", sealed).unwrap_err();
+        assert!(matches!(err, Error::Decryption(_)));
+    }
+
+    #[tokio::test]
+    async fn test_memory_state_ttl_expiry() {
+        let state = MemoryStateManager::new();
+
+        state
+            .set("key1", b"value1".to_vec(), Some(Duration::from_millis(20)))
+            .await
+            .unwrap();
+        assert_eq!(state.get("key1").await.unwrap(), Some(b"value1".to_vec()));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(state.get("key1").await.unwrap(), None);
+        assert!(!state.exists("key1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sled_state_ttl_expiry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = SledStateManager::new(temp_dir.path().to_str().unwrap()).unwrap();
+
+        state
+            .set("key1", b"value1".to_vec(), Some(Duration::from_millis(20)))
+            .await
+            .unwrap();
+        assert_eq!(state.get("key1").await.unwrap(), Some(b"value1".to_vec()));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(state.get("key1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_redb_state_basic() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("state.redb");
+        let state = RedbStateManager::new(path.to_str().unwrap()).unwrap();
+
+        // Set and get
+        state.set("key1", b"value1".to_vec(), None).await.unwrap();
+        let value = state.get("key1").await.unwrap();
+        assert_eq!(value, Some(b"value1".to_vec()));
+
+        // Exists / delete
+        assert!(state.exists("key1").await.unwrap());
+        state.delete("key1").await.unwrap();
+        assert!(!state.exists("key1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_redb_state_ttl_expiry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("state.redb");
+        let state = RedbStateManager::new(path.to_str().unwrap()).unwrap();
+
+        state
+            .set("key1", b"value1".to_vec(), Some(Duration::from_millis(20)))
+            .await
+            .unwrap();
+        assert_eq!(state.get("key1").await.unwrap(), Some(b"value1".to_vec()));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(state.get("key1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_state_scan_prefix_excludes_other_namespaces_and_expired() {
+        let state = MemoryStateManager::new();
+
+        state
+            .set("session:a", b"1".to_vec(), None)
+            .await
+            .unwrap();
+        state
+            .set("session:b", b"2".to_vec(), Some(Duration::from_millis(20)))
+            .await
+            .unwrap();
+        state.set("other:c", b"3".to_vec(), None).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        let mut results = state.scan_prefix("session:").await.unwrap();
+        results.sort();
+        assert_eq!(results, vec![("session:a".to_string(), b"1".to_vec())]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_state_get_many_and_set_many() {
+        let state = MemoryStateManager::new();
+
+        state
+            .set_many(vec![
+                ("a".to_string(), b"1".to_vec(), None),
+                ("b".to_string(), b"2".to_vec(), None),
+            ])
+            .await
+            .unwrap();
+
+        let results = state
+            .get_many(&["a".to_string(), "b".to_string(), "missing".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![
+                ("a".to_string(), Some(b"1".to_vec())),
+                ("b".to_string(), Some(b"2".to_vec())),
+                ("missing".to_string(), None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sled_state_scan_prefix() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = SledStateManager::new(temp_dir.path().to_str().unwrap()).unwrap();
+
+        state
+            .set("session:a", b"1".to_vec(), None)
+            .await
+            .unwrap();
+        state
+            .set("session:b", b"2".to_vec(), None)
+            .await
+            .unwrap();
+        state.set("other:c", b"3".to_vec(), None).await.unwrap();
+
+        let mut results = state.scan_prefix("session:").await.unwrap();
+        results.sort();
+        assert_eq!(
+            results,
+            vec![
+                ("session:a".to_string(), b"1".to_vec()),
+                ("session:b".to_string(), b"2".to_vec()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sled_state_set_many() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = SledStateManager::new(temp_dir.path().to_str().unwrap()).unwrap();
+
+        state
+            .set_many(vec![
+                ("a".to_string(), b"1".to_vec(), None),
+                ("b".to_string(), b"2".to_vec(), None),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(state.get("a").await.unwrap(), Some(b"1".to_vec()));
+        assert_eq!(state.get("b").await.unwrap(), Some(b"2".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_redb_state_scan_prefix() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("state.redb");
+        let state = RedbStateManager::new(path.to_str().unwrap()).unwrap();
+
+        state
+            .set("session:a", b"1".to_vec(), None)
+            .await
+            .unwrap();
+        state.set("other:c", b"3".to_vec(), None).await.unwrap();
+
+        let results = state.scan_prefix("session:").await.unwrap();
+        assert_eq!(results, vec![("session:a".to_string(), b"1".to_vec())]);
+    }
 }
@@ -30,10 +30,79 @@
 //! # }
 //! ```
 
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime};
 
+/// How many [`RequestRecord`]s [`MetricsCollector`] keeps around - oldest
+/// dropped first once full, so per-request history stays bounded memory
+/// regardless of how long the server has been running.
+const MAX_RECENT_RECORDS: usize = 256;
+
+/// One dispatch, in the "when/took" shape latency telemetry conventionally
+/// uses: a Unix-epoch timestamp plus a duration, here paired with which
+/// tool it was and whether it failed.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RequestRecord {
+    /// Unix epoch seconds the request was recorded at.
+    pub when: f64,
+    /// Dispatch latency in milliseconds.
+    pub took: u64,
+    pub tool: String,
+    pub failed: bool,
+}
+
+/// Upper bounds (in microseconds) of the finite latency histogram buckets
+/// `MetricsCollector` tracks per tool; observations above the last bound
+/// fall into an implicit `+Inf` bucket. Chosen to span "fast in-process
+/// handler" (sub-millisecond) through "slow network call" (multi-second)
+/// without needing per-deployment tuning.
+const LATENCY_BUCKET_BOUNDS_MICROS: [u64; 5] = [1_000, 10_000, 100_000, 1_000_000, 10_000_000];
+
+/// Non-cumulative per-bucket observation counts: one slot per finite bound
+/// in [`LATENCY_BUCKET_BOUNDS_MICROS`] plus one for the `+Inf` overflow
+/// bucket.
+type LatencyHistogram = [AtomicU64; LATENCY_BUCKET_BOUNDS_MICROS.len() + 1];
+
+/// `service.name`/`service.version` resource attributes an OTLP export
+/// tags every metric with, so a collector ingesting multiple servers can
+/// tell them apart. Typically built from `ForgeMetadata::name`/`version`.
+#[derive(Debug, Clone)]
+pub struct OtlpResource {
+    pub service_name: String,
+    pub service_version: String,
+}
+
+impl OtlpResource {
+    pub fn new(service_name: impl Into<String>, service_version: impl Into<String>) -> Self {
+        Self {
+            service_name: service_name.into(),
+            service_version: service_version.into(),
+        }
+    }
+}
+
+/// Where and how often [`MetricsCollector::spawn_otlp_exporter`] pushes
+/// metrics to an OTLP collector.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Base URL of the OTLP HTTP/JSON receiver, e.g. `http://localhost:4318`
+    /// - `/v1/metrics` is appended automatically.
+    pub endpoint: String,
+    pub resource: OtlpResource,
+    pub export_interval: Duration,
+}
+
+fn bucket_index(micros: u64) -> usize {
+    LATENCY_BUCKET_BOUNDS_MICROS
+        .iter()
+        .position(|&bound| micros <= bound)
+        .unwrap_or(LATENCY_BUCKET_BOUNDS_MICROS.len())
+}
+
 /// Prometheus-compatible metrics collector
 #[derive(Clone)]
 pub struct MetricsCollector {
@@ -43,6 +112,11 @@ pub struct MetricsCollector {
     error_counts: Arc<dashmap::DashMap<String, AtomicU64>>,
     /// Request latencies (sum in microseconds)
     latency_sums: Arc<dashmap::DashMap<String, AtomicU64>>,
+    /// Request latency histogram, by tool name
+    latency_histograms: Arc<dashmap::DashMap<String, LatencyHistogram>>,
+    /// Most recent individual requests, across all tools, oldest first -
+    /// bounded to [`MAX_RECENT_RECORDS`].
+    recent_records: Arc<Mutex<VecDeque<RequestRecord>>>,
     /// Server start time
     start_time: Arc<Instant>,
 }
@@ -54,6 +128,8 @@ impl MetricsCollector {
             request_counts: Arc::new(dashmap::DashMap::new()),
             error_counts: Arc::new(dashmap::DashMap::new()),
             latency_sums: Arc::new(dashmap::DashMap::new()),
+            latency_histograms: Arc::new(dashmap::DashMap::new()),
+            recent_records: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_RECENT_RECORDS))),
             start_time: Arc::new(Instant::now()),
         }
     }
@@ -73,6 +149,11 @@ impl MetricsCollector {
             .or_insert_with(|| AtomicU64::new(0))
             .fetch_add(micros, Ordering::Relaxed);
 
+        self.latency_histograms
+            .entry(tool.to_string())
+            .or_insert_with(|| std::array::from_fn(|_| AtomicU64::new(0)))[bucket_index(micros)]
+            .fetch_add(1, Ordering::Relaxed);
+
         // Record error if applicable
         if !success {
             self.error_counts
@@ -80,6 +161,32 @@ impl MetricsCollector {
                 .or_insert_with(|| AtomicU64::new(0))
                 .fetch_add(1, Ordering::Relaxed);
         }
+
+        let when = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let mut records = self.recent_records.lock().expect("recent_records poisoned");
+        if records.len() >= MAX_RECENT_RECORDS {
+            records.pop_front();
+        }
+        records.push_back(RequestRecord {
+            when,
+            took: latency.as_millis() as u64,
+            tool: tool.to_string(),
+            failed: !success,
+        });
+    }
+
+    /// The most recent individual requests recorded, oldest first, capped
+    /// at [`MAX_RECENT_RECORDS`].
+    pub fn recent_records(&self) -> Vec<RequestRecord> {
+        self.recent_records
+            .lock()
+            .expect("recent_records poisoned")
+            .iter()
+            .cloned()
+            .collect()
     }
 
     /// Get total request count for a tool
@@ -114,6 +221,49 @@ impl MetricsCollector {
         Some(sum as f64 / count as f64)
     }
 
+    /// Estimate the `q`th percentile (0.0..=1.0, e.g. `0.99` for p99) of
+    /// `tool`'s recorded latency in microseconds. Walks the cumulative
+    /// bucket counts to find the bucket containing the `q * count`-th
+    /// sample, then linearly interpolates within that bucket's bounds -
+    /// the same approximation Prometheus's `histogram_quantile` makes over
+    /// a bucketed histogram, since individual sample values aren't kept.
+    /// Returns `None` if `tool` has no recorded requests.
+    pub fn get_percentile(&self, tool: &str, q: f64) -> Option<f64> {
+        let histogram = self.latency_histograms.get(tool)?;
+        let counts: Vec<u64> = histogram
+            .value()
+            .iter()
+            .map(|c| c.load(Ordering::Relaxed))
+            .collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return None;
+        }
+
+        let target = ((q.clamp(0.0, 1.0) * total as f64).ceil() as u64).max(1);
+
+        let mut cumulative = 0u64;
+        let mut lower_bound = 0u64;
+        for (i, &count) in counts.iter().enumerate() {
+            let upper_bound = LATENCY_BUCKET_BOUNDS_MICROS.get(i).copied();
+            cumulative += count;
+            if target <= cumulative {
+                return Some(match upper_bound {
+                    Some(bound) => {
+                        let within = (target - (cumulative - count)) as f64 / count.max(1) as f64;
+                        lower_bound as f64 + within * (bound - lower_bound) as f64
+                    }
+                    // The `+Inf` bucket has no upper bound to interpolate
+                    // against; report its lower edge.
+                    None => lower_bound as f64,
+                });
+            }
+            lower_bound = upper_bound.unwrap_or(lower_bound);
+        }
+
+        unreachable!("cumulative bucket counts for tool '{}' must reach its total", tool)
+    }
+
     /// Get error rate (0.0 to 1.0) for a tool
     pub fn get_error_rate(&self, tool: &str) -> f64 {
         let total = self.get_request_count(tool);
@@ -158,9 +308,30 @@ impl MetricsCollector {
             ));
         }
 
-        // Latency metric
-        output.push_str("# HELP pforge_latency_microseconds_sum Sum of request latencies\n");
-        output.push_str("# TYPE pforge_latency_microseconds_sum counter\n");
+        // Latency histogram: buckets, then the `_sum`/`_count` series that
+        // complete the standard Prometheus histogram shape.
+        output.push_str("# HELP pforge_latency_microseconds Request latency in microseconds\n");
+        output.push_str("# TYPE pforge_latency_microseconds histogram\n");
+        for entry in self.latency_histograms.iter() {
+            let tool = entry.key();
+            let mut cumulative = 0u64;
+            for (i, bound) in LATENCY_BUCKET_BOUNDS_MICROS.iter().enumerate() {
+                cumulative += entry.value()[i].load(Ordering::Relaxed);
+                output.push_str(&format!(
+                    "pforge_latency_microseconds_bucket{{tool=\"{}\",le=\"{}\"}} {}\n",
+                    tool, bound, cumulative
+                ));
+            }
+            cumulative += entry.value()[LATENCY_BUCKET_BOUNDS_MICROS.len()].load(Ordering::Relaxed);
+            output.push_str(&format!(
+                "pforge_latency_microseconds_bucket{{tool=\"{}\",le=\"+Inf\"}} {}\n",
+                tool, cumulative
+            ));
+            output.push_str(&format!(
+                "pforge_latency_microseconds_count{{tool=\"{}\"}} {}\n",
+                tool, cumulative
+            ));
+        }
         for entry in self.latency_sums.iter() {
             let sum = entry.value().load(Ordering::Relaxed);
             output.push_str(&format!(
@@ -181,6 +352,139 @@ impl MetricsCollector {
         output
     }
 
+    /// Render this collector's counters, latency histograms, and uptime
+    /// gauge as an OTLP/HTTP-JSON `ExportMetricsServiceRequest` body (see
+    /// the [OTLP JSON mapping](https://github.com/open-telemetry/opentelemetry-proto/blob/main/docs/specification.md)),
+    /// tagged with `resource`'s `service.name`/`service.version`
+    /// attributes. Used both by [`MetricsCollector::spawn_otlp_exporter`]'s
+    /// periodic push and by on-demand `otlp`-format reads of the same
+    /// snapshot [`MetricsCollector::export_prometheus`]/[`MetricsCollector::export_json`]
+    /// already expose.
+    pub fn export_otlp_json(&self, resource: &OtlpResource) -> serde_json::Value {
+        let now_nanos = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            .to_string();
+
+        let mut request_points = Vec::new();
+        for entry in self.request_counts.iter() {
+            request_points.push(serde_json::json!({
+                "attributes": [{"key": "tool", "value": {"stringValue": entry.key()}}],
+                "timeUnixNano": now_nanos,
+                "asInt": entry.value().load(Ordering::Relaxed).to_string(),
+            }));
+        }
+
+        let mut error_points = Vec::new();
+        for entry in self.error_counts.iter() {
+            error_points.push(serde_json::json!({
+                "attributes": [{"key": "tool", "value": {"stringValue": entry.key()}}],
+                "timeUnixNano": now_nanos,
+                "asInt": entry.value().load(Ordering::Relaxed).to_string(),
+            }));
+        }
+
+        let mut histogram_points = Vec::new();
+        for entry in self.latency_histograms.iter() {
+            let tool = entry.key();
+            let bucket_counts: Vec<u64> = entry
+                .value()
+                .iter()
+                .map(|c| c.load(Ordering::Relaxed))
+                .collect();
+            let count: u64 = bucket_counts.iter().sum();
+            let sum = self
+                .latency_sums
+                .get(tool)
+                .map(|v| v.load(Ordering::Relaxed))
+                .unwrap_or(0);
+
+            histogram_points.push(serde_json::json!({
+                "attributes": [{"key": "tool", "value": {"stringValue": tool}}],
+                "timeUnixNano": now_nanos,
+                "count": count.to_string(),
+                "sum": sum as f64,
+                "bucketCounts": bucket_counts.iter().map(u64::to_string).collect::<Vec<_>>(),
+                "explicitBounds": LATENCY_BUCKET_BOUNDS_MICROS,
+            }));
+        }
+
+        serde_json::json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": [
+                        {"key": "service.name", "value": {"stringValue": resource.service_name}},
+                        {"key": "service.version", "value": {"stringValue": resource.service_version}},
+                    ]
+                },
+                "scopeMetrics": [{
+                    "scope": {"name": "pforge"},
+                    "metrics": [
+                        {
+                            "name": "pforge_requests_total",
+                            "unit": "1",
+                            "sum": {
+                                "dataPoints": request_points,
+                                "aggregationTemporality": "AGGREGATION_TEMPORALITY_CUMULATIVE",
+                                "isMonotonic": true,
+                            },
+                        },
+                        {
+                            "name": "pforge_errors_total",
+                            "unit": "1",
+                            "sum": {
+                                "dataPoints": error_points,
+                                "aggregationTemporality": "AGGREGATION_TEMPORALITY_CUMULATIVE",
+                                "isMonotonic": true,
+                            },
+                        },
+                        {
+                            "name": "pforge_latency_microseconds",
+                            "unit": "us",
+                            "histogram": {
+                                "dataPoints": histogram_points,
+                                "aggregationTemporality": "AGGREGATION_TEMPORALITY_CUMULATIVE",
+                            },
+                        },
+                        {
+                            "name": "pforge_uptime_seconds",
+                            "unit": "s",
+                            "gauge": {
+                                "dataPoints": [{
+                                    "timeUnixNano": now_nanos,
+                                    "asInt": self.uptime_seconds().to_string(),
+                                }],
+                            },
+                        },
+                    ],
+                }],
+            }],
+        })
+    }
+
+    /// Spawn a background task that calls [`MetricsCollector::export_otlp_json`]
+    /// and HTTP-POSTs it to `config.endpoint`'s `/v1/metrics` path every
+    /// `config.export_interval`, until the returned handle is aborted
+    /// (dropping the handle detaches the task; it keeps running). A collector that's unreachable or rejects a push is logged
+    /// and otherwise ignored - metrics export should never take an MCP
+    /// server down.
+    pub fn spawn_otlp_exporter(self: &Arc<Self>, config: OtlpConfig) -> tokio::task::JoinHandle<()> {
+        let collector = Arc::clone(self);
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let url = format!("{}/v1/metrics", config.endpoint.trim_end_matches('/'));
+            let mut ticker = tokio::time::interval(config.export_interval);
+            loop {
+                ticker.tick().await;
+                let payload = collector.export_otlp_json(&config.resource);
+                if let Err(e) = client.post(&url).json(&payload).send().await {
+                    tracing::warn!("OTLP export to {} failed: {}", url, e);
+                }
+            }
+        })
+    }
+
     /// Get metrics summary as JSON
     pub fn export_json(&self) -> serde_json::Value {
         let mut tools = serde_json::Map::new();
@@ -201,13 +505,22 @@ impl MetricsCollector {
             if let Some(latency) = avg_latency {
                 tool_data.insert("avg_latency_micros".to_string(), serde_json::json!(latency));
             }
+            for (field, q) in [("p50", 0.5), ("p95", 0.95), ("p99", 0.99)] {
+                if let Some(percentile) = self.get_percentile(tool, q) {
+                    tool_data.insert(
+                        format!("{}_latency_micros", field),
+                        serde_json::json!(percentile),
+                    );
+                }
+            }
 
             tools.insert(tool.clone(), serde_json::Value::Object(tool_data));
         }
 
         serde_json::json!({
             "uptime_seconds": self.uptime_seconds(),
-            "tools": tools
+            "tools": tools,
+            "recent_requests": self.recent_records()
         })
     }
 }
@@ -219,7 +532,7 @@ impl Default for MetricsCollector {
 }
 
 /// Health check status
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
 pub enum HealthStatus {
     /// Service is healthy and ready
     Healthy,
@@ -372,22 +685,931 @@ impl Default for HealthCheck {
     }
 }
 
-/// Telemetry middleware for automatic metrics collection
+/// Outcome of a single [`HealthProbe::check`] invocation: a status plus an
+/// optional human-readable detail (e.g. why a check is degraded).
+#[derive(Debug, Clone)]
+pub struct HealthCheckOutcome {
+    pub status: HealthStatus,
+    pub message: Option<String>,
+}
+
+impl HealthCheckOutcome {
+    pub fn healthy() -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            message: None,
+        }
+    }
+
+    pub fn degraded(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Degraded,
+            message: Some(message.into()),
+        }
+    }
+
+    pub fn unhealthy(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Unhealthy,
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// An active health probe a component runs on demand, as opposed to
+/// [`HealthCheck`]'s push model of externally-set component status. Named
+/// `HealthProbe` rather than `HealthCheck` to avoid colliding with that
+/// existing type, which the `telemetry-server` example already exposes as
+/// public API.
+#[async_trait::async_trait]
+pub trait HealthProbe: Send + Sync {
+    async fn check(&self) -> HealthCheckOutcome;
+}
+
+/// Per-component detail in a [`HealthReport`].
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ComponentReport {
+    pub name: String,
+    pub critical: bool,
+    pub status: HealthStatus,
+    pub message: Option<String>,
+    /// Unix epoch seconds of the last time this probe's `check()` actually
+    /// completed (whatever status it reported) rather than timing out or
+    /// panicking - `None` if it has never once completed. Epoch seconds
+    /// (matching [`RequestRecord::when`]) rather than `SystemTime` itself,
+    /// since `schemars` has no `JsonSchema` impl for the latter.
+    pub last_success: Option<f64>,
+}
+
+impl ComponentReport {
+    /// Seconds since [`ComponentReport::last_success`], as of `now` -
+    /// `None` if the probe has never completed.
+    pub fn staleness_secs(&self, now: SystemTime) -> Option<f64> {
+        let now_secs = now
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        self.last_success.map(|last| (now_secs - last).max(0.0))
+    }
+}
+
+/// Result of running every probe in a [`HealthRegistry`].
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub components: Vec<ComponentReport>,
+}
+
+struct RegisteredProbe {
+    probe: Arc<dyn HealthProbe>,
+    critical: bool,
+    /// `None` means the probe may run as long as it likes; `Some(d)` means
+    /// a `check()` that hasn't returned within `d` is treated as
+    /// [`HealthStatus::Unhealthy`] rather than left hanging.
+    timeout: Option<Duration>,
+    /// Epoch seconds of the last `check()` that actually completed
+    /// (timed-out or panicked runs don't update this), read back into
+    /// [`ComponentReport::last_success`].
+    last_success: Arc<Mutex<Option<f64>>>,
+}
+
+/// How long [`HealthRegistry::get_liveness`] allows the async scheduler to
+/// respond to a trivial yielded task before concluding the event loop
+/// itself is stuck.
+const LIVENESS_DEADLINE: Duration = Duration::from_millis(500);
+
+/// Registry of named [`HealthProbe`]s that [`McpServer`](crate::server::McpServer)
+/// owns alongside its handler registry. [`run`](Self::run) executes every
+/// registered probe concurrently and aggregates the results: the overall
+/// status is [`HealthStatus::Unhealthy`] if any *critical* probe reports
+/// unhealthy, [`HealthStatus::Degraded`] if any probe (critical or not)
+/// reports degraded or a non-critical probe reports unhealthy, and
+/// [`HealthStatus::Healthy`] otherwise.
+///
+/// Distinguishes two separate Kubernetes-style questions: **readiness**
+/// ([`get_readiness`](Self::get_readiness)/[`export_readiness_json`](Self::export_readiness_json))
+/// reflects dependency health as of the last [`run`](Self::run) - either
+/// called on demand or, more usually, on the interval
+/// [`spawn`](Self::spawn) ticks on in the background, so a probe hitting a
+/// slow dependency doesn't block every readiness check. **Liveness**
+/// ([`get_liveness`](Self::get_liveness)/[`export_liveness_json`](Self::export_liveness_json))
+/// is narrower and cheaper: it only asks whether the process's own async
+/// scheduler is still making progress, independent of any dependency.
+#[derive(Clone, Default)]
+pub struct HealthRegistry {
+    probes: Arc<dashmap::DashMap<String, RegisteredProbe>>,
+    /// Cached result of the most recent [`run`](Self::run) call, read by
+    /// [`get_readiness`](Self::get_readiness) so a readiness probe hit
+    /// never blocks on a live dependency check.
+    latest_report: Arc<Mutex<Option<HealthReport>>>,
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a named probe with no deadline. `critical` controls
+    /// whether this probe failing unhealthy drags the whole server
+    /// unhealthy, or merely degraded.
+    pub fn register(&self, name: impl Into<String>, probe: impl HealthProbe + 'static, critical: bool) {
+        self.register_inner(name.into(), Arc::new(probe), critical, None);
+    }
+
+    /// Register a named probe that's treated as [`HealthStatus::Unhealthy`]
+    /// if `check()` hasn't returned within `timeout`, instead of blocking
+    /// [`run`](Self::run) indefinitely on one slow dependency.
+    pub fn register_with_timeout(
+        &self,
+        name: impl Into<String>,
+        probe: impl HealthProbe + 'static,
+        critical: bool,
+        timeout: Duration,
+    ) {
+        self.register_inner(name.into(), Arc::new(probe), critical, Some(timeout));
+    }
+
+    fn register_inner(
+        &self,
+        name: String,
+        probe: Arc<dyn HealthProbe>,
+        critical: bool,
+        timeout: Option<Duration>,
+    ) {
+        self.probes.insert(
+            name,
+            RegisteredProbe {
+                probe,
+                critical,
+                timeout,
+                last_success: Arc::new(Mutex::new(None)),
+            },
+        );
+    }
+
+    pub fn unregister(&self, name: &str) {
+        self.probes.remove(name);
+    }
+
+    /// Run every registered probe concurrently, enforcing each one's
+    /// timeout, and aggregate the result - also caching it for
+    /// [`get_readiness`](Self::get_readiness) to read back without
+    /// re-running anything.
+    pub async fn run(&self) -> HealthReport {
+        let tasks: Vec<_> = self
+            .probes
+            .iter()
+            .map(|entry| {
+                let name = entry.key().clone();
+                let critical = entry.value().critical;
+                let probe = entry.value().probe.clone();
+                let timeout = entry.value().timeout;
+                let last_success = Arc::clone(&entry.value().last_success);
+                tokio::spawn(async move {
+                    let (outcome, completed) = match timeout {
+                        Some(deadline) => match tokio::time::timeout(deadline, probe.check()).await {
+                            Ok(outcome) => (outcome, true),
+                            Err(_) => (
+                                HealthCheckOutcome::unhealthy(format!(
+                                    "probe exceeded its {:?} deadline",
+                                    deadline
+                                )),
+                                false,
+                            ),
+                        },
+                        None => (probe.check().await, true),
+                    };
+
+                    if completed {
+                        let now = SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs_f64();
+                        *last_success.lock().expect("last_success poisoned") = Some(now);
+                    }
+
+                    let last_success = *last_success.lock().expect("last_success poisoned");
+                    (name, critical, outcome, last_success)
+                })
+            })
+            .collect();
+
+        let mut components = Vec::with_capacity(tasks.len());
+        let mut status = HealthStatus::Healthy;
+        let mut has_degraded = false;
+
+        for task in tasks {
+            let (name, critical, outcome, last_success) = match task.await {
+                Ok(result) => result,
+                Err(e) => (
+                    "<panicked probe>".to_string(),
+                    true,
+                    HealthCheckOutcome::unhealthy(format!("probe task panicked: {}", e)),
+                    None,
+                ),
+            };
+
+            match outcome.status {
+                HealthStatus::Unhealthy if critical => status = HealthStatus::Unhealthy,
+                HealthStatus::Unhealthy | HealthStatus::Degraded => has_degraded = true,
+                HealthStatus::Healthy => {}
+            }
+
+            components.push(ComponentReport {
+                name,
+                critical,
+                status: outcome.status,
+                message: outcome.message,
+                last_success,
+            });
+        }
+
+        if status != HealthStatus::Unhealthy && has_degraded {
+            status = HealthStatus::Degraded;
+        }
+
+        components.sort_by(|a, b| a.name.cmp(&b.name));
+        let report = HealthReport { status, components };
+
+        *self
+            .latest_report
+            .lock()
+            .expect("latest_report poisoned") = Some(report.clone());
+
+        report
+    }
+
+    /// Spawn a background task that calls [`run`](Self::run) every
+    /// `interval`, until the returned handle is aborted (dropping the handle
+    /// detaches the task; it keeps running). Lets
+    /// [`get_readiness`](Self::get_readiness) stay a cheap cache read
+    /// instead of a live probe run on every Kubernetes readiness hit.
+    pub fn spawn(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                registry.run().await;
+            }
+        })
+    }
+
+    /// The most recent cached [`run`](Self::run) result, or an empty
+    /// `Healthy` report if [`run`](Self::run) has never been called - the
+    /// same "no components registered yet" default [`HealthCheck::get_status`]
+    /// uses.
+    pub fn get_readiness(&self) -> HealthReport {
+        self.latest_report
+            .lock()
+            .expect("latest_report poisoned")
+            .clone()
+            .unwrap_or(HealthReport {
+                status: HealthStatus::Healthy,
+                components: Vec::new(),
+            })
+    }
+
+    /// `(http_status, json)` for a readiness endpoint, reusing
+    /// [`HealthStatus::http_status`] for the status code Kubernetes expects
+    /// (`200` healthy/degraded, `503` unhealthy) and including each
+    /// component's [`ComponentReport::staleness_secs`] alongside its
+    /// status.
+    pub fn export_readiness_json(&self) -> (u16, serde_json::Value) {
+        let report = self.get_readiness();
+        let now = SystemTime::now();
+        let components: Vec<_> = report
+            .components
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "name": c.name,
+                    "critical": c.critical,
+                    "status": format!("{:?}", c.status),
+                    "message": c.message,
+                    "last_success_secs": c.last_success,
+                    "staleness_secs": c.staleness_secs(now),
+                })
+            })
+            .collect();
+
+        (
+            report.status.http_status(),
+            serde_json::json!({
+                "status": format!("{:?}", report.status),
+                "components": components,
+            }),
+        )
+    }
+
+    /// Whether the async scheduler can still respond within
+    /// [`LIVENESS_DEADLINE`] - narrower than readiness, and independent of
+    /// every registered [`HealthProbe`]: a server deadlocked in its own
+    /// event loop fails liveness even if every dependency it talks to is
+    /// healthy.
+    pub async fn get_liveness(&self) -> HealthStatus {
+        match tokio::time::timeout(LIVENESS_DEADLINE, tokio::task::yield_now()).await {
+            Ok(()) => HealthStatus::Healthy,
+            Err(_) => HealthStatus::Unhealthy,
+        }
+    }
+
+    /// `(http_status, json)` for a liveness endpoint.
+    pub async fn export_liveness_json(&self) -> (u16, serde_json::Value) {
+        let status = self.get_liveness().await;
+        (
+            status.http_status(),
+            serde_json::json!({ "status": format!("{:?}", status) }),
+        )
+    }
+}
+
+/// How many [`TelemetryEvent`]s [`TelemetryMiddleware`] buffers before
+/// [`TelemetryMiddleware::flush`] is called - oldest dropped first once
+/// full, the same bounded-ring policy [`MAX_RECENT_RECORDS`] gives
+/// `MetricsCollector::recent_records`.
+const MAX_TELEMETRY_EVENTS: usize = 256;
+
+/// Brackets one dispatch with both a monotonic clock (for an accurate
+/// elapsed duration, immune to wall-clock adjustments) and a wall clock
+/// (since [`Instant`] has no epoch, so it can't be serialized as a
+/// timestamp on its own). [`Stopwatch::start`] captures both; `finish()`
+/// uses the [`Instant`] to compute `took` and the [`SystemTime`] to compute
+/// `when`.
+#[derive(Debug, Clone, Copy)]
+pub enum Stopwatch {
+    Started(SystemTime, Instant),
+    Finished { when: f64, took: Duration },
+}
+
+impl Stopwatch {
+    /// Start timing a dispatch.
+    pub fn start() -> Self {
+        Self::Started(SystemTime::now(), Instant::now())
+    }
+
+    /// Stop the clock, turning a `Started` stopwatch into `Finished`.
+    /// Already-`Finished` stopwatches pass through unchanged rather than
+    /// recomputing `took` from a second, later `Instant`.
+    pub fn finish(self) -> Self {
+        match self {
+            Self::Started(when, instant) => Self::Finished {
+                when: when
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64(),
+                took: instant.elapsed(),
+            },
+            finished @ Self::Finished { .. } => finished,
+        }
+    }
+
+    /// `(when, took)` once [`Stopwatch::finish`] has been called; `None`
+    /// while still `Started`.
+    pub fn reading(&self) -> Option<(f64, Duration)> {
+        match self {
+            Self::Finished { when, took } => Some((*when, *took)),
+            Self::Started(..) => None,
+        }
+    }
+}
+
+fn is_zero_millis(took: &u64) -> bool {
+    *took == 0
+}
+
+/// One finished dispatch, recorded by [`TelemetryMiddleware`] and batched
+/// up for [`TelemetryMiddleware::flush`]. Distinct from
+/// [`MetricsCollector`]'s [`RequestRecord`] - that one backs the
+/// always-on `pforge/metrics` snapshot; this one backs the pluggable
+/// [`TelemetrySink`] export path, and so serializes a bit closer to the
+/// wire (skipping `took` entirely when it rounds down to zero, rather than
+/// always writing a `0`).
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TelemetryEvent {
+    pub tool: String,
+    /// Unix epoch seconds the dispatch started at.
+    pub when: f64,
+    /// Dispatch latency in milliseconds.
+    #[serde(skip_serializing_if = "is_zero_millis")]
+    pub took: u64,
+}
+
+/// Destination for the batches [`TelemetryMiddleware::flush`] hands off,
+/// the same swappable-behavior-through-a-trait shape as
+/// [`crate::recovery::FallbackHandler`]. The crate's only built-in
+/// implementation is [`OtlpHttpSink`]; embedders wanting stdout logs, a
+/// file, or an in-memory test double implement this directly.
+#[async_trait::async_trait]
+pub trait TelemetrySink: Send + Sync {
+    async fn send(&self, batch: serde_json::Value);
+}
+
+/// Posts each [`TelemetryMiddleware::flush`] batch as OTLP/HTTP-JSON logs
+/// to `endpoint`'s `/v1/logs` path - the per-event counterpart to
+/// [`MetricsCollector::spawn_otlp_exporter`]'s periodic aggregate push. A
+/// collector that's unreachable or rejects a batch is logged and otherwise
+/// ignored; telemetry export should never take an MCP server down.
+pub struct OtlpHttpSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl OtlpHttpSink {
+    pub fn new(endpoint: impl AsRef<str>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: format!("{}/v1/logs", endpoint.as_ref().trim_end_matches('/')),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TelemetrySink for OtlpHttpSink {
+    async fn send(&self, batch: serde_json::Value) {
+        if let Err(e) = self.client.post(&self.url).json(&batch).send().await {
+            tracing::warn!("OTLP log export to {} failed: {}", self.url, e);
+        }
+    }
+}
+
+/// Telemetry middleware for automatic metrics collection. Also implements
+/// [`crate::dispatch_middleware::DispatchMiddleware`], so registering it
+/// with [`crate::HandlerRegistry::register_middleware`] wraps every
+/// dispatch in a [`Stopwatch`] and records the resulting [`TelemetryEvent`]
+/// automatically, with no per-handler opt-in.
 pub struct TelemetryMiddleware {
     /// Metrics collector
     collector: MetricsCollector,
+    /// Bounded ring of events since the last [`TelemetryMiddleware::flush`].
+    events: Mutex<VecDeque<TelemetryEvent>>,
+    /// Absent when no sink was configured; `flush()` still drains and
+    /// returns the batch, it just doesn't hand it anywhere.
+    sink: Option<Arc<dyn TelemetrySink>>,
 }
 
 impl TelemetryMiddleware {
-    /// Create new telemetry middleware
+    /// Create new telemetry middleware with no sink - `flush()` drains the
+    /// event buffer into a returned JSON batch without exporting it.
     pub fn new(collector: MetricsCollector) -> Self {
-        Self { collector }
+        Self {
+            collector,
+            events: Mutex::new(VecDeque::with_capacity(MAX_TELEMETRY_EVENTS)),
+            sink: None,
+        }
+    }
+
+    /// Attach a [`TelemetrySink`] so every [`TelemetryMiddleware::flush`]
+    /// also exports the batch, e.g. via [`OtlpHttpSink`].
+    pub fn with_sink(mut self, sink: Arc<dyn TelemetrySink>) -> Self {
+        self.sink = Some(sink);
+        self
     }
 
     /// Get reference to metrics collector
     pub fn collector(&self) -> &MetricsCollector {
         &self.collector
     }
+
+    fn record_event(&self, event: TelemetryEvent) {
+        let mut events = self.events.lock().expect("telemetry events poisoned");
+        if events.len() >= MAX_TELEMETRY_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Drain the buffered events into a `{"events": [...]}` JSON batch and,
+    /// if a [`TelemetrySink`] is attached, hand it off. Returns the batch
+    /// either way, so callers without a sink can still inspect or log it.
+    pub async fn flush(&self) -> serde_json::Value {
+        let events: Vec<TelemetryEvent> = {
+            let mut buf = self.events.lock().expect("telemetry events poisoned");
+            buf.drain(..).collect()
+        };
+        let batch = serde_json::json!({ "events": events });
+        if let Some(sink) = &self.sink {
+            sink.send(batch.clone()).await;
+        }
+        batch
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::dispatch_middleware::DispatchMiddleware for TelemetryMiddleware {
+    async fn handle(
+        &self,
+        tool: &str,
+        params: &[u8],
+        next: crate::dispatch_middleware::Next<'_>,
+    ) -> crate::Result<Vec<u8>> {
+        use tracing::Instrument;
+
+        let request_id = crate::correlation::generate_correlation_id();
+        let span = tracing::info_span!(
+            "dispatch",
+            tool = %tool,
+            request_id = %request_id,
+            latency_us = tracing::field::Empty,
+            error = tracing::field::Empty,
+        );
+
+        let stopwatch = Stopwatch::start();
+        let result = next
+            .call(tool, params)
+            .instrument(span.clone())
+            .await;
+
+        if let Some((when, took)) = stopwatch.finish().reading() {
+            span.record("latency_us", took.as_micros() as u64);
+            if let Err(e) = &result {
+                span.record("error", tracing::field::display(e));
+            }
+            self.collector.record_request(tool, took, result.is_ok());
+            self.record_event(TelemetryEvent {
+                tool: tool.to_string(),
+                when,
+                took: took.as_millis() as u64,
+            });
+        }
+        result
+    }
+}
+
+impl TelemetryMiddleware {
+    /// Register `self` on `registry`'s dispatch-middleware chain, wrapping
+    /// every future `registry.dispatch(...)` call in a tracing span and a
+    /// [`Stopwatch`] automatically - the one-line opt-in this middleware is
+    /// meant to be used through, instead of wiring
+    /// [`crate::dispatch_middleware::DispatchMiddleware`] by hand.
+    pub fn instrument(self: Arc<Self>, registry: &mut crate::HandlerRegistry) {
+        registry.register_middleware(self as Arc<dyn crate::dispatch_middleware::DispatchMiddleware>);
+    }
+}
+
+/// Install a global `tracing-subscriber` pipeline so the `dispatch` spans
+/// [`TelemetryMiddleware`] emits are actually printed (or, with
+/// tokio-console wired in, browsable live). Call once at server startup,
+/// before the first dispatch - a second call panics, matching
+/// `tracing_subscriber::registry()::init`'s own behavior.
+///
+/// Built with `--cfg tokio_unstable` and the `console` feature, this also
+/// spawns [`console_subscriber`]'s layer, so the same per-tool `dispatch`
+/// spans show up in `tokio-console` alongside task scheduling data with no
+/// separate wiring. Without both of those, it falls back to a plain
+/// structured-fmt layer on stdout.
+#[cfg(all(tokio_unstable, feature = "console"))]
+pub fn init_tracing_with_console() {
+    use tracing_subscriber::prelude::*;
+    tracing_subscriber::registry()
+        .with(console_subscriber::spawn())
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+}
+
+#[cfg(not(all(tokio_unstable, feature = "console")))]
+pub fn init_tracing_with_console() {
+    tracing_subscriber::fmt::init();
+}
+
+/// Combines the runtime's independent observability stores - per-tool
+/// invocation counts and latency from [`MetricsCollector`], circuit-breaker
+/// and error-classification state from [`crate::recovery::RecoveryMiddleware`]
+/// - behind one admin surface: a single Prometheus export and a single JSON
+/// snapshot. Reachable via the reserved `pforge/metrics` JSON-RPC method
+/// (stdio, SSE, and WebSocket alike, since all three share
+/// [`crate::server`]'s dispatch path) or the side-channel HTTP endpoint
+/// [`serve_metrics`] exposes.
+#[derive(Clone)]
+pub struct AdminMetrics {
+    pub collector: Arc<MetricsCollector>,
+    /// Absent when the server wasn't configured with circuit-breaker
+    /// recovery; the combined export simply omits that section.
+    pub recovery: Option<Arc<crate::recovery::RecoveryMiddleware>>,
+    /// Absent when the server wasn't configured with host resource
+    /// sampling; the combined export simply omits the `pforge_cpu_usage_ratio`,
+    /// `pforge_memory_bytes`, and `pforge_load_average` series.
+    pub resources: Option<Arc<ResourceCollector>>,
+}
+
+impl AdminMetrics {
+    /// An admin surface over a fresh, empty `MetricsCollector` and no
+    /// recovery or resource state - the default every
+    /// [`crate::server::McpServer`] starts with.
+    pub fn new() -> Self {
+        Self {
+            collector: Arc::new(MetricsCollector::new()),
+            recovery: None,
+            resources: None,
+        }
+    }
+
+    /// Attach circuit-breaker/error-tracker state to the admin surface, so
+    /// its exports include the `pforge_circuit_breaker_*` and
+    /// `pforge_recovery_errors_*` series alongside request counts/latency.
+    pub fn with_recovery(mut self, recovery: Arc<crate::recovery::RecoveryMiddleware>) -> Self {
+        self.recovery = Some(recovery);
+        self
+    }
+
+    /// Attach host resource gauges to the admin surface, so its exports
+    /// include `pforge_cpu_usage_ratio`, `pforge_memory_bytes`, and
+    /// `pforge_load_average` alongside request counts/latency. Callers are
+    /// responsible for calling [`ResourceCollector::spawn`] themselves -
+    /// this only wires an already-sampling (or not-yet-started) collector
+    /// into the combined export.
+    pub fn with_resources(mut self, resources: Arc<ResourceCollector>) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
+    /// Concatenate [`MetricsCollector::export_prometheus`] with
+    /// [`crate::recovery::RecoveryMiddleware::export_prometheus`] and
+    /// [`ResourceCollector::export_prometheus`] (each when attached) into
+    /// one Prometheus text document.
+    pub async fn export_prometheus(&self) -> String {
+        let mut out = self.collector.export_prometheus();
+        if let Some(recovery) = &self.recovery {
+            out.push_str(&recovery.export_prometheus().await);
+        }
+        if let Some(resources) = &self.resources {
+            out.push_str(&resources.export_prometheus());
+        }
+        out
+    }
+
+    /// Merge [`MetricsCollector::export_json`] with
+    /// [`crate::recovery::RecoveryMiddleware::export_json`] and
+    /// [`ResourceCollector::export_json`] (each when attached) into one
+    /// JSON snapshot.
+    pub async fn export_json(&self) -> serde_json::Value {
+        let mut snapshot = self.collector.export_json();
+        if let Some(recovery) = &self.recovery {
+            if let serde_json::Value::Object(map) = &mut snapshot {
+                map.insert("recovery".to_string(), recovery.export_json().await);
+            }
+        }
+        if let Some(resources) = &self.resources {
+            if let serde_json::Value::Object(map) = &mut snapshot {
+                map.insert("resources".to_string(), resources.export_json());
+            }
+        }
+        snapshot
+    }
+}
+
+impl Default for AdminMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Process CPU ticks read from `/proc/self/stat`, Linux's native unit for
+/// this value - kept as ticks (rather than converting to seconds here) so
+/// [`ResourceCollector::sample`] can take an exact integer delta between
+/// two readings before scaling by [`CLK_TCK`].
+fn read_process_cpu_ticks() -> Option<u64> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // The second field (`comm`, the executable name) is parenthesized and
+    // may itself contain spaces or closing parens, so locate the *last*
+    // `)` rather than splitting on whitespace from the start of the line.
+    let after_comm = stat.rsplit_once(')')?.1;
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    // Fields after `comm)` start at overall field 3 (`state`); `utime` is
+    // field 14 and `stime` is field 15, i.e. indices 11 and 12 here.
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// `sysconf(_SC_CLK_TCK)` is 100 on every Linux platform pforge targets;
+/// hard-coding it avoids a `libc` dependency for a single syscall.
+const CLK_TCK: u64 = 100;
+
+/// Resident set size in bytes, read from `/proc/self/status`'s `VmRSS`
+/// line (reported in kB).
+fn read_resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    for line in status.lines() {
+        if let Some(rest) = line.strip_prefix("VmRSS:") {
+            let kb: u64 = rest.trim().trim_end_matches("kB").trim().parse().ok()?;
+            return Some(kb * 1024);
+        }
+    }
+    None
+}
+
+/// 1/5/15-minute load averages from `/proc/loadavg`'s first three fields.
+fn read_load_average() -> Option<(f64, f64, f64)> {
+    let raw = std::fs::read_to_string("/proc/loadavg").ok()?;
+    let mut fields = raw.split_whitespace();
+    let one = fields.next()?.parse().ok()?;
+    let five = fields.next()?.parse().ok()?;
+    let fifteen = fields.next()?.parse().ok()?;
+    Some((one, five, fifteen))
+}
+
+/// Host-level resource gauges, sampled on a background interval by
+/// [`ResourceCollector::spawn`] and exported alongside [`MetricsCollector`]'s
+/// request metrics via [`AdminMetrics::with_resources`]. Reads `/proc`
+/// directly - Linux only, and zero new dependencies - rather than pulling in
+/// a platform-abstraction crate for three numbers.
+///
+/// `std` has no atomic float type, so the `f64` gauges are stored bit-packed
+/// into `AtomicU64` via [`f64::to_bits`]/[`f64::from_bits`]; that's fine here
+/// since every gauge is written at most once per sample interval and read
+/// far more often, on every `/metrics` scrape.
+#[derive(Clone, Default)]
+pub struct ResourceCollector {
+    cpu_usage_ratio: Arc<AtomicU64>,
+    memory_bytes: Arc<AtomicU64>,
+    load_average_1m: Arc<AtomicU64>,
+    load_average_5m: Arc<AtomicU64>,
+    load_average_15m: Arc<AtomicU64>,
+}
+
+impl ResourceCollector {
+    /// A fresh collector whose gauges read as `0.0`/`0` until the first
+    /// sample lands - see [`ResourceCollector::spawn`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process CPU utilization (fraction of one core) since the previous
+    /// sample. `0.0` before the second sample has landed, since a ratio
+    /// needs two points to compute a delta.
+    pub fn cpu_usage_ratio(&self) -> f64 {
+        f64::from_bits(self.cpu_usage_ratio.load(Ordering::Relaxed))
+    }
+
+    /// Resident memory (RSS) in bytes as of the latest sample.
+    pub fn memory_bytes(&self) -> u64 {
+        self.memory_bytes.load(Ordering::Relaxed)
+    }
+
+    /// 1-minute system load average as of the latest sample.
+    pub fn load_average_1m(&self) -> f64 {
+        f64::from_bits(self.load_average_1m.load(Ordering::Relaxed))
+    }
+
+    /// 5-minute system load average as of the latest sample.
+    pub fn load_average_5m(&self) -> f64 {
+        f64::from_bits(self.load_average_5m.load(Ordering::Relaxed))
+    }
+
+    /// 15-minute system load average as of the latest sample.
+    pub fn load_average_15m(&self) -> f64 {
+        f64::from_bits(self.load_average_15m.load(Ordering::Relaxed))
+    }
+
+    fn store_f64(cell: &AtomicU64, value: f64) {
+        cell.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Take one reading, updating every gauge that could be read. `previous`
+    /// carries the (ticks, wall-clock) pair from the last call so CPU
+    /// utilization can be computed as a delta; it's `None` on the very first
+    /// call, which leaves `cpu_usage_ratio` at its default until the second.
+    fn sample(&self, previous: &mut Option<(u64, Instant)>) {
+        let now = Instant::now();
+        if let Some(ticks) = read_process_cpu_ticks() {
+            if let Some((prev_ticks, prev_time)) = previous {
+                let wall_secs = now.duration_since(*prev_time).as_secs_f64();
+                if wall_secs > 0.0 {
+                    let cpu_secs = ticks.saturating_sub(*prev_ticks) as f64 / CLK_TCK as f64;
+                    Self::store_f64(&self.cpu_usage_ratio, cpu_secs / wall_secs);
+                }
+            }
+            *previous = Some((ticks, now));
+        }
+
+        if let Some(rss) = read_resident_memory_bytes() {
+            self.memory_bytes.store(rss, Ordering::Relaxed);
+        }
+
+        if let Some((one, five, fifteen)) = read_load_average() {
+            Self::store_f64(&self.load_average_1m, one);
+            Self::store_f64(&self.load_average_5m, five);
+            Self::store_f64(&self.load_average_15m, fifteen);
+        }
+    }
+
+    /// Spawn a background task that samples host metrics every `interval`
+    /// until the returned handle is aborted (tokio
+    /// tasks keep running if the handle is merely dropped; callers that
+    /// need the sampling loop to stop on shutdown should `abort()` it).
+    pub fn spawn(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let collector = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut previous: Option<(u64, Instant)> = None;
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                collector.sample(&mut previous);
+            }
+        })
+    }
+
+    /// Render the host gauges as Prometheus text exposition format.
+    pub fn export_prometheus(&self) -> String {
+        format!(
+            "# HELP pforge_cpu_usage_ratio Process CPU utilization (fraction of one core) since the last sample\n\
+             # TYPE pforge_cpu_usage_ratio gauge\n\
+             pforge_cpu_usage_ratio {}\n\
+             # HELP pforge_memory_bytes Process resident memory (RSS) in bytes\n\
+             # TYPE pforge_memory_bytes gauge\n\
+             pforge_memory_bytes {}\n\
+             # HELP pforge_load_average System load average\n\
+             # TYPE pforge_load_average gauge\n\
+             pforge_load_average{{window=\"1m\"}} {}\n\
+             pforge_load_average{{window=\"5m\"}} {}\n\
+             pforge_load_average{{window=\"15m\"}} {}\n",
+            self.cpu_usage_ratio(),
+            self.memory_bytes(),
+            self.load_average_1m(),
+            self.load_average_5m(),
+            self.load_average_15m(),
+        )
+    }
+
+    /// Render the host gauges as a JSON snapshot.
+    pub fn export_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "cpu_usage_ratio": self.cpu_usage_ratio(),
+            "memory_bytes": self.memory_bytes(),
+            "load_average": {
+                "1m": self.load_average_1m(),
+                "5m": self.load_average_5m(),
+                "15m": self.load_average_15m(),
+            }
+        })
+    }
+}
+
+/// Serve [`AdminMetrics::export_prometheus`] as `GET /metrics` (or the JSON
+/// snapshot as `GET /metrics?format=json`) on `addr`, reusing the minimal
+/// HTTP/1.1 parsing [`crate::server::McpServer`]'s SSE transport already
+/// hand-rolls rather than pulling in a web framework for one endpoint. Any
+/// `GET` whose path starts with `/metrics` is served (so `/metrics/x` is
+/// too); any other method or path gets a 404. Runs until the listener errors;
+/// intended to be spawned alongside the main MCP transport, not awaited on
+/// its own.
+pub async fn serve_metrics(addr: &str, admin_metrics: AdminMetrics) -> crate::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(crate::Error::Io)?;
+    eprintln!("Metrics endpoint listening on {}", addr);
+
+    loop {
+        let (stream, peer) = listener.accept().await.map_err(crate::Error::Io)?;
+        let admin_metrics = admin_metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_metrics_connection(stream, &admin_metrics).await {
+                eprintln!("metrics connection {} error: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_metrics_connection(
+    stream: tokio::net::TcpStream,
+    admin_metrics: &AdminMetrics,
+) -> crate::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let (mut reader, request_line, _headers) = crate::server::read_http_request_head(stream).await?;
+    let (method, path) = crate::server::parse_request_line(&request_line)?;
+
+    let response = if method == "GET" && path.starts_with("/metrics") {
+        if path.contains("format=json") {
+            let body = admin_metrics.export_json().await.to_string();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        } else {
+            let body = admin_metrics.export_prometheus().await;
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        }
+    } else {
+        let body = "not found";
+        format!(
+            "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    };
+
+    reader
+        .write_all(response.as_bytes())
+        .await
+        .map_err(crate::Error::Io)?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -435,6 +1657,82 @@ mod tests {
         assert!(json["uptime_seconds"].is_u64());
         assert!(json["tools"]["greet"]["requests"].is_u64());
         assert_eq!(json["tools"]["greet"]["requests"], 1);
+        assert_eq!(json["recent_requests"].as_array().unwrap().len(), 1);
+        assert_eq!(json["recent_requests"][0]["tool"], "greet");
+        assert_eq!(json["recent_requests"][0]["failed"], false);
+    }
+
+    #[test]
+    fn test_percentile_none_when_no_requests_recorded() {
+        let collector = MetricsCollector::new();
+        assert_eq!(collector.get_percentile("greet", 0.5), None);
+    }
+
+    #[test]
+    fn test_percentile_interpolates_within_bucket() {
+        let collector = MetricsCollector::new();
+        for _ in 0..4 {
+            collector.record_request("greet", Duration::from_micros(500), true);
+        }
+
+        // All 4 samples land in the first bucket, (0, 1_000]µs: p50 is the
+        // 2nd-of-4 sample, interpolated halfway through the bucket (500µs);
+        // p99 rounds up to the 4th-of-4 sample, at the bucket's far edge
+        // (1_000µs).
+        assert_eq!(collector.get_percentile("greet", 0.5), Some(500.0));
+        assert_eq!(collector.get_percentile("greet", 0.99), Some(1_000.0));
+    }
+
+    #[test]
+    fn test_percentile_is_monotonically_nondecreasing() {
+        let collector = MetricsCollector::new();
+        collector.record_request("greet", Duration::from_micros(50), true);
+        collector.record_request("greet", Duration::from_micros(2_000), true);
+        collector.record_request("greet", Duration::from_micros(500_000), true);
+
+        let p50 = collector.get_percentile("greet", 0.5).unwrap();
+        let p95 = collector.get_percentile("greet", 0.95).unwrap();
+        let p99 = collector.get_percentile("greet", 0.99).unwrap();
+        assert!(p50 <= p95);
+        assert!(p95 <= p99);
+    }
+
+    #[test]
+    fn test_json_export_includes_percentiles() {
+        let collector = MetricsCollector::new();
+        collector.record_request("greet", Duration::from_micros(100), true);
+
+        let json = collector.export_json();
+        assert!(json["tools"]["greet"]["p50_latency_micros"].is_number());
+        assert!(json["tools"]["greet"]["p95_latency_micros"].is_number());
+        assert!(json["tools"]["greet"]["p99_latency_micros"].is_number());
+    }
+
+    #[test]
+    fn test_recent_records_bounded_and_ordered() {
+        let collector = MetricsCollector::new();
+        for i in 0..(MAX_RECENT_RECORDS + 10) {
+            collector.record_request(&format!("tool{i}"), Duration::from_micros(1), true);
+        }
+
+        let records = collector.recent_records();
+        assert_eq!(records.len(), MAX_RECENT_RECORDS);
+        assert_eq!(records.first().unwrap().tool, "tool10");
+        assert_eq!(
+            records.last().unwrap().tool,
+            format!("tool{}", MAX_RECENT_RECORDS + 9)
+        );
+    }
+
+    #[test]
+    fn test_recent_records_mark_failed() {
+        let collector = MetricsCollector::new();
+        collector.record_request("greet", Duration::from_micros(100), false);
+
+        let records = collector.recent_records();
+        assert_eq!(records.len(), 1);
+        assert!(records[0].failed);
+        assert_eq!(records[0].took, 0);
     }
 
     #[test]
@@ -482,4 +1780,432 @@ mod tests {
         health.remove_component("test");
         assert!(health.get_component("test").is_none());
     }
+
+    struct FixedProbe(HealthCheckOutcome);
+
+    #[async_trait::async_trait]
+    impl HealthProbe for FixedProbe {
+        async fn check(&self) -> HealthCheckOutcome {
+            self.0.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_empty_is_healthy() {
+        let registry = HealthRegistry::new();
+        let report = registry.run().await;
+        assert_eq!(report.status, HealthStatus::Healthy);
+        assert!(report.components.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_noncritical_unhealthy_is_degraded() {
+        let registry = HealthRegistry::new();
+        registry.register("cache", FixedProbe(HealthCheckOutcome::unhealthy("down")), false);
+
+        let report = registry.run().await;
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.components.len(), 1);
+        assert_eq!(report.components[0].status, HealthStatus::Unhealthy);
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_critical_unhealthy_is_unhealthy() {
+        let registry = HealthRegistry::new();
+        registry.register("database", FixedProbe(HealthCheckOutcome::unhealthy("down")), true);
+        registry.register("cache", FixedProbe(HealthCheckOutcome::healthy()), false);
+
+        let report = registry.run().await;
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_degraded_probe_degrades_overall() {
+        let registry = HealthRegistry::new();
+        registry.register(
+            "queue",
+            FixedProbe(HealthCheckOutcome::degraded("backlog growing")),
+            true,
+        );
+
+        let report = registry.run().await;
+        assert_eq!(report.status, HealthStatus::Degraded);
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_unregister_removes_probe() {
+        let registry = HealthRegistry::new();
+        registry.register("temp", FixedProbe(HealthCheckOutcome::healthy()), false);
+        registry.unregister("temp");
+
+        let report = registry.run().await;
+        assert!(report.components.is_empty());
+    }
+
+    struct SlowProbe;
+
+    #[async_trait::async_trait]
+    impl HealthProbe for SlowProbe {
+        async fn check(&self) -> HealthCheckOutcome {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            HealthCheckOutcome::healthy()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_health_registry_timeout_marks_unhealthy_without_blocking() {
+        let registry = HealthRegistry::new();
+        registry.register_with_timeout("slow", SlowProbe, true, Duration::from_millis(10));
+
+        let report = registry.run().await;
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+        assert_eq!(report.components[0].status, HealthStatus::Unhealthy);
+        assert!(report.components[0].last_success.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_last_success_recorded_on_completion() {
+        let registry = HealthRegistry::new();
+        registry.register("cache", FixedProbe(HealthCheckOutcome::healthy()), false);
+
+        let report = registry.run().await;
+        let component = &report.components[0];
+        assert!(component.last_success.is_some());
+        let at_last_success =
+            SystemTime::UNIX_EPOCH + Duration::from_secs_f64(component.last_success.unwrap());
+        assert_eq!(component.staleness_secs(at_last_success), Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_get_readiness_is_empty_before_first_run() {
+        let registry = HealthRegistry::new();
+        registry.register("cache", FixedProbe(HealthCheckOutcome::healthy()), false);
+
+        // Never called `run()` - readiness falls back to the empty default.
+        let report = registry.get_readiness();
+        assert!(report.components.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_get_readiness_reflects_last_run() {
+        let registry = HealthRegistry::new();
+        registry.register("cache", FixedProbe(HealthCheckOutcome::unhealthy("down")), false);
+        registry.run().await;
+
+        let report = registry.get_readiness();
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.components.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_export_readiness_json_has_status_code() {
+        let registry = HealthRegistry::new();
+        registry.register("database", FixedProbe(HealthCheckOutcome::unhealthy("down")), true);
+        registry.run().await;
+
+        let (code, json) = registry.export_readiness_json();
+        assert_eq!(code, 503);
+        assert_eq!(json["status"], "Unhealthy");
+        assert!(json["components"][0]["staleness_secs"].is_number());
+    }
+
+    #[tokio::test]
+    async fn test_health_registry_export_liveness_json_is_healthy_by_default() {
+        let registry = HealthRegistry::new();
+        let (code, json) = registry.export_liveness_json().await;
+        assert_eq!(code, 200);
+        assert_eq!(json["status"], "Healthy");
+    }
+
+    #[tokio::test]
+    async fn test_admin_metrics_without_recovery_omits_recovery_section() {
+        let admin_metrics = AdminMetrics::new();
+        admin_metrics
+            .collector
+            .record_request("greet", Duration::from_micros(100), true);
+
+        let prometheus = admin_metrics.export_prometheus().await;
+        assert!(prometheus.contains("pforge_requests_total"));
+        assert!(!prometheus.contains("pforge_recovery_errors_total"));
+
+        let json = admin_metrics.export_json().await;
+        assert_eq!(json["tools"]["greet"]["requests"], 1);
+        assert!(json.get("recovery").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_admin_metrics_combines_collector_and_recovery() {
+        use crate::recovery::RecoveryMiddleware;
+        use std::sync::Arc;
+
+        let recovery = Arc::new(RecoveryMiddleware::new());
+        recovery
+            .error_tracker()
+            .track_error("greet", &crate::Error::Handler("boom".to_string()))
+            .await;
+
+        let admin_metrics = AdminMetrics::new().with_recovery(recovery);
+        admin_metrics
+            .collector
+            .record_request("greet", Duration::from_micros(100), false);
+
+        let prometheus = admin_metrics.export_prometheus().await;
+        assert!(prometheus.contains("pforge_requests_total"));
+        assert!(prometheus.contains("pforge_recovery_errors_total"));
+
+        let json = admin_metrics.export_json().await;
+        assert_eq!(json["tools"]["greet"]["requests"], 1);
+        assert_eq!(json["recovery"]["total_errors"], 1);
+    }
+
+    #[test]
+    fn test_resource_collector_defaults_to_zero_before_any_sample() {
+        let resources = ResourceCollector::new();
+        assert_eq!(resources.cpu_usage_ratio(), 0.0);
+        assert_eq!(resources.memory_bytes(), 0);
+        assert_eq!(resources.load_average_1m(), 0.0);
+    }
+
+    #[test]
+    fn test_resource_collector_sample_populates_memory_and_load_average() {
+        let resources = ResourceCollector::new();
+        let mut previous = None;
+        resources.sample(&mut previous);
+
+        // `/proc` is assumed present (Linux CI); memory/load should be
+        // populated by the very first sample, unlike CPU ratio which needs
+        // a second reading to compute a delta.
+        assert!(resources.memory_bytes() > 0);
+        assert!(resources.load_average_1m() >= 0.0);
+    }
+
+    #[test]
+    fn test_resource_collector_cpu_ratio_needs_two_samples() {
+        let resources = ResourceCollector::new();
+        let mut previous = None;
+        resources.sample(&mut previous);
+        assert_eq!(resources.cpu_usage_ratio(), 0.0);
+
+        std::thread::sleep(Duration::from_millis(10));
+        resources.sample(&mut previous);
+        assert!(resources.cpu_usage_ratio() >= 0.0);
+    }
+
+    #[test]
+    fn test_resource_collector_prometheus_export_contains_all_series() {
+        let resources = ResourceCollector::new();
+        let output = resources.export_prometheus();
+        assert!(output.contains("pforge_cpu_usage_ratio"));
+        assert!(output.contains("pforge_memory_bytes"));
+        assert!(output.contains("pforge_load_average{window=\"1m\"}"));
+        assert!(output.contains("pforge_load_average{window=\"5m\"}"));
+        assert!(output.contains("pforge_load_average{window=\"15m\"}"));
+    }
+
+    #[test]
+    fn test_resource_collector_json_export_shape() {
+        let resources = ResourceCollector::new();
+        let json = resources.export_json();
+        assert!(json["cpu_usage_ratio"].is_number());
+        assert!(json["memory_bytes"].is_number());
+        assert!(json["load_average"]["1m"].is_number());
+        assert!(json["load_average"]["5m"].is_number());
+        assert!(json["load_average"]["15m"].is_number());
+    }
+
+    #[tokio::test]
+    async fn test_admin_metrics_combines_collector_and_resources() {
+        use std::sync::Arc;
+
+        let resources = Arc::new(ResourceCollector::new());
+        let mut previous = None;
+        resources.sample(&mut previous);
+
+        let admin_metrics = AdminMetrics::new().with_resources(Arc::clone(&resources));
+        admin_metrics
+            .collector
+            .record_request("greet", Duration::from_micros(100), true);
+
+        let prometheus = admin_metrics.export_prometheus().await;
+        assert!(prometheus.contains("pforge_requests_total"));
+        assert!(prometheus.contains("pforge_memory_bytes"));
+
+        let json = admin_metrics.export_json().await;
+        assert_eq!(json["tools"]["greet"]["requests"], 1);
+        assert!(json["resources"]["memory_bytes"].is_number());
+    }
+
+    #[test]
+    fn test_stopwatch_finish_computes_elapsed() {
+        let stopwatch = Stopwatch::start();
+        std::thread::sleep(Duration::from_millis(5));
+        let (when, took) = stopwatch.finish().reading().unwrap();
+        assert!(when > 0.0);
+        assert!(took >= Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_stopwatch_reading_is_none_until_finished() {
+        let stopwatch = Stopwatch::start();
+        assert!(stopwatch.reading().is_none());
+    }
+
+    #[test]
+    fn test_stopwatch_finish_is_idempotent() {
+        let stopwatch = Stopwatch::start().finish();
+        let first = stopwatch.reading().unwrap();
+        let second = stopwatch.finish().reading().unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_telemetry_event_serialization_skips_zero_took() {
+        let event = TelemetryEvent {
+            tool: "greet".to_string(),
+            when: 1.0,
+            took: 0,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert!(json.get("took").is_none());
+
+        let event = TelemetryEvent {
+            tool: "greet".to_string(),
+            when: 1.0,
+            took: 5,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["took"], 5);
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    struct TelemetryTestInput {
+        value: i32,
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    struct TelemetryTestOutput {
+        value: i32,
+    }
+
+    struct TelemetryEchoHandler;
+
+    #[async_trait::async_trait]
+    impl crate::Handler for TelemetryEchoHandler {
+        type Input = TelemetryTestInput;
+        type Output = TelemetryTestOutput;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> crate::Result<Self::Output> {
+            Ok(TelemetryTestOutput { value: input.value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_middleware_records_event_per_dispatch() {
+        use crate::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", TelemetryEchoHandler);
+
+        let middleware = Arc::new(TelemetryMiddleware::new(MetricsCollector::new()));
+        registry.register_middleware(Arc::clone(&middleware) as Arc<dyn crate::dispatch_middleware::DispatchMiddleware>);
+
+        let input = serde_json::to_vec(&TelemetryTestInput { value: 1 }).unwrap();
+        registry.dispatch("echo", &input).await.unwrap();
+        registry.dispatch("echo", &input).await.unwrap();
+
+        assert_eq!(middleware.collector().get_request_count("echo"), 2);
+
+        let batch = middleware.flush().await;
+        let events = batch["events"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["tool"], "echo");
+
+        // A second flush with nothing new buffered returns an empty batch.
+        let batch = middleware.flush().await;
+        assert!(batch["events"].as_array().unwrap().is_empty());
+    }
+
+    struct RecordingSink {
+        batches: Arc<Mutex<Vec<serde_json::Value>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TelemetrySink for RecordingSink {
+        async fn send(&self, batch: serde_json::Value) {
+            self.batches.lock().expect("batches poisoned").push(batch);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_middleware_flush_hands_batch_to_sink() {
+        use crate::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", TelemetryEchoHandler);
+
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::new(RecordingSink {
+            batches: Arc::clone(&batches),
+        });
+        let middleware = Arc::new(TelemetryMiddleware::new(MetricsCollector::new()).with_sink(sink));
+        registry.register_middleware(Arc::clone(&middleware) as Arc<dyn crate::dispatch_middleware::DispatchMiddleware>);
+
+        let input = serde_json::to_vec(&TelemetryTestInput { value: 1 }).unwrap();
+        registry.dispatch("echo", &input).await.unwrap();
+        middleware.flush().await;
+
+        let recorded = batches.lock().expect("batches poisoned");
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0]["events"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_middleware_instrument_wires_into_registry() {
+        use crate::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", TelemetryEchoHandler);
+
+        let middleware = Arc::new(TelemetryMiddleware::new(MetricsCollector::new()));
+        Arc::clone(&middleware).instrument(&mut registry);
+
+        let input = serde_json::to_vec(&TelemetryTestInput { value: 1 }).unwrap();
+        registry.dispatch("echo", &input).await.unwrap();
+
+        assert_eq!(middleware.collector().get_request_count("echo"), 1);
+        let batch = middleware.flush().await;
+        assert_eq!(batch["events"].as_array().unwrap().len(), 1);
+    }
+
+    struct FailingHandler;
+
+    #[async_trait::async_trait]
+    impl crate::Handler for FailingHandler {
+        type Input = TelemetryTestInput;
+        type Output = TelemetryTestOutput;
+        type Error = crate::Error;
+
+        async fn handle(&self, _input: Self::Input) -> crate::Result<Self::Output> {
+            Err(crate::Error::Handler("boom".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_middleware_records_failed_dispatch() {
+        use crate::HandlerRegistry;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register("fail", FailingHandler);
+
+        let middleware = Arc::new(TelemetryMiddleware::new(MetricsCollector::new()));
+        Arc::clone(&middleware).instrument(&mut registry);
+
+        let input = serde_json::to_vec(&TelemetryTestInput { value: 1 }).unwrap();
+        assert!(registry.dispatch("fail", &input).await.is_err());
+
+        assert_eq!(middleware.collector().get_error_count("fail"), 1);
+        let batch = middleware.flush().await;
+        assert_eq!(batch["events"].as_array().unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,464 @@
+//! Declarative golden-test runner for MCP tools, modeled on Deno's test
+//! runner: cases are data (tool name, input, expected output or error
+//! substring), not Rust functions, so a suite can be written once in YAML
+//! and exercised by the `pforge test` CLI command without recompiling.
+//!
+//! Cases dispatch through the same [`HandlerRegistry`] every other call
+//! path uses, so a case referencing a `ToolDef::Pipeline` tool runs through
+//! [`crate::handlers::PipelineHandler`] exactly as it would in production -
+//! no special-casing needed here, since the pipeline is itself just another
+//! registered [`crate::Handler`].
+
+use crate::{HandlerRegistry, Result};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// One declarative case: call `tool` with `input` and assert on the result.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+    pub tool: String,
+    #[serde(default = "default_input")]
+    pub input: serde_json::Value,
+    #[serde(default)]
+    pub expected: Option<serde_json::Value>,
+    #[serde(default)]
+    pub expected_error: Option<String>,
+}
+
+fn default_input() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+/// A suite is just a flat list of cases - there's no shared setup/teardown
+/// step, mirroring the fact that every case dispatches independently
+/// through the registry rather than sharing handler state across cases.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestSuite {
+    pub name: String,
+    pub cases: Vec<TestCase>,
+}
+
+/// Options controlling how a [`TestSuite`] is run, set from the `pforge
+/// test` CLI flags.
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// Only run cases whose name contains this substring.
+    pub filter: Option<String>,
+    /// Deterministically reshuffle case order so ordering-dependent bugs
+    /// surface reproducibly instead of only on whichever order the suite
+    /// happens to be declared in.
+    pub shuffle_seed: Option<u64>,
+    /// Maximum number of cases dispatched concurrently.
+    pub concurrency: usize,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            filter: None,
+            shuffle_seed: None,
+            concurrency: 1,
+        }
+    }
+}
+
+/// Outcome of a single case.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseResult {
+    pub name: String,
+    pub tool: String,
+    pub passed: bool,
+    pub duration_micros: u64,
+    /// Set when `passed` is false: what was expected vs. what actually
+    /// happened.
+    pub message: Option<String>,
+}
+
+/// Aggregate report for a full suite run, also the shape emitted by
+/// `--format json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteReport {
+    pub name: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub duration_micros: u64,
+    pub cases: Vec<CaseResult>,
+}
+
+/// Select and order the cases that will actually run, applying `filter`
+/// then `shuffle_seed` in that order so the seed reshuffles only the
+/// already-filtered set.
+fn select_cases(cases: &[TestCase], options: &RunOptions) -> Vec<TestCase> {
+    let mut selected: Vec<TestCase> = match &options.filter {
+        Some(filter) => cases
+            .iter()
+            .filter(|c| c.name.contains(filter.as_str()))
+            .cloned()
+            .collect(),
+        None => cases.to_vec(),
+    };
+
+    if let Some(seed) = options.shuffle_seed {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        selected.shuffle(&mut rng);
+    }
+
+    selected
+}
+
+/// Run every case in `suite` against `registry`, honoring `options`'
+/// filter, shuffle and concurrency settings.
+pub async fn run_suite(
+    suite: &TestSuite,
+    registry: Arc<tokio::sync::RwLock<HandlerRegistry>>,
+    options: &RunOptions,
+) -> Result<SuiteReport> {
+    let skipped = suite.cases.len();
+    let selected = select_cases(&suite.cases, options);
+    let skipped = skipped - selected.len();
+
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let start = Instant::now();
+
+    let mut handles = Vec::with_capacity(selected.len());
+    for case in selected {
+        let semaphore = semaphore.clone();
+        let registry = registry.clone();
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("semaphore not closed");
+            run_case(&case, &registry).await
+        }));
+    }
+
+    let mut cases = Vec::with_capacity(handles.len());
+    for handle in handles {
+        cases.push(handle.await.expect("test case task panicked"));
+    }
+
+    let passed = cases.iter().filter(|c| c.passed).count();
+    let failed = cases.len() - passed;
+
+    Ok(SuiteReport {
+        name: suite.name.clone(),
+        total: cases.len() + skipped,
+        passed,
+        failed,
+        skipped,
+        duration_micros: start.elapsed().as_micros() as u64,
+        cases,
+    })
+}
+
+async fn run_case(
+    case: &TestCase,
+    registry: &Arc<tokio::sync::RwLock<HandlerRegistry>>,
+) -> CaseResult {
+    let start = Instant::now();
+    let input_bytes = match serde_json::to_vec(&case.input) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return CaseResult {
+                name: case.name.clone(),
+                tool: case.tool.clone(),
+                passed: false,
+                duration_micros: start.elapsed().as_micros() as u64,
+                message: Some(format!("failed to serialize input: {}", e)),
+            }
+        }
+    };
+
+    let outcome = {
+        let registry = registry.read().await;
+        registry.dispatch(&case.tool, &input_bytes).await
+    };
+
+    let decoded = outcome.and_then(|bytes| serde_json::from_slice(&bytes).map_err(Into::into));
+    judge_case(case, start, decoded)
+}
+
+/// Run every case in `suite` against a live [`crate::container::RunningContainer`]
+/// instead of an in-process registry, so `pforge test --integration` exercises
+/// a generated server exactly as it's actually deployed. Cases run strictly
+/// sequentially - `options.concurrency` is ignored, since a container's
+/// stdio transport is one request/response pipe, not a registry that
+/// tolerates concurrent dispatch.
+pub fn run_suite_against_container(
+    suite: &TestSuite,
+    container: &mut crate::container::RunningContainer,
+    options: &RunOptions,
+) -> SuiteReport {
+    let total_before_filter = suite.cases.len();
+    let selected = select_cases(&suite.cases, options);
+    let skipped = total_before_filter - selected.len();
+
+    let start = Instant::now();
+    let cases: Vec<CaseResult> = selected
+        .iter()
+        .map(|case| {
+            let case_start = Instant::now();
+            let outcome = container.call(&case.tool, &case.input);
+            judge_case(case, case_start, outcome)
+        })
+        .collect();
+
+    let passed = cases.iter().filter(|c| c.passed).count();
+    let failed = cases.len() - passed;
+
+    SuiteReport {
+        name: suite.name.clone(),
+        total: cases.len() + skipped,
+        passed,
+        failed,
+        skipped,
+        duration_micros: start.elapsed().as_micros() as u64,
+        cases,
+    }
+}
+
+/// Shared pass/fail judging for a case's already-decoded outcome, used by
+/// both the in-process ([`run_case`]) and containerized
+/// ([`run_suite_against_container`]) dispatch paths so the two only differ
+/// in how they obtain `outcome`, not in how they grade it.
+fn judge_case(
+    case: &TestCase,
+    start: Instant,
+    outcome: Result<serde_json::Value>,
+) -> CaseResult {
+    let (passed, message) = match outcome {
+        Ok(actual) => match &case.expected_error {
+            Some(_) => (
+                false,
+                Some("expected an error, tool succeeded instead".to_string()),
+            ),
+            None => match &case.expected {
+                Some(expected) => {
+                    if &actual == expected {
+                        (true, None)
+                    } else {
+                        (
+                            false,
+                            Some(format!("expected {}, got {}", expected, actual)),
+                        )
+                    }
+                }
+                None => (true, None),
+            },
+        },
+        Err(e) => match &case.expected_error {
+            Some(substring) => {
+                let actual = e.to_string();
+                if actual.contains(substring.as_str()) {
+                    (true, None)
+                } else {
+                    (
+                        false,
+                        Some(format!(
+                            "expected error containing {:?}, got {:?}",
+                            substring, actual
+                        )),
+                    )
+                }
+            }
+            None => (false, Some(format!("unexpected error: {}", e))),
+        },
+    };
+
+    CaseResult {
+        name: case.name.clone(),
+        tool: case.tool.clone(),
+        passed,
+        duration_micros: start.elapsed().as_micros() as u64,
+        message,
+    }
+}
+
+/// Render a compact pass/fail summary with per-case timing, the default
+/// (non-`--format json`) report.
+pub fn format_summary(report: &SuiteReport) -> String {
+    let mut out = String::new();
+    for case in &report.cases {
+        let mark = if case.passed { "✓" } else { "✗" };
+        out.push_str(&format!(
+            "  {} {} ({}us)\n",
+            mark, case.name, case.duration_micros
+        ));
+        if let Some(message) = &case.message {
+            out.push_str(&format!("      {}\n", message));
+        }
+    }
+    out.push_str(&format!(
+        "\n{} passed, {} failed, {} skipped ({} total) in {}us\n",
+        report.passed, report.failed, report.skipped, report.total, report.duration_micros
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Handler;
+    use schemars::JsonSchema;
+
+    #[derive(Debug, Deserialize, JsonSchema)]
+    struct EchoInput {
+        value: i32,
+    }
+
+    #[derive(Debug, Serialize, JsonSchema)]
+    struct EchoOutput {
+        value: i32,
+    }
+
+    struct EchoHandler;
+
+    #[async_trait::async_trait]
+    impl Handler for EchoHandler {
+        type Input = EchoInput;
+        type Output = EchoOutput;
+        type Error = crate::Error;
+
+        async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+            if input.value < 0 {
+                return Err(crate::Error::Handler("value must be non-negative".to_string()));
+            }
+            Ok(EchoOutput { value: input.value })
+        }
+    }
+
+    fn registry_with_echo() -> Arc<tokio::sync::RwLock<HandlerRegistry>> {
+        let mut registry = HandlerRegistry::new();
+        registry.register("echo", EchoHandler);
+        Arc::new(tokio::sync::RwLock::new(registry))
+    }
+
+    #[tokio::test]
+    async fn test_run_suite_passes_on_matching_output() {
+        let suite = TestSuite {
+            name: "echo".to_string(),
+            cases: vec![TestCase {
+                name: "basic".to_string(),
+                tool: "echo".to_string(),
+                input: serde_json::json!({"value": 7}),
+                expected: Some(serde_json::json!({"value": 7})),
+                expected_error: None,
+            }],
+        };
+
+        let report = run_suite(&suite, registry_with_echo(), &RunOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.failed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_suite_fails_on_mismatched_output() {
+        let suite = TestSuite {
+            name: "echo".to_string(),
+            cases: vec![TestCase {
+                name: "basic".to_string(),
+                tool: "echo".to_string(),
+                input: serde_json::json!({"value": 7}),
+                expected: Some(serde_json::json!({"value": 8})),
+                expected_error: None,
+            }],
+        };
+
+        let report = run_suite(&suite, registry_with_echo(), &RunOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_suite_matches_expected_error_substring() {
+        let suite = TestSuite {
+            name: "echo".to_string(),
+            cases: vec![TestCase {
+                name: "negative".to_string(),
+                tool: "echo".to_string(),
+                input: serde_json::json!({"value": -1}),
+                expected: None,
+                expected_error: Some("non-negative".to_string()),
+            }],
+        };
+
+        let report = run_suite(&suite, registry_with_echo(), &RunOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(report.passed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_suite_filter_selects_matching_cases_only() {
+        let suite = TestSuite {
+            name: "echo".to_string(),
+            cases: vec![
+                TestCase {
+                    name: "keep_me".to_string(),
+                    tool: "echo".to_string(),
+                    input: serde_json::json!({"value": 1}),
+                    expected: Some(serde_json::json!({"value": 1})),
+                    expected_error: None,
+                },
+                TestCase {
+                    name: "drop_me".to_string(),
+                    tool: "echo".to_string(),
+                    input: serde_json::json!({"value": 2}),
+                    expected: Some(serde_json::json!({"value": 2})),
+                    expected_error: None,
+                },
+            ],
+        };
+
+        let options = RunOptions {
+            filter: Some("keep".to_string()),
+            ..RunOptions::default()
+        };
+
+        let report = run_suite(&suite, registry_with_echo(), &options).await.unwrap();
+
+        assert_eq!(report.total, 2);
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.skipped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_suite_shuffle_is_deterministic_for_same_seed() {
+        let cases: Vec<TestCase> = (0..20)
+            .map(|i| TestCase {
+                name: format!("case_{}", i),
+                tool: "echo".to_string(),
+                input: serde_json::json!({"value": i}),
+                expected: Some(serde_json::json!({"value": i})),
+                expected_error: None,
+            })
+            .collect();
+        let suite = TestSuite {
+            name: "echo".to_string(),
+            cases,
+        };
+
+        let options = RunOptions {
+            shuffle_seed: Some(42),
+            ..RunOptions::default()
+        };
+
+        let first = run_suite(&suite, registry_with_echo(), &options).await.unwrap();
+        let second = run_suite(&suite, registry_with_echo(), &options).await.unwrap();
+
+        let first_order: Vec<_> = first.cases.iter().map(|c| c.name.clone()).collect();
+        let second_order: Vec<_> = second.cases.iter().map(|c| c.name.clone()).collect();
+        assert_eq!(first_order, second_order);
+    }
+}
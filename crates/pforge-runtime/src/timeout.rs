@@ -1,45 +1,123 @@
-use crate::{Error, Middleware, Result};
+use crate::middleware::{BeforeOutcome, Extensions, Next};
+use crate::recovery::CircuitBreaker;
+use crate::{Error, ErrorKind, Middleware, Result};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time::timeout;
 
-/// Timeout middleware - enforces time limits on handler execution
-/// Note: This is a placeholder - actual timeout enforcement happens in handler execution
+/// Reads the `"tool"` field off a request `Value`, the same convention
+/// [`crate::recovery::RecoveryMiddleware`] uses to key its circuit breakers
+/// per tool rather than globally.
+fn tool_key(request: &Value) -> &str {
+    request
+        .get("tool")
+        .and_then(Value::as_str)
+        .unwrap_or("_unscoped")
+}
+
+/// Timeout middleware - wraps the handler (and every inner middleware) in a
+/// per-tool deadline via the `around` hook, so a hung handler returns a
+/// structured timeout error instead of never resolving - the equivalent of
+/// actix-web's slow-request handling, but enforced in the middleware chain.
+/// Tools with no configured timeout (and no `default_timeout`) pass through
+/// unbounded.
 pub struct TimeoutMiddleware {
-    duration: Duration,
+    default_timeout: Option<Duration>,
+    per_tool: HashMap<String, Duration>,
 }
 
 impl TimeoutMiddleware {
-    pub fn new(duration: Duration) -> Self {
-        Self { duration }
+    pub fn new() -> Self {
+        Self {
+            default_timeout: None,
+            per_tool: HashMap::new(),
+        }
     }
 
     pub fn from_millis(millis: u64) -> Self {
-        Self::new(Duration::from_millis(millis))
+        Self::new().with_default(Duration::from_millis(millis))
     }
 
     pub fn from_secs(secs: u64) -> Self {
-        Self::new(Duration::from_secs(secs))
+        Self::new().with_default(Duration::from_secs(secs))
+    }
+
+    /// Set the deadline applied to tools with no tool-specific entry.
+    pub fn with_default(mut self, duration: Duration) -> Self {
+        self.default_timeout = Some(duration);
+        self
+    }
+
+    /// Read from the tool config's `timeout_ms`, e.g. `ToolDef::Cli`'s.
+    pub fn with_tool_timeout_ms(mut self, tool: impl Into<String>, timeout_ms: u64) -> Self {
+        self.per_tool
+            .insert(tool.into(), Duration::from_millis(timeout_ms));
+        self
+    }
+
+    fn timeout_for(&self, tool: &str) -> Option<Duration> {
+        self.per_tool.get(tool).copied().or(self.default_timeout)
     }
+}
 
-    pub fn duration(&self) -> Duration {
-        self.duration
+impl Default for TimeoutMiddleware {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait::async_trait]
 impl Middleware for TimeoutMiddleware {
-    async fn before(&self, request: Value) -> Result<Value> {
-        Ok(request)
+    async fn before(&self, request: Value, _extensions: &mut Extensions) -> Result<BeforeOutcome> {
+        Ok(BeforeOutcome::Continue(request))
     }
 
-    async fn after(&self, _request: Value, response: Value) -> Result<Value> {
+    async fn after(&self, _request: Value, response: Value, _extensions: &Extensions) -> Result<Value> {
         Ok(response)
     }
+
+    async fn around(&self, request: Value, next: Next<'_>) -> Result<Value> {
+        let tool = tool_key(&request).to_string();
+        match self.timeout_for(&tool) {
+            Some(duration) => match timeout(duration, next.call(request)).await {
+                Ok(result) => result,
+                Err(_) => Err(Error::Handler(format!(
+                    "tool '{}' exceeded its {:?} timeout",
+                    tool, duration
+                ))),
+            },
+            None => next.call(request).await,
+        }
+    }
+}
+
+/// How [`RetryPolicy::backoff_duration`]/[`RetryPolicy::next_backoff`]
+/// randomize the exponential backoff delay, to avoid many clients that
+/// failed at the same moment retrying in lockstep against the same
+/// dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JitterStrategy {
+    /// No randomization - always sleep the full capped exponential delay.
+    None,
+    /// AWS's "full jitter": a uniform random value in `[0, capped]`, where
+    /// `capped = min(max_backoff, initial_backoff * multiplier^attempt)`.
+    /// Spreads retries across the entire range rather than clustering near
+    /// the capped value, which is what most dramatically reduces contention.
+    Full,
+    /// AWS's "decorrelated jitter": `next = min(max_backoff,
+    /// random_uniform(initial_backoff, prev_sleep * 3))`, seeded with
+    /// `prev_sleep = initial_backoff` for the first retry. Needs the
+    /// previous delay as state, so it's only available via
+    /// [`RetryPolicy::next_backoff`], not the stateless
+    /// [`RetryPolicy::backoff_duration`].
+    Decorrelated,
 }
 
 /// Retry policy configuration
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct RetryPolicy {
     /// Maximum number of retry attempts
     pub max_attempts: u32,
@@ -49,8 +127,29 @@ pub struct RetryPolicy {
     pub max_backoff: Duration,
     /// Backoff multiplier (exponential backoff)
     pub backoff_multiplier: f64,
-    /// Whether to use jitter
-    pub use_jitter: bool,
+    /// Jitter strategy applied on top of the exponential backoff.
+    pub jitter: JitterStrategy,
+    /// Shared retry-storm guard (see [`RetryTokenBucket`]), gating *retries*
+    /// across every caller using this policy. `None` (the default) retries
+    /// exactly as before, unbounded by anything but `max_attempts`.
+    pub token_bucket: Option<Arc<RetryTokenBucket>>,
+    /// User-supplied retry classifier, set via [`RetryPolicy::retry_if`].
+    /// `None` (the default) falls back to [`Error::classify`].
+    retry_if: Option<Arc<dyn Fn(&Error) -> bool + Send + Sync>>,
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("backoff_multiplier", &self.backoff_multiplier)
+            .field("jitter", &self.jitter)
+            .field("token_bucket", &self.token_bucket.is_some())
+            .field("retry_if", &self.retry_if.is_some())
+            .finish()
+    }
 }
 
 impl RetryPolicy {
@@ -60,7 +159,9 @@ impl RetryPolicy {
             initial_backoff: Duration::from_millis(100),
             max_backoff: Duration::from_secs(30),
             backoff_multiplier: 2.0,
-            use_jitter: true,
+            jitter: JitterStrategy::Full,
+            token_bucket: None,
+            retry_if: None,
         }
     }
 
@@ -75,39 +176,105 @@ impl RetryPolicy {
         self
     }
 
+    /// Kept for backward compatibility with the old `use_jitter: bool`
+    /// field: `true` maps to [`JitterStrategy::Full`] (the prior jittered
+    /// behavior), `false` to [`JitterStrategy::None`]. Use
+    /// [`Self::with_jitter_strategy`] to opt into [`JitterStrategy::Decorrelated`].
     pub fn with_jitter(mut self, use_jitter: bool) -> Self {
-        self.use_jitter = use_jitter;
+        self.jitter = if use_jitter {
+            JitterStrategy::Full
+        } else {
+            JitterStrategy::None
+        };
+        self
+    }
+
+    pub fn with_jitter_strategy(mut self, jitter: JitterStrategy) -> Self {
+        self.jitter = jitter;
         self
     }
 
-    /// Calculate backoff duration for given attempt
+    /// Share `bucket` across every call using this policy, so
+    /// [`retry_with_policy`] gates each *retry* (never the initial attempt)
+    /// on that bucket instead of retrying unconditionally up to
+    /// `max_attempts` - see [`RetryTokenBucket`] for why this matters under
+    /// a sustained, widespread failure.
+    pub fn with_token_bucket(mut self, bucket: Arc<RetryTokenBucket>) -> Self {
+        self.token_bucket = Some(bucket);
+        self
+    }
+
+    /// Calculate the backoff duration for a given attempt, applying `self.jitter`
+    /// (`None` or `Full` - see [`JitterStrategy`]). [`JitterStrategy::Decorrelated`]
+    /// needs the previous sleep as state, so it's treated the same as `None`
+    /// here; use [`Self::next_backoff`] to get decorrelated jitter.
     pub fn backoff_duration(&self, attempt: u32) -> Duration {
+        let capped = self.capped_exponential_delay(attempt);
+
+        match self.jitter {
+            JitterStrategy::Full => Duration::from_millis((rand::random::<f64>() * capped) as u64),
+            JitterStrategy::None | JitterStrategy::Decorrelated => {
+                Duration::from_millis(capped as u64)
+            }
+        }
+    }
+
+    /// Calculate the backoff duration for a given attempt, given `prev_sleep`
+    /// (the delay actually used for the previous attempt, or
+    /// `initial_backoff` for the first retry). Identical to
+    /// [`Self::backoff_duration`] unless `self.jitter` is
+    /// [`JitterStrategy::Decorrelated`], in which case it computes
+    /// `min(max_backoff, random_uniform(initial_backoff, prev_sleep * 3))`.
+    pub fn next_backoff(&self, attempt: u32, prev_sleep: Duration) -> Duration {
+        if self.jitter != JitterStrategy::Decorrelated {
+            return self.backoff_duration(attempt);
+        }
+
+        let cap_ms = self.max_backoff.as_millis() as f64;
+        let lower_ms = self.initial_backoff.as_millis() as f64;
+        let upper_ms = (prev_sleep.as_millis() as f64 * 3.0).min(cap_ms).max(lower_ms);
+
+        Duration::from_millis((lower_ms + rand::random::<f64>() * (upper_ms - lower_ms)) as u64)
+    }
+
+    fn capped_exponential_delay(&self, attempt: u32) -> f64 {
         let base_duration =
             self.initial_backoff.as_millis() as f64 * self.backoff_multiplier.powi(attempt as i32);
+        base_duration.min(self.max_backoff.as_millis() as f64)
+    }
 
-        let capped = base_duration.min(self.max_backoff.as_millis() as f64);
+    /// Like [`Self::backoff_duration`], but for an [`ErrorKind::Throttling`]
+    /// error: always full-jitter (regardless of `self.jitter`) over a doubled
+    /// range, since a throttling response is the remote explicitly asking
+    /// callers to slow down harder than a generic transient failure warrants.
+    pub fn throttled_backoff_duration(&self, attempt: u32) -> Duration {
+        let capped = self.capped_exponential_delay(attempt) * 2.0;
+        Duration::from_millis((rand::random::<f64>() * capped) as u64)
+    }
 
-        if self.use_jitter {
-            let jitter = rand::random::<f64>() * capped * 0.1; // 10% jitter
-            Duration::from_millis((capped + jitter) as u64)
-        } else {
-            Duration::from_millis(capped as u64)
-        }
+    /// Use `f` to classify which errors are retryable instead of the default
+    /// [`Error::classify`]-based mapping (`retry_if` fully replaces it, not
+    /// just the fallback text match it used to be) - e.g. retry
+    /// `Error::Http` only on 5xx/429 and never on 4xx, or treat specific
+    /// `Error::Io` kinds as transient.
+    pub fn retry_if<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.retry_if = Some(Arc::new(f));
+        self
     }
 
     /// Check if error is retryable
     pub fn is_retryable(&self, error: &Error) -> bool {
-        // Retry on specific errors (can be customized)
-        match error {
-            Error::Handler(msg) => {
-                // Retry on transient errors
-                msg.contains("timeout")
-                    || msg.contains("timed out")
-                    || msg.contains("connection")
-                    || msg.contains("temporary")
-            }
-            _ => false,
+        if let Some(classifier) = &self.retry_if {
+            return classifier(error);
         }
+
+        matches!(
+            error.classify(),
+            ErrorKind::Transient | ErrorKind::Throttling | ErrorKind::ServerError
+        )
     }
 }
 
@@ -117,21 +284,171 @@ impl Default for RetryPolicy {
     }
 }
 
-/// Retry middleware - retries failed requests with backoff
-/// Note: This is a marker - actual retry happens in handler execution layer
+/// Tokens charged per retry for an [`ErrorKind::Transient`] error, cheaper
+/// than [`TRANSIENT_RETRY_COST`] since the call likely made useful progress
+/// (e.g. a timeout, as opposed to a connection reset).
+const TIMEOUT_RETRY_COST: usize = 5;
+/// Tokens charged per retry for an [`ErrorKind::ServerError`].
+const TRANSIENT_RETRY_COST: usize = 10;
+/// Tokens charged per retry for an [`ErrorKind::Throttling`] error - the
+/// remote explicitly asked callers to slow down, so retries of it should
+/// drain the shared bucket fastest.
+const THROTTLING_RETRY_COST: usize = 20;
+/// Tokens credited back for a call that succeeded without needing any retry.
+const SUCCESS_CREDIT: usize = 1;
+
+/// Shared retry-storm guard for [`RetryPolicy`]/[`retry_with_policy`]: a
+/// fixed-capacity bucket gating *retries* (never the initial attempt) so
+/// that when a downstream dependency is broadly unhealthy, every in-flight
+/// call sharing this bucket can't each independently burn their own full
+/// retry budget and amplify load during the outage. Held in an
+/// [`AtomicUsize`] rather than behind a [`Mutex`] (unlike [`RetryBudget`])
+/// since every operation here is a single integer add/subtract, so callers
+/// can share one `Arc<RetryTokenBucket>` lock-free.
+pub struct RetryTokenBucket {
+    tokens: AtomicUsize,
+    capacity: usize,
+}
+
+impl RetryTokenBucket {
+    /// Create a bucket starting (and capped, on refill) at `capacity` tokens.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            tokens: AtomicUsize::new(capacity),
+            capacity,
+        }
+    }
+
+    /// Token cost to charge one retry of `error`, varying by
+    /// [`Error::classify`]: [`THROTTLING_RETRY_COST`] for
+    /// [`ErrorKind::Throttling`], [`TIMEOUT_RETRY_COST`] for
+    /// [`ErrorKind::Transient`], [`TRANSIENT_RETRY_COST`] otherwise.
+    pub fn cost_for(error: &Error) -> usize {
+        match error.classify() {
+            ErrorKind::Throttling => THROTTLING_RETRY_COST,
+            ErrorKind::Transient => TIMEOUT_RETRY_COST,
+            _ => TRANSIENT_RETRY_COST,
+        }
+    }
+
+    /// Try to spend `cost` tokens for one retry attempt. Returns `false`
+    /// (spending nothing) if the bucket doesn't currently hold `cost`.
+    fn try_spend(&self, cost: usize) -> bool {
+        self.tokens
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |tokens| {
+                tokens.checked_sub(cost)
+            })
+            .is_ok()
+    }
+
+    /// Credit the bucket by `amount`, capped at the original `capacity`.
+    fn credit(&self, amount: usize) {
+        let _ = self
+            .tokens
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |tokens| {
+                Some((tokens + amount).min(self.capacity))
+            });
+    }
+
+    /// Tokens currently available, for tests and diagnostics.
+    pub fn available_tokens(&self) -> usize {
+        self.tokens.load(Ordering::SeqCst)
+    }
+}
+
+/// Token-bucket retry budget: each primary request credits the bucket with
+/// `token_ratio` tokens (e.g. `0.1` allows roughly one retry per ten primary
+/// requests) up to `max_tokens`, and each retry spends one token, refusing
+/// the retry once the bucket is empty. This bounds retry amplification to a
+/// small fraction of primary traffic instead of letting naive, aggressive
+/// client retries against a failing node pile up into a request storm that
+/// pins CPU and prevents recovery.
+pub struct RetryBudget {
+    tokens: Mutex<f64>,
+    max_tokens: f64,
+    token_ratio: f64,
+}
+
+impl RetryBudget {
+    pub fn new(max_tokens: f64, token_ratio: f64) -> Self {
+        Self {
+            tokens: Mutex::new(max_tokens),
+            max_tokens,
+            token_ratio,
+        }
+    }
+
+    /// Credit the budget for one primary (non-retry) request.
+    pub fn record_request(&self) {
+        let mut tokens = self.tokens.lock().unwrap();
+        *tokens = (*tokens + self.token_ratio).min(self.max_tokens);
+    }
+
+    /// Try to spend one token for a retry. Returns `false` if the budget is
+    /// exhausted, in which case the caller should give up instead of retrying.
+    pub fn try_spend(&self) -> bool {
+        let mut tokens = self.tokens.lock().unwrap();
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tokens currently available, for tests and diagnostics.
+    pub fn available_tokens(&self) -> f64 {
+        *self.tokens.lock().unwrap()
+    }
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self::new(10.0, 0.1)
+    }
+}
+
+/// Retry middleware - retries failed requests with backoff by re-invoking
+/// `next` through the `around` hook, which (unlike `on_error`) can call back
+/// into the handler. Built on [`handle_retry_result`] and the same
+/// [`apply_backoff_delay`] helper [`retry_with_policy`] uses.
+///
+/// When `circuit_breaker` is set, every attempt (including the first) is
+/// routed through [`CircuitBreaker::call`] instead of calling `next`
+/// directly - same wiring as [`retry_with_breaker`], just exposed through
+/// the actual dispatch middleware chain instead of a standalone function
+/// only its own tests could reach. That feeds both outcomes into
+/// `on_success`/`on_failure`, and an `Open` circuit's
+/// `Error::Handler("Circuit breaker is OPEN")` classifies as
+/// [`ErrorKind::Unrecoverable`] via [`Error::classify`], which
+/// [`RetryPolicy::is_retryable`] already treats as non-retryable by default
+/// - so a tripped breaker fails the request fast instead of burning through
+/// `max_attempts` worth of backoff sleeps against a dependency it already
+/// knows is down.
 pub struct RetryMiddleware {
     policy: RetryPolicy,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
 }
 
 impl RetryMiddleware {
     pub fn new(policy: RetryPolicy) -> Self {
-        Self { policy }
+        Self {
+            policy,
+            circuit_breaker: None,
+        }
     }
 
     pub fn with_max_attempts(max_attempts: u32) -> Self {
         Self::new(RetryPolicy::new(max_attempts))
     }
 
+    /// Gate every retry attempt on `circuit_breaker`'s state and feed
+    /// outcomes back into it, per [`Self`]'s doc comment.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
     pub fn policy(&self) -> &RetryPolicy {
         &self.policy
     }
@@ -139,18 +456,61 @@ impl RetryMiddleware {
 
 #[async_trait::async_trait]
 impl Middleware for RetryMiddleware {
-    async fn on_error(&self, _request: Value, error: Error) -> Result<Value> {
-        // Note: Actual retry logic requires handler re-execution
-        // This middleware marks errors as retryable
-        // Full retry implementation needs to be in the execution layer
-        Err(error)
+    async fn around(&self, request: Value, next: Next<'_>) -> Result<Value> {
+        let mut attempt = 0;
+        let mut last_error = None;
+        let mut prev_sleep = self.policy.initial_backoff;
+
+        while attempt < self.policy.max_attempts {
+            let outcome = match &self.circuit_breaker {
+                Some(breaker) => breaker.call(|| next.call(request.clone())).await,
+                None => next.call(request.clone()).await,
+            };
+
+            match outcome {
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    if let Some(result) =
+                        handle_retry_result(error, &self.policy, &mut attempt, &mut last_error)
+                    {
+                        return result;
+                    }
+                    let triggering_error = last_error.as_ref().expect("set by handle_retry_result");
+                    apply_backoff_delay(
+                        &self.policy,
+                        attempt,
+                        self.policy.max_attempts,
+                        triggering_error,
+                        &mut prev_sleep,
+                    )
+                    .await;
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| Error::Handler("All retry attempts failed".to_string())))
     }
 }
 
-/// Handle retry backoff delay
-async fn apply_backoff_delay(policy: &RetryPolicy, attempt: u32, max_attempts: u32) {
+/// Handle retry backoff delay. `error` is the failure that triggered this
+/// retry - when it's [`ErrorKind::Throttling`]-classified, use
+/// [`RetryPolicy::throttled_backoff_duration`] instead of the normal backoff.
+/// `prev_sleep` carries the delay actually used last time, for
+/// [`JitterStrategy::Decorrelated`]'s [`RetryPolicy::next_backoff`].
+async fn apply_backoff_delay(
+    policy: &RetryPolicy,
+    attempt: u32,
+    max_attempts: u32,
+    error: &Error,
+    prev_sleep: &mut Duration,
+) {
     if attempt < max_attempts {
-        let backoff = policy.backoff_duration(attempt - 1);
+        let backoff = if error.classify() == ErrorKind::Throttling {
+            policy.throttled_backoff_duration(attempt - 1)
+        } else {
+            policy.next_backoff(attempt - 1, *prev_sleep)
+        };
+        *prev_sleep = backoff;
         tokio::time::sleep(backoff).await;
     }
 }
@@ -171,7 +531,13 @@ fn handle_retry_result<T>(
     None
 }
 
-/// Retry executor - wraps a future with retry logic
+/// Retry executor - wraps a future with retry logic. When `policy` carries a
+/// [`RetryTokenBucket`], each retry (never the initial attempt) must first
+/// acquire its [`RetryTokenBucket::cost_for`] cost from the bucket; if the
+/// bucket can't afford it, retrying stops immediately and the triggering
+/// error is returned. A call that succeeds without retrying credits the
+/// bucket [`SUCCESS_CREDIT`]; a call that only succeeds after retrying
+/// refunds every token it spent along the way.
 pub async fn retry_with_policy<F, Fut, T>(policy: &RetryPolicy, mut operation: F) -> Result<T>
 where
     F: FnMut() -> Fut,
@@ -179,17 +545,44 @@ where
 {
     let mut attempt = 0;
     let mut last_error = None;
+    let mut tokens_spent = 0usize;
+    let mut prev_sleep = policy.initial_backoff;
 
     while attempt < policy.max_attempts {
         match operation().await {
-            Ok(result) => return Ok(result),
+            Ok(result) => {
+                if let Some(bucket) = &policy.token_bucket {
+                    bucket.credit(if tokens_spent > 0 {
+                        tokens_spent
+                    } else {
+                        SUCCESS_CREDIT
+                    });
+                }
+                return Ok(result);
+            }
             Err(error) => {
-                if let Some(result) =
-                    handle_retry_result(error, policy, &mut attempt, &mut last_error)
-                {
-                    return result;
+                if !policy.is_retryable(&error) {
+                    return Err(error);
                 }
-                apply_backoff_delay(policy, attempt, policy.max_attempts).await;
+
+                if let Some(bucket) = &policy.token_bucket {
+                    let cost = RetryTokenBucket::cost_for(&error);
+                    if !bucket.try_spend(cost) {
+                        return Err(error);
+                    }
+                    tokens_spent += cost;
+                }
+
+                attempt += 1;
+                apply_backoff_delay(
+                    policy,
+                    attempt,
+                    policy.max_attempts,
+                    &error,
+                    &mut prev_sleep,
+                )
+                .await;
+                last_error = Some(error);
             }
         }
     }
@@ -197,6 +590,48 @@ where
     Err(last_error.unwrap_or_else(|| Error::Handler("All retry attempts failed".to_string())))
 }
 
+/// Retry executor that routes every attempt through `circuit_breaker` (so
+/// outcomes feed its `on_success`/`on_failure`, and an `Open` circuit fails
+/// fast without spending a retry) and spends one `budget` token per retry,
+/// giving up early if the budget is exhausted even though `policy` would
+/// otherwise allow another attempt.
+pub async fn retry_with_breaker<F, Fut, T>(
+    policy: &RetryPolicy,
+    budget: &RetryBudget,
+    circuit_breaker: &CircuitBreaker,
+    mut operation: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    budget.record_request();
+
+    let mut attempt = 0;
+    let mut prev_sleep = policy.initial_backoff;
+    loop {
+        match circuit_breaker.call(&mut operation).await {
+            Ok(result) => return Ok(result),
+            Err(error) => {
+                attempt += 1;
+                if attempt >= policy.max_attempts || !policy.is_retryable(&error) {
+                    return Err(error);
+                }
+                if !budget.try_spend() {
+                    return Err(error);
+                }
+                let backoff = if error.classify() == ErrorKind::Throttling {
+                    policy.throttled_backoff_duration(attempt - 1)
+                } else {
+                    policy.next_backoff(attempt - 1, prev_sleep)
+                };
+                prev_sleep = backoff;
+                tokio::time::sleep(backoff).await;
+            }
+        }
+    }
+}
+
 /// Timeout executor - wraps a future with timeout
 pub async fn with_timeout<F>(duration: Duration, future: F) -> Result<F::Output>
 where
@@ -379,6 +814,91 @@ mod tests {
         assert_eq!(policy.backoff_duration(3).as_millis(), 2700);
     }
 
+    #[test]
+    fn test_throttled_backoff_duration_always_full_jitter_over_doubled_range() {
+        // with_jitter(false) has no effect here - throttled backoff is always
+        // full-jitter, over twice the normal capped range.
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(10))
+            .with_multiplier(3.0)
+            .with_jitter(false);
+
+        for _ in 0..20 {
+            let throttled = policy.throttled_backoff_duration(1).as_millis();
+            assert!(throttled <= 600, "expected <= 600ms, got {throttled}ms");
+        }
+    }
+
+    #[test]
+    fn test_jitter_strategy_none_is_deterministic() {
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(10))
+            .with_multiplier(2.0)
+            .with_jitter_strategy(JitterStrategy::None);
+
+        assert_eq!(policy.backoff_duration(0).as_millis(), 100);
+        assert_eq!(policy.backoff_duration(1).as_millis(), 200);
+        assert_eq!(policy.next_backoff(1, Duration::from_millis(100)).as_millis(), 200);
+    }
+
+    #[test]
+    fn test_jitter_strategy_full_stays_within_capped_range() {
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(10))
+            .with_multiplier(2.0)
+            .with_jitter_strategy(JitterStrategy::Full);
+
+        for _ in 0..20 {
+            let delay = policy.backoff_duration(2).as_millis();
+            assert!(delay <= 400, "expected <= 400ms, got {delay}ms");
+        }
+    }
+
+    #[test]
+    fn test_jitter_strategy_decorrelated_grows_from_prev_sleep() {
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(10))
+            .with_jitter_strategy(JitterStrategy::Decorrelated);
+
+        // First retry seeds prev_sleep = initial_backoff, so next is in
+        // [initial_backoff, initial_backoff * 3].
+        for _ in 0..20 {
+            let delay = policy.next_backoff(0, Duration::from_millis(100)).as_millis();
+            assert!((100..=300).contains(&delay), "expected 100..=300ms, got {delay}ms");
+        }
+
+        // A larger prev_sleep widens the range, still capped at max_backoff.
+        for _ in 0..20 {
+            let delay = policy
+                .next_backoff(3, Duration::from_secs(5))
+                .as_millis();
+            assert!((100..=10_000).contains(&delay), "expected 100..=10000ms, got {delay}ms");
+        }
+    }
+
+    #[test]
+    fn test_jitter_strategy_decorrelated_respects_max_backoff_cap() {
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+            .with_jitter_strategy(JitterStrategy::Decorrelated);
+
+        for _ in 0..20 {
+            let delay = policy
+                .next_backoff(5, Duration::from_secs(10))
+                .as_millis();
+            assert!(delay <= 500, "expected <= 500ms, got {delay}ms");
+        }
+    }
+
+    #[test]
+    fn test_with_jitter_backward_compatible_with_jitter_strategy() {
+        let full = RetryPolicy::new(3).with_jitter(true);
+        assert_eq!(full.jitter, JitterStrategy::Full);
+
+        let none = RetryPolicy::new(3).with_jitter(false);
+        assert_eq!(none.jitter, JitterStrategy::None);
+    }
+
     #[test]
     fn test_is_retryable_logic() {
         // Kills mutants that change || to && in is_retryable
@@ -392,7 +912,45 @@ mod tests {
 
         // Should NOT retry on other errors
         assert!(!policy.is_retryable(&Error::Handler("fatal error".to_string())));
-        assert!(!policy.is_retryable(&Error::Timeout));
+
+        // Error::Timeout classifies as ErrorKind::Transient, so it's
+        // retryable by default now too (see error.rs for classify tests).
+        assert!(policy.is_retryable(&Error::Timeout));
+    }
+
+    #[test]
+    fn test_retry_if_overrides_default_classification() {
+        // A custom classifier can disagree with Error::classify, e.g.
+        // refusing to retry Error::Http regardless of status code.
+        let policy = RetryPolicy::new(3).retry_if(|error| matches!(error, Error::Timeout));
+
+        assert!(policy.is_retryable(&Error::Timeout));
+        // And it fully replaces the default logic - a message that would
+        // have matched the substring check is now ignored.
+        assert!(!policy.is_retryable(&Error::Handler("timeout error".to_string())));
+    }
+
+    #[test]
+    fn test_retry_if_unset_preserves_default_behavior() {
+        let policy = RetryPolicy::new(3);
+        assert!(policy.is_retryable(&Error::Handler("connection failed".to_string())));
+        assert!(!policy.is_retryable(&Error::Handler("fatal error".to_string())));
+    }
+
+    #[test]
+    fn test_retry_if_can_classify_http_status_codes() {
+        // Demonstrates the motivating use case: a custom classifier that
+        // retries Error::Http only on 503/429 and never on other codes,
+        // narrower than the default Error::classify mapping (which retries
+        // every 5xx as well as 429).
+        let policy = RetryPolicy::new(3).retry_if(|error| match error {
+            Error::Http(msg) => msg.contains("503") || msg.contains("429"),
+            _ => false,
+        });
+
+        assert!(policy.is_retryable(&Error::Http("503 Service Unavailable".to_string())));
+        assert!(policy.is_retryable(&Error::Http("429 Too Many Requests".to_string())));
+        assert!(!policy.is_retryable(&Error::Http("404 Not Found".to_string())));
     }
 
     #[tokio::test]
@@ -453,4 +1011,432 @@ mod tests {
             total_time
         );
     }
+
+    #[test]
+    fn test_retry_budget_spends_and_refuses_when_empty() {
+        let budget = RetryBudget::new(2.0, 0.0);
+
+        assert!(budget.try_spend());
+        assert!(budget.try_spend());
+        assert!(!budget.try_spend());
+    }
+
+    #[test]
+    fn test_retry_budget_record_request_refills_up_to_max() {
+        let budget = RetryBudget::new(1.0, 0.5);
+
+        assert!(budget.try_spend());
+        assert!(!budget.try_spend());
+
+        budget.record_request();
+        budget.record_request();
+        budget.record_request(); // Should cap at max_tokens, not overflow past it
+
+        assert_eq!(budget.available_tokens(), 1.0);
+        assert!(budget.try_spend());
+    }
+
+    #[test]
+    fn test_retry_token_bucket_cost_for_classifies_timeout_cheaper() {
+        assert_eq!(
+            RetryTokenBucket::cost_for(&Error::Handler("timed out".to_string())),
+            5
+        );
+        assert_eq!(RetryTokenBucket::cost_for(&Error::Timeout), 5);
+        assert_eq!(
+            RetryTokenBucket::cost_for(&Error::Handler("connection reset".to_string())),
+            10
+        );
+    }
+
+    #[test]
+    fn test_retry_token_bucket_cost_for_charges_throttling_most() {
+        // A Throttling-classified error should drain the shared bucket
+        // fastest, since the remote explicitly asked callers to back off.
+        assert_eq!(
+            RetryTokenBucket::cost_for(&Error::Http("429 Too Many Requests".to_string())),
+            20
+        );
+        assert_eq!(
+            RetryTokenBucket::cost_for(&Error::Http("503 Service Unavailable".to_string())),
+            10
+        );
+    }
+
+    #[test]
+    fn test_retry_token_bucket_credit_caps_at_capacity() {
+        let bucket = RetryTokenBucket::new(10);
+        assert!(bucket.try_spend(10));
+        assert_eq!(bucket.available_tokens(), 0);
+
+        bucket.credit(100);
+        assert_eq!(bucket.available_tokens(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_policy_stops_immediately_once_bucket_exhausted() {
+        let bucket = Arc::new(RetryTokenBucket::new(8));
+        let policy = RetryPolicy::new(10)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+            .with_jitter(false)
+            .with_token_bucket(bucket.clone());
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let result: Result<()> = retry_with_policy(&policy, || {
+            let counter = counter_clone.clone();
+            async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+                Err(Error::Handler("connection reset".to_string()))
+            }
+        })
+        .await;
+
+        assert!(result.is_err());
+        // Capacity 8, each retry costs 10 (non-timeout) - the first retry
+        // can't afford it, so only the initial attempt runs.
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+        assert_eq!(bucket.available_tokens(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_policy_refunds_tokens_on_eventual_success() {
+        let bucket = Arc::new(RetryTokenBucket::new(500));
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+            .with_jitter(false)
+            .with_token_bucket(bucket.clone());
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let result = retry_with_policy(&policy, || {
+            let counter = counter_clone.clone();
+            async move {
+                let count = counter.fetch_add(1, Ordering::SeqCst);
+                if count < 2 {
+                    Err(Error::Handler("timed out".to_string()))
+                } else {
+                    Ok(42)
+                }
+            }
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 42);
+        // Two retries at cost 5 each were spent then fully refunded on success.
+        assert_eq!(bucket.available_tokens(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_policy_credits_success_with_no_retries() {
+        let bucket = Arc::new(RetryTokenBucket::new(500));
+        bucket.try_spend(3);
+        let policy = RetryPolicy::new(3).with_token_bucket(bucket.clone());
+
+        let result = retry_with_policy(&policy, || async { Ok::<_, Error>(()) }).await;
+
+        assert!(result.is_ok());
+        assert_eq!(bucket.available_tokens(), 498);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_breaker_gives_up_when_budget_exhausted() {
+        use crate::recovery::{CircuitBreakerConfig, FailureDetectionMode};
+
+        let policy = RetryPolicy::new(10)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+            .with_jitter(false);
+        let budget = RetryBudget::new(1.0, 0.0); // only 1 retry allowed total
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 100, // high enough that the breaker itself never opens
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
+        });
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let result: Result<()> = retry_with_breaker(&policy, &budget, &breaker, || {
+            let counter = counter_clone.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Err(Error::Handler("timeout error".to_string()))
+            }
+        })
+        .await;
+
+        assert!(result.is_err());
+        // First attempt plus exactly one budgeted retry, then the budget runs dry.
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_breaker_stops_retrying_once_circuit_opens() {
+        use crate::recovery::{CircuitBreakerConfig, CircuitState, FailureDetectionMode};
+
+        let policy = RetryPolicy::new(10)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+            .with_jitter(false);
+        let budget = RetryBudget::new(100.0, 1.0); // budget never the bottleneck here
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
+        });
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let result: Result<()> = retry_with_breaker(&policy, &budget, &breaker, || {
+            let counter = counter_clone.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Err(Error::Handler("timeout error".to_string()))
+            }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(breaker.get_state().await, CircuitState::Open);
+        // 2 failures open the breaker; the 3rd call fails fast with a
+        // non-retryable "Circuit breaker is OPEN" error instead of invoking
+        // the operation again.
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_backoff_full_jitter_stays_within_capped_range() {
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .with_multiplier(2.0)
+            .with_jitter(true);
+
+        for _ in 0..50 {
+            let backoff = policy.backoff_duration(1); // capped at 200ms
+            assert!(backoff <= Duration::from_millis(200));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_middleware_around_completes_under_deadline() {
+        use crate::middleware::MiddlewareChain;
+        use serde_json::json;
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(
+            TimeoutMiddleware::new().with_tool_timeout_ms("slow_tool", 200),
+        ));
+
+        let result = chain
+            .execute(json!({"tool": "slow_tool"}), |_| async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(json!({"done": true}))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result["done"], true);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_middleware_around_exceeds_deadline() {
+        use crate::middleware::MiddlewareChain;
+        use serde_json::json;
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(
+            TimeoutMiddleware::new().with_tool_timeout_ms("slow_tool", 20),
+        ));
+
+        let result = chain
+            .execute(json!({"tool": "slow_tool"}), |_| async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok(json!({"done": true}))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("timeout"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_middleware_tool_without_entry_passes_through() {
+        use crate::middleware::MiddlewareChain;
+        use serde_json::json;
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(
+            TimeoutMiddleware::new().with_tool_timeout_ms("other_tool", 1),
+        ));
+
+        let result = chain
+            .execute(json!({"tool": "unconfigured_tool"}), |_| async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(json!({"done": true}))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result["done"], true);
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware_around_retries_through_the_handler() {
+        use crate::middleware::MiddlewareChain;
+        use serde_json::json;
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(RetryMiddleware::new(
+            RetryPolicy::new(3)
+                .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+                .with_jitter(false),
+        )));
+
+        let result = chain
+            .execute(json!({}), move |_| {
+                let counter = counter_clone.clone();
+                async move {
+                    let count = counter.fetch_add(1, Ordering::SeqCst);
+                    if count < 2 {
+                        Err(Error::Handler("timeout error".to_string()))
+                    } else {
+                        Ok(json!({"attempt": count}))
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result["attempt"], 2);
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware_around_gives_up_on_non_retryable_error() {
+        use crate::middleware::MiddlewareChain;
+        use serde_json::json;
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(RetryMiddleware::new(RetryPolicy::new(3))));
+
+        let result: Result<serde_json::Value> = chain
+            .execute(json!({}), move |_| {
+                let counter = counter_clone.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Err(Error::Handler("fatal error".to_string()))
+                }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware_with_circuit_breaker_feeds_outcomes_back() {
+        use crate::middleware::MiddlewareChain;
+        use crate::recovery::{CircuitBreakerConfig, CircuitState, FailureDetectionMode};
+        use serde_json::json;
+
+        let breaker = Arc::new(CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 100, // high enough that the breaker itself never opens here
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
+        }));
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(
+            RetryMiddleware::new(
+                RetryPolicy::new(3)
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+                    .with_jitter(false),
+            )
+            .with_circuit_breaker(breaker.clone()),
+        ));
+
+        let result = chain
+            .execute(json!({}), move |_| {
+                let counter = counter_clone.clone();
+                async move {
+                    let count = counter.fetch_add(1, Ordering::SeqCst);
+                    if count < 2 {
+                        Err(Error::Handler("timeout error".to_string()))
+                    } else {
+                        Ok(json!({"attempt": count}))
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result["attempt"], 2);
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        // The eventual success fed `on_success`, resetting the breaker's
+        // failure streak rather than leaving it tripped.
+        assert_eq!(breaker.get_state().await, CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_retry_middleware_with_circuit_breaker_stops_retrying_once_open() {
+        use crate::middleware::MiddlewareChain;
+        use crate::recovery::{CircuitBreakerConfig, CircuitState, FailureDetectionMode};
+        use serde_json::json;
+
+        let breaker = Arc::new(CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+            failure_detection: FailureDetectionMode::Consecutive,
+            half_open_max_concurrent: 1,
+        }));
+
+        let counter = Arc::new(AtomicU32::new(0));
+        let counter_clone = counter.clone();
+
+        let mut chain = MiddlewareChain::new();
+        chain.add(Arc::new(
+            RetryMiddleware::new(
+                RetryPolicy::new(10)
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+                    .with_jitter(false),
+            )
+            .with_circuit_breaker(breaker.clone()),
+        ));
+
+        let result: Result<serde_json::Value> = chain
+            .execute(json!({}), move |_| {
+                let counter = counter_clone.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Err(Error::Handler("timeout error".to_string()))
+                }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(breaker.get_state().await, CircuitState::Open);
+        // 2 failures open the breaker; further retry attempts see
+        // `Error::Handler("Circuit breaker is OPEN")`, which classifies as
+        // non-retryable, so the 3rd attempt never invokes the handler.
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
 }
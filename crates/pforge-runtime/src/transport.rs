@@ -3,51 +3,65 @@
 //! This module provides transport creation based on configuration.
 
 use crate::{Error, Result};
-use pforge_config::TransportType;
+use pforge_config::{TransportTuning, TransportType};
 use pmcp::shared::{
     OptimizedSseConfig, OptimizedSseTransport, StdioTransport, Transport, WebSocketConfig,
     WebSocketTransport,
 };
 use std::time::Duration;
 
-/// Create a transport based on configuration
+/// Create a transport based on configuration, using default tuning (the same
+/// localhost dev endpoint and timeouts pforge has always hardcoded).
 pub fn create_transport(transport_type: &TransportType) -> Result<Box<dyn Transport>> {
+    create_transport_with_config(transport_type, &TransportTuning::default())
+}
+
+/// Create a transport based on configuration, with explicit control over the
+/// target endpoint and connection tuning via `tuning` - typically
+/// `ForgeConfig.forge.transport_tuning`, loaded straight from YAML.
+pub fn create_transport_with_config(
+    transport_type: &TransportType,
+    tuning: &TransportTuning,
+) -> Result<Box<dyn Transport>> {
     match transport_type {
         TransportType::Stdio => {
             let transport = StdioTransport::new();
             Ok(Box::new(transport))
         }
         TransportType::Sse => {
-            let config = OptimizedSseConfig {
-                url: "http://localhost:8080/sse".to_string(),
-                connection_timeout: Duration::from_secs(30),
-                keepalive_interval: Duration::from_secs(15),
-                max_reconnects: 5,
-                reconnect_delay: Duration::from_secs(1),
-                buffer_size: 100,
-                flush_interval: Duration::from_millis(100),
-                enable_pooling: true,
-                max_connections: 10,
-                enable_compression: false,
+            let sse = &tuning.sse;
+            let sse_config = OptimizedSseConfig {
+                url: sse.url.clone(),
+                connection_timeout: Duration::from_millis(sse.connection_timeout_ms),
+                keepalive_interval: Duration::from_millis(sse.keepalive_interval_ms),
+                max_reconnects: sse.max_reconnects,
+                reconnect_delay: Duration::from_millis(sse.reconnect_delay_ms),
+                buffer_size: sse.buffer_size,
+                flush_interval: Duration::from_millis(sse.flush_interval_ms),
+                enable_pooling: sse.enable_pooling,
+                max_connections: sse.max_connections,
+                enable_compression: sse.enable_compression,
             };
-            let transport = OptimizedSseTransport::new(config);
+            let transport = OptimizedSseTransport::new(sse_config);
             Ok(Box::new(transport))
         }
         TransportType::WebSocket => {
-            let url = "ws://localhost:8080/ws"
+            let ws = &tuning.websocket;
+            let url = ws
+                .url
                 .parse()
                 .map_err(|e| Error::Handler(format!("Invalid WebSocket URL: {}", e)))?;
 
-            let config = WebSocketConfig {
+            let ws_config = WebSocketConfig {
                 url,
-                auto_reconnect: true,
-                reconnect_delay: Duration::from_secs(1),
-                max_reconnect_delay: Duration::from_secs(30),
-                max_reconnect_attempts: Some(5),
-                ping_interval: Some(Duration::from_secs(30)),
-                request_timeout: Duration::from_secs(10),
+                auto_reconnect: ws.auto_reconnect,
+                reconnect_delay: Duration::from_millis(ws.reconnect_delay_ms),
+                max_reconnect_delay: Duration::from_millis(ws.max_reconnect_delay_ms),
+                max_reconnect_attempts: ws.max_reconnect_attempts,
+                ping_interval: ws.ping_interval_ms.map(Duration::from_millis),
+                request_timeout: Duration::from_millis(ws.request_timeout_ms),
             };
-            let transport = WebSocketTransport::new(config);
+            let transport = WebSocketTransport::new(ws_config);
             Ok(Box::new(transport))
         }
     }
@@ -77,5 +91,30 @@ mod tests {
         assert!(transport.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_create_transport_with_custom_buffer_size() {
+        let mut tuning = TransportTuning::default();
+        tuning.sse.buffer_size = 4096;
+        let transport = create_transport_with_config(&TransportType::Sse, &tuning);
+        assert!(transport.is_ok());
+    }
+
+    #[test]
+    fn test_transport_tuning_default_matches_historical_hardcoded_values() {
+        let tuning = TransportTuning::default();
+        assert_eq!(tuning.sse.url, "http://localhost:8080/sse");
+        assert_eq!(tuning.sse.buffer_size, 100);
+        assert_eq!(tuning.websocket.url, "ws://localhost:8080/ws");
+        assert_eq!(tuning.websocket.max_reconnect_attempts, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_create_transport_with_custom_websocket_url() {
+        let mut tuning = TransportTuning::default();
+        tuning.websocket.url = "ws://example.com:9000/ws".to_string();
+        let transport = create_transport_with_config(&TransportType::WebSocket, &tuning);
+        assert!(transport.is_ok());
+    }
+
     // Note: SSE and WebSocket tests require server running, so they're integration tests
 }
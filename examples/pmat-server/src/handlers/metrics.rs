@@ -1,7 +1,11 @@
-use pforge_runtime::{Handler, Result};
+use pforge_runtime::{Error, Handler, Result};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct MetricsSummaryInput {
@@ -22,8 +26,25 @@ pub struct MetricsSummaryOutput {
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct MetricsResult {
     pub passed: bool,
-    pub value: String,
-    pub threshold: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub violations: Vec<String>,
+    /// Change versus the previous history run. `None` unless
+    /// `include_history` was set and a prior run exists.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<f64>,
+    /// Direction over the last [`HISTORY_WINDOW`] runs. `None` unless
+    /// `include_history` was set and enough history has accumulated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trend: Option<Trend>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Trend {
+    Improving,
+    Stable,
+    Regressing,
 }
 
 #[derive(Debug, Serialize, JsonSchema)]
@@ -34,6 +55,47 @@ pub struct QualitySummary {
     pub recommendations: Vec<String>,
 }
 
+/// A single `pmat analyze ... --format json` result.
+#[derive(Debug, Deserialize)]
+struct PmatMetric {
+    #[allow(dead_code)]
+    metric: String,
+    value: f64,
+    #[serde(default)]
+    violations: Vec<String>,
+}
+
+/// Which direction of `value` relative to `threshold` counts as passing.
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+    /// Passes when `value <= threshold` (e.g. complexity, SATD count).
+    Max,
+    /// Passes when `value >= threshold` (e.g. TDG score).
+    Min,
+}
+
+impl Comparison {
+    fn passes(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Max => value <= threshold,
+            Comparison::Min => value >= threshold,
+        }
+    }
+}
+
+/// One run's metric values, appended as a line to the history file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoryEntry {
+    timestamp: u64,
+    complexity: f64,
+    satd: f64,
+    tdg: f64,
+    cognitive: f64,
+}
+
+/// How many of the most recent runs feed the trend slope.
+const HISTORY_WINDOW: usize = 5;
+
 pub struct MetricsSummary;
 
 #[async_trait::async_trait]
@@ -43,55 +105,66 @@ impl Handler for MetricsSummary {
     type Error = pforge_runtime::Error;
 
     async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
-        // Run all PMAT analyses
-        let complexity_result = run_pmat_command(&["analyze", "complexity", "--max", "20", &input.path])?;
-        let satd_result = run_pmat_command(&["analyze", "satd", "--max", "0", &input.path])?;
-        let tdg_result = run_pmat_command(&["analyze", "tdg", "--min", "0.75", &input.path])?;
-        let cognitive_result = run_pmat_command(&["analyze", "cognitive", "--max", "15", &input.path])?;
-
-        // Note: include_history parameter reserved for future use (historical trend analysis)
-        let _include_history = input.include_history;
-
-        // Parse results (simplified - in production, parse actual JSON output)
-        let complexity_passed = complexity_result.contains("PASS") || !complexity_result.contains("FAIL");
-        let satd_passed = satd_result.contains("PASS") || !satd_result.contains("FAIL");
-        let tdg_passed = tdg_result.contains("PASS") || !tdg_result.contains("FAIL");
-        let cognitive_passed = cognitive_result.contains("PASS") || !cognitive_result.contains("FAIL");
-
-        let passed_checks = [complexity_passed, satd_passed, tdg_passed, cognitive_passed]
-            .iter()
-            .filter(|&&x| x)
-            .count() as u32;
+        // Run all PMAT analyses as structured JSON
+        let complexity = run_pmat_metric(&[
+            "analyze", "complexity", "--max", "20", "--format", "json", &input.path,
+        ])?;
+        let satd = run_pmat_metric(&[
+            "analyze", "satd", "--max", "0", "--format", "json", &input.path,
+        ])?;
+        let tdg = run_pmat_metric(&[
+            "analyze", "tdg", "--min", "0.75", "--format", "json", &input.path,
+        ])?;
+        let cognitive = run_pmat_metric(&[
+            "analyze", "cognitive", "--max", "15", "--format", "json", &input.path,
+        ])?;
+
+        let history = if input.include_history {
+            Some(append_history(
+                &input.path,
+                complexity.value,
+                satd.value,
+                tdg.value,
+                cognitive.value,
+            )?)
+        } else {
+            None
+        };
+
+        let complexity_result = build_result(complexity, 20.0, Comparison::Max, history.as_deref(), |h| {
+            h.complexity
+        });
+        let satd_result =
+            build_result(satd, 0.0, Comparison::Max, history.as_deref(), |h| h.satd);
+        let tdg_result =
+            build_result(tdg, 0.75, Comparison::Min, history.as_deref(), |h| h.tdg);
+        let cognitive_result = build_result(cognitive, 15.0, Comparison::Max, history.as_deref(), |h| {
+            h.cognitive
+        });
+
+        let passed_checks = [
+            complexity_result.passed,
+            satd_result.passed,
+            tdg_result.passed,
+            cognitive_result.passed,
+        ]
+        .iter()
+        .filter(|&&x| x)
+        .count() as u32;
 
         let overall_grade = calculate_grade(passed_checks, 4);
         let recommendations = generate_recommendations(
-            complexity_passed,
-            satd_passed,
-            tdg_passed,
-            cognitive_passed,
+            complexity_result.passed,
+            satd_result.passed,
+            tdg_result.passed,
+            cognitive_result.passed,
         );
 
         Ok(MetricsSummaryOutput {
-            complexity: MetricsResult {
-                passed: complexity_passed,
-                value: extract_value(&complexity_result),
-                threshold: "≤20".to_string(),
-            },
-            satd: MetricsResult {
-                passed: satd_passed,
-                value: extract_value(&satd_result),
-                threshold: "0".to_string(),
-            },
-            tdg: MetricsResult {
-                passed: tdg_passed,
-                value: extract_value(&tdg_result),
-                threshold: "≥0.75".to_string(),
-            },
-            cognitive: MetricsResult {
-                passed: cognitive_passed,
-                value: extract_value(&cognitive_result),
-                threshold: "≤15".to_string(),
-            },
+            complexity: complexity_result,
+            satd: satd_result,
+            tdg: tdg_result,
+            cognitive: cognitive_result,
             summary: QualitySummary {
                 overall_grade,
                 passed_checks,
@@ -102,28 +175,161 @@ impl Handler for MetricsSummary {
     }
 }
 
-fn run_pmat_command(args: &[&str]) -> Result<String> {
+fn run_pmat_metric(args: &[&str]) -> Result<PmatMetric> {
     let output = Command::new("pmat")
         .args(args)
         .output()
-        .map_err(|e| pforge_runtime::Error::Handler(format!("Failed to run pmat: {}", e)))?;
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-}
-
-fn extract_value(output: &str) -> String {
-    // Simplified extraction - in production, parse JSON
-    output
-        .lines()
-        .find(|line| line.contains("value:") || line.contains("score:"))
-        .map(|line| {
-            line.split(':')
-                .nth(1)
-                .unwrap_or("unknown")
-                .trim()
-                .to_string()
-        })
-        .unwrap_or_else(|| "N/A".to_string())
+        .map_err(|e| Error::Handler(format!("Failed to run pmat: {}", e)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str(&stdout)
+        .map_err(|e| Error::Handler(format!("Failed to parse pmat JSON output: {}", e)))
+}
+
+fn build_result(
+    metric: PmatMetric,
+    threshold: f64,
+    cmp: Comparison,
+    history: Option<&[HistoryEntry]>,
+    select: impl Fn(&HistoryEntry) -> f64,
+) -> MetricsResult {
+    let passed = cmp.passes(metric.value, threshold);
+
+    let (delta, trend) = match history {
+        Some(entries) if entries.len() >= 2 => {
+            let previous = select(&entries[entries.len() - 2]);
+            let window_start = entries.len().saturating_sub(HISTORY_WINDOW);
+            let values: Vec<f64> = entries[window_start..].iter().map(&select).collect();
+
+            (Some(metric.value - previous), Some(trend_direction(&values, cmp)))
+        }
+        _ => (None, None),
+    };
+
+    MetricsResult {
+        passed,
+        value: metric.value,
+        threshold,
+        violations: metric.violations,
+        delta,
+        trend,
+    }
+}
+
+/// Direction of `values` (oldest first, most recent last) based on the sign
+/// of their linear-regression slope, oriented so "improving" always means
+/// moving toward the passing side of `cmp`.
+fn trend_direction(values: &[f64], cmp: Comparison) -> Trend {
+    if values.len() < 2 {
+        return Trend::Stable;
+    }
+
+    let slope = linear_slope(values);
+    let improving_slope = match cmp {
+        Comparison::Max => -slope,
+        Comparison::Min => slope,
+    };
+
+    const EPSILON: f64 = 1e-9;
+    if improving_slope > EPSILON {
+        Trend::Improving
+    } else if improving_slope < -EPSILON {
+        Trend::Regressing
+    } else {
+        Trend::Stable
+    }
+}
+
+/// Least-squares slope of `values` against their index.
+fn linear_slope(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for (i, y) in values.iter().enumerate() {
+        let x = i as f64 - mean_x;
+        numerator += x * (y - mean_y);
+        denominator += x * x;
+    }
+
+    if denominator.abs() < f64::EPSILON {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+fn history_file_path(path: &str) -> PathBuf {
+    Path::new(path).join(".pforge").join("metrics-history.jsonl")
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Append this run to `<path>/.pforge/metrics-history.jsonl` and return the
+/// full history including the new entry (oldest first).
+fn append_history(
+    path: &str,
+    complexity: f64,
+    satd: f64,
+    tdg: f64,
+    cognitive: f64,
+) -> Result<Vec<HistoryEntry>> {
+    let history_path = history_file_path(path);
+    if let Some(parent) = history_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| Error::Handler(format!("Failed to create history dir: {}", e)))?;
+    }
+
+    let mut entries = read_history(&history_path)?;
+    let entry = HistoryEntry {
+        timestamp: now_unix(),
+        complexity,
+        satd,
+        tdg,
+        cognitive,
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&history_path)
+        .map_err(|e| Error::Handler(format!("Failed to open history file: {}", e)))?;
+    let line = serde_json::to_string(&entry)
+        .map_err(|e| Error::Handler(format!("Failed to serialize history entry: {}", e)))?;
+    writeln!(file, "{}", line)
+        .map_err(|e| Error::Handler(format!("Failed to write history entry: {}", e)))?;
+
+    entries.push(entry);
+    Ok(entries)
+}
+
+fn read_history(path: &Path) -> Result<Vec<HistoryEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = std::fs::File::open(path)
+        .map_err(|e| Error::Handler(format!("Failed to open history file: {}", e)))?;
+    let reader = BufReader::new(file);
+
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| Error::Handler(format!("Failed to read history file: {}", e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: HistoryEntry = serde_json::from_str(&line)
+            .map_err(|e| Error::Handler(format!("Failed to parse history entry: {}", e)))?;
+        entries.push(entry);
+    }
+    Ok(entries)
 }
 
 fn calculate_grade(passed: u32, total: u32) -> String {
@@ -199,4 +405,85 @@ mod tests {
         assert!(recs.iter().any(|r| r.contains("complexity")));
         assert!(recs.iter().any(|r| r.contains("Technical Debt Grade")));
     }
+
+    #[test]
+    fn test_comparison_passes() {
+        assert!(Comparison::Max.passes(10.0, 20.0));
+        assert!(!Comparison::Max.passes(30.0, 20.0));
+        assert!(Comparison::Min.passes(0.8, 0.75));
+        assert!(!Comparison::Min.passes(0.5, 0.75));
+    }
+
+    #[test]
+    fn test_trend_direction_improving_for_max_metric() {
+        // Complexity dropping over time is improving for a Max-style metric.
+        let values = vec![30.0, 25.0, 20.0, 15.0];
+        assert_eq!(trend_direction(&values, Comparison::Max), Trend::Improving);
+    }
+
+    #[test]
+    fn test_trend_direction_regressing_for_min_metric() {
+        // TDG score dropping over time is regressing for a Min-style metric.
+        let values = vec![0.9, 0.85, 0.8, 0.7];
+        assert_eq!(trend_direction(&values, Comparison::Min), Trend::Regressing);
+    }
+
+    #[test]
+    fn test_trend_direction_stable_for_flat_values() {
+        let values = vec![10.0, 10.0, 10.0];
+        assert_eq!(trend_direction(&values, Comparison::Max), Trend::Stable);
+    }
+
+    #[test]
+    fn test_trend_direction_requires_at_least_two_values() {
+        assert_eq!(trend_direction(&[10.0], Comparison::Max), Trend::Stable);
+        assert_eq!(trend_direction(&[], Comparison::Max), Trend::Stable);
+    }
+
+    #[test]
+    fn test_append_and_read_history_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().to_str().unwrap();
+
+        let first = append_history(path, 20.0, 0.0, 0.75, 15.0).unwrap();
+        assert_eq!(first.len(), 1);
+
+        let second = append_history(path, 18.0, 0.0, 0.80, 14.0).unwrap();
+        assert_eq!(second.len(), 2);
+        assert_eq!(second[0].complexity, 20.0);
+        assert_eq!(second[1].complexity, 18.0);
+
+        let history_path = history_file_path(path);
+        assert!(history_path.exists());
+    }
+
+    #[test]
+    fn test_build_result_computes_delta_from_previous_run() {
+        let history = vec![
+            HistoryEntry {
+                timestamp: 1,
+                complexity: 20.0,
+                satd: 0.0,
+                tdg: 0.75,
+                cognitive: 15.0,
+            },
+            HistoryEntry {
+                timestamp: 2,
+                complexity: 18.0,
+                satd: 0.0,
+                tdg: 0.78,
+                cognitive: 14.0,
+            },
+        ];
+
+        let metric = PmatMetric {
+            metric: "complexity".to_string(),
+            value: 18.0,
+            violations: vec![],
+        };
+
+        let result = build_result(metric, 20.0, Comparison::Max, Some(&history), |h| h.complexity);
+        assert_eq!(result.delta, Some(-2.0));
+        assert!(result.passed);
+    }
 }
@@ -1,13 +1,19 @@
-use pforge_runtime::{Handler, Result, StateManager, MemoryStateManager};
+use pforge_runtime::{Handler, Result, StateManager};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CounterInput {
     pub name: String,
     #[serde(default = "default_increment")]
     pub increment: i64,
+    /// Optional TTL for the counter, in milliseconds. When set, the counter
+    /// expires and resets to 0 if left untouched for this long; omit for a
+    /// counter that persists indefinitely.
+    #[serde(default)]
+    pub ttl_ms: Option<u64>,
 }
 
 fn default_increment() -> i64 {
@@ -23,11 +29,14 @@ pub struct CounterOutput {
 }
 
 pub struct CounterHandler {
-    state: Arc<MemoryStateManager>,
+    state: Arc<dyn StateManager>,
 }
 
 impl CounterHandler {
-    pub fn new(state: Arc<MemoryStateManager>) -> Self {
+    /// Accepts any `StateManager` backend (in-memory, Sled, redb, Redis, ...)
+    /// so a server can swap in persistent storage without touching this
+    /// handler - counters then survive process restarts and reconnects.
+    pub fn new(state: Arc<dyn StateManager>) -> Self {
         Self { state }
     }
 }
@@ -51,8 +60,13 @@ impl Handler for CounterHandler {
         let new_value = previous_value + input.increment;
 
         // Store new value
+        let ttl = input.ttl_ms.map(Duration::from_millis);
         self.state
-            .set(&input.name, new_value.to_string().as_bytes().to_vec(), None)
+            .set(
+                &input.name,
+                new_value.to_string().as_bytes().to_vec(),
+                ttl,
+            )
             .await
             .map_err(|e| pforge_runtime::Error::Handler(format!("State error: {}", e)))?;
 
@@ -68,6 +82,7 @@ impl Handler for CounterHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pforge_runtime::MemoryStateManager;
 
     #[tokio::test]
     async fn test_counter_increment() {
@@ -77,6 +92,7 @@ mod tests {
         let input = CounterInput {
             name: "test".to_string(),
             increment: 5,
+            ttl_ms: None,
         };
 
         let result = handler.handle(input).await.unwrap();
@@ -94,6 +110,7 @@ mod tests {
         let input1 = CounterInput {
             name: "persistent".to_string(),
             increment: 10,
+            ttl_ms: None,
         };
         let result1 = handler.handle(input1).await.unwrap();
         assert_eq!(result1.value, 10);
@@ -102,9 +119,35 @@ mod tests {
         let input2 = CounterInput {
             name: "persistent".to_string(),
             increment: 5,
+            ttl_ms: None,
         };
         let result2 = handler.handle(input2).await.unwrap();
         assert_eq!(result2.value, 15);
         assert_eq!(result2.previous_value, 10);
     }
+
+    #[tokio::test]
+    async fn test_counter_ttl_expires_and_resets() {
+        let state = Arc::new(MemoryStateManager::new());
+        let handler = CounterHandler::new(state);
+
+        let input1 = CounterInput {
+            name: "ephemeral".to_string(),
+            increment: 10,
+            ttl_ms: Some(20),
+        };
+        let result1 = handler.handle(input1).await.unwrap();
+        assert_eq!(result1.value, 10);
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        let input2 = CounterInput {
+            name: "ephemeral".to_string(),
+            increment: 1,
+            ttl_ms: Some(20),
+        };
+        let result2 = handler.handle(input2).await.unwrap();
+        assert_eq!(result2.previous_value, 0);
+        assert_eq!(result2.value, 1);
+    }
 }
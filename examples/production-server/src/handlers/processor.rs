@@ -5,9 +5,18 @@ use serde_json::Value;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ProcessorInput {
-    pub data: Value,
-    #[serde(default = "default_format")]
-    pub format: String,
+    /// Already-parsed data. Takes precedence over `raw` + `from_format` when
+    /// both are given.
+    #[serde(default)]
+    pub data: Option<Value>,
+    /// Raw input string to parse according to `from_format` before conversion.
+    #[serde(default)]
+    pub raw: Option<String>,
+    /// Format `raw` is encoded in. Ignored when `data` is supplied directly.
+    #[serde(default)]
+    pub from_format: Option<String>,
+    #[serde(default = "default_format", alias = "format")]
+    pub to_format: String,
 }
 
 fn default_format() -> String {
@@ -29,6 +38,8 @@ pub struct ValidationResult {
     pub warnings: Vec<String>,
 }
 
+const VALID_FORMATS: &[&str] = &["json", "yaml", "toml", "ron", "msgpack", "cbor"];
+
 pub struct DataProcessor;
 
 #[async_trait::async_trait]
@@ -38,41 +49,117 @@ impl Handler for DataProcessor {
     type Error = pforge_runtime::Error;
 
     async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
-        // Validate format
-        let valid_formats = vec!["json", "yaml", "toml"];
-        if !valid_formats.contains(&input.format.as_str()) {
+        if !VALID_FORMATS.contains(&input.to_format.as_str()) {
             return Err(pforge_runtime::Error::Handler(format!(
                 "Invalid format: {}. Supported: {:?}",
-                input.format, valid_formats
+                input.to_format, VALID_FORMATS
             )));
         }
 
-        // Process based on format
-        let processed_data = match input.format.as_str() {
-            "json" => serde_json::to_string_pretty(&input.data)
-                .map_err(|e| pforge_runtime::Error::Handler(format!("JSON error: {}", e)))?,
-            "yaml" => serde_yaml::to_string(&input.data)
-                .map_err(|e| pforge_runtime::Error::Handler(format!("YAML error: {}", e)))?,
-            "toml" => {
-                // TOML requires specific structure, simplified for example
-                format!("# TOML format\ndata = {}", serde_json::to_string(&input.data)?)
+        let data = match (&input.data, &input.raw, &input.from_format) {
+            (Some(data), _, _) => data.clone(),
+            (None, Some(raw), Some(from_format)) => parse_from_format(raw, from_format)?,
+            (None, Some(_), None) => {
+                return Err(pforge_runtime::Error::Handler(
+                    "raw input requires from_format".to_string(),
+                ));
+            }
+            (None, None, _) => {
+                return Err(pforge_runtime::Error::Handler(
+                    "one of data or raw must be provided".to_string(),
+                ));
             }
-            _ => unreachable!(),
         };
 
-        // Validate data
-        let validation = validate_data(&input.data);
+        let processed_data = to_format(&data, &input.to_format)?;
+        let validation = validate_data(&data);
         let size_bytes = processed_data.len();
 
         Ok(ProcessorOutput {
             processed_data,
-            format: input.format,
+            format: input.to_format,
             size_bytes,
             validation,
         })
     }
 }
 
+fn parse_from_format(raw: &str, from_format: &str) -> Result<Value> {
+    if !VALID_FORMATS.contains(&from_format) {
+        return Err(pforge_runtime::Error::Handler(format!(
+            "Invalid from_format: {}. Supported: {:?}",
+            from_format, VALID_FORMATS
+        )));
+    }
+
+    match from_format {
+        "json" => serde_json::from_str(raw)
+            .map_err(|e| pforge_runtime::Error::Handler(format!("JSON parse error: {}", e))),
+        "yaml" => serde_yaml::from_str(raw)
+            .map_err(|e| pforge_runtime::Error::Handler(format!("YAML parse error: {}", e))),
+        "toml" => raw
+            .parse::<toml::Value>()
+            .map_err(|e| pforge_runtime::Error::Handler(format!("TOML parse error: {}", e)))
+            .and_then(|v| {
+                serde_json::to_value(v)
+                    .map_err(|e| pforge_runtime::Error::Handler(format!("TOML convert error: {}", e)))
+            }),
+        "ron" => ron::from_str(raw)
+            .map_err(|e| pforge_runtime::Error::Handler(format!("RON parse error: {}", e))),
+        "msgpack" => {
+            use base64::Engine;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(raw)
+                .map_err(|e| pforge_runtime::Error::Handler(format!("base64 decode error: {}", e)))?;
+            rmp_serde::from_slice(&bytes)
+                .map_err(|e| pforge_runtime::Error::Handler(format!("MessagePack parse error: {}", e)))
+        }
+        "cbor" => {
+            use base64::Engine;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(raw)
+                .map_err(|e| pforge_runtime::Error::Handler(format!("base64 decode error: {}", e)))?;
+            ciborium::from_reader(bytes.as_slice())
+                .map_err(|e| pforge_runtime::Error::Handler(format!("CBOR parse error: {}", e)))
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn to_format(data: &Value, format: &str) -> Result<String> {
+    match format {
+        "json" => serde_json::to_string_pretty(data)
+            .map_err(|e| pforge_runtime::Error::Handler(format!("JSON error: {}", e))),
+        "yaml" => serde_yaml::to_string(data)
+            .map_err(|e| pforge_runtime::Error::Handler(format!("YAML error: {}", e))),
+        "toml" => {
+            let value: toml::Value = serde_json::from_value(data.clone())
+                .map_err(|e| pforge_runtime::Error::Handler(format!("TOML convert error: {}", e)))?;
+            toml::to_string_pretty(&value)
+                .map_err(|e| pforge_runtime::Error::Handler(format!("TOML error: {}", e)))
+        }
+        "ron" => ron::ser::to_string_pretty(data, ron::ser::PrettyConfig::default())
+            .map_err(|e| pforge_runtime::Error::Handler(format!("RON error: {}", e))),
+        "msgpack" => {
+            let bytes = rmp_serde::to_vec(data)
+                .map_err(|e| pforge_runtime::Error::Handler(format!("MessagePack error: {}", e)))?;
+            Ok(encode_binary(&bytes))
+        }
+        "cbor" => {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(data, &mut bytes)
+                .map_err(|e| pforge_runtime::Error::Handler(format!("CBOR error: {}", e)))?;
+            Ok(encode_binary(&bytes))
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn encode_binary(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
 fn validate_data(data: &Value) -> ValidationResult {
     let errors = Vec::new();
     let mut warnings = Vec::new();
@@ -106,15 +193,22 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    fn input(data: Value, to_format: &str) -> ProcessorInput {
+        ProcessorInput {
+            data: Some(data),
+            raw: None,
+            from_format: None,
+            to_format: to_format.to_string(),
+        }
+    }
+
     #[tokio::test]
     async fn test_json_processing() {
         let handler = DataProcessor;
-        let input = ProcessorInput {
-            data: json!({"key": "value"}),
-            format: "json".to_string(),
-        };
-
-        let result = handler.handle(input).await.unwrap();
+        let result = handler
+            .handle(input(json!({"key": "value"}), "json"))
+            .await
+            .unwrap();
         assert_eq!(result.format, "json");
         assert!(result.validation.valid);
         assert!(result.processed_data.contains("key"));
@@ -123,12 +217,10 @@ mod tests {
     #[tokio::test]
     async fn test_yaml_processing() {
         let handler = DataProcessor;
-        let input = ProcessorInput {
-            data: json!({"test": "data"}),
-            format: "yaml".to_string(),
-        };
-
-        let result = handler.handle(input).await.unwrap();
+        let result = handler
+            .handle(input(json!({"test": "data"}), "yaml"))
+            .await
+            .unwrap();
         assert_eq!(result.format, "yaml");
         assert!(result.processed_data.contains("test:"));
     }
@@ -136,12 +228,89 @@ mod tests {
     #[tokio::test]
     async fn test_invalid_format() {
         let handler = DataProcessor;
-        let input = ProcessorInput {
-            data: json!({}),
-            format: "xml".to_string(),
-        };
+        let result = handler.handle(input(json!({}), "xml")).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_toml_processing() {
+        let handler = DataProcessor;
+        let result = handler
+            .handle(input(json!({"name": "pforge"}), "toml"))
+            .await
+            .unwrap();
+        assert!(result.processed_data.contains("name"));
+    }
+
+    #[tokio::test]
+    async fn test_ron_processing() {
+        let handler = DataProcessor;
+        let result = handler
+            .handle(input(json!({"key": "value"}), "ron"))
+            .await
+            .unwrap();
+        assert!(result.processed_data.contains("key"));
+    }
 
-        let result = handler.handle(input).await;
+    #[tokio::test]
+    async fn test_msgpack_round_trip_is_base64() {
+        let handler = DataProcessor;
+        let result = handler
+            .handle(input(json!({"a": 1}), "msgpack"))
+            .await
+            .unwrap();
+
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&result.processed_data)
+            .unwrap();
+        let decoded: Value = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded, json!({"a": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_cbor_round_trip_is_base64() {
+        let handler = DataProcessor;
+        let result = handler
+            .handle(input(json!({"a": 1}), "cbor"))
+            .await
+            .unwrap();
+
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&result.processed_data)
+            .unwrap();
+        let decoded: Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, json!({"a": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_from_format_parses_raw_input() {
+        let handler = DataProcessor;
+        let result = handler
+            .handle(ProcessorInput {
+                data: None,
+                raw: Some(r#"{"key": "value"}"#.to_string()),
+                from_format: Some("json".to_string()),
+                to_format: "yaml".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.format, "yaml");
+        assert!(result.processed_data.contains("key:"));
+    }
+
+    #[tokio::test]
+    async fn test_raw_without_from_format_errors() {
+        let handler = DataProcessor;
+        let result = handler
+            .handle(ProcessorInput {
+                data: None,
+                raw: Some("{}".to_string()),
+                from_format: None,
+                to_format: "json".to_string(),
+            })
+            .await;
         assert!(result.is_err());
     }
 }
@@ -1,7 +1,9 @@
 mod handlers;
 
 use pforge_config::parse_config;
-use pforge_runtime::{McpServer, MemoryStateManager};
+use pforge_runtime::{
+    CommandExistsProbe, HttpReachabilityProbe, McpServer, MemoryStateManager, StateManagerProbe,
+};
 use std::path::Path;
 use std::sync::Arc;
 use tracing::{info, warn};
@@ -62,12 +64,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         info!("Registered handler: data_processor");
     }
 
+    // Wire health probes for this server's dependencies, reachable via the
+    // built-in `health_check` tool.
+    let health = server.health_registry();
+    health.register("state_manager", StateManagerProbe::new(state.clone()), true);
+    health.register("log_stream_dependency", CommandExistsProbe::new("journalctl"), false);
+    health.register(
+        "api_fetch_dependency",
+        HttpReachabilityProbe::new("https://api.github.com"),
+        false,
+    );
+    info!("Registered health probes: state_manager, log_stream_dependency, api_fetch_dependency");
+
     eprintln!("Available tools:");
     eprintln!("  • counter_increment(name, increment?) - Stateful counter");
     eprintln!("  • data_processor(data, format?) - Data validation & formatting");
     eprintln!("  • log_stream() - Real-time log streaming (CLI)");
     eprintln!("  • api_fetch() - GitHub API integration (HTTP)");
     eprintln!("  • full_workflow() - Complete pipeline");
+    eprintln!("  • health_check() - Aggregate readiness/liveness probe");
     eprintln!();
     eprintln!("Resources:");
     eprintln!("  • server_documentation - README.md");
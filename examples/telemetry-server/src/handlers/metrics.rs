@@ -1,10 +1,35 @@
-use pforge_runtime::{Handler, MetricsCollector, Result};
+use pforge_runtime::{Handler, MetricsCollector, OtlpResource, Result};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-#[derive(Debug, Deserialize, JsonSchema)]
-pub struct GetMetricsInput {}
+/// Which shape [`GetMetricsHandler`] should render its snapshot in.
+/// `Otlp` renders the same OTLP/HTTP-JSON document the background exporter
+/// pushes, for on-demand inspection without waiting for the next push tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Prometheus,
+    Json,
+    Otlp,
+}
+
+impl ExportFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Prometheus => "prometheus",
+            ExportFormat::Json => "json",
+            ExportFormat::Otlp => "otlp",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct GetMetricsInput {
+    #[serde(default)]
+    pub export_format: ExportFormat,
+}
 
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct GetMetricsOutput {
@@ -14,6 +39,9 @@ pub struct GetMetricsOutput {
 
 pub struct GetMetricsHandler {
     pub collector: Arc<MetricsCollector>,
+    /// `service.name`/`service.version` resource attributes stamped onto
+    /// the `otlp` export format.
+    pub resource: OtlpResource,
 }
 
 #[async_trait::async_trait]
@@ -22,12 +50,19 @@ impl Handler for GetMetricsHandler {
     type Output = GetMetricsOutput;
     type Error = pforge_runtime::Error;
 
-    async fn handle(&self, _input: Self::Input) -> Result<Self::Output> {
-        let prometheus = self.collector.export_prometheus();
+    async fn handle(&self, input: Self::Input) -> Result<Self::Output> {
+        let metrics = match input.export_format {
+            ExportFormat::Prometheus => self.collector.export_prometheus(),
+            ExportFormat::Json => self.collector.export_json().to_string(),
+            ExportFormat::Otlp => self
+                .collector
+                .export_otlp_json(&self.resource)
+                .to_string(),
+        };
 
         Ok(GetMetricsOutput {
-            format: "prometheus".to_string(),
-            metrics: prometheus,
+            format: input.export_format.as_str().to_string(),
+            metrics,
         })
     }
 }
@@ -38,16 +73,38 @@ mod tests {
     use std::time::Duration;
 
     #[tokio::test]
-    async fn test_get_metrics_handler() {
+    async fn test_get_metrics_handler_defaults_to_prometheus() {
         let collector = Arc::new(MetricsCollector::new());
         collector.record_request("test", Duration::from_micros(100), true);
 
         let handler = GetMetricsHandler {
             collector: collector.clone(),
+            resource: OtlpResource::new("telemetry-server", "0.1.0"),
         };
 
-        let output = handler.handle(GetMetricsInput {}).await.unwrap();
+        let output = handler.handle(GetMetricsInput::default()).await.unwrap();
         assert_eq!(output.format, "prometheus");
         assert!(output.metrics.contains("pforge_requests_total"));
     }
+
+    #[tokio::test]
+    async fn test_get_metrics_handler_otlp_format() {
+        let collector = Arc::new(MetricsCollector::new());
+        collector.record_request("test", Duration::from_micros(100), true);
+
+        let handler = GetMetricsHandler {
+            collector: collector.clone(),
+            resource: OtlpResource::new("telemetry-server", "0.1.0"),
+        };
+
+        let output = handler
+            .handle(GetMetricsInput {
+                export_format: ExportFormat::Otlp,
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.format, "otlp");
+        assert!(output.metrics.contains("resourceMetrics"));
+        assert!(output.metrics.contains("service.name"));
+    }
 }
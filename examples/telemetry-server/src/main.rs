@@ -1,9 +1,12 @@
 mod handlers;
 
 use pforge_config::parse_config;
-use pforge_runtime::{HealthCheck, McpServer, MetricsCollector};
+use pforge_runtime::{
+    DispatchLatencyRecorder, HealthCheck, McpServer, MetricsCollector, OtlpConfig, OtlpResource,
+};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -20,6 +23,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Parse configuration
     let config = parse_config(Path::new("pforge.yaml"))?;
+    let resource = OtlpResource::new(config.forge.name.clone(), config.forge.version.clone());
 
     // Create shared metrics collector and health check
     let metrics = Arc::new(MetricsCollector::new());
@@ -28,6 +32,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Register initial health status
     health.register_component("server", pforge_runtime::HealthStatus::Healthy);
 
+    // If an OTLP collector endpoint is configured, push metrics to it in the
+    // background alongside the pull-based `get_metrics`/`/metrics` surface.
+    if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        tracing::info!("Starting OTLP metrics exporter to {}", endpoint);
+        metrics.spawn_otlp_exporter(OtlpConfig {
+            endpoint,
+            resource: resource.clone(),
+            export_interval: Duration::from_secs(15),
+        });
+    }
+
     // Create MCP server
     let server = McpServer::new(config);
     let registry = server.registry();
@@ -40,6 +55,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "get_metrics",
             handlers::metrics::GetMetricsHandler {
                 collector: metrics.clone(),
+                resource: resource.clone(),
             },
         );
 
@@ -59,13 +75,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         reg.register("echo", handlers::echo::EchoHandler);
         reg.register("error_test", handlers::echo::ErrorTestHandler);
+
+        reg.register_middleware(Arc::new(DispatchLatencyRecorder::new((*metrics).clone())));
     }
 
     tracing::info!("Handlers registered, starting server");
 
-    // TODO: Integrate metrics collection into dispatch loop
-    // This would require middleware support in the server
-
     server.run().await?;
 
     Ok(())
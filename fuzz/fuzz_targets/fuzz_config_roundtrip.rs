@@ -0,0 +1,40 @@
+#![no_main]
+
+//! Fuzzes `parse_config_from_str` -> `serde_yaml::to_string` ->
+//! `parse_config_from_str` for differential round-trip bugs: anything that
+//! parses successfully must re-serialize to YAML that parses back into an
+//! identical config. A mismatch here means serialization is silently
+//! dropping or renaming a field (the same class of bug the param-struct and
+//! handler-registration codegen paths are exposed to whenever a config
+//! field is round-tripped through generated source instead of serde).
+//!
+//! `ForgeConfig` doesn't derive `PartialEq` (several of its fields nest
+//! `serde_json::Value`, which does), so the two parses are compared via
+//! their canonical `serde_json::Value` representation rather than directly.
+
+use libfuzzer_sys::fuzz_target;
+use pforge_config::parse_config_from_str;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(yaml_str) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let first = match parse_config_from_str(yaml_str) {
+        Ok(config) => config,
+        Err(_) => return,
+    };
+
+    let reserialized = serde_yaml::to_string(&first).expect("re-serializing a parsed config must not fail");
+
+    let second = parse_config_from_str(&reserialized)
+        .expect("re-parsing a config's own serialization must not fail");
+
+    let first_json = serde_json::to_value(&first).expect("parsed config must convert to JSON");
+    let second_json = serde_json::to_value(&second).expect("re-parsed config must convert to JSON");
+
+    assert_eq!(
+        first_json, second_json,
+        "config changed shape after a parse -> serialize -> parse round-trip"
+    );
+});
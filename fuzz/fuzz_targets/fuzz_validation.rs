@@ -1,33 +1,39 @@
 #![no_main]
 
+//! Fuzzes the full `parse_config_from_str` -> `validate_config` pipeline:
+//! every rejection must come back as a well-typed `ConfigError` variant
+//! (enforced by the compiler via the exhaustive match below) and neither
+//! call may panic or hang - including on self-referential pipeline steps,
+//! which `validate_config`'s cycle detection must terminate on rather than
+//! loop forever chasing.
+
 use libfuzzer_sys::fuzz_target;
-use pforge_config::{ForgeConfig, ToolDef};
+use pforge_config::{parse_config_from_str, validate_config, ConfigError};
 
 fuzz_target!(|data: &[u8]| {
-    // Try to parse YAML and validate it
-    if let Ok(yaml_str) = std::str::from_utf8(data) {
-        if let Ok(config) = serde_yaml::from_str::<ForgeConfig>(yaml_str) {
-            // Validate the config structure
-            // Check tool name uniqueness
-            let mut tool_names = std::collections::HashSet::new();
-            for tool in &config.tools {
-                let name = match tool {
-                    ToolDef::Native { name, .. } => name,
-                    ToolDef::Cli { name, .. } => name,
-                    ToolDef::Http { name, .. } => name,
-                    ToolDef::Pipeline { name, .. } => name,
-                };
-                tool_names.insert(name.clone());
-            }
+    let Ok(yaml_str) = std::str::from_utf8(data) else {
+        return;
+    };
 
-            // Verify all tools have valid names (non-empty)
-            for tool in &config.tools {
-                let name = tool.name();
-                assert!(!name.is_empty(), "Tool name should not be empty");
-            }
+    let config = match parse_config_from_str(yaml_str) {
+        Ok(config) => config,
+        Err(_) => return,
+    };
 
-            // Try to serialize back
-            let _ = serde_yaml::to_string(&config);
-        }
+    match validate_config(&config) {
+        Ok(())
+        | Err(
+            ConfigError::IoError(..)
+            | ConfigError::ParseError { .. }
+            | ConfigError::DuplicateToolName { .. }
+            | ConfigError::InvalidHandlerPath { .. }
+            | ConfigError::UnknownToolReference { .. }
+            | ConfigError::ValidationError(_)
+            | ConfigError::InterpolationError(_)
+            | ConfigError::MultipleErrors(_),
+        ) => {}
     }
+
+    // Round-tripping back through serde shouldn't panic either.
+    let _ = serde_yaml::to_string(&config);
 });